@@ -0,0 +1,13 @@
+use std::env;
+use std::process::Command;
+
+/// Records the version of rustc building Maxwell. Native plugins must be built by the same compiler,
+/// as the layout of Rust types and trait objects is not stable between versions.
+fn main() {
+    let rustc = env::var("RUSTC").unwrap_or(String::from("rustc"));
+    let version = Command::new(rustc).arg("--version").output()
+        .ok()
+        .and_then(|output| String::from_utf8(output.stdout).ok())
+        .unwrap_or_default();
+    println!("cargo:rustc-env=MAXWELL_RUSTC_VERSION={}", version.trim());
+}
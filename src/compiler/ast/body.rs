@@ -1,26 +1,131 @@
 use super::statement::StatementNode;
+use super::directive::DirectiveNode;
 
-use super::Node;
+use super::{Node, CodeWriter, SourceLocation};
+
+use std::fmt;
 
 #[derive(Debug, PartialEq, Clone)]
 pub struct BodyNode {
+    pub directives: Vec<DirectiveNode>,
     pub content: Vec<StatementNode>,
+    /// Where the braces of a block or function body are, the whole source for the body of a tree.
+    pub location: SourceLocation,
 }
 
 impl BodyNode {
     pub fn new(content: Vec<StatementNode>) -> Self {
         return BodyNode {
-            content
+            directives: Vec::new(),
+            content,
+            location: SourceLocation::default()
         };
     }
+
+    pub fn with_directives(directives: Vec<DirectiveNode>, content: Vec<StatementNode>) -> Self {
+        return BodyNode {
+            directives,
+            content,
+            location: SourceLocation::default()
+        };
+    }
+
+    pub fn has_directive(&self, value: &str) -> bool {
+        return self.directives.iter().any(|directive| directive.value == value);
+    }
+
+    pub fn is_strict(&self) -> bool {
+        return self.has_directive("use strict");
+    }
+
+    /// Appends a directive to the prologue, unless it is already present. The directive is put on
+    /// its own line after any existing directives. Without any, it goes before the first statement,
+    /// on the line of its own that statement starts on when something precedes the body.
+    pub fn insert_directive(&mut self, value: &str) {
+        if self.has_directive(value) {
+            return;
+        }
+
+        let mut directive = DirectiveNode::new(value);
+        match self.directives.last() {
+            Some(last) => {
+                directive.quote_kind = last.quote_kind.clone();
+                directive.trivia.prefix = String::from("\n");
+            }
+            None => {
+                if let Some(first) = self.content.first_mut() {
+                    let prefix = &first.trivia.prefix;
+                    let line_break = prefix.find('\n').filter(|&index| prefix[..index].trim().is_empty());
+                    match line_break {
+                        Some(index) => {
+                            let line = prefix.rsplit('\n').next().unwrap_or_default();
+                            let indent = match line.trim().is_empty() {
+                                true => line,
+                                false => ""
+                            };
+                            directive.trivia.prefix = format!("{}{}", &prefix[..index + 1], indent);
+                        }
+                        None => first.trivia.prefix.insert(0, '\n')
+                    }
+                }
+            }
+        }
+        self.directives.push(directive);
+    }
+
+    /// Inserts statements before the statement at the index. Every statement is put on a line of its
+    /// own, indented like its neighbours, except for a first statement inserted at the very start
+    /// of a source.
+    pub fn insert_statements(&mut self, index: usize, statements: Vec<StatementNode>) {
+        let neighbour = self.content.get(index).or_else(|| index.checked_sub(1).and_then(|previous| self.content.get(previous)));
+        let (indent, at_start) = match neighbour {
+            Some(neighbour) => {
+                let line = neighbour.trivia.prefix.rsplit('\n').next().unwrap_or_default();
+                let indent = match line.trim().is_empty() && neighbour.trivia.prefix.contains('\n') {
+                    true => line.to_owned(),
+                    false => String::new()
+                };
+                (indent, index == 0 && self.directives.len() == 0 && !neighbour.trivia.prefix.contains('\n'))
+            }
+            None => (String::new(), index == 0 && self.directives.len() == 0)
+        };
+
+        let count = statements.len();
+        for (i, mut statement) in statements.into_iter().enumerate() {
+            if i == 0 && at_start {
+                statement.trivia.prefix = statement.trivia.prefix.trim_start_matches('\n').to_owned();
+            } else if statement.trivia.prefix.trim().is_empty() {
+                let lines = statement.trivia.prefix.matches('\n').count().max(1);
+                statement.trivia.prefix = format!("{}{}", "\n".repeat(lines), indent);
+            } else if !statement.trivia.prefix.starts_with('\n') {
+                statement.trivia.prefix = format!("\n{}{}", indent, statement.trivia.prefix.trim_start());
+            }
+            self.content.insert(index + i, statement);
+        }
+
+        if let Some(next) = self.content.get_mut(index + count) {
+            if count > 0 && !next.trivia.prefix.starts_with('\n') {
+                next.trivia.prefix.insert(0, '\n');
+            }
+        }
+    }
+
+    /// Removes a directive from the prologue, returning whether it was present.
+    pub fn remove_directive(&mut self, value: &str) -> bool {
+        let count = self.directives.len();
+        self.directives.retain(|directive| directive.value != value);
+        return self.directives.len() != count;
+    }
 }
 
 impl Node for BodyNode {
-    fn generate(&self) -> String {
-        let mut string = String::new();
+    fn write(&self, output: &mut CodeWriter) -> fmt::Result {
+        for directive in &self.directives {
+            directive.write(output)?;
+        }
         for statement in &self.content {
-            string += &statement.generate();
+            statement.write(output)?;
         }
-        return string;
+        return Ok(());
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,170 @@
+//! Constructors for nodes inserted by plugins. Built nodes have no location and are padded the way
+//! they are usually written, `a = b`, `f(a, b)` or `{ a: 1 }`, so they generate readable code
+//! wherever they are put. Larger fragments are easier to write as source with
+//! `transform::Template`.
+
+use super::{NodeTrivia, SourceLocation};
+use super::expression::{ExpressionNode, Expression, MemberTrivia, OperatorTrivia, ObjectTrivia, ArrayTrivia, CallTrivia};
+use super::property::{PropertyNode, PropertyKey, PropertyKind, PropertyTrivia};
+use super::declaration::{DeclarationKind, DeclarationNode, DeclarationTrivia};
+use super::statement::{StatementNode, Statement, StatementTerminator, ImportDeclaration, ImportTrivia, ModuleSpecifier, ModuleSpecifierTrivia};
+use super::super::{Literal, QuoteKind, OperatorKind, Symbol};
+
+pub fn node(expression: Expression) -> ExpressionNode {
+    let mut node = ExpressionNode::new(expression, NodeTrivia::new());
+    node.location = SourceLocation::default();
+    return node;
+}
+
+/// Puts a space before the node.
+pub fn spaced(mut node: ExpressionNode) -> ExpressionNode {
+    node.trivia.prefix = String::from(" ");
+    return node;
+}
+
+pub fn identifier(name: &str) -> ExpressionNode {
+    return node(Expression::Identifier(name.into()));
+}
+
+pub fn literal(literal: Literal) -> ExpressionNode {
+    return node(Expression::Literal(literal));
+}
+
+/// A string literal in speech marks.
+pub fn string(value: &str) -> ExpressionNode {
+    return literal(Literal::quoted(value, QuoteKind::SpeechMark));
+}
+
+/// A dotted name such as `React.createElement` as a chain of member expressions.
+pub fn path(name: &str) -> ExpressionNode {
+    let mut parts = name.split('.');
+    let mut expression = identifier(parts.next().unwrap_or_default());
+    for part in parts {
+        expression = member(expression, part);
+    }
+    return expression;
+}
+
+pub fn member(object: ExpressionNode, property: &str) -> ExpressionNode {
+    let trivia = MemberTrivia { accessor_prefix: String::new(), property_prefix: String::new() };
+    return node(Expression::Member { object: Box::new(object), property: property.into(), trivia });
+}
+
+pub fn call(callee: ExpressionNode, arguments: Vec<ExpressionNode>) -> ExpressionNode {
+    return node(Expression::Call { callee: Box::new(callee), parameters: list(arguments), trivia: CallTrivia::new() });
+}
+
+pub fn assign(left: ExpressionNode, right: ExpressionNode) -> ExpressionNode {
+    let trivia = OperatorTrivia { operator_prefix: String::from(" ") };
+    return node(Expression::Assignment { left: Box::new(left), operator: OperatorKind::Assign, right: Box::new(spaced(right)), trivia });
+}
+
+pub fn array(elements: Vec<ExpressionNode>) -> ExpressionNode {
+    let elements = list(elements).into_iter().map(Some).collect();
+    return node(Expression::Array { elements, trivia: ArrayTrivia::new() });
+}
+
+pub fn object(properties: Vec<PropertyNode>) -> ExpressionNode {
+    let mut trivia = ObjectTrivia::new();
+    if properties.len() > 0 {
+        trivia.properties_suffix = String::from(" ");
+    }
+    return node(Expression::Object { properties, trivia });
+}
+
+/// A `name: value` property. Names which are not identifiers are written as strings.
+pub fn property(name: &str, value: ExpressionNode) -> PropertyNode {
+    let is_identifier = !name.starts_with(|char: char| char.is_ascii_digit()) && name.chars().all(|char| char.is_alphanumeric() || char == '_' || char == '$');
+    let key = match is_identifier {
+        true => PropertyKey::Identifier(name.into()),
+        false => PropertyKey::Literal(Literal::quoted(name, QuoteKind::SpeechMark))
+    };
+
+    let mut trivia = PropertyTrivia::new();
+    trivia.prefix = String::from(" ");
+    return PropertyNode { key, value: Some(spaced(value)), kind: PropertyKind::Init, is_static: false, location: SourceLocation::default(), key_location: SourceLocation::default(), trivia };
+}
+
+/// A statement on a line of its own.
+pub fn statement(statement: Statement) -> StatementNode {
+    let mut trivia = NodeTrivia::new();
+    trivia.prefix = String::from("\n");
+    return StatementNode::new(statement, trivia, StatementTerminator::Semicolon);
+}
+
+pub fn expression_statement(expression: ExpressionNode) -> StatementNode {
+    return statement(Statement::Expression { expression });
+}
+
+pub fn declaration(kind: DeclarationKind, name: &str, value: Option<ExpressionNode>) -> StatementNode {
+    let mut trivia = DeclarationTrivia::new();
+    trivia.prefix = String::from(" ");
+    if value.is_some() {
+        trivia.assign_prefix = String::from(" ");
+    }
+    let declaration = DeclarationNode::new(Symbol::from(name), value.map(spaced), trivia);
+    return statement(Statement::Declaration { kind, declarations: vec![declaration] });
+}
+
+pub fn return_statement(expression: Option<ExpressionNode>) -> StatementNode {
+    return statement(Statement::Return { expression: expression.map(spaced) });
+}
+
+/// `import { name as alias } from "source";` with a specifier for every name and optional alias.
+pub fn import_named(specifiers: &[(&str, Option<&str>)], source: &str) -> StatementNode {
+    let mut module_specifiers = Vec::new();
+    for &(name, alias) in specifiers {
+        let mut trivia = ModuleSpecifierTrivia::new();
+        trivia.prefix = String::from(" ");
+        if alias.is_some() {
+            trivia.as_prefix = String::from(" ");
+            trivia.alias_prefix = String::from(" ");
+        }
+        module_specifiers.push(ModuleSpecifier { name: Symbol::from(name), alias: alias.map(Symbol::from), location: SourceLocation::default(), trivia });
+    }
+
+    let mut trivia = import_trivia();
+    trivia.specifiers_suffix = String::from(" ");
+    return import(None, ImportDeclaration::Multiple(module_specifiers), source, trivia);
+}
+
+/// `import name from "source";`
+pub fn import_default(name: &str, source: &str) -> StatementNode {
+    return import(None, ImportDeclaration::Single(Symbol::from(name)), source, import_trivia());
+}
+
+/// `import * as alias from "source";`
+pub fn import_all(alias: &str, source: &str) -> StatementNode {
+    let mut trivia = import_trivia();
+    trivia.as_prefix = String::from(" ");
+    trivia.alias_prefix = String::from(" ");
+    return import(Some(Symbol::from(alias)), ImportDeclaration::All, source, trivia);
+}
+
+fn import(alias: Option<Symbol>, declaration: ImportDeclaration, source: &str, trivia: ImportTrivia) -> StatementNode {
+    let source = match Literal::quoted(source, QuoteKind::SpeechMark) {
+        Literal::String(source, _) => source,
+        _ => source.to_owned()
+    };
+    return statement(Statement::Import { alias, declaration, source, trivia });
+}
+
+fn import_trivia() -> ImportTrivia {
+    let mut trivia = ImportTrivia::new();
+    trivia.declaration_prefix = String::from(" ");
+    trivia.from_prefix = String::from(" ");
+    trivia.source_prefix = String::from(" ");
+    trivia.quote_kind = QuoteKind::SpeechMark;
+    return trivia;
+}
+
+/// Separates the nodes of a list with a space after each comma.
+fn list(nodes: Vec<ExpressionNode>) -> Vec<ExpressionNode> {
+    return nodes.into_iter().enumerate().map(|(i, mut node)| match i {
+        0 => {
+            node.trivia.prefix = String::new();
+            node
+        }
+        _ => spaced(node)
+    }).collect();
+}
@@ -0,0 +1,86 @@
+use super::SourceLocation;
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum CommentKind {
+    /// A `// ...` comment, running to the end of its line.
+    Line,
+    /// A `/* ... */` comment.
+    Block,
+}
+
+/// A comment of the source. Its text also stays in the trivia of the node it was read with, so
+/// generating the tree reproduces it wherever that node is moved.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Comment {
+    pub kind: CommentKind,
+    /// The comment as written, including its `//` or `/* */` delimiters.
+    pub text: String,
+    pub location: SourceLocation,
+}
+
+impl Comment {
+    pub fn new(text: String, location: SourceLocation) -> Self {
+        let kind = match text.starts_with("/*") {
+            true => CommentKind::Block,
+            false => CommentKind::Line
+        };
+        return Comment { kind, text, location };
+    }
+
+    /// The text of the comment without its delimiters.
+    pub fn value(&self) -> &str {
+        return match self.kind {
+            CommentKind::Line => &self.text[2..],
+            CommentKind::Block => &self.text[2..self.text.len() - 2],
+        };
+    }
+
+    /// Whether this is a `/** ... */` documentation comment.
+    pub fn is_jsdoc(&self) -> bool {
+        return self.kind == CommentKind::Block && self.text.starts_with("/**") && self.text != "/**/";
+    }
+
+    /// Whether this is a `/*#__PURE__*/` or `/*@__PURE__*/` annotation, which marks the call or
+    /// `new` expression following it as free of side effects.
+    pub fn is_pure_annotation(&self) -> bool {
+        let value = self.value().trim();
+        return self.kind == CommentKind::Block && (value == "#__PURE__" || value == "@__PURE__");
+    }
+
+    /// Whether this is a `/*! ... */` comment or one with a `@license` or `@preserve` tag, which
+    /// minified code keeps.
+    pub fn is_license(&self) -> bool {
+        return self.kind == CommentKind::Block && (self.text.starts_with("/*!") || self.has_tag("license") || self.has_tag("preserve"));
+    }
+
+    /// Whether the comment holds a `@tag` such as `@ts-ignore`, `@param` or `@deprecated`.
+    pub fn has_tag(&self, tag: &str) -> bool {
+        let tag = format!("@{}", tag);
+        let value = self.value();
+        return value.match_indices(&tag).any(|(index, _)| {
+            let preceded = value[..index].chars().next_back().map_or(false, is_tag_char);
+            let followed = value[index + tag.len()..].chars().next().map_or(false, is_tag_char);
+            !preceded && !followed
+        });
+    }
+
+    /// The value of a `key: value` entry of a magic comment like `/* webpackChunkName: "vendor" */`,
+    /// as written. Entries are separated by commas.
+    pub fn magic_value(&self, key: &str) -> Option<&str> {
+        if self.kind != CommentKind::Block {
+            return None;
+        }
+        for entry in self.value().split(',') {
+            let mut parts = entry.splitn(2, ':');
+            match (parts.next(), parts.next()) {
+                (Some(name), Some(value)) if name.trim() == key => return Some(value.trim()),
+                _ => {}
+            }
+        }
+        return None;
+    }
+}
+
+fn is_tag_char(char: char) -> bool {
+    return char.is_alphanumeric() || char == '_' || char == '-' || char == '$';
+}
@@ -1,21 +1,29 @@
+use super::super::Symbol;
 use super::expression::ExpressionNode;
-use super::Node;
+use super::{Node, CodeWriter, SourceLocation};
 
+use std::fmt;
+
+/// Padding before the name of a declarator, before its `=` and before the comma following it.
 #[derive(Debug, PartialEq, Clone)]
 pub struct DeclarationTrivia {
-    pub assign_prefix: String
+    pub prefix: String,
+    pub assign_prefix: String,
+    pub suffix: String,
 }
 
 impl DeclarationTrivia {
     pub fn new() -> Self {
-        return DeclarationTrivia { assign_prefix: String::new() };
+        return DeclarationTrivia { prefix: String::new(), assign_prefix: String::new(), suffix: String::new() };
     }
 }
 
 #[derive(Debug, PartialEq, Clone)]
 pub struct DeclarationNode {
-    pub name: String,
+    pub name: Symbol,
     pub expression: Option<ExpressionNode>,
+    /// From the name to the end of the initialiser.
+    pub location: SourceLocation,
     pub trivia: DeclarationTrivia,
 }
 
@@ -27,22 +35,25 @@ pub enum DeclarationKind {
 }
 
 impl DeclarationNode {
-    pub fn new(name: String, expression: Option<ExpressionNode>, trivia: DeclarationTrivia) -> Self {
-        return DeclarationNode { name, expression, trivia };
+    pub fn new(name: Symbol, expression: Option<ExpressionNode>, trivia: DeclarationTrivia) -> Self {
+        return DeclarationNode { name, expression, location: SourceLocation::default(), trivia };
     }
 }
 
 impl Node for DeclarationNode {
-    fn generate(&self) -> String {
-        let mut expression = String::new();
+    fn write(&self, output: &mut CodeWriter) -> fmt::Result {
+        output.write(&self.trivia.prefix)?;
+        output.mark(&self.location, Some(&self.name));
+        output.write(&self.name)?;
         match self.expression {
             Some(ref expression_node) => {
-                expression += " =";
-                expression += &expression_node.generate();
+                output.write(&self.trivia.assign_prefix)?;
+                output.write("=")?;
+                expression_node.write(output)?;
             }
             None => {}
         }
-        return format!("{}{}", self.name, expression);
+        return output.write(&self.trivia.suffix);
     }
 }
 
@@ -0,0 +1,41 @@
+use super::statement::StatementTerminator;
+use super::{NodeTrivia, SourceLocation, Node, CodeWriter};
+use super::super::QuoteKind;
+
+use std::fmt;
+use std::fmt::Write;
+
+/// A string literal statement in the prologue of a program or function body, such as `'use strict'`.
+#[derive(Debug, PartialEq, Clone)]
+pub struct DirectiveNode {
+    pub value: String,
+    pub quote_kind: QuoteKind,
+    pub location: SourceLocation,
+    pub trivia: NodeTrivia,
+    pub terminator: StatementTerminator,
+}
+
+impl DirectiveNode {
+    pub fn new(value: &str) -> Self {
+        return DirectiveNode {
+            value: value.to_owned(),
+            quote_kind: QuoteKind::Apostrophe,
+            location: SourceLocation::default(),
+            trivia: NodeTrivia::new(),
+            terminator: StatementTerminator::Semicolon,
+        };
+    }
+}
+
+impl Node for DirectiveNode {
+    fn write(&self, output: &mut CodeWriter) -> fmt::Result {
+        output.write(&self.trivia.prefix)?;
+        output.mark(&self.location, None);
+        return write!(output, "{}{}{}{}{}",
+                      self.quote_kind.to_string(),
+                      self.value,
+                      self.quote_kind.to_string(),
+                      self.trivia.suffix,
+                      self.terminator.to_string());
+    }
+}
@@ -0,0 +1,821 @@
+extern crate json;
+
+use self::json::JsonValue;
+
+use super::super::{SyntaxTree, SourceLocation, Parameter, Comment, CommentKind};
+use super::super::body::BodyNode;
+use super::super::directive::DirectiveNode;
+use super::super::declaration::{DeclarationKind, DeclarationNode};
+use super::super::statement::{StatementNode, Statement, ImportDeclaration, ExportDeclaration, ModuleSpecifier};
+use super::super::expression::{ExpressionNode, Expression};
+use super::super::property::{PropertyNode, PropertyKey, PropertyKind};
+use super::super::jsx::{JsxElement, JsxAttribute, JsxAttributeValue, JsxChild, JsxContainer};
+use super::super::super::{Literal, OperatorKind};
+
+/// How a syntax tree is converted to ESTree.
+#[derive(Debug, PartialEq, Clone)]
+pub struct EstreeOptions {
+    /// Keeps brackets as `ParenthesizedExpression` nodes, the way acorn reports them with
+    /// `preserveParens`. Without it only the expression inside them is exported.
+    pub preserve_parens: bool,
+}
+
+impl EstreeOptions {
+    pub fn new() -> Self {
+        return EstreeOptions { preserve_parens: false };
+    }
+
+    pub fn with_preserve_parens(mut self, preserve_parens: bool) -> Self {
+        self.preserve_parens = preserve_parens;
+        return self;
+    }
+}
+
+/// Converts a syntax tree to an ESTree `Program`. The source is the one the tree was parsed from,
+/// used to turn offsets into lines and columns.
+pub fn to_estree(tree: &SyntaxTree, source: &str) -> JsonValue {
+    return to_estree_with(tree, source, &EstreeOptions::new());
+}
+
+/// Converts a syntax tree to an ESTree `Program` like `to_estree`, with the given options.
+pub fn to_estree_with(tree: &SyntaxTree, source: &str, options: &EstreeOptions) -> JsonValue {
+    return EstreeExporter::new(source, options).program(tree);
+}
+
+struct EstreeExporter<'a> {
+    source: &'a str,
+    options: &'a EstreeOptions,
+    /// Byte offset and UTF-16 offset of the start of every line.
+    lines: Vec<(usize, usize)>,
+}
+
+impl<'a> EstreeExporter<'a> {
+    fn new(source: &'a str, options: &'a EstreeOptions) -> Self {
+        let mut lines = vec![(0, 0)];
+        let mut utf16_offset = 0;
+        for (index, char) in source.char_indices() {
+            utf16_offset += char.len_utf16();
+            if char == '\n' {
+                lines.push((index + 1, utf16_offset));
+            }
+        }
+        return EstreeExporter { source, options, lines };
+    }
+
+    /// The UTF-16 offset, line and column of a byte offset into the source.
+    fn position(&self, offset: usize) -> (usize, usize, usize) {
+        let mut offset = offset.min(self.source.len());
+        while !self.source.is_char_boundary(offset) {
+            offset -= 1;
+        }
+
+        let line = match self.lines.binary_search_by_key(&offset, |&(start, _)| start) {
+            Ok(line) => line,
+            Err(line) => line - 1
+        };
+        let (line_start, utf16_line_start) = self.lines[line];
+        let column = self.source[line_start..offset].encode_utf16().count();
+        return (utf16_line_start + column, line + 1, column);
+    }
+
+    /// Creates a node of the given type, located when the syntax tree knows where it is.
+    fn node(&self, kind: &str, location: Option<&SourceLocation>) -> JsonValue {
+        let mut node = JsonValue::new_object();
+        node["type"] = kind.into();
+        if let Some(location) = location {
+            let (start, start_line, start_column) = self.position(location.start);
+            let (end, end_line, end_column) = self.position(location.end);
+            node["start"] = start.into();
+            node["end"] = end.into();
+
+            let mut loc = JsonValue::new_object();
+            loc["start"] = EstreeExporter::line_column(start_line, start_column);
+            loc["end"] = EstreeExporter::line_column(end_line, end_column);
+            node["loc"] = loc;
+            node["range"] = JsonValue::Array(vec![start.into(), end.into()]);
+        }
+        return node;
+    }
+
+    fn line_column(line: usize, column: usize) -> JsonValue {
+        let mut position = JsonValue::new_object();
+        position["line"] = line.into();
+        position["column"] = column.into();
+        return position;
+    }
+
+    fn optional<T, F>(&self, value: Option<&T>, convert: F) -> JsonValue where F: Fn(&T) -> JsonValue {
+        return match value {
+            Some(value) => convert(value),
+            None => JsonValue::Null
+        };
+    }
+
+    fn program(&self, tree: &SyntaxTree) -> JsonValue {
+        let mut program = self.node("Program", Some(&SourceLocation::new(0, self.source.len())));
+        program["sourceType"] = tree.source_type.to_string().into();
+        program["hashbang"] = self.optional(tree.hashbang.as_ref(), |hashbang| hashbang.trim_start_matches("#!").into());
+        program["body"] = self.body(&tree.base_node);
+        program["comments"] = JsonValue::Array(tree.comments.iter().map(|comment| self.comment(comment)).collect());
+        return program;
+    }
+
+    /// A comment the way acorn reports it, a `Line` or `Block` node holding the text inside its
+    /// delimiters.
+    fn comment(&self, comment: &Comment) -> JsonValue {
+        let kind = match comment.kind {
+            CommentKind::Line => "Line",
+            CommentKind::Block => "Block",
+        };
+        let mut node = self.node(kind, Some(&comment.location));
+        node["value"] = comment.value().into();
+        return node;
+    }
+
+    fn body(&self, body: &BodyNode) -> JsonValue {
+        let mut statements: Vec<JsonValue> = body.directives.iter().map(|directive| self.directive(directive)).collect();
+        statements.extend(body.content.iter().map(|statement| self.statement(statement)));
+        return JsonValue::Array(statements);
+    }
+
+    fn block(&self, body: &BodyNode, location: Option<&SourceLocation>) -> JsonValue {
+        let mut block = self.node("BlockStatement", location);
+        block["body"] = self.body(body);
+        return block;
+    }
+
+    fn directive(&self, directive: &DirectiveNode) -> JsonValue {
+        let raw = format!("{}{}{}", directive.quote_kind.to_string(), directive.value, directive.quote_kind.to_string());
+        let mut literal = self.node("Literal", Some(&directive.location));
+        literal["value"] = unescape(&directive.value).into();
+        literal["raw"] = raw.into();
+
+        let mut statement = self.node("ExpressionStatement", Some(&directive.location));
+        statement["expression"] = literal;
+        statement["directive"] = directive.value.as_str().into();
+        return statement;
+    }
+
+    fn statement(&self, node: &StatementNode) -> JsonValue {
+        let location = Some(&node.location);
+        return match node.statement {
+            Statement::Empty => self.node("EmptyStatement", location),
+            Statement::Expression { ref expression } if expression.expression.is_declaration() => self.declaration(expression),
+            Statement::Expression { ref expression } => {
+                let mut statement = self.node("ExpressionStatement", location);
+                statement["expression"] = self.expression(expression);
+                statement
+            }
+            Statement::Declaration { ref kind, ref declarations } => self.variable_declaration(kind, declarations, location),
+            Statement::Return { ref expression } => {
+                let mut statement = self.node("ReturnStatement", location);
+                statement["argument"] = self.optional(expression.as_ref(), |expression| self.expression(expression));
+                statement
+            }
+            Statement::Import { ref alias, ref declaration, ref source, ref trivia } => {
+                // The default and namespace bindings are found from the padding written before them.
+                let declaration_start = located(&node.location).map(|location| location.start + "import".len() + trivia.declaration_prefix.len());
+                let mut specifiers = Vec::new();
+                match *declaration {
+                    ImportDeclaration::All => {
+                        if let Some(ref alias) = *alias {
+                            let end = declaration_start.map(|start| start + format!("*{}as{}{}", trivia.as_prefix, trivia.alias_prefix, alias).len());
+                            let location = declaration_start.and_then(|start| end.map(|end| SourceLocation::new(start, end)));
+                            let mut specifier = self.node("ImportNamespaceSpecifier", location.as_ref());
+                            specifier["local"] = self.identifier(alias, location.as_ref().and_then(|location| trailing(location, alias)).as_ref());
+                            specifiers.push(specifier);
+                        }
+                    }
+                    ImportDeclaration::Single(ref name) => {
+                        let location = declaration_start.map(|start| SourceLocation::new(start, start + name.len()));
+                        let mut specifier = self.node("ImportDefaultSpecifier", location.as_ref());
+                        specifier["local"] = self.identifier(name, location.as_ref());
+                        specifiers.push(specifier);
+                    }
+                    ImportDeclaration::Multiple(ref module_specifiers) => {
+                        for module_specifier in module_specifiers {
+                            let mut specifier = self.node("ImportSpecifier", located(&module_specifier.location));
+                            let (name, alias) = self.module_specifier_names(module_specifier);
+                            specifier["imported"] = name;
+                            specifier["local"] = alias;
+                            specifiers.push(specifier);
+                        }
+                    }
+                }
+
+                let mut statement = self.node("ImportDeclaration", location);
+                statement["specifiers"] = JsonValue::Array(specifiers);
+                statement["source"] = self.literal(&Literal::String(source.to_owned(), trivia.quote_kind.clone()), None);
+                statement
+            }
+            Statement::Export { declaration: ExportDeclaration::Default(ref expression), .. } => {
+                let mut statement = self.node("ExportDefaultDeclaration", location);
+                statement["declaration"] = match expression.expression {
+                    Expression::Function { .. } | Expression::Class { .. } => self.declaration(expression),
+                    _ => self.expression(expression)
+                };
+                statement
+            }
+            Statement::Export { declaration: ExportDeclaration::Statement(ref declaration), .. } => {
+                let mut statement = self.node("ExportNamedDeclaration", location);
+                statement["declaration"] = self.statement(declaration);
+                statement["specifiers"] = JsonValue::new_array();
+                statement["source"] = JsonValue::Null;
+                statement
+            }
+            Statement::Export { declaration: ExportDeclaration::Named(ref module_specifiers), .. } => {
+                let specifiers = module_specifiers.iter().map(|module_specifier| {
+                    let mut specifier = self.node("ExportSpecifier", located(&module_specifier.location));
+                    let (name, alias) = self.module_specifier_names(module_specifier);
+                    specifier["local"] = name;
+                    specifier["exported"] = alias;
+                    specifier
+                }).collect();
+
+                let mut statement = self.node("ExportNamedDeclaration", location);
+                statement["declaration"] = JsonValue::Null;
+                statement["specifiers"] = JsonValue::Array(specifiers);
+                statement["source"] = JsonValue::Null;
+                statement
+            }
+            Statement::With { ref object, ref body, .. } => {
+                let mut statement = self.node("WithStatement", location);
+                statement["object"] = self.expression(object);
+                statement["body"] = self.statement(body);
+                statement
+            }
+            Statement::Block { ref body, .. } => self.block(body, location),
+            Statement::For { ref init, ref test, ref update, ref body, .. } => {
+                let mut statement = self.node("ForStatement", location);
+                statement["init"] = self.optional(init.as_ref(), |init| self.for_head(init));
+                statement["test"] = self.optional(test.as_ref(), |test| self.expression(test));
+                statement["update"] = self.optional(update.as_ref(), |update| self.expression(update));
+                statement["body"] = self.statement(body);
+                statement
+            }
+            Statement::ForIn { ref left, ref right, ref body, .. } => {
+                let mut statement = self.node("ForInStatement", location);
+                statement["left"] = self.for_head(left);
+                statement["right"] = self.expression(right);
+                statement["body"] = self.statement(body);
+                statement
+            }
+            Statement::ForOf { ref left, ref right, ref body, is_await, .. } => {
+                let mut statement = self.node("ForOfStatement", location);
+                statement["await"] = is_await.into();
+                statement["left"] = self.for_head(left);
+                statement["right"] = self.expression(right);
+                statement["body"] = self.statement(body);
+                statement
+            }
+        };
+    }
+
+    /// The identifiers of the name and the alias of a specifier, the name again when it has no alias.
+    fn module_specifier_names(&self, specifier: &ModuleSpecifier) -> (JsonValue, JsonValue) {
+        let location = located(&specifier.location);
+        let name_location = location.map(|location| leading(location, &specifier.name));
+        let name = self.identifier(&specifier.name, name_location.as_ref());
+        let alias = match specifier.alias {
+            Some(ref alias) => self.identifier(alias, location.and_then(|location| trailing(location, alias)).as_ref()),
+            None => name.clone()
+        };
+        return (name, alias);
+    }
+
+    /// The initialiser or left side of a loop head, a declaration or a bare expression.
+    fn for_head(&self, node: &StatementNode) -> JsonValue {
+        return match node.statement {
+            Statement::Expression { ref expression } => self.expression(expression),
+            _ => self.statement(node)
+        };
+    }
+
+    fn variable_declaration(&self, kind: &DeclarationKind, declarations: &Vec<DeclarationNode>, location: Option<&SourceLocation>) -> JsonValue {
+        let declarators = declarations.iter().map(|declaration| {
+            let location = located(&declaration.location);
+            let mut declarator = self.node("VariableDeclarator", location);
+            declarator["id"] = self.identifier(&declaration.name, location.map(|location| leading(location, &declaration.name)).as_ref());
+            declarator["init"] = self.optional(declaration.expression.as_ref(), |expression| self.expression(expression));
+            declarator
+        }).collect();
+
+        let mut statement = self.node("VariableDeclaration", location);
+        statement["declarations"] = JsonValue::Array(declarators);
+        statement["kind"] = kind.to_string().into();
+        return statement;
+    }
+
+    /// A function or class declaration, which the syntax tree keeps as an expression.
+    fn declaration(&self, expression: &ExpressionNode) -> JsonValue {
+        let mut declaration = self.expression(expression);
+        declaration["type"] = match expression.expression {
+            Expression::Class { .. } => "ClassDeclaration",
+            _ => "FunctionDeclaration"
+        }.into();
+        return declaration;
+    }
+
+    fn expression(&self, node: &ExpressionNode) -> JsonValue {
+        use self::Expression::*;
+
+        let location = Some(&node.location);
+        return match node.expression {
+            Void => JsonValue::Null,
+            This => self.node("ThisExpression", location),
+            Super => self.node("Super", location),
+            Bracketed { ref expression, .. } if self.options.preserve_parens => {
+                let mut parenthesized = self.node("ParenthesizedExpression", location);
+                parenthesized["expression"] = self.expression(expression);
+                parenthesized
+            }
+            Bracketed { ref expression, .. } => self.expression(expression),
+            Function { ref name, is_async, is_generator, ref parameters, ref body, .. } => {
+                self.function("FunctionExpression", name, is_async, is_generator, parameters, body, location)
+            }
+            ArrowFunction { ref parameters, ref body, .. } => {
+                self.function("ArrowFunctionExpression", "", false, false, parameters, body, location)
+            }
+            Call { ref callee, ref parameters, .. } => {
+                let mut call = self.node("CallExpression", location);
+                call["callee"] = self.expression(callee);
+                call["arguments"] = self.expressions(parameters);
+                call["optional"] = false.into();
+                call
+            }
+            New { ref callee, ref parameters, .. } => {
+                let mut new = self.node("NewExpression", location);
+                new["callee"] = self.expression(callee);
+                new["arguments"] = match *parameters {
+                    Some(ref parameters) => self.expressions(parameters),
+                    None => JsonValue::new_array()
+                };
+                new
+            }
+            Member { ref object, ref property, .. } => {
+                let mut member = self.node("MemberExpression", location);
+                member["object"] = self.expression(object);
+                member["property"] = self.identifier(property, located(&node.location).and_then(|location| trailing(location, property)).as_ref());
+                member["computed"] = false.into();
+                member["optional"] = false.into();
+                member
+            }
+            ComputedMember { ref object, ref property, .. } => {
+                let mut member = self.node("MemberExpression", location);
+                member["object"] = self.expression(object);
+                member["property"] = self.expression(property);
+                member["computed"] = true.into();
+                member["optional"] = false.into();
+                member
+            }
+            Unary { operator: OperatorKind::Spread, ref argument } => {
+                let mut spread = self.node("SpreadElement", location);
+                spread["argument"] = self.expression(argument);
+                spread
+            }
+            Unary { ref operator, ref argument } => {
+                let mut unary = self.node("UnaryExpression", location);
+                unary["operator"] = operator.to_string().into();
+                unary["prefix"] = true.into();
+                unary["argument"] = self.expression(argument);
+                unary
+            }
+            Update { ref operator, ref argument, prefix, .. } => {
+                let mut update = self.node("UpdateExpression", location);
+                update["operator"] = operator.to_string().into();
+                update["prefix"] = prefix.into();
+                update["argument"] = self.expression(argument);
+                update
+            }
+            Binary { ref left, ref operator, ref right, .. } => {
+                let kind = match *operator {
+                    OperatorKind::LogicalAnd | OperatorKind::LogicalOr => "LogicalExpression",
+                    _ => "BinaryExpression"
+                };
+                self.operation(kind, left, operator, right, location)
+            }
+            Assignment { ref left, ref operator, ref right, .. } => self.operation("AssignmentExpression", left, operator, right, location),
+            Await { ref argument } => {
+                let mut await_expression = self.node("AwaitExpression", location);
+                await_expression["argument"] = self.expression(argument);
+                await_expression
+            }
+            Yield { ref argument, delegate, .. } => {
+                let mut yield_expression = self.node("YieldExpression", location);
+                yield_expression["argument"] = self.optional(argument.as_ref(), |argument| self.expression(argument));
+                yield_expression["delegate"] = delegate.into();
+                yield_expression
+            }
+            Object { ref properties, .. } => {
+                let mut object = self.node("ObjectExpression", location);
+                object["properties"] = JsonValue::Array(properties.iter().map(|property| self.property(property)).collect());
+                object
+            }
+            Array { ref elements, .. } => {
+                let mut array = self.node("ArrayExpression", location);
+                array["elements"] = JsonValue::Array(elements.iter().map(|element| self.optional(element.as_ref(), |element| self.expression(element))).collect());
+                array
+            }
+            Class { ref name, ref super_class, ref members, .. } => {
+                let mut body = self.node("ClassBody", None);
+                body["body"] = JsonValue::Array(members.iter().map(|member| self.class_member(member)).collect());
+
+                let mut class = self.node("ClassExpression", location);
+                class["id"] = self.name(name);
+                class["superClass"] = self.optional(super_class.as_ref(), |super_class| self.expression(super_class));
+                class["body"] = body;
+                class
+            }
+            Conditional { ref test, ref consequent, ref alternate, .. } => {
+                let mut conditional = self.node("ConditionalExpression", location);
+                conditional["test"] = self.expression(test);
+                conditional["consequent"] = self.expression(consequent);
+                conditional["alternate"] = self.expression(alternate);
+                conditional
+            }
+            Jsx(ref element) => self.jsx_element(element, location),
+            Identifier(ref name) => self.identifier(name, location),
+            Literal(ref literal) => self.literal(literal, location),
+        };
+    }
+
+    fn expressions(&self, nodes: &Vec<ExpressionNode>) -> JsonValue {
+        return JsonValue::Array(nodes.iter().map(|node| self.expression(node)).collect());
+    }
+
+    fn operation(&self, kind: &str, left: &ExpressionNode, operator: &OperatorKind, right: &ExpressionNode, location: Option<&SourceLocation>) -> JsonValue {
+        let mut operation = self.node(kind, location);
+        operation["left"] = self.expression(left);
+        operation["operator"] = operator.to_string().into();
+        operation["right"] = self.expression(right);
+        return operation;
+    }
+
+    fn function(&self, kind: &str, name: &str, is_async: bool, is_generator: bool, parameters: &Vec<Parameter>, body: &BodyNode, location: Option<&SourceLocation>) -> JsonValue {
+        let mut function = self.node(kind, location);
+        function["id"] = self.name(name);
+        function["params"] = JsonValue::Array(parameters.iter().map(|parameter| self.parameter(parameter)).collect());
+        function["body"] = self.block(body, located(&body.location));
+        function["async"] = is_async.into();
+        function["generator"] = is_generator.into();
+        function["expression"] = false.into();
+        return function;
+    }
+
+    fn parameter(&self, parameter: &Parameter) -> JsonValue {
+        let identifier = self.identifier(&parameter.name, None);
+        return match parameter.default {
+            Some(ref default) => {
+                let mut pattern = self.node("AssignmentPattern", None);
+                pattern["left"] = identifier;
+                pattern["right"] = self.literal(default, None);
+                pattern
+            }
+            None => identifier
+        };
+    }
+
+    fn property(&self, property: &PropertyNode) -> JsonValue {
+        let mut node = self.node("Property", Some(&property.location));
+        node["key"] = self.property_key(&property.key, &property.key_location);
+        node["computed"] = is_computed(&property.key).into();
+        node["value"] = match property.value {
+            Some(ref value) => self.expression(value),
+            None => match property.key {
+                PropertyKey::Identifier(ref name) => self.identifier(name, Some(&property.location)),
+                _ => JsonValue::Null
+            }
+        };
+        node["kind"] = match property.kind {
+            PropertyKind::Get => "get",
+            PropertyKind::Set => "set",
+            _ => "init"
+        }.into();
+        node["method"] = (property.kind == PropertyKind::Method).into();
+        node["shorthand"] = (property.kind == PropertyKind::Shorthand).into();
+        return node;
+    }
+
+    fn class_member(&self, member: &PropertyNode) -> JsonValue {
+        let mut node = match member.kind {
+            PropertyKind::Field => self.node("PropertyDefinition", Some(&member.location)),
+            _ => self.node("MethodDefinition", Some(&member.location))
+        };
+        node["key"] = self.property_key(&member.key, &member.key_location);
+        node["computed"] = is_computed(&member.key).into();
+        node["static"] = member.is_static.into();
+        node["value"] = self.optional(member.value.as_ref(), |value| self.expression(value));
+        if member.kind != PropertyKind::Field {
+            let is_constructor = !member.is_static && match member.key {
+                PropertyKey::Identifier(ref name) => name == "constructor",
+                PropertyKey::Literal(Literal::String(ref name, _)) => name == "constructor",
+                _ => false
+            };
+            node["kind"] = match member.kind {
+                PropertyKind::Get => "get",
+                PropertyKind::Set => "set",
+                _ if is_constructor => "constructor",
+                _ => "method"
+            }.into();
+        }
+        return node;
+    }
+
+    fn property_key(&self, key: &PropertyKey, location: &SourceLocation) -> JsonValue {
+        return match *key {
+            PropertyKey::Identifier(ref name) => self.identifier(name, located(location)),
+            PropertyKey::Literal(ref literal) => self.literal(literal, located(location)),
+            PropertyKey::Computed(ref expression) => self.expression(expression),
+        };
+    }
+
+    fn identifier(&self, name: &str, location: Option<&SourceLocation>) -> JsonValue {
+        let mut identifier = self.node("Identifier", location);
+        identifier["name"] = name.into();
+        return identifier;
+    }
+
+    /// The identifier of a function or class, `null` when it is anonymous.
+    fn name(&self, name: &str) -> JsonValue {
+        return match name.is_empty() {
+            true => JsonValue::Null,
+            false => self.identifier(name, None)
+        };
+    }
+
+    fn literal(&self, literal: &Literal, location: Option<&SourceLocation>) -> JsonValue {
+        let value = match *literal {
+            Literal::Undefined => return self.identifier("undefined", location),
+            Literal::Null => JsonValue::Null,
+            Literal::Boolean(value) => value.into(),
+            Literal::Binary(value) => value.into(),
+            Literal::Number(ref raw) => number_value(raw),
+            Literal::String(ref raw, _) => unescape(raw).into(),
+        };
+
+        let mut node = self.node("Literal", location);
+        node["value"] = value;
+        node["raw"] = literal.to_string().into();
+        if let Literal::Number(ref raw) = *literal {
+            if raw.ends_with('n') {
+                node["bigint"] = raw.trim_end_matches('n').replace('_', "").into();
+            }
+        }
+        return node;
+    }
+
+    fn jsx_element(&self, element: &JsxElement, location: Option<&SourceLocation>) -> JsonValue {
+        let children = match element.children {
+            Some(ref children) => JsonValue::Array(children.iter().map(|child| self.jsx_child(child)).collect()),
+            None => JsonValue::new_array()
+        };
+
+        let name = match element.name {
+            Some(ref name) => name,
+            None => {
+                let mut fragment = self.node("JSXFragment", location);
+                fragment["openingFragment"] = self.node("JSXOpeningFragment", None);
+                fragment["closingFragment"] = self.node("JSXClosingFragment", None);
+                fragment["children"] = children;
+                return fragment;
+            }
+        };
+
+        let mut opening = self.node("JSXOpeningElement", None);
+        opening["name"] = self.jsx_name(name);
+        opening["attributes"] = JsonValue::Array(element.attributes.iter().map(|attribute| self.jsx_attribute(attribute)).collect());
+        opening["selfClosing"] = element.children.is_none().into();
+
+        let closing = match element.children {
+            Some(_) => {
+                let mut closing = self.node("JSXClosingElement", None);
+                closing["name"] = self.jsx_name(name);
+                closing
+            }
+            None => JsonValue::Null
+        };
+
+        let mut node = self.node("JSXElement", location);
+        node["openingElement"] = opening;
+        node["closingElement"] = closing;
+        node["children"] = children;
+        return node;
+    }
+
+    /// An element or attribute name, which may be namespaced (`a:b`) or a member expression (`a.b`).
+    fn jsx_name(&self, name: &str) -> JsonValue {
+        if let Some(index) = name.find(':') {
+            let mut namespaced = self.node("JSXNamespacedName", None);
+            namespaced["namespace"] = self.jsx_identifier(&name[..index]);
+            namespaced["name"] = self.jsx_identifier(&name[index + 1..]);
+            return namespaced;
+        }
+
+        let mut segments = name.split('.');
+        let mut object = self.jsx_identifier(segments.next().unwrap_or_default());
+        for segment in segments {
+            let mut member = self.node("JSXMemberExpression", None);
+            member["object"] = object;
+            member["property"] = self.jsx_identifier(segment);
+            object = member;
+        }
+        return object;
+    }
+
+    fn jsx_identifier(&self, name: &str) -> JsonValue {
+        let mut identifier = self.node("JSXIdentifier", None);
+        identifier["name"] = name.into();
+        return identifier;
+    }
+
+    fn jsx_attribute(&self, attribute: &JsxAttribute) -> JsonValue {
+        return match *attribute {
+            JsxAttribute::Named { ref name, ref value, .. } => {
+                let mut node = self.node("JSXAttribute", None);
+                node["name"] = self.jsx_name(name);
+                node["value"] = match *value {
+                    Some(JsxAttributeValue::String(ref value, ref quote)) => {
+                        let mut literal = self.node("Literal", None);
+                        literal["value"] = value.as_str().into();
+                        literal["raw"] = format!("{}{}{}", quote.to_string(), value, quote.to_string()).into();
+                        literal
+                    }
+                    Some(JsxAttributeValue::Container(ref container)) => self.jsx_container(container),
+                    Some(JsxAttributeValue::Element(ref element)) => self.expression(element),
+                    None => JsonValue::Null
+                };
+                node
+            }
+            JsxAttribute::Spread { ref argument, .. } => {
+                let mut node = self.node("JSXSpreadAttribute", None);
+                node["argument"] = self.expression(argument);
+                node
+            }
+        };
+    }
+
+    fn jsx_child(&self, child: &JsxChild) -> JsonValue {
+        return match *child {
+            JsxChild::Text(ref text) => {
+                let mut node = self.node("JSXText", None);
+                node["value"] = text.as_str().into();
+                node["raw"] = text.as_str().into();
+                node
+            }
+            JsxChild::Container(ref container) => self.jsx_container(container),
+            JsxChild::Spread { ref argument, .. } => {
+                let mut node = self.node("JSXSpreadChild", None);
+                node["expression"] = self.expression(argument);
+                node
+            }
+            JsxChild::Element(ref element) => self.expression(element),
+        };
+    }
+
+    fn jsx_container(&self, container: &JsxContainer) -> JsonValue {
+        let mut node = self.node("JSXExpressionContainer", None);
+        node["expression"] = match container.expression {
+            Some(ref expression) => self.expression(expression),
+            None => self.node("JSXEmptyExpression", None)
+        };
+        return node;
+    }
+}
+
+/// The location of a node, `None` when it was not parsed from the source.
+fn located(location: &SourceLocation) -> Option<&SourceLocation> {
+    return match *location == SourceLocation::default() {
+        true => None,
+        false => Some(location)
+    };
+}
+
+/// Where a name starting a located node is.
+fn leading(location: &SourceLocation, name: &str) -> SourceLocation {
+    return SourceLocation::new(location.start, location.start + name.len());
+}
+
+/// Where a name ending a located node is, such as the property of a member expression.
+fn trailing(location: &SourceLocation, name: &str) -> Option<SourceLocation> {
+    return match location.end.checked_sub(name.len()) {
+        Some(start) if start >= location.start => Some(SourceLocation::new(start, location.end)),
+        _ => None
+    };
+}
+
+fn is_computed(key: &PropertyKey) -> bool {
+    return match *key {
+        PropertyKey::Computed(_) => true,
+        _ => false
+    };
+}
+
+/// The value of a numeric literal, `null` for BigInts which JSON cannot hold.
+fn number_value(raw: &str) -> JsonValue {
+    let digits = raw.replace('_', "");
+    if digits.ends_with('n') {
+        return JsonValue::Null;
+    }
+
+    let lower = digits.to_lowercase();
+    let radix = match lower.get(..2) {
+        Some("0x") => Some(16),
+        Some("0o") => Some(8),
+        Some("0b") => Some(2),
+        _ if lower.len() > 1 && lower.starts_with('0') && lower.chars().all(|char| char >= '0' && char <= '7') => Some(8),
+        _ => None
+    };
+    let value = match radix {
+        Some(8) if !lower.starts_with("0o") => u64::from_str_radix(&lower[1..], 8).ok().map(|value| value as f64),
+        Some(radix) => u64::from_str_radix(&lower[2..], radix).ok().map(|value| value as f64),
+        None => lower.parse::<f64>().ok()
+    };
+    return match value {
+        Some(value) => value.into(),
+        None => JsonValue::Null
+    };
+}
+
+/// The value of a string literal from its source text without the quotes.
+fn unescape(raw: &str) -> String {
+    let mut units: Vec<u16> = Vec::new();
+    let mut chars = raw.chars().peekable();
+    while let Some(char) = chars.next() {
+        if char != '\\' {
+            let mut buffer = [0; 2];
+            units.extend_from_slice(char.encode_utf16(&mut buffer));
+            continue;
+        }
+
+        let escaped = match chars.next() {
+            Some(escaped) => escaped,
+            None => break
+        };
+        let unit = match escaped {
+            'n' => '\n' as u32,
+            'r' => '\r' as u32,
+            't' => '\t' as u32,
+            'b' => 0x08,
+            'f' => 0x0C,
+            'v' => 0x0B,
+            'x' => read_hex(&mut chars, 2),
+            'u' if chars.peek() == Some(&'{') => {
+                chars.next();
+                let mut code = 0;
+                while let Some(digit) = chars.next() {
+                    match digit.to_digit(16) {
+                        Some(digit) => code = code * 16 + digit,
+                        None => break
+                    }
+                }
+                let mut buffer = [0; 2];
+                units.extend_from_slice(::std::char::from_u32(code).unwrap_or('\u{FFFD}').encode_utf16(&mut buffer));
+                continue;
+            }
+            'u' => read_hex(&mut chars, 4),
+            '0'..='7' => {
+                let mut code = escaped.to_digit(8).unwrap_or(0);
+                let max_digits = if escaped <= '3' { 2 } else { 1 };
+                for _ in 0..max_digits {
+                    match chars.peek().and_then(|digit| digit.to_digit(8)) {
+                        Some(digit) => {
+                            code = code * 8 + digit;
+                            chars.next();
+                        }
+                        None => break
+                    }
+                }
+                code
+            }
+            // Line continuations add nothing to the value.
+            '\r' => {
+                if chars.peek() == Some(&'\n') {
+                    chars.next();
+                }
+                continue;
+            }
+            '\n' | '\u{2028}' | '\u{2029}' => continue,
+            other => {
+                let mut buffer = [0; 2];
+                units.extend_from_slice(other.encode_utf16(&mut buffer));
+                continue;
+            }
+        };
+        units.push(unit as u16);
+    }
+    return String::from_utf16_lossy(&units);
+}
+
+fn read_hex<I>(chars: &mut ::std::iter::Peekable<I>, count: usize) -> u32 where I: Iterator<Item = char> {
+    let mut code = 0;
+    for _ in 0..count {
+        match chars.peek().and_then(|digit| digit.to_digit(16)) {
+            Some(digit) => {
+                code = code * 16 + digit;
+                chars.next();
+            }
+            None => break
+        }
+    }
+    return code;
+}
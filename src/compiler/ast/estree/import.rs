@@ -0,0 +1,958 @@
+extern crate json;
+
+use self::json::JsonValue;
+
+use super::EstreeError;
+use super::super::{SyntaxTree, SourceLocation, NodeTrivia, Parameter, ParameterTrivia};
+use super::super::visit_mut::VisitMut;
+use super::super::body::BodyNode;
+use super::super::directive::DirectiveNode;
+use super::super::declaration::{DeclarationKind, DeclarationNode, DeclarationTrivia};
+use super::super::statement::{StatementNode, Statement, StatementTerminator, ImportDeclaration, ImportTrivia, ExportDeclaration, ExportTrivia, ModuleSpecifier, ModuleSpecifierTrivia, WithTrivia, BlockTrivia, ForTrivia};
+use super::super::expression::{ExpressionNode, Expression, FunctionTrivia, ArrowFunctionTrivia, OperatorTrivia, MemberTrivia, ComputedMemberTrivia, CallTrivia, ConditionalTrivia, ObjectTrivia, ArrayTrivia, ClassTrivia, BracketedTrivia};
+use super::super::property::{PropertyNode, PropertyKey, PropertyKind, PropertyTrivia};
+use super::super::jsx::{JsxElement, JsxTrivia, JsxAttribute, JsxAttributeTrivia, JsxAttributeValue, JsxChild, JsxContainer, JsxSpreadTrivia};
+use super::super::super::{Literal, QuoteKind, OperatorKind, Symbol};
+use super::super::super::parser::SourceType;
+use super::super::super::lexicon;
+
+use std::mem;
+
+/// Words which would not be read back as the name of a variable, function or parameter. The
+/// tokenizer reads `undefined` as a literal.
+const RESERVED_WORDS: &[&str] = &[
+    "break", "case", "catch", "class", "const", "continue", "debugger", "default", "delete", "do",
+    "else", "enum", "export", "extends", "false", "finally", "for", "function", "if", "import", "in",
+    "instanceof", "new", "null", "return", "super", "switch", "this", "throw", "true", "try",
+    "typeof", "var", "void", "while", "with", "undefined",
+];
+
+/// Reads an ESTree `Program` back into a syntax tree. Statements are put on lines of their own and
+/// tokens are separated by single spaces where the syntax needs them.
+pub fn from_estree(program: &JsonValue) -> Result<SyntaxTree, EstreeError> {
+    let mut tree = self::program(program)?;
+    Relocation::new(None).visit_syntax_tree(&mut tree);
+    return Ok(tree);
+}
+
+/// Reads an ESTree `Program` exported from `source` back into a syntax tree like `from_estree`, but
+/// keeps the `start` and `end` of its nodes as locations in that source. Nodes without them, such as
+/// ones a tool added, are left unlocated.
+pub fn from_estree_in(program: &JsonValue, source: &str) -> Result<SyntaxTree, EstreeError> {
+    let mut tree = self::program(program)?;
+    Relocation::new(Some(source)).visit_syntax_tree(&mut tree);
+    return Ok(tree);
+}
+
+/// Turns the UTF-16 offsets read from the JSON into byte offsets of the source, or drops them when
+/// there is no source.
+struct Relocation {
+    /// The byte offset of every UTF-16 offset of the source, and of its end.
+    byte_offsets: Option<Vec<usize>>,
+}
+
+impl Relocation {
+    fn new(source: Option<&str>) -> Self {
+        let byte_offsets = source.map(|source| {
+            let mut byte_offsets = Vec::with_capacity(source.len() + 1);
+            for (index, char) in source.char_indices() {
+                for _ in 0..char.len_utf16() {
+                    byte_offsets.push(index);
+                }
+            }
+            byte_offsets.push(source.len());
+            byte_offsets
+        });
+        return Relocation { byte_offsets };
+    }
+
+    fn relocate(&self, location: &mut SourceLocation) {
+        *location = match self.byte_offsets {
+            Some(ref byte_offsets) => {
+                let byte_offset = |offset: usize| byte_offsets[offset.min(byte_offsets.len() - 1)];
+                SourceLocation::new(byte_offset(location.start), byte_offset(location.end))
+            }
+            None => SourceLocation::default()
+        };
+    }
+}
+
+impl VisitMut for Relocation {
+    fn enter_body(&mut self, body: &mut BodyNode) {
+        self.relocate(&mut body.location);
+    }
+
+    fn enter_directive(&mut self, directive: &mut DirectiveNode) {
+        self.relocate(&mut directive.location);
+    }
+
+    fn enter_statement(&mut self, statement: &mut StatementNode) {
+        self.relocate(&mut statement.location);
+    }
+
+    fn enter_declaration(&mut self, declaration: &mut DeclarationNode) {
+        self.relocate(&mut declaration.location);
+    }
+
+    fn enter_module_specifier(&mut self, specifier: &mut ModuleSpecifier) {
+        self.relocate(&mut specifier.location);
+    }
+
+    fn enter_expression(&mut self, expression: &mut ExpressionNode) {
+        self.relocate(&mut expression.location);
+    }
+
+    fn enter_property(&mut self, property: &mut PropertyNode) {
+        self.relocate(&mut property.location);
+        self.relocate(&mut property.key_location);
+    }
+}
+
+fn program(program: &JsonValue) -> Result<SyntaxTree, EstreeError> {
+    if node_type(program) != "Program" {
+        return Err(EstreeError::InvalidNode(String::from("a Program")));
+    }
+
+    let mut body = body(&program["body"])?;
+    body.location = location(program);
+    let hashbang = program["hashbang"].as_str().map(|hashbang| format!("#!{}", hashbang));
+    if hashbang.is_none() {
+        match body.directives.first_mut() {
+            Some(directive) => directive.trivia.prefix = String::new(),
+            None => if let Some(statement) = body.content.first_mut() {
+                statement.trivia.prefix = String::new();
+            }
+        }
+    }
+
+    let mut tree = SyntaxTree::new(body);
+    tree.hashbang = hashbang;
+    tree.suffix = String::from("\n");
+    tree.source_type = program["sourceType"].as_str().and_then(SourceType::from_name).unwrap_or(SourceType::Module);
+    return Ok(tree);
+}
+
+fn node_type(node: &JsonValue) -> &str {
+    return node["type"].as_str().unwrap_or("");
+}
+
+/// The `start` and `end` of a node as they are in the JSON, in UTF-16 code units.
+fn location(node: &JsonValue) -> SourceLocation {
+    return match (node["start"].as_usize(), node["end"].as_usize()) {
+        (Some(start), Some(end)) if start <= end => SourceLocation::new(start, end),
+        _ => SourceLocation::default()
+    };
+}
+
+fn unsupported(node: &JsonValue, expected: &str) -> EstreeError {
+    return match node_type(node) {
+        "" => EstreeError::InvalidNode(String::from(expected)),
+        kind => EstreeError::Unsupported(String::from(kind))
+    };
+}
+
+fn spaced(mut expression: ExpressionNode) -> ExpressionNode {
+    expression.trivia.prefix = String::from(" ");
+    return expression;
+}
+
+fn on_new_line(statement: Statement, terminator: StatementTerminator) -> StatementNode {
+    let mut trivia = NodeTrivia::new();
+    trivia.prefix = String::from("\n");
+    return StatementNode::new(statement, trivia, terminator);
+}
+
+/// The padding before the closing brace of a body, which goes on a line of its own unless the body is empty.
+fn closing_suffix(is_empty: bool) -> String {
+    return match is_empty {
+        true => String::new(),
+        false => String::from("\n")
+    };
+}
+
+/// The name of an identifier, which may be a reserved word where it names a property.
+fn identifier_name(node: &JsonValue) -> Result<Symbol, EstreeError> {
+    return match (node_type(node), node["name"].as_str()) {
+        ("Identifier", Some(name)) if lexicon::is_identifier(name) => Ok(Symbol::from(name)),
+        ("Identifier", Some(name)) => Err(EstreeError::InvalidIdentifier(name.to_owned())),
+        _ => Err(unsupported(node, "an Identifier"))
+    };
+}
+
+/// The name of an identifier which refers to a variable, so cannot be a reserved word.
+fn binding_name(node: &JsonValue) -> Result<Symbol, EstreeError> {
+    let name = identifier_name(node)?;
+    if RESERVED_WORDS.contains(&name.as_str()) {
+        return Err(EstreeError::InvalidIdentifier(name.to_string()));
+    }
+    return Ok(name);
+}
+
+/// The name of a function or class, empty when it is anonymous.
+fn optional_name(node: &JsonValue) -> Result<Symbol, EstreeError> {
+    return match node.is_null() {
+        true => Ok(Symbol::new("")),
+        false => binding_name(node)
+    };
+}
+
+fn body(statements: &JsonValue) -> Result<BodyNode, EstreeError> {
+    let mut directives = Vec::new();
+    let mut content = Vec::new();
+    for statement in statements.members() {
+        if let (true, Some(value)) = (content.is_empty(), statement["directive"].as_str()) {
+            let mut directive = DirectiveNode::new(value);
+            if let Some(raw) = statement["expression"]["raw"].as_str() {
+                let (value, quote_kind) = unquote(raw, value);
+                directive.value = value;
+                directive.quote_kind = quote_kind;
+            }
+            directive.trivia.prefix = String::from("\n");
+            directive.location = location(statement);
+            directives.push(directive);
+            continue;
+        }
+        content.push(self::statement(statement)?);
+    }
+    return Ok(BodyNode::with_directives(directives, content));
+}
+
+fn statement(node: &JsonValue) -> Result<StatementNode, EstreeError> {
+    use self::StatementTerminator::*;
+
+    let (statement, terminator) = match node_type(node) {
+        "EmptyStatement" => (Statement::Empty, Semicolon),
+        "ExpressionStatement" => {
+            let mut expression = expression(&node["expression"])?;
+            // Statements starting with these would be read as declarations or blocks.
+            match expression.expression {
+                Expression::Function { .. } | Expression::Class { .. } | Expression::Object { .. } => {
+                    expression = ExpressionNode::new(Expression::Bracketed { expression: Box::new(expression), trivia: BracketedTrivia::new() }, NodeTrivia::new());
+                }
+                _ => {}
+            }
+            (Statement::Expression { expression }, Semicolon)
+        }
+        "FunctionDeclaration" | "ClassDeclaration" => (Statement::Expression { expression: self::expression(node)? }, Block),
+        "VariableDeclaration" => (variable_declaration(node)?, Semicolon),
+        "ReturnStatement" => {
+            let expression = match node["argument"].is_null() {
+                true => None,
+                false => Some(spaced(self::expression(&node["argument"])?))
+            };
+            (Statement::Return { expression }, Semicolon)
+        }
+        "ImportDeclaration" => (import(node)?, Semicolon),
+        "ExportDefaultDeclaration" => {
+            let declaration = &node["declaration"];
+            let terminator = match node_type(declaration) {
+                "FunctionDeclaration" | "ClassDeclaration" => Block,
+                _ => Semicolon
+            };
+            let mut trivia = ExportTrivia::new();
+            trivia.declaration_prefix = String::from(" ");
+            (Statement::Export { declaration: ExportDeclaration::Default(spaced(expression(declaration)?)), trivia }, terminator)
+        }
+        "ExportNamedDeclaration" => {
+            if !node["source"].is_null() {
+                return Err(EstreeError::Unsupported(String::from("Re-exporting from another module")));
+            }
+            let mut trivia = ExportTrivia::new();
+            if !node["declaration"].is_null() {
+                let mut declaration = self::statement(&node["declaration"])?;
+                declaration.trivia.prefix = String::from(" ");
+                (Statement::Export { declaration: ExportDeclaration::Statement(Box::new(declaration)), trivia }, Implicit)
+            } else {
+                let specifiers = module_specifiers(&node["specifiers"], "local", "exported")?;
+                trivia.declaration_prefix = String::from(" ");
+                trivia.specifiers_suffix = padding(specifiers.is_empty());
+                (Statement::Export { declaration: ExportDeclaration::Named(specifiers), trivia }, Semicolon)
+            }
+        }
+        "WithStatement" => {
+            let mut body = self::statement(&node["body"])?;
+            body.trivia.prefix = String::from(" ");
+            let mut trivia = WithTrivia::new();
+            trivia.object_gap = String::from(" ");
+            (Statement::With { object: expression(&node["object"])?, body: Box::new(body), trivia }, Implicit)
+        }
+        "BlockStatement" => {
+            let mut body = self::body(&node["body"])?;
+            body.location = location(node);
+            let body_suffix = closing_suffix(body.content.is_empty());
+            (Statement::Block { body, trivia: BlockTrivia { body_suffix } }, Block)
+        }
+        "ForStatement" => {
+            let init = match node["init"].is_null() {
+                true => None,
+                false => Some(Box::new(for_head(&node["init"])?))
+            };
+            let test = match node["test"].is_null() {
+                true => None,
+                false => Some(spaced(expression(&node["test"])?))
+            };
+            let update = match node["update"].is_null() {
+                true => None,
+                false => Some(spaced(expression(&node["update"])?))
+            };
+            (Statement::For { init, test, update, body: Box::new(loop_body(node)?), trivia: for_trivia(false) }, Implicit)
+        }
+        "ForInStatement" => {
+            let left = Box::new(for_head(&node["left"])?);
+            let right = spaced(expression(&node["right"])?);
+            (Statement::ForIn { left, right, body: Box::new(loop_body(node)?), trivia: for_trivia(false) }, Implicit)
+        }
+        "ForOfStatement" => {
+            let is_await = node["await"].as_bool().unwrap_or(false);
+            let left = Box::new(for_head(&node["left"])?);
+            let right = spaced(expression(&node["right"])?);
+            (Statement::ForOf { left, right, body: Box::new(loop_body(node)?), is_await, trivia: for_trivia(is_await) }, Implicit)
+        }
+        _ => return Err(unsupported(node, "a statement"))
+    };
+    let mut statement = on_new_line(statement, terminator);
+    statement.location = location(node);
+    return Ok(statement);
+}
+
+fn padding(is_empty: bool) -> String {
+    return match is_empty {
+        true => String::new(),
+        false => String::from(" ")
+    };
+}
+
+fn for_trivia(is_await: bool) -> ForTrivia {
+    let mut trivia = ForTrivia::new();
+    trivia.head_gap = String::from(" ");
+    trivia.operator_prefix = String::from(" ");
+    if is_await {
+        trivia.await_prefix = String::from(" ");
+    }
+    return trivia;
+}
+
+fn loop_body(node: &JsonValue) -> Result<StatementNode, EstreeError> {
+    let mut body = statement(&node["body"])?;
+    body.trivia.prefix = String::from(" ");
+    return Ok(body);
+}
+
+/// The initialiser or left side of a loop head, a declaration or a bare expression.
+fn for_head(node: &JsonValue) -> Result<StatementNode, EstreeError> {
+    let statement = match node_type(node) {
+        "VariableDeclaration" => variable_declaration(node)?,
+        _ => Statement::Expression { expression: expression(node)? }
+    };
+    let mut statement = StatementNode::new(statement, NodeTrivia::new(), StatementTerminator::Implicit);
+    statement.location = location(node);
+    return Ok(statement);
+}
+
+fn variable_declaration(node: &JsonValue) -> Result<Statement, EstreeError> {
+    let kind = match node["kind"].as_str() {
+        Some("var") => DeclarationKind::Var,
+        Some("let") => DeclarationKind::Let,
+        Some("const") => DeclarationKind::Const,
+        _ => return Err(EstreeError::InvalidNode(String::from("a var, let or const VariableDeclaration")))
+    };
+
+    let mut declarations = Vec::new();
+    for declarator in node["declarations"].members() {
+        let name = binding_name(&declarator["id"])?;
+        let expression = match declarator["init"].is_null() {
+            true => None,
+            false => Some(spaced(expression(&declarator["init"])?))
+        };
+        let mut trivia = DeclarationTrivia::new();
+        trivia.prefix = String::from(" ");
+        if expression.is_some() {
+            trivia.assign_prefix = String::from(" ");
+        }
+        let mut declaration = DeclarationNode::new(name, expression, trivia);
+        declaration.location = location(declarator);
+        declarations.push(declaration);
+    }
+    if declarations.is_empty() {
+        return Err(EstreeError::InvalidNode(String::from("a VariableDeclaration with declarators")));
+    }
+    return Ok(Statement::Declaration { kind, declarations });
+}
+
+fn import(node: &JsonValue) -> Result<Statement, EstreeError> {
+    let mut default = None;
+    let mut namespace = None;
+    let mut named = Vec::new();
+    for specifier in node["specifiers"].members() {
+        match node_type(specifier) {
+            "ImportDefaultSpecifier" => default = Some(binding_name(&specifier["local"])?),
+            "ImportNamespaceSpecifier" => namespace = Some(binding_name(&specifier["local"])?),
+            "ImportSpecifier" => named.push(module_specifier(specifier, "imported", "local")?),
+            _ => return Err(unsupported(specifier, "an import specifier"))
+        }
+    }
+
+    let mut trivia = ImportTrivia::new();
+    trivia.declaration_prefix = String::from(" ");
+    trivia.from_prefix = String::from(" ");
+    trivia.source_prefix = String::from(" ");
+
+    let (alias, declaration) = match (default, namespace, named.is_empty()) {
+        (Some(name), None, true) => (None, ImportDeclaration::Single(name)),
+        (None, Some(name), true) => {
+            trivia.as_prefix = String::from(" ");
+            trivia.alias_prefix = String::from(" ");
+            (Some(name), ImportDeclaration::All)
+        }
+        (None, None, _) => {
+            trivia.specifiers_suffix = padding(named.is_empty());
+            (None, ImportDeclaration::Multiple(named))
+        }
+        _ => return Err(EstreeError::Unsupported(String::from("Combining default, namespace and named imports")))
+    };
+
+    let (source, quote_kind) = match (node["source"]["value"].as_str(), node["source"]["raw"].as_str()) {
+        (Some(value), Some(raw)) => unquote(raw, value),
+        (Some(value), None) => (escape(value), QuoteKind::Apostrophe),
+        _ => return Err(EstreeError::InvalidNode(String::from("a string Literal")))
+    };
+    trivia.quote_kind = quote_kind;
+    return Ok(Statement::Import { alias, declaration, source, trivia });
+}
+
+fn module_specifiers(specifiers: &JsonValue, name_key: &str, alias_key: &str) -> Result<Vec<ModuleSpecifier>, EstreeError> {
+    return specifiers.members().map(|specifier| module_specifier(specifier, name_key, alias_key)).collect();
+}
+
+/// A specifier within braces, given which of its identifiers is the name and which the alias.
+fn module_specifier(specifier: &JsonValue, name_key: &str, alias_key: &str) -> Result<ModuleSpecifier, EstreeError> {
+    let name = identifier_name(&specifier[name_key])?;
+    let alias = match specifier[alias_key].is_null() {
+        true => None,
+        false => Some(identifier_name(&specifier[alias_key])?)
+    };
+
+    let mut trivia = ModuleSpecifierTrivia::new();
+    trivia.prefix = String::from(" ");
+    let alias = match alias {
+        Some(ref alias) if *alias == name => None,
+        Some(alias) => {
+            trivia.as_prefix = String::from(" ");
+            trivia.alias_prefix = String::from(" ");
+            Some(alias)
+        }
+        None => None
+    };
+    return Ok(ModuleSpecifier { name, alias, location: location(specifier), trivia });
+}
+
+fn expression(node: &JsonValue) -> Result<ExpressionNode, EstreeError> {
+    let expression = match node_type(node) {
+        "ThisExpression" => Expression::This,
+        "Super" => Expression::Super,
+        "ParenthesizedExpression" => Expression::Bracketed { expression: Box::new(expression(&node["expression"])?), trivia: BracketedTrivia::new() },
+        "FunctionExpression" | "FunctionDeclaration" => function(node)?,
+        "ArrowFunctionExpression" => {
+            if node_type(&node["body"]) != "BlockStatement" {
+                return Err(EstreeError::Unsupported(String::from("Arrow functions without a block body")));
+            }
+            let mut trivia = ArrowFunctionTrivia::default();
+            trivia.arrow_gap = String::from(" ");
+            trivia.body_gap = String::from(" ");
+            let body = function_body(&node["body"])?;
+            trivia.body_suffix = closing_suffix(body.content.is_empty());
+            Expression::ArrowFunction { parameters: parameters(&node["params"])?, body, trivia }
+        }
+        "CallExpression" => {
+            if node["optional"].as_bool() == Some(true) {
+                return Err(EstreeError::Unsupported(String::from("Optional chaining")));
+            }
+            let callee = operand(expression(&node["callee"])?, OperatorKind::Accessor.precedence());
+            Expression::Call { callee: Box::new(callee), parameters: arguments(&node["arguments"])?, trivia: CallTrivia::new() }
+        }
+        "NewExpression" => {
+            // The arguments of a call in the callee would be taken as those of `new`.
+            let callee = match expression(&node["callee"])? {
+                callee @ ExpressionNode { expression: Expression::Call { .. }, .. } => bracketed(callee),
+                callee => operand(callee, OperatorKind::Accessor.precedence())
+            };
+            Expression::New { callee: Box::new(spaced(callee)), parameters: Some(arguments(&node["arguments"])?), trivia: CallTrivia::new() }
+        }
+        "MemberExpression" => {
+            if node["optional"].as_bool() == Some(true) {
+                return Err(EstreeError::Unsupported(String::from("Optional chaining")));
+            }
+            let object = match expression(&node["object"])? {
+                // The `.` after an integer would be read as its decimal point.
+                object @ ExpressionNode { expression: Expression::Literal(Literal::Number(_)), .. } => bracketed(object),
+                object => operand(object, OperatorKind::Accessor.precedence())
+            };
+            let object = Box::new(object);
+            match node["computed"].as_bool().unwrap_or(false) {
+                true => {
+                    let trivia = ComputedMemberTrivia { open_prefix: String::new(), close_prefix: String::new() };
+                    Expression::ComputedMember { object, property: Box::new(expression(&node["property"])?), trivia }
+                }
+                false => {
+                    let trivia = MemberTrivia { accessor_prefix: String::new(), property_prefix: String::new() };
+                    Expression::Member { object, property: identifier_name(&node["property"])?, trivia }
+                }
+            }
+        }
+        "SpreadElement" => Expression::Unary { operator: OperatorKind::Spread, argument: Box::new(expression(&node["argument"])?) },
+        "UnaryExpression" => {
+            let operator = operator(node)?;
+            let mut argument = operand(expression(&node["argument"])?, OperatorKind::LogicalNot.precedence());
+            // `typeof x` and `- -x` need a space to be read back the same way.
+            let needs_space = operator.is_word() || match argument.expression {
+                Expression::Unary { .. } | Expression::Update { prefix: true, .. } => true,
+                _ => false
+            };
+            if needs_space {
+                argument = spaced(argument);
+            }
+            Expression::Unary { operator, argument: Box::new(argument) }
+        }
+        "UpdateExpression" => {
+            let prefix = node["prefix"].as_bool().unwrap_or(false);
+            Expression::Update { operator: operator(node)?, argument: Box::new(expression(&node["argument"])?), prefix, trivia: OperatorTrivia::new() }
+        }
+        "BinaryExpression" | "LogicalExpression" => {
+            let (left, operator, right, trivia) = operation(node)?;
+            Expression::Binary { left, operator, right, trivia }
+        }
+        "AssignmentExpression" => {
+            let (left, operator, right, trivia) = operation(node)?;
+            Expression::Assignment { left, operator, right, trivia }
+        }
+        "AwaitExpression" => {
+            let argument = operand(expression(&node["argument"])?, OperatorKind::LogicalNot.precedence());
+            Expression::Await { argument: Box::new(spaced(argument)) }
+        }
+        "YieldExpression" => {
+            let argument = match node["argument"].is_null() {
+                true => None,
+                false => Some(Box::new(spaced(expression(&node["argument"])?)))
+            };
+            Expression::Yield { argument, delegate: node["delegate"].as_bool().unwrap_or(false), trivia: OperatorTrivia::new() }
+        }
+        "ObjectExpression" => {
+            let properties = node["properties"].members().map(property).collect::<Result<Vec<_>, _>>()?;
+            let mut trivia = ObjectTrivia::new();
+            trivia.properties_suffix = padding(properties.is_empty());
+            Expression::Object { properties, trivia }
+        }
+        "ArrayExpression" => {
+            let mut elements = Vec::new();
+            for (index, element) in node["elements"].members().enumerate() {
+                elements.push(match (element.is_null(), index) {
+                    (true, _) => None,
+                    (false, 0) => Some(expression(element)?),
+                    (false, _) => Some(spaced(expression(element)?))
+                });
+            }
+            Expression::Array { elements, trivia: ArrayTrivia::new() }
+        }
+        "ClassExpression" | "ClassDeclaration" => class(node)?,
+        "ConditionalExpression" => {
+            let trivia = ConditionalTrivia { question_prefix: String::from(" "), colon_prefix: String::from(" ") };
+            Expression::Conditional {
+                test: Box::new(operand(expression(&node["test"])?, OperatorKind::Conditional.precedence() + 1)),
+                consequent: Box::new(spaced(expression(&node["consequent"])?)),
+                alternate: Box::new(spaced(expression(&node["alternate"])?)),
+                trivia
+            }
+        }
+        "JSXElement" | "JSXFragment" => Expression::Jsx(Box::new(jsx_element(node)?)),
+        // The tokenizer reads `undefined` as a literal.
+        "Identifier" if node["name"].as_str() == Some("undefined") => Expression::Literal(Literal::Undefined),
+        "Identifier" => Expression::Identifier(binding_name(node)?),
+        "Literal" => Expression::Literal(literal(node)?),
+        _ => return Err(unsupported(node, "an expression"))
+    };
+    let mut expression = ExpressionNode::new(expression, NodeTrivia::new());
+    expression.location = location(node);
+    return Ok(expression);
+}
+
+fn arguments(nodes: &JsonValue) -> Result<Vec<ExpressionNode>, EstreeError> {
+    let mut arguments = Vec::new();
+    for (index, node) in nodes.members().enumerate() {
+        let argument = expression(node)?;
+        arguments.push(match index {
+            0 => argument,
+            _ => spaced(argument)
+        });
+    }
+    return Ok(arguments);
+}
+
+fn operation(node: &JsonValue) -> Result<(Box<ExpressionNode>, OperatorKind, Box<ExpressionNode>, OperatorTrivia), EstreeError> {
+    let mut trivia = OperatorTrivia::new();
+    trivia.operator_prefix = String::from(" ");
+    let operator = operator(node)?;
+    let mut left = expression(&node["left"])?;
+    let mut right = expression(&node["right"])?;
+    if !operator.is_assignment() {
+        let precedence = operator.precedence();
+        // The side an operator groups towards may hold the same operator, the other side only
+        // tighter ones. `-a ** b` is not allowed, so the left of `**` cannot be unary either.
+        let (left_precedence, right_precedence) = match operator {
+            OperatorKind::Exponent => (OperatorKind::LogicalNot.precedence() + 1, precedence),
+            _ => (precedence, precedence + 1)
+        };
+        left = operand(left, left_precedence);
+        right = operand(right, right_precedence);
+    }
+    return Ok((Box::new(left), operator, Box::new(spaced(right)), trivia));
+}
+
+/// How tightly an expression holds together, on the scale of `OperatorKind::precedence`.
+/// Expressions which need no brackets anywhere bind tighter than any operator.
+fn binding_power(expression: &Expression) -> u8 {
+    return match *expression {
+        Expression::Assignment { .. } | Expression::ArrowFunction { .. } | Expression::Yield { .. } => OperatorKind::Assign.precedence(),
+        Expression::Unary { operator: OperatorKind::Spread, .. } => OperatorKind::Spread.precedence(),
+        Expression::Conditional { .. } => OperatorKind::Conditional.precedence(),
+        Expression::Binary { ref operator, .. } => operator.precedence(),
+        Expression::Unary { .. } | Expression::Await { .. } => OperatorKind::LogicalNot.precedence(),
+        Expression::Update { .. } => OperatorKind::Increment.precedence(),
+        Expression::New { parameters: None, .. } => OperatorKind::New.precedence(),
+        Expression::New { .. } | Expression::Call { .. } | Expression::Member { .. } | Expression::ComputedMember { .. } => OperatorKind::Accessor.precedence(),
+        _ => OperatorKind::Accessor.precedence() + 1
+    };
+}
+
+/// An operand of an operator of the given precedence, in brackets when it binds less tightly. ESTree
+/// only keeps brackets as `ParenthesizedExpression` when a tool preserves them.
+fn operand(expression: ExpressionNode, precedence: u8) -> ExpressionNode {
+    return match binding_power(&expression.expression) < precedence {
+        true => bracketed(expression),
+        false => expression
+    };
+}
+
+fn bracketed(mut expression: ExpressionNode) -> ExpressionNode {
+    let mut trivia = NodeTrivia::new();
+    trivia.prefix = mem::replace(&mut expression.trivia.prefix, String::new());
+    return ExpressionNode::new(Expression::Bracketed { expression: Box::new(expression), trivia: BracketedTrivia::new() }, trivia);
+}
+
+fn operator(node: &JsonValue) -> Result<OperatorKind, EstreeError> {
+    use self::OperatorKind::*;
+
+    let symbol = node["operator"].as_str().unwrap_or("");
+    return Ok(match symbol {
+        "++" => Increment,
+        "--" => Decrement,
+        "!" => LogicalNot,
+        "~" => BitwiseNot,
+        "typeof" => Typeof,
+        "void" => Void,
+        "delete" => Delete,
+        "*" => Multiplication,
+        "/" => Division,
+        "%" => Remainder,
+        "**" => Exponent,
+        "+" => Addition,
+        "-" => Subtraction,
+        "<<" => BitShiftLeft,
+        ">>" => BitShiftRight,
+        ">>>" => UBitShiftRight,
+        "<" => Lesser,
+        "<=" => LesserEquals,
+        ">" => Greater,
+        ">=" => GreaterEquals,
+        "instanceof" => InstanceOf,
+        "in" => In,
+        "===" => StrictEquality,
+        "!==" => StrictInequality,
+        "==" => Equality,
+        "!=" => Inequality,
+        "&" => BitwiseAnd,
+        "^" => BitwiseXor,
+        "|" => BitwiseOr,
+        "&&" => LogicalAnd,
+        "||" => LogicalOr,
+        "=" => Assign,
+        "+=" => AddAssign,
+        "-=" => SubtractAssign,
+        "**=" => ExponentAssign,
+        "*=" => MultiplyAssign,
+        "/=" => DivideAssign,
+        "%=" => RemainderAssign,
+        "<<=" => BSLAssign,
+        ">>=" => BSRAssign,
+        ">>>=" => UBSRAssign,
+        "&=" => BitAndAssign,
+        "^=" => BitXorAssign,
+        "|=" => BitOrAssign,
+        _ => return Err(EstreeError::Unsupported(format!("The '{}' operator", symbol)))
+    });
+}
+
+fn function(node: &JsonValue) -> Result<Expression, EstreeError> {
+    let name = optional_name(&node["id"])?;
+    let body = function_body(&node["body"])?;
+
+    let mut trivia = FunctionTrivia::default();
+    trivia.async_gap = String::from(" ");
+    trivia.identifier_gap = padding(name.is_empty());
+    trivia.body_gap = String::from(" ");
+    trivia.body_suffix = closing_suffix(body.content.is_empty());
+    return Ok(Expression::Function {
+        name,
+        is_async: node["async"].as_bool().unwrap_or(false),
+        is_generator: node["generator"].as_bool().unwrap_or(false),
+        parameters: parameters(&node["params"])?,
+        body,
+        trivia
+    });
+}
+
+fn function_body(node: &JsonValue) -> Result<BodyNode, EstreeError> {
+    if node_type(node) != "BlockStatement" {
+        return Err(EstreeError::InvalidNode(String::from("a BlockStatement")));
+    }
+    let mut body = body(&node["body"])?;
+    body.location = location(node);
+    return Ok(body);
+}
+
+fn parameters(nodes: &JsonValue) -> Result<Vec<Parameter>, EstreeError> {
+    let mut parameters = Vec::new();
+    for node in nodes.members() {
+        let (name, default) = match node_type(node) {
+            "Identifier" => (binding_name(node)?, None),
+            "AssignmentPattern" if node_type(&node["right"]) == "Literal" => (binding_name(&node["left"])?, Some(literal(&node["right"])?)),
+            _ => return Err(EstreeError::Unsupported(String::from("Parameters other than identifiers with literal defaults")))
+        };
+        let prefix = match parameters.is_empty() {
+            true => String::new(),
+            false => String::from(" ")
+        };
+        let trivia = ParameterTrivia { prefix, suffix: String::new() };
+        parameters.push(Parameter { name, default, location: location(node), trivia });
+    }
+    return Ok(parameters);
+}
+
+fn class(node: &JsonValue) -> Result<Expression, EstreeError> {
+    let name = optional_name(&node["id"])?;
+    let super_class = match node["superClass"].is_null() {
+        true => None,
+        false => Some(Box::new(spaced(expression(&node["superClass"])?)))
+    };
+    let members = node["body"]["body"].members().map(class_member).collect::<Result<Vec<_>, _>>()?;
+
+    let mut trivia = ClassTrivia::new();
+    trivia.identifier_gap = padding(name.is_empty());
+    trivia.extends_prefix = String::from(" ");
+    trivia.body_gap = String::from(" ");
+    trivia.body_suffix = closing_suffix(members.is_empty());
+    return Ok(Expression::Class { name, super_class, members, trivia });
+}
+
+fn property(node: &JsonValue) -> Result<PropertyNode, EstreeError> {
+    if node_type(node) != "Property" {
+        return Err(unsupported(node, "a Property"));
+    }
+
+    let is_method = node["method"].as_bool().unwrap_or(false);
+    let kind = match node["kind"].as_str() {
+        Some("get") => PropertyKind::Get,
+        Some("set") => PropertyKind::Set,
+        _ if is_method => PropertyKind::Method,
+        _ if node["shorthand"].as_bool().unwrap_or(false) => PropertyKind::Shorthand,
+        _ => PropertyKind::Init
+    };
+    let value = match kind {
+        PropertyKind::Shorthand => None,
+        PropertyKind::Init => Some(spaced(expression(&node["value"])?)),
+        _ => Some(expression(&node["value"])?)
+    };
+
+    let mut property = member(node, kind, value, false)?;
+    property.trivia.prefix = String::from(" ");
+    return Ok(property);
+}
+
+fn class_member(node: &JsonValue) -> Result<PropertyNode, EstreeError> {
+    let is_static = node["static"].as_bool().unwrap_or(false);
+    let mut member = match node_type(node) {
+        "MethodDefinition" => {
+            let kind = match node["kind"].as_str() {
+                Some("get") => PropertyKind::Get,
+                Some("set") => PropertyKind::Set,
+                _ => PropertyKind::Method
+            };
+            self::member(node, kind, Some(expression(&node["value"])?), is_static)?
+        }
+        "PropertyDefinition" => {
+            let value = match node["value"].is_null() {
+                true => None,
+                false => Some(spaced(expression(&node["value"])?))
+            };
+            let mut member = self::member(node, PropertyKind::Field, value, is_static)?;
+            member.trivia.value_prefix = String::from(" ");
+            member.trivia.suffix = String::from(";");
+            member
+        }
+        _ => return Err(unsupported(node, "a class member"))
+    };
+    member.trivia.prefix = String::from("\n");
+    return Ok(member);
+}
+
+fn member(node: &JsonValue, kind: PropertyKind, value: Option<ExpressionNode>, is_static: bool) -> Result<PropertyNode, EstreeError> {
+    let key = match node["computed"].as_bool().unwrap_or(false) {
+        true => PropertyKey::Computed(Box::new(expression(&node["key"])?)),
+        false => match node_type(&node["key"]) {
+            "Identifier" => PropertyKey::Identifier(identifier_name(&node["key"])?),
+            "Literal" => PropertyKey::Literal(literal(&node["key"])?),
+            _ => return Err(unsupported(&node["key"], "a property key"))
+        }
+    };
+
+    let mut trivia = PropertyTrivia::new();
+    trivia.static_gap = String::from(" ");
+    trivia.async_gap = String::from(" ");
+    trivia.accessor_gap = String::from(" ");
+    return Ok(PropertyNode { key, value, kind, is_static, location: location(node), key_location: location(&node["key"]), trivia });
+}
+
+fn literal(node: &JsonValue) -> Result<Literal, EstreeError> {
+    let value = &node["value"];
+    let raw = node["raw"].as_str();
+
+    if let Some(string) = value.as_str() {
+        return Ok(match raw {
+            Some(raw) => {
+                let (value, quote_kind) = unquote(raw, string);
+                Literal::String(value, quote_kind)
+            }
+            None => Literal::String(escape(string), QuoteKind::Apostrophe)
+        });
+    }
+    if let Some(boolean) = value.as_bool() {
+        return Ok(Literal::Boolean(boolean));
+    }
+    if value.is_number() {
+        return Ok(Literal::Number(raw.map_or_else(|| value.dump(), |raw| raw.to_owned())));
+    }
+    if let Some(bigint) = node["bigint"].as_str() {
+        return Ok(Literal::Number(raw.map_or_else(|| format!("{}n", bigint), |raw| raw.to_owned())));
+    }
+    if !node["regex"].is_null() {
+        return Err(EstreeError::Unsupported(String::from("Regular expression literals")));
+    }
+    return match value.is_null() {
+        true => Ok(Literal::Null),
+        false => Err(EstreeError::InvalidNode(String::from("a Literal")))
+    };
+}
+
+/// Splits the source text of a string literal into its contents and quotes, falling back to the
+/// value when the text is not quoted.
+fn unquote(raw: &str, value: &str) -> (String, QuoteKind) {
+    let quote_kind = match raw.chars().next() {
+        Some('"') => QuoteKind::SpeechMark,
+        Some('\'') => QuoteKind::Apostrophe,
+        _ => return (escape(value), QuoteKind::Apostrophe)
+    };
+    if raw.len() < 2 || !raw.ends_with(&quote_kind.to_string()) {
+        return (escape(value), QuoteKind::Apostrophe);
+    }
+    return (raw[1..raw.len() - 1].to_owned(), quote_kind);
+}
+
+/// The contents of an apostrophe quoted string literal with the given value.
+fn escape(value: &str) -> String {
+    let mut string = String::new();
+    for char in value.chars() {
+        match char {
+            '\\' => string += "\\\\",
+            '\'' => string += "\\'",
+            '\n' => string += "\\n",
+            '\r' => string += "\\r",
+            '\u{2028}' => string += "\\u2028",
+            '\u{2029}' => string += "\\u2029",
+            char => string.push(char)
+        }
+    }
+    return string;
+}
+
+fn jsx_element(node: &JsonValue) -> Result<JsxElement, EstreeError> {
+    let children = node["children"].members().map(jsx_child).collect::<Result<Vec<_>, _>>()?;
+    if node_type(node) == "JSXFragment" {
+        return Ok(JsxElement { name: None, attributes: Vec::new(), children: Some(children), trivia: JsxTrivia::new() });
+    }
+
+    let opening = &node["openingElement"];
+    let name = jsx_name(&opening["name"])?;
+    let attributes = opening["attributes"].members().map(jsx_attribute).collect::<Result<Vec<_>, _>>()?;
+    let children = match opening["selfClosing"].as_bool().unwrap_or(false) {
+        true => None,
+        false => Some(children)
+    };
+    return Ok(JsxElement { name: Some(name), attributes, children, trivia: JsxTrivia::new() });
+}
+
+fn jsx_name(node: &JsonValue) -> Result<String, EstreeError> {
+    return match node_type(node) {
+        "JSXIdentifier" => match node["name"].as_str() {
+            Some(name) => Ok(name.to_owned()),
+            None => Err(EstreeError::InvalidNode(String::from("a named JSXIdentifier")))
+        },
+        "JSXNamespacedName" => Ok(format!("{}:{}", jsx_name(&node["namespace"])?, jsx_name(&node["name"])?)),
+        "JSXMemberExpression" => Ok(format!("{}.{}", jsx_name(&node["object"])?, jsx_name(&node["property"])?)),
+        _ => Err(unsupported(node, "a JSX name"))
+    };
+}
+
+fn jsx_attribute(node: &JsonValue) -> Result<JsxAttribute, EstreeError> {
+    return match node_type(node) {
+        "JSXAttribute" => {
+            let value = &node["value"];
+            let value = match node_type(value) {
+                _ if value.is_null() => None,
+                "Literal" => {
+                    let string = value["value"].as_str().unwrap_or("");
+                    Some(match value["raw"].as_str().map(|raw| unquote(raw, string)) {
+                        Some((string, quote_kind)) => JsxAttributeValue::String(string, quote_kind),
+                        None => JsxAttributeValue::String(string.to_owned(), QuoteKind::SpeechMark)
+                    })
+                }
+                "JSXExpressionContainer" => Some(JsxAttributeValue::Container(jsx_container(value)?)),
+                _ => Some(JsxAttributeValue::Element(expression(value)?))
+            };
+            let mut trivia = JsxAttributeTrivia::new();
+            trivia.prefix = String::from(" ");
+            Ok(JsxAttribute::Named { name: jsx_name(&node["name"])?, value, trivia })
+        }
+        "JSXSpreadAttribute" => {
+            let mut trivia = JsxSpreadTrivia::new();
+            trivia.prefix = String::from(" ");
+            Ok(JsxAttribute::Spread { argument: expression(&node["argument"])?, trivia })
+        }
+        _ => Err(unsupported(node, "a JSX attribute"))
+    };
+}
+
+fn jsx_child(node: &JsonValue) -> Result<JsxChild, EstreeError> {
+    return match node_type(node) {
+        "JSXText" => Ok(JsxChild::Text(node["raw"].as_str().or(node["value"].as_str()).unwrap_or("").to_owned())),
+        "JSXExpressionContainer" => Ok(JsxChild::Container(jsx_container(node)?)),
+        "JSXSpreadChild" => Ok(JsxChild::Spread { argument: expression(&node["expression"])?, trivia: JsxSpreadTrivia::new() }),
+        _ => Ok(JsxChild::Element(expression(node)?))
+    };
+}
+
+fn jsx_container(node: &JsonValue) -> Result<JsxContainer, EstreeError> {
+    let expression = match node_type(&node["expression"]) {
+        "JSXEmptyExpression" => None,
+        _ => Some(expression(&node["expression"])?)
+    };
+    return Ok(JsxContainer { expression, suffix: String::new() });
+}
@@ -0,0 +1,44 @@
+//! Conversion between syntax trees and ESTree JSON, the AST format shared by most JavaScript
+//! tools. A `#!` line is kept as `hashbang` on the `Program`. Parenthesised expressions are
+//! exported as the expression inside them, or as `ParenthesizedExpression` nodes with
+//! `EstreeOptions::preserve_parens`. Reading JSON back puts operands in brackets where the
+//! precedence of their operator needs them, whether or not the brackets were preserved.
+//!
+//! Exported nodes carry `start`, `end`, `range` and `loc` where the syntax tree knows their
+//! location. Offsets and columns count UTF-16 code units like JavaScript tools do, lines start at 1
+//! and columns at 0. Trivia is not part of ESTree, so a tree read back from JSON is laid out anew.
+//! It has no locations, unless it is read with `from_estree_in` and the source it was exported from.
+//!
+//! Comments are exported as a `comments` array on the `Program`, like acorn collects them with
+//! `onComment`. They are not read back.
+
+mod export;
+mod import;
+
+pub use self::export::{to_estree, to_estree_with, EstreeOptions};
+pub use self::import::{from_estree, from_estree_in};
+
+use std::error::Error;
+use std::fmt;
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum EstreeError {
+    /// A node was expected where the JSON holds something else. Holds what was expected.
+    InvalidNode(String),
+    /// A node type or construct Maxwell's syntax tree has no equivalent for.
+    Unsupported(String),
+    /// An `Identifier` whose name is not a valid identifier, or a reserved word where a variable is named.
+    InvalidIdentifier(String),
+}
+
+impl fmt::Display for EstreeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        return match *self {
+            EstreeError::InvalidNode(ref expected) => write!(f, "Expected {} node", expected),
+            EstreeError::Unsupported(ref what) => write!(f, "{} is not supported", what),
+            EstreeError::InvalidIdentifier(ref name) => write!(f, "'{}' is not a valid identifier", name),
+        };
+    }
+}
+
+impl Error for EstreeError {}
@@ -1,14 +1,29 @@
-use super::super::Literal;
+use super::super::{Literal, OperatorKind, Symbol};
 use super::Parameter;
 
 use super::body::BodyNode;
+use super::property::PropertyNode;
+use super::jsx::JsxElement;
 
 use super::SourceLocation;
 use super::NodeTrivia;
-use super::Node;
+use super::{Node, CodeWriter};
+
+use std::fmt;
+
+// Precedences of expressions which are not binary operators, in the scale of
+// `OperatorKind::precedence`. An operand needs brackets when its precedence is lower than the one
+// its position asks for.
+pub const ASSIGNMENT_PRECEDENCE: u8 = 2;
+pub const UNARY_PRECEDENCE: u8 = 15;
+pub const POSTFIX_PRECEDENCE: u8 = 16;
+pub const MEMBER_PRECEDENCE: u8 = 18;
+pub const PRIMARY_PRECEDENCE: u8 = 20;
 
 #[derive(Debug, PartialEq, Clone)]
 pub struct FunctionTrivia {
+    pub async_gap: String,
+    pub generator_gap: String,
     pub identifier_gap: String,
     pub parameters_gap: String,
     pub body_gap: String,
@@ -19,6 +34,8 @@ pub struct FunctionTrivia {
 impl FunctionTrivia {
     pub fn default() -> Self {
         return FunctionTrivia {
+            async_gap: String::new(),
+            generator_gap: String::new(),
             body_suffix: String::new(),
             identifier_gap: String::new(),
             parameters_gap: String::new(),
@@ -49,15 +66,121 @@ impl ArrowFunctionTrivia {
     }
 }
 
+#[derive(Debug, PartialEq, Clone)]
+pub struct OperatorTrivia {
+    pub operator_prefix: String,
+}
+
+impl OperatorTrivia {
+    pub fn new() -> Self {
+        return OperatorTrivia { operator_prefix: String::new() };
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct MemberTrivia {
+    pub accessor_prefix: String,
+    pub property_prefix: String,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct ComputedMemberTrivia {
+    pub open_prefix: String,
+    pub close_prefix: String,
+}
+
+/// Padding of an argument list. The padding before each comma, or before the closing bracket, is
+/// the suffix of the argument it follows.
+#[derive(Debug, PartialEq, Clone)]
+pub struct CallTrivia {
+    pub open_prefix: String,
+    pub trailing_comma: bool,
+    pub arguments_suffix: String,
+}
+
+impl CallTrivia {
+    pub fn new() -> Self {
+        return CallTrivia { open_prefix: String::new(), trailing_comma: false, arguments_suffix: String::new() };
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct ConditionalTrivia {
+    pub question_prefix: String,
+    pub colon_prefix: String,
+}
+
+/// Padding of a bracketed expression, the padding before `)` is kept in `expression_suffix`.
+#[derive(Debug, PartialEq, Clone)]
+pub struct BracketedTrivia {
+    pub expression_suffix: String,
+}
+
+impl BracketedTrivia {
+    pub fn new() -> Self {
+        return BracketedTrivia { expression_suffix: String::new() };
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct ObjectTrivia {
+    pub trailing_comma: bool,
+    pub properties_suffix: String,
+}
+
+impl ObjectTrivia {
+    pub fn new() -> Self {
+        return ObjectTrivia { trailing_comma: false, properties_suffix: String::new() };
+    }
+}
+
+/// Padding of an array literal. The padding before the comma following an element is the suffix of
+/// that element, holes keep theirs in `hole_prefixes`, in order.
+#[derive(Debug, PartialEq, Clone)]
+pub struct ArrayTrivia {
+    pub trailing_comma: bool,
+    pub elements_suffix: String,
+    pub hole_prefixes: Vec<String>,
+}
+
+impl ArrayTrivia {
+    pub fn new() -> Self {
+        return ArrayTrivia { trailing_comma: false, elements_suffix: String::new(), hole_prefixes: Vec::new() };
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct ClassTrivia {
+    pub identifier_gap: String,
+    pub extends_prefix: String,
+    pub body_gap: String,
+    pub body_suffix: String,
+}
+
+impl ClassTrivia {
+    pub fn new() -> Self {
+        return ClassTrivia {
+            identifier_gap: String::new(),
+            extends_prefix: String::new(),
+            body_gap: String::new(),
+            body_suffix: String::new()
+        };
+    }
+}
+
 #[derive(Debug, PartialEq, Clone)]
 pub enum Expression {
     Void,
     This,
+    Super,
     Bracketed {
-        expression: Box<ExpressionNode>
+        expression: Box<ExpressionNode>,
+        trivia: BracketedTrivia
     },
     Function {
-        name: String,
+        name: Symbol,
+        is_async: bool,
+        is_generator: bool,
         parameters: Vec<Parameter>,
         body: BodyNode,
         trivia: FunctionTrivia
@@ -68,20 +191,135 @@ pub enum Expression {
         trivia: ArrowFunctionTrivia
     },
     Call {
-        identifier: String,
-        parameters: Vec<ExpressionNode>
+        callee: Box<ExpressionNode>,
+        parameters: Vec<ExpressionNode>,
+        trivia: CallTrivia
+    },
+    New {
+        callee: Box<ExpressionNode>,
+        parameters: Option<Vec<ExpressionNode>>,
+        trivia: CallTrivia
+    },
+    Member {
+        object: Box<ExpressionNode>,
+        property: Symbol,
+        trivia: MemberTrivia
     },
-    Identifier(String),
+    ComputedMember {
+        object: Box<ExpressionNode>,
+        property: Box<ExpressionNode>,
+        trivia: ComputedMemberTrivia
+    },
+    Unary {
+        operator: OperatorKind,
+        argument: Box<ExpressionNode>
+    },
+    Update {
+        operator: OperatorKind,
+        argument: Box<ExpressionNode>,
+        prefix: bool,
+        trivia: OperatorTrivia
+    },
+    Binary {
+        left: Box<ExpressionNode>,
+        operator: OperatorKind,
+        right: Box<ExpressionNode>,
+        trivia: OperatorTrivia
+    },
+    Assignment {
+        left: Box<ExpressionNode>,
+        operator: OperatorKind,
+        right: Box<ExpressionNode>,
+        trivia: OperatorTrivia
+    },
+    Await {
+        argument: Box<ExpressionNode>
+    },
+    Yield {
+        argument: Option<Box<ExpressionNode>>,
+        delegate: bool,
+        trivia: OperatorTrivia
+    },
+    Object {
+        properties: Vec<PropertyNode>,
+        trivia: ObjectTrivia
+    },
+    /// An array literal, holes are `None`.
+    Array {
+        elements: Vec<Option<ExpressionNode>>,
+        trivia: ArrayTrivia
+    },
+    Class {
+        name: Symbol,
+        super_class: Option<Box<ExpressionNode>>,
+        members: Vec<PropertyNode>,
+        trivia: ClassTrivia
+    },
+    Conditional {
+        test: Box<ExpressionNode>,
+        consequent: Box<ExpressionNode>,
+        alternate: Box<ExpressionNode>,
+        trivia: ConditionalTrivia
+    },
+    Jsx(Box<JsxElement>),
+    Identifier(Symbol),
     Literal(Literal)
 }
 
 #[derive(Debug, PartialEq, Clone)]
 pub struct ExpressionNode {
     pub expression: Expression,
-    location: SourceLocation,
+    pub location: SourceLocation,
     pub trivia: NodeTrivia,
 }
 
+impl Expression {
+    /// How tightly the expression binds, in the scale of `OperatorKind::precedence`.
+    pub fn precedence(&self) -> u8 {
+        return match *self {
+            Expression::Bracketed { ref expression, .. } => expression.expression.precedence(),
+            Expression::ArrowFunction { .. } | Expression::Assignment { .. } | Expression::Yield { .. } => ASSIGNMENT_PRECEDENCE,
+            Expression::Unary { operator: OperatorKind::Spread, .. } => ASSIGNMENT_PRECEDENCE,
+            Expression::Conditional { .. } => OperatorKind::Conditional.precedence(),
+            Expression::Binary { ref operator, .. } => operator.precedence(),
+            Expression::Unary { .. } | Expression::Await { .. } | Expression::Update { prefix: true, .. } => UNARY_PRECEDENCE,
+            Expression::Update { prefix: false, .. } => POSTFIX_PRECEDENCE,
+            Expression::Call { .. } | Expression::New { .. } | Expression::Member { .. } | Expression::ComputedMember { .. } => MEMBER_PRECEDENCE,
+            _ => PRIMARY_PRECEDENCE
+        };
+    }
+
+    /// Whether the expression is an integer, whose decimal point a following dot would be read as.
+    pub fn is_integer(&self) -> bool {
+        return match *self {
+            Expression::Bracketed { ref expression, .. } => expression.expression.is_integer(),
+            Expression::Literal(Literal::Number(ref value)) => value.chars().all(|char| char.is_ascii_digit()),
+            Expression::Literal(Literal::Binary(_)) => true,
+            _ => false
+        };
+    }
+
+    /// Whether a call ends the member chain of the expression, so as the callee of `new` the call
+    /// would take the arguments of `new` for its own.
+    pub fn contains_call(&self) -> bool {
+        return match *self {
+            Expression::Bracketed { ref expression, .. } => expression.expression.contains_call(),
+            Expression::Call { .. } => true,
+            Expression::Member { ref object, .. } | Expression::ComputedMember { ref object, .. } => object.expression.contains_call(),
+            _ => false
+        };
+    }
+
+    /// Whether the expression, at statement level, declares a function or class. Expression
+    /// statements cannot start with `function` or `class`, so any named one is a declaration.
+    pub fn is_declaration(&self) -> bool {
+        return match *self {
+            Expression::Function { ref name, .. } | Expression::Class { ref name, .. } => !name.is_empty(),
+            _ => false
+        };
+    }
+}
+
 impl ExpressionNode {
     pub fn new(expression: Expression, trivia: NodeTrivia) -> Self {
         return ExpressionNode {
@@ -93,41 +331,199 @@ impl ExpressionNode {
 }
 
 impl Node for ExpressionNode {
-    fn generate(&self) -> String {
+    fn write(&self, output: &mut CodeWriter) -> fmt::Result {
         use self::Expression::*;
 
-        let string = match self.expression {
-            //TODO: Cleanup
-            Function { ref name, ref parameters, ref body, ref trivia } => {
-                let mut params = String::new();
-                for i in 0..parameters.len() {
-                    params += &format!("{}{}", match i {
-                        0 => "",
-                        _ => ","
-                    }, parameters[i].name);
+        output.write(&self.trivia.prefix)?;
+        match self.expression {
+            Identifier(ref name) => output.mark(&self.location, Some(name)),
+            _ => output.mark(&self.location, None)
+        }
+        match self.expression {
+            Function { ref name, is_async, is_generator, ref parameters, ref body, ref trivia } => {
+                if is_async {
+                    output.write("async")?;
+                    output.write(&trivia.async_gap)?;
+                }
+                output.write("function")?;
+                if is_generator {
+                    output.write(&trivia.generator_gap)?;
+                    output.write("*")?;
                 }
-                let mut string = format!("function{}{}{}({}{}){}{{", trivia.identifier_gap, name, trivia.parameters_gap, trivia.parameters_padding, &params, trivia.body_gap);
-                string += &body.generate();
-                string += &trivia.body_suffix;
-                string += "}";
-                string
+                output.write(&trivia.identifier_gap)?;
+                output.write(name)?;
+                write_function_tail(parameters, body, trivia, output)?;
             }
-            Call { ref identifier, ref parameters } => {
-                let mut params = String::new();
-                for i in 0..parameters.len() {
-                    params += &format!("{}{}", match i {
-                        0 => "",
-                        _ => ","
-                    }, parameters[i].generate());
+            Call { ref callee, ref parameters, ref trivia } => {
+                callee.write(output)?;
+                write_arguments(parameters, trivia, output)?;
+            }
+            New { ref callee, ref parameters, ref trivia } => {
+                output.write("new")?;
+                callee.write(output)?;
+                if let Some(ref parameters) = *parameters {
+                    write_arguments(parameters, trivia, output)?;
                 }
-                format!("{}({})", identifier, params)
             }
-            Bracketed { ref expression } => format!("({})", expression.generate()),
-            This => "this".to_owned(),
-            Identifier(ref string) => string.to_owned(),
-            Literal(ref literal) => literal.to_string().to_owned(),
-            _ => "ERROR".to_owned()
-        };
-        return format!("{}{}{}", self.trivia.prefix, string, self.trivia.suffix);
+            Member { ref object, ref property, ref trivia } => {
+                object.write(output)?;
+                output.write(&trivia.accessor_prefix)?;
+                output.write(".")?;
+                output.write(&trivia.property_prefix)?;
+                output.write(property)?;
+            }
+            ComputedMember { ref object, ref property, ref trivia } => {
+                object.write(output)?;
+                output.write(&trivia.open_prefix)?;
+                output.write("[")?;
+                property.write(output)?;
+                output.write(&trivia.close_prefix)?;
+                output.write("]")?;
+            }
+            Unary { ref operator, ref argument } => {
+                output.write(&operator.to_string())?;
+                argument.write(output)?;
+            }
+            Update { ref operator, ref argument, prefix, ref trivia } => {
+                match prefix {
+                    true => {
+                        output.write(&operator.to_string())?;
+                        argument.write(output)?;
+                    }
+                    false => {
+                        argument.write(output)?;
+                        output.write(&trivia.operator_prefix)?;
+                        output.write(&operator.to_string())?;
+                    }
+                }
+            }
+            Binary { ref left, ref operator, ref right, ref trivia } | Assignment { ref left, ref operator, ref right, ref trivia } => {
+                left.write(output)?;
+                output.write(&trivia.operator_prefix)?;
+                output.write(&operator.to_string())?;
+                right.write(output)?;
+            }
+            Await { ref argument } => {
+                output.write("await")?;
+                argument.write(output)?;
+            }
+            Yield { ref argument, delegate, ref trivia } => {
+                output.write("yield")?;
+                if delegate {
+                    output.write(&trivia.operator_prefix)?;
+                    output.write("*")?;
+                }
+                if let Some(ref argument) = *argument {
+                    argument.write(output)?;
+                }
+            }
+            Object { ref properties, ref trivia } => {
+                output.write("{")?;
+                for (i, property) in properties.iter().enumerate() {
+                    if i > 0 {
+                        output.write(",")?;
+                    }
+                    property.write(output)?;
+                }
+                if trivia.trailing_comma {
+                    output.write(",")?;
+                }
+                output.write(&trivia.properties_suffix)?;
+                output.write("}")?;
+            }
+            Array { ref elements, ref trivia } => {
+                output.write("[")?;
+                let mut holes = trivia.hole_prefixes.iter();
+                for (i, element) in elements.iter().enumerate() {
+                    if i > 0 {
+                        output.write(",")?;
+                    }
+                    match *element {
+                        Some(ref element) => element.write(output)?,
+                        None => output.write(holes.next().map_or("", |prefix| prefix.as_str()))?
+                    }
+                }
+                if trivia.trailing_comma {
+                    output.write(",")?;
+                }
+                output.write(&trivia.elements_suffix)?;
+                output.write("]")?;
+            }
+            Class { ref name, ref super_class, ref members, ref trivia } => {
+                output.write("class")?;
+                output.write(&trivia.identifier_gap)?;
+                output.write(name)?;
+                if let Some(ref super_class) = *super_class {
+                    output.write(&trivia.extends_prefix)?;
+                    output.write("extends")?;
+                    super_class.write(output)?;
+                }
+                output.write(&trivia.body_gap)?;
+                output.write("{")?;
+                for member in members {
+                    member.write(output)?;
+                }
+                output.write(&trivia.body_suffix)?;
+                output.write("}")?;
+            }
+            Conditional { ref test, ref consequent, ref alternate, ref trivia } => {
+                test.write(output)?;
+                output.write(&trivia.question_prefix)?;
+                output.write("?")?;
+                consequent.write(output)?;
+                output.write(&trivia.colon_prefix)?;
+                output.write(":")?;
+                alternate.write(output)?;
+            }
+            Bracketed { ref expression, ref trivia } => {
+                output.write("(")?;
+                expression.write(output)?;
+                output.write(&trivia.expression_suffix)?;
+                output.write(")")?;
+            }
+            Jsx(ref element) => element.write(output)?,
+            This => output.write("this")?,
+            Super => output.write("super")?,
+            Identifier(ref name) => output.write(name)?,
+            Literal(ref literal) => output.write(&literal.to_string())?,
+            _ => output.write("ERROR")?
+        }
+        return output.write(&self.trivia.suffix);
     }
-}
\ No newline at end of file
+}
+fn write_arguments(nodes: &Vec<ExpressionNode>, trivia: &CallTrivia, output: &mut CodeWriter) -> fmt::Result {
+    output.write(&trivia.open_prefix)?;
+    output.write("(")?;
+    for (i, node) in nodes.iter().enumerate() {
+        if i > 0 {
+            output.write(",")?;
+        }
+        node.write(output)?;
+    }
+    if trivia.trailing_comma {
+        output.write(",")?;
+    }
+    output.write(&trivia.arguments_suffix)?;
+    return output.write(")");
+}
+
+/// Writes everything of a function after its name, shared by function expressions and methods.
+pub fn write_function_tail(parameters: &Vec<Parameter>, body: &BodyNode, trivia: &FunctionTrivia, output: &mut CodeWriter) -> fmt::Result {
+    output.write(&trivia.parameters_gap)?;
+    output.write("(")?;
+    output.write(&trivia.parameters_padding)?;
+    for (i, parameter) in parameters.iter().enumerate() {
+        if i > 0 {
+            output.write(",")?;
+        }
+        output.write(&parameter.trivia.prefix)?;
+        output.write(&parameter.name)?;
+        output.write(&parameter.trivia.suffix)?;
+    }
+    output.write(")")?;
+    output.write(&trivia.body_gap)?;
+    output.write("{")?;
+    body.write(output)?;
+    output.write(&trivia.body_suffix)?;
+    return output.write("}");
+}
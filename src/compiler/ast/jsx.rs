@@ -0,0 +1,192 @@
+use super::expression::ExpressionNode;
+use super::{Node, CodeWriter};
+use super::super::QuoteKind;
+
+use std::fmt;
+
+/// Padding inside the tags of an element. For fragments the name gaps are the padding inside `<>` and `</>`.
+#[derive(Debug, PartialEq, Clone)]
+pub struct JsxTrivia {
+    pub name_prefix: String,
+    pub attributes_suffix: String,
+    pub closing_name_prefix: String,
+    pub closing_suffix: String,
+}
+
+impl JsxTrivia {
+    pub fn new() -> Self {
+        return JsxTrivia {
+            name_prefix: String::new(),
+            attributes_suffix: String::new(),
+            closing_name_prefix: String::new(),
+            closing_suffix: String::new(),
+        };
+    }
+}
+
+/// Padding of a `{...argument}` spread, either as an attribute or as a child.
+#[derive(Debug, PartialEq, Clone)]
+pub struct JsxSpreadTrivia {
+    pub prefix: String,
+    pub spread_prefix: String,
+    pub suffix: String,
+}
+
+impl JsxSpreadTrivia {
+    pub fn new() -> Self {
+        return JsxSpreadTrivia { prefix: String::new(), spread_prefix: String::new(), suffix: String::new() };
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct JsxAttributeTrivia {
+    pub prefix: String,
+    pub assign_prefix: String,
+    pub value_prefix: String,
+}
+
+impl JsxAttributeTrivia {
+    pub fn new() -> Self {
+        return JsxAttributeTrivia { prefix: String::new(), assign_prefix: String::new(), value_prefix: String::new() };
+    }
+}
+
+/// An expression container, `{expression}`. The expression is `None` for empty containers, which
+/// may still hold comments in their suffix.
+#[derive(Debug, PartialEq, Clone)]
+pub struct JsxContainer {
+    pub expression: Option<ExpressionNode>,
+    pub suffix: String,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum JsxAttributeValue {
+    String(String, QuoteKind),
+    Container(JsxContainer),
+    Element(ExpressionNode),
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum JsxAttribute {
+    Named {
+        name: String,
+        value: Option<JsxAttributeValue>,
+        trivia: JsxAttributeTrivia
+    },
+    Spread {
+        argument: ExpressionNode,
+        trivia: JsxSpreadTrivia
+    },
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum JsxChild {
+    Text(String),
+    Container(JsxContainer),
+    Spread {
+        argument: ExpressionNode,
+        trivia: JsxSpreadTrivia
+    },
+    Element(ExpressionNode),
+}
+
+/// A JSX element, or a fragment when it has no name. Self-closing elements have no children.
+#[derive(Debug, PartialEq, Clone)]
+pub struct JsxElement {
+    pub name: Option<String>,
+    pub attributes: Vec<JsxAttribute>,
+    pub children: Option<Vec<JsxChild>>,
+    pub trivia: JsxTrivia,
+}
+
+impl Node for JsxContainer {
+    fn write(&self, output: &mut CodeWriter) -> fmt::Result {
+        output.write("{")?;
+        if let Some(ref expression) = self.expression {
+            expression.write(output)?;
+        }
+        output.write(&self.suffix)?;
+        return output.write("}");
+    }
+}
+
+fn write_spread(argument: &ExpressionNode, trivia: &JsxSpreadTrivia, output: &mut CodeWriter) -> fmt::Result {
+    output.write(&trivia.prefix)?;
+    output.write("{")?;
+    output.write(&trivia.spread_prefix)?;
+    output.write("...")?;
+    argument.write(output)?;
+    output.write(&trivia.suffix)?;
+    return output.write("}");
+}
+
+impl Node for JsxAttribute {
+    fn write(&self, output: &mut CodeWriter) -> fmt::Result {
+        return match *self {
+            JsxAttribute::Named { ref name, ref value, ref trivia } => {
+                output.write(&trivia.prefix)?;
+                output.write(name)?;
+                if let Some(ref value) = *value {
+                    output.write(&trivia.assign_prefix)?;
+                    output.write("=")?;
+                    output.write(&trivia.value_prefix)?;
+                    match *value {
+                        JsxAttributeValue::String(ref value, ref quote) => {
+                            output.write(&quote.to_string())?;
+                            output.write(value)?;
+                            output.write(&quote.to_string())?;
+                        }
+                        JsxAttributeValue::Container(ref container) => container.write(output)?,
+                        JsxAttributeValue::Element(ref element) => element.write(output)?,
+                    }
+                }
+                Ok(())
+            }
+            JsxAttribute::Spread { ref argument, ref trivia } => write_spread(argument, trivia, output),
+        };
+    }
+}
+
+impl Node for JsxChild {
+    fn write(&self, output: &mut CodeWriter) -> fmt::Result {
+        return match *self {
+            JsxChild::Text(ref text) => output.write(text),
+            JsxChild::Container(ref container) => container.write(output),
+            JsxChild::Spread { ref argument, ref trivia } => write_spread(argument, trivia, output),
+            JsxChild::Element(ref element) => element.write(output),
+        };
+    }
+}
+
+impl Node for JsxElement {
+    fn write(&self, output: &mut CodeWriter) -> fmt::Result {
+        let trivia = &self.trivia;
+        let name = match self.name {
+            Some(ref name) => name.as_str(),
+            None => ""
+        };
+
+        output.write("<")?;
+        output.write(&trivia.name_prefix)?;
+        output.write(name)?;
+        for attribute in &self.attributes {
+            attribute.write(output)?;
+        }
+        output.write(&trivia.attributes_suffix)?;
+
+        return match self.children {
+            Some(ref children) => {
+                output.write(">")?;
+                for child in children {
+                    child.write(output)?;
+                }
+                output.write("</")?;
+                output.write(&trivia.closing_name_prefix)?;
+                output.write(name)?;
+                output.write(&trivia.closing_suffix)?;
+                output.write(">")
+            }
+            None => output.write("/>")
+        };
+    }
+}
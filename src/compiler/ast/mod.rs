@@ -2,13 +2,23 @@ mod parameter;
 mod source_location;
 mod node;
 mod syntax_tree;
+mod comment;
 
 pub mod statement;
 pub mod expression;
 pub mod declaration;
 pub mod body;
+pub mod directive;
+pub mod property;
+pub mod jsx;
+pub mod visit;
+pub mod visit_mut;
+pub mod traverse;
+pub mod estree;
+pub mod build;
 
 pub use self::syntax_tree::*;
 pub use self::source_location::*;
 pub use self::parameter::*;
 pub use self::node::*;
+pub use self::comment::*;
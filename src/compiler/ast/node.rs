@@ -1,5 +1,139 @@
+use super::SourceLocation;
+use super::super::lexicon;
+
+use std::fmt;
+use std::mem;
+
 pub trait Node {
-    fn generate(&self) -> String;
+    /// Writes the code of the node with its trivia.
+    fn write(&self, output: &mut CodeWriter) -> fmt::Result;
+
+    /// The code of the node with its trivia.
+    fn generate(&self) -> String {
+        let mut code = String::new();
+        // Writing to a `String` cannot fail.
+        self.write(&mut CodeWriter::new(&mut code)).unwrap();
+        return code;
+    }
+}
+
+/// A zero-based line and column in code. Columns count UTF-16 code units, as source maps do.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Position {
+    pub line: usize,
+    pub column: usize,
+}
+
+impl Position {
+    pub fn new() -> Self {
+        return Position { line: 0, column: 0 };
+    }
+
+    /// Moves the position past some code.
+    pub fn advance(&mut self, code: &str) {
+        for char in code.chars() {
+            if char == '\n' {
+                self.line += 1;
+                self.column = 0;
+            } else {
+                self.column += char.len_utf16();
+            }
+        }
+    }
+}
+
+/// A position in generated code, marked while generating as the start of the code of a node.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Mark {
+    pub generated: Position,
+    /// Byte offset of the node in its source.
+    pub original: usize,
+    /// The name the node had in the source, where it was renamed.
+    pub name: Option<String>,
+}
+
+impl Mark {
+    /// A mark of the node at `location` of `source`. A node written as `name` which starts with a
+    /// different name in the source keeps that one.
+    pub fn new(generated: Position, source: &str, location: &SourceLocation, name: Option<&str>) -> Self {
+        let original = &source[location.start..];
+        let length = original.char_indices().find(|&(_, char)| !lexicon::is_ident(char)).map_or(original.len(), |(index, _)| index);
+        let name = match name {
+            Some(name) if name != &original[..length] && length > 0 => Some(original[..length].to_owned()),
+            _ => None
+        };
+        return Mark { generated, original: location.start, name };
+    }
+
+    /// Whether a node at `location` can be marked as a node of `source`. Nodes built by plugins
+    /// have no location.
+    pub fn is_located(source: &str, location: &SourceLocation) -> bool {
+        return location.end > location.start && location.end <= source.len() && source.is_char_boundary(location.start);
+    }
+}
+
+/// Writes code into a sink as it is generated, keeping track of how far it got. Nodes write their
+/// parts one after another, so no node builds the code of its children into a string of its own.
+pub struct CodeWriter<'a> {
+    sink: &'a mut fmt::Write,
+    offset: usize,
+    position: Position,
+    /// Source the located nodes written are marked against, when the code gets a source map.
+    source: Option<&'a str>,
+    marks: Vec<Mark>,
+}
+
+impl<'a> CodeWriter<'a> {
+    pub fn new(sink: &'a mut fmt::Write) -> Self {
+        return CodeWriter { sink, offset: 0, position: Position::new(), source: None, marks: Vec::new() };
+    }
+
+    /// Marks the start of every located node written from now on, or stops marking with `None`.
+    pub fn mark_source(&mut self, source: Option<&'a str>) {
+        self.source = source;
+    }
+
+    /// Marks the next code written as the start of the node at `location`. Identifiers pass the
+    /// name they are written as. The innermost of the nodes starting at a position keeps the mark.
+    pub fn mark(&mut self, location: &SourceLocation, name: Option<&str>) {
+        let source = match self.source {
+            Some(source) if Mark::is_located(source, location) => source,
+            _ => return
+        };
+        let mark = Mark::new(self.position, source, location, name);
+        if self.marks.last().map_or(false, |last| last.generated == self.position) {
+            self.marks.pop();
+        }
+        self.marks.push(mark);
+    }
+
+    /// Takes the marks made so far.
+    pub fn take_marks(&mut self) -> Vec<Mark> {
+        return mem::replace(&mut self.marks, Vec::new());
+    }
+
+    pub fn write(&mut self, code: &str) -> fmt::Result {
+        self.sink.write_str(code)?;
+        self.offset += code.len();
+        self.position.advance(code);
+        return Ok(());
+    }
+
+    /// Bytes written so far.
+    pub fn offset(&self) -> usize {
+        return self.offset;
+    }
+
+    /// Position of the next code written.
+    pub fn position(&self) -> Position {
+        return self.position;
+    }
+}
+
+impl<'a> fmt::Write for CodeWriter<'a> {
+    fn write_str(&mut self, code: &str) -> fmt::Result {
+        return self.write(code);
+    }
 }
 
 #[derive(Debug, PartialEq, Clone)]
@@ -13,4 +147,3 @@ impl NodeTrivia {
         return NodeTrivia { prefix: String::new(), suffix: String::new() };
     }
 }
-
@@ -1,5 +1,7 @@
-use super::super::Literal;
+use super::super::{Literal, Symbol};
+use super::SourceLocation;
 
+/// Padding before the name of a parameter and before the comma or bracket following it.
 #[derive(Debug, PartialEq, Clone)]
 pub struct ParameterTrivia {
     pub prefix : String,
@@ -8,7 +10,9 @@ pub struct ParameterTrivia {
 
 #[derive(Debug, PartialEq, Clone)]
 pub struct Parameter {
-    pub name: String,
+    pub name: Symbol,
     pub default: Option<Literal>,
+    /// Where the parameter is written, from its name to the end of its default.
+    pub location: SourceLocation,
     pub trivia: ParameterTrivia,
 }
\ No newline at end of file
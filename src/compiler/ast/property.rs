@@ -0,0 +1,146 @@
+use super::expression::{ExpressionNode, Expression, write_function_tail};
+use super::{SourceLocation, Node, CodeWriter};
+use super::super::{Literal, Symbol};
+
+use std::fmt;
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum PropertyKind {
+    Init,
+    Shorthand,
+    Method,
+    Get,
+    Set,
+    Field,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum PropertyKey {
+    Identifier(Symbol),
+    Literal(Literal),
+    Computed(Box<ExpressionNode>),
+}
+
+/// Padding around the parts of a property. Each modifier gap is the padding that follows that modifier.
+#[derive(Debug, PartialEq, Clone)]
+pub struct PropertyTrivia {
+    pub prefix: String,
+    pub static_gap: String,
+    pub async_gap: String,
+    pub generator_gap: String,
+    pub accessor_gap: String,
+    pub computed_suffix: String,
+    pub value_prefix: String,
+    pub suffix: String,
+}
+
+impl PropertyTrivia {
+    pub fn new() -> Self {
+        return PropertyTrivia {
+            prefix: String::new(),
+            static_gap: String::new(),
+            async_gap: String::new(),
+            generator_gap: String::new(),
+            accessor_gap: String::new(),
+            computed_suffix: String::new(),
+            value_prefix: String::new(),
+            suffix: String::new(),
+        };
+    }
+}
+
+/// A property of an object literal or a member of a class body. Methods and accessors hold a
+/// function expression as their value, which also records whether they are async or generators.
+#[derive(Debug, PartialEq, Clone)]
+pub struct PropertyNode {
+    pub key: PropertyKey,
+    pub value: Option<ExpressionNode>,
+    pub kind: PropertyKind,
+    pub is_static: bool,
+    pub location: SourceLocation,
+    /// Where the key is, after any modifiers, with the brackets of a computed key.
+    pub key_location: SourceLocation,
+    pub trivia: PropertyTrivia,
+}
+
+impl Node for PropertyKey {
+    fn write(&self, output: &mut CodeWriter) -> fmt::Result {
+        return match *self {
+            PropertyKey::Identifier(ref name) => output.write(name),
+            PropertyKey::Literal(ref literal) => output.write(&literal.to_string()),
+            PropertyKey::Computed(ref expression) => {
+                output.write("[")?;
+                expression.write(output)
+            }
+        };
+    }
+}
+
+impl Node for PropertyNode {
+    fn write(&self, output: &mut CodeWriter) -> fmt::Result {
+        let trivia = &self.trivia;
+        output.write(&trivia.prefix)?;
+        output.mark(&self.location, None);
+        if self.is_static {
+            output.write("static")?;
+            output.write(&trivia.static_gap)?;
+        }
+
+        let function = match self.value {
+            Some(ExpressionNode { expression: Expression::Function { is_async, is_generator, ref parameters, ref body, trivia: ref function_trivia, .. }, .. }) => {
+                Some((is_async, is_generator, parameters, body, function_trivia))
+            }
+            _ => None
+        };
+
+        match (&self.kind, function) {
+            (&PropertyKind::Method, Some((is_async, is_generator, _, _, _))) => {
+                if is_async {
+                    output.write("async")?;
+                    output.write(&trivia.async_gap)?;
+                }
+                if is_generator {
+                    output.write("*")?;
+                    output.write(&trivia.generator_gap)?;
+                }
+            }
+            (&PropertyKind::Get, _) => {
+                output.write("get")?;
+                output.write(&trivia.accessor_gap)?;
+            }
+            (&PropertyKind::Set, _) => {
+                output.write("set")?;
+                output.write(&trivia.accessor_gap)?;
+            }
+            _ => {}
+        }
+
+        match self.key {
+            PropertyKey::Identifier(ref name) => output.mark(&self.key_location, Some(name)),
+            _ => output.mark(&self.key_location, None)
+        }
+        self.key.write(output)?;
+        if let PropertyKey::Computed(_) = self.key {
+            output.write(&trivia.computed_suffix)?;
+            output.write("]")?;
+        }
+
+        match function {
+            Some((_, _, parameters, body, function_trivia)) if self.kind != PropertyKind::Init && self.kind != PropertyKind::Field => {
+                write_function_tail(parameters, body, function_trivia, output)?;
+            }
+            _ => {
+                if let Some(ref value) = self.value {
+                    output.write(&trivia.value_prefix)?;
+                    output.write(match self.kind {
+                        PropertyKind::Field => "=",
+                        _ => ":"
+                    })?;
+                    value.write(output)?;
+                }
+            }
+        }
+
+        return output.write(&trivia.suffix);
+    }
+}
@@ -1,4 +1,4 @@
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
 pub struct SourceLocation {
     pub start: usize,
     pub end: usize,
@@ -12,4 +12,14 @@ impl SourceLocation {
     pub fn default() -> Self {
         return SourceLocation::new(0, 0);
     }
-}
\ No newline at end of file
+}
+
+/// One-based line and column of a byte index of a source. Columns count characters.
+pub fn line_column(source: &str, index: usize) -> (usize, usize) {
+    let before = match source.get(..index) {
+        Some(before) => before,
+        None => source
+    };
+    let line_start = before.rfind('\n').map(|newline| newline + 1).unwrap_or(0);
+    return (before.matches('\n').count() + 1, before[line_start..].chars().count() + 1);
+}
@@ -1,7 +1,10 @@
 use super::expression::{ExpressionNode, Expression};
 use super::declaration::{DeclarationKind, DeclarationNode};
-use super::{NodeTrivia, SourceLocation, Node};
-use super::super::QuoteKind;
+use super::body::BodyNode;
+use super::{NodeTrivia, SourceLocation, Node, CodeWriter};
+use super::super::{QuoteKind, Symbol};
+
+use std::fmt;
 
 #[derive(Debug, PartialEq, Clone)]
 pub struct ImportTrivia {
@@ -10,6 +13,7 @@ pub struct ImportTrivia {
     pub alias_prefix: String,
     pub from_prefix: String,
     pub source_prefix: String,
+    pub specifiers_suffix: String,
     pub quote_kind: QuoteKind,
 }
 
@@ -21,6 +25,7 @@ impl ImportTrivia {
             from_prefix: String::new(),
             alias_prefix: String::new(),
             source_prefix: String::new(),
+            specifiers_suffix: String::new(),
             quote_kind: QuoteKind::Apostrophe,
         };
     }
@@ -29,12 +34,98 @@ impl ImportTrivia {
 #[derive(Debug, PartialEq, Clone)]
 pub enum ImportDeclaration {
     All,
-    Single(String),
-    Multiple(Vec<String>)
+    Single(Symbol),
+    Multiple(Vec<ModuleSpecifier>)
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct WithTrivia {
+    pub object_gap: String,
+    pub object_suffix: String,
+}
+
+impl WithTrivia {
+    pub fn new() -> Self {
+        return WithTrivia { object_gap: String::new(), object_suffix: String::new() };
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct BlockTrivia {
+    pub body_suffix: String,
+}
+
+/// Padding inside a `for` head. `init_suffix`/`test_suffix` precede the two semicolons of a plain
+/// loop, `operator_prefix` precedes the `in`/`of` of the other forms.
+#[derive(Debug, PartialEq, Clone)]
+pub struct ForTrivia {
+    pub await_prefix: String,
+    pub head_gap: String,
+    pub init_suffix: String,
+    pub test_suffix: String,
+    pub operator_prefix: String,
+    pub head_suffix: String,
+}
+
+impl ForTrivia {
+    pub fn new() -> Self {
+        return ForTrivia {
+            await_prefix: String::new(),
+            head_gap: String::new(),
+            init_suffix: String::new(),
+            test_suffix: String::new(),
+            operator_prefix: String::new(),
+            head_suffix: String::new(),
+        };
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct ExportTrivia {
+    pub declaration_prefix: String,
+    pub specifiers_suffix: String,
+}
+
+impl ExportTrivia {
+    pub fn new() -> Self {
+        return ExportTrivia { declaration_prefix: String::new(), specifiers_suffix: String::new() };
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct ModuleSpecifierTrivia {
+    pub prefix: String,
+    pub as_prefix: String,
+    pub alias_prefix: String,
+    pub suffix: String,
+}
+
+impl ModuleSpecifierTrivia {
+    pub fn new() -> Self {
+        return ModuleSpecifierTrivia { prefix: String::new(), as_prefix: String::new(), alias_prefix: String::new(), suffix: String::new() };
+    }
+}
+
+/// A `name as alias` pair inside the braces of an import or export.
+#[derive(Debug, PartialEq, Clone)]
+pub struct ModuleSpecifier {
+    pub name: Symbol,
+    pub alias: Option<Symbol>,
+    /// From the name to the end of the alias.
+    pub location: SourceLocation,
+    pub trivia: ModuleSpecifierTrivia,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum ExportDeclaration {
+    Default(ExpressionNode),
+    Statement(Box<StatementNode>),
+    Named(Vec<ModuleSpecifier>),
 }
 
 #[derive(Debug, PartialEq, Clone)]
 pub enum Statement {
+    Empty,
     Expression {
         expression: ExpressionNode
     },
@@ -46,18 +137,63 @@ pub enum Statement {
         expression: Option<ExpressionNode>
     },
     Import {
-        alias: Option<String>,
+        alias: Option<Symbol>,
         declaration: ImportDeclaration,
         source: String,
         trivia: ImportTrivia
+    },
+    Export {
+        declaration: ExportDeclaration,
+        trivia: ExportTrivia
+    },
+    With {
+        object: ExpressionNode,
+        body: Box<StatementNode>,
+        trivia: WithTrivia
+    },
+    Block {
+        body: BodyNode,
+        trivia: BlockTrivia
+    },
+    For {
+        init: Option<Box<StatementNode>>,
+        test: Option<ExpressionNode>,
+        update: Option<ExpressionNode>,
+        body: Box<StatementNode>,
+        trivia: ForTrivia
+    },
+    ForIn {
+        left: Box<StatementNode>,
+        right: ExpressionNode,
+        body: Box<StatementNode>,
+        trivia: ForTrivia
+    },
+    ForOf {
+        left: Box<StatementNode>,
+        right: ExpressionNode,
+        body: Box<StatementNode>,
+        is_await: bool,
+        trivia: ForTrivia
     }
 }
 
 #[derive(Debug, PartialEq, Clone)]
 pub enum StatementTerminator {
     Semicolon,
-    Newline,
+    /// A line break ending the statement, kept as written: `\n`, `\r\n` or `\r`.
+    Newline(String),
     Block,
+    Implicit,
+}
+
+impl ToString for StatementTerminator {
+    fn to_string(&self) -> String {
+        return match *self {
+            StatementTerminator::Semicolon => ";",
+            StatementTerminator::Newline(ref line_break) => line_break,
+            _ => ""
+        }.to_owned();
+    }
 }
 
 #[derive(Debug, PartialEq, Clone)]
@@ -82,56 +218,165 @@ impl StatementNode {
 }
 
 impl Node for StatementNode {
-    fn generate(&self) -> String {
+    fn write(&self, output: &mut CodeWriter) -> fmt::Result {
         use self::Statement::*;
 
-        let mut string = match self.statement {
+        output.write(&self.trivia.prefix)?;
+        output.mark(&self.location, None);
+        match self.statement {
+            Empty => {}
             Declaration { ref kind, ref declarations } => {
-                let mut string = String::new();
-                for i in 0..declarations.len() {
-                    string += match i {
-                        0 => "",
-                        _ => ","
-                    };
-                    string += &declarations[i].generate();
+                output.write(&kind.to_string())?;
+                for (i, declaration) in declarations.iter().enumerate() {
+                    if i > 0 {
+                        output.write(",")?;
+                    }
+                    declaration.write(output)?;
                 }
-                format!("{} {}", kind.to_string(), string)
             }
-            Expression { ref expression } => expression.generate().to_owned(),
+            Expression { ref expression } => expression.write(output)?,
             Return { ref expression } => {
-                format!("return{}", match expression {
-                    &Some(ref value) => value.generate(),
-                    &None => "".to_owned()
-                })
+                output.write("return")?;
+                if let Some(ref value) = *expression {
+                    value.write(output)?;
+                }
             }
             Import { ref alias, ref declaration, ref source, ref trivia } => {
-                let declaration_string = match declaration {
-                    &ImportDeclaration::All => String::from("*"),
-                    &ImportDeclaration::Single(ref name) => name.to_owned(),
-                    &ImportDeclaration::Multiple(ref names) => names.join(",")
-                };
-                let alias_string = match alias {
-                    &Some(ref name) => {
-                        format!("{}as{}{}", trivia.as_prefix, trivia.alias_prefix, name)
+                output.write("import")?;
+                output.write(&trivia.declaration_prefix)?;
+                match *declaration {
+                    ImportDeclaration::All => output.write("*")?,
+                    ImportDeclaration::Single(ref name) => output.write(name)?,
+                    ImportDeclaration::Multiple(ref specifiers) => {
+                        output.write("{")?;
+                        write_specifiers(specifiers, output)?;
+                        output.write(&trivia.specifiers_suffix)?;
+                        output.write("}")?;
                     }
-                    &None => String::new(),
-                };
+                }
+                if let Some(ref name) = *alias {
+                    output.write(&trivia.as_prefix)?;
+                    output.write("as")?;
+                    output.write(&trivia.alias_prefix)?;
+                    output.write(name)?;
+                }
                 //TODO: We don't store the literal quote type. Could be "" or ''
-                format!("import{}{}{}{}from{}{}{}{}",
-                        trivia.declaration_prefix,
-                        declaration_string,
-                        alias_string,
-                        trivia.from_prefix,
-                        trivia.source_prefix,
-                        trivia.quote_kind.to_string(),
-                        source.to_owned(),
-                        trivia.quote_kind.to_string())
+                output.write(&trivia.from_prefix)?;
+                output.write("from")?;
+                output.write(&trivia.source_prefix)?;
+                output.write(&trivia.quote_kind.to_string())?;
+                output.write(source)?;
+                output.write(&trivia.quote_kind.to_string())?;
             }
-        };
-        return format!("{}{}{}{}", self.trivia.prefix, string, self.trivia.suffix, match self.terminator {
-            StatementTerminator::Semicolon => ";",
-            StatementTerminator::Newline => "\n",
-            _ => ""
-        });
+            Export { ref declaration, ref trivia } => {
+                output.write("export")?;
+                match *declaration {
+                    ExportDeclaration::Default(ref expression) => {
+                        output.write(&trivia.declaration_prefix)?;
+                        output.write("default")?;
+                        expression.write(output)?;
+                    }
+                    ExportDeclaration::Statement(ref statement) => statement.write(output)?,
+                    ExportDeclaration::Named(ref specifiers) => {
+                        output.write(&trivia.declaration_prefix)?;
+                        output.write("{")?;
+                        write_specifiers(specifiers, output)?;
+                        output.write(&trivia.specifiers_suffix)?;
+                        output.write("}")?;
+                    }
+                }
+            }
+            With { ref object, ref body, ref trivia } => {
+                output.write("with")?;
+                output.write(&trivia.object_gap)?;
+                output.write("(")?;
+                object.write(output)?;
+                output.write(&trivia.object_suffix)?;
+                output.write(")")?;
+                body.write(output)?;
+            }
+            Block { ref body, ref trivia } => {
+                output.write("{")?;
+                body.write(output)?;
+                output.write(&trivia.body_suffix)?;
+                output.write("}")?;
+            }
+            For { ref init, ref test, ref update, ref body, ref trivia } => {
+                output.write("for")?;
+                output.write(&trivia.head_gap)?;
+                output.write("(")?;
+                if let Some(ref init) = *init {
+                    init.write(output)?;
+                }
+                output.write(&trivia.init_suffix)?;
+                output.write(";")?;
+                if let Some(ref test) = *test {
+                    test.write(output)?;
+                }
+                output.write(&trivia.test_suffix)?;
+                output.write(";")?;
+                if let Some(ref update) = *update {
+                    update.write(output)?;
+                }
+                output.write(&trivia.head_suffix)?;
+                output.write(")")?;
+                body.write(output)?;
+            }
+            ForIn { ref left, ref right, ref body, ref trivia } => {
+                output.write("for")?;
+                output.write(&trivia.head_gap)?;
+                output.write("(")?;
+                left.write(output)?;
+                output.write(&trivia.operator_prefix)?;
+                output.write("in")?;
+                right.write(output)?;
+                output.write(&trivia.head_suffix)?;
+                output.write(")")?;
+                body.write(output)?;
+            }
+            ForOf { ref left, ref right, ref body, is_await, ref trivia } => {
+                output.write("for")?;
+                if is_await {
+                    output.write(&trivia.await_prefix)?;
+                    output.write("await")?;
+                }
+                output.write(&trivia.head_gap)?;
+                output.write("(")?;
+                left.write(output)?;
+                output.write(&trivia.operator_prefix)?;
+                output.write("of")?;
+                right.write(output)?;
+                output.write(&trivia.head_suffix)?;
+                output.write(")")?;
+                body.write(output)?;
+            }
+        }
+        output.write(&self.trivia.suffix)?;
+        return output.write(&self.terminator.to_string());
+    }
+}
+
+impl Node for ModuleSpecifier {
+    fn write(&self, output: &mut CodeWriter) -> fmt::Result {
+        output.write(&self.trivia.prefix)?;
+        output.mark(&self.location, Some(&self.name));
+        output.write(&self.name)?;
+        if let Some(ref alias) = self.alias {
+            output.write(&self.trivia.as_prefix)?;
+            output.write("as")?;
+            output.write(&self.trivia.alias_prefix)?;
+            output.write(alias)?;
+        }
+        return output.write(&self.trivia.suffix);
     }
-}
\ No newline at end of file
+}
+
+fn write_specifiers(specifiers: &Vec<ModuleSpecifier>, output: &mut CodeWriter) -> fmt::Result {
+    for (i, specifier) in specifiers.iter().enumerate() {
+        if i > 0 {
+            output.write(",")?;
+        }
+        specifier.write(output)?;
+    }
+    return Ok(());
+}
@@ -1,20 +1,46 @@
 use super::body::BodyNode;
-use super::Node;
+use super::{Node, Comment, CodeWriter};
+use super::super::parser::SourceType;
+
+use std::fmt;
 
 #[derive(Debug, PartialEq, Clone)]
 pub struct SyntaxTree {
+    pub hashbang: Option<String>,
     pub base_node: Box<BodyNode>,
+    pub suffix: String,
+    /// Whether the tree was parsed as a script or as a module.
+    pub source_type: SourceType,
+    /// Every comment of the source, in order. Use a `CommentMap` to find the nodes they belong to.
+    pub comments: Vec<Comment>,
 }
 
 impl SyntaxTree {
     pub fn new(base_node: BodyNode) -> Self {
-        return SyntaxTree { base_node: Box::new(base_node) };
+        return SyntaxTree { hashbang: None, base_node: Box::new(base_node), suffix: String::new(), source_type: SourceType::Module, comments: Vec::new() };
+    }
+
+    /// Appends a directive to the prologue of the tree like `BodyNode::insert_directive`, keeping it
+    /// off the line of the hashbang.
+    pub fn insert_directive(&mut self, value: &str) {
+        self.base_node.insert_directive(value);
+        if self.hashbang.is_some() {
+            if let Some(first) = self.base_node.directives.first_mut() {
+                if !first.trivia.prefix.contains('\n') {
+                    first.trivia.prefix.insert(0, '\n');
+                }
+            }
+        }
     }
 }
 
 impl Node for SyntaxTree {
-    fn generate(&self) -> String {
-        return self.base_node.generate();
+    fn write(&self, output: &mut CodeWriter) -> fmt::Result {
+        if let Some(ref hashbang) = self.hashbang {
+            output.write(hashbang)?;
+        }
+        self.base_node.write(output)?;
+        return output.write(&self.suffix);
     }
 }
 
@@ -0,0 +1,360 @@
+//! Traversal with context, for transforms which need to know where a node is. Unlike `VisitMut`,
+//! hooks are given a `NodePath` along with the node: its ancestors, the function and scope
+//! containing it and, for statements of a body, their siblings. Through the path hooks replace,
+//! remove or insert statements, and skip the children of a node or stop the traversal.
+//!
+//! Statement edits are made to the statement being visited or, in expression hooks, to the
+//! statement containing the expression. They are applied once the hook returns. Statements
+//! inserted or put in place of another are not traversed.
+
+use super::{SyntaxTree, SourceLocation, NodeTrivia};
+use super::body::BodyNode;
+use super::statement::{StatementNode, Statement, StatementTerminator, BlockTrivia, ExportDeclaration};
+use super::expression::{ExpressionNode, Expression};
+use super::property::PropertyNode;
+use super::visit_mut::{VisitMut, walk_expression, walk_property};
+use super::super::scope::{ScopeTree, ScopeId, ScopeKind};
+
+use std::mem;
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum AncestorKind {
+    Statement,
+    Expression,
+    /// A function or arrow function expression, including those of declarations and methods.
+    Function,
+    Class,
+    /// A property of an object literal or a member of a class.
+    Property,
+}
+
+/// A node enclosing the one being visited.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Ancestor {
+    pub kind: AncestorKind,
+    pub location: SourceLocation,
+}
+
+pub trait Traverse {
+    fn enter_statement(&mut self, _statement: &mut StatementNode, _path: &mut NodePath) {}
+    fn exit_statement(&mut self, _statement: &mut StatementNode, _path: &mut NodePath) {}
+    fn enter_expression(&mut self, _expression: &mut ExpressionNode, _path: &mut NodePath) {}
+    fn exit_expression(&mut self, _expression: &mut ExpressionNode, _path: &mut NodePath) {}
+}
+
+pub fn traverse<T: Traverse + ?Sized>(visitor: &mut T, tree: &mut SyntaxTree) {
+    let mut traverser = Traverser { visitor, ancestors: Vec::new(), edits: vec![Edits::new()], stopped: false };
+    traverser.visit_body(&mut tree.base_node);
+}
+
+/// Statement edits requested by the hooks of a statement and of the expressions inside of it.
+struct Edits {
+    before: Vec<StatementNode>,
+    after: Vec<StatementNode>,
+    replacement: Option<Vec<StatementNode>>,
+}
+
+impl Edits {
+    fn new() -> Self {
+        return Edits { before: Vec::new(), after: Vec::new(), replacement: None };
+    }
+
+    fn is_empty(&self) -> bool {
+        return self.before.len() == 0 && self.after.len() == 0 && self.replacement.is_none();
+    }
+}
+
+/// Where a node is in the tree, given to the hooks of `Traverse`.
+pub struct NodePath<'a> {
+    ancestors: &'a [Ancestor],
+    location: SourceLocation,
+    previous: &'a [StatementNode],
+    next: &'a [StatementNode],
+    edits: &'a mut Edits,
+    skipped: bool,
+    stopped: bool,
+}
+
+impl<'a> NodePath<'a> {
+    /// The location of the node, from before any hook changed it.
+    pub fn location(&self) -> &SourceLocation {
+        return &self.location;
+    }
+
+    /// The nodes enclosing this one, outermost first.
+    pub fn ancestors(&self) -> &[Ancestor] {
+        return self.ancestors;
+    }
+
+    pub fn parent(&self) -> Option<&Ancestor> {
+        return self.ancestors.last();
+    }
+
+    /// The closest function enclosing the node, `None` at the top level.
+    pub fn function(&self) -> Option<&Ancestor> {
+        return self.ancestors.iter().rev().find(|ancestor| ancestor.kind == AncestorKind::Function);
+    }
+
+    /// The scope the node is in, given the scopes of the tree as it was before the traversal.
+    pub fn scope(&self, scopes: &ScopeTree) -> ScopeId {
+        let unlocated = SourceLocation::default();
+        for ancestor in self.ancestors.iter().rev().filter(|ancestor| ancestor.location != unlocated) {
+            let scope = scopes.scopes.iter().rposition(|scope| scope.location == ancestor.location);
+            if let Some(scope) = scope {
+                return scope;
+            }
+        }
+        let root = scopes.root();
+        return scopes.scope(root).children.iter().cloned().find(|&child| scopes.scope(child).kind == ScopeKind::Module).unwrap_or(root);
+    }
+
+    /// The statements before this one in its body, in order. Empty for statements which are not
+    /// in a body and in expression hooks.
+    pub fn previous_siblings(&self) -> &[StatementNode] {
+        return self.previous;
+    }
+
+    /// The statements after this one in its body, in order.
+    pub fn next_siblings(&self) -> &[StatementNode] {
+        return self.next;
+    }
+
+    /// Replaces the statement with others. A statement which is not in a body, like the body of a
+    /// `for` loop, is replaced with a block when given more than one.
+    pub fn replace_with(&mut self, statements: Vec<StatementNode>) {
+        self.edits.replacement = Some(statements);
+    }
+
+    pub fn remove(&mut self) {
+        self.replace_with(Vec::new());
+    }
+
+    pub fn insert_before(&mut self, statements: Vec<StatementNode>) {
+        self.edits.before.extend(statements);
+    }
+
+    pub fn insert_after(&mut self, statements: Vec<StatementNode>) {
+        self.edits.after.extend(statements);
+    }
+
+    /// Leaves out the children of the node. Its exit hook still runs.
+    pub fn skip(&mut self) {
+        self.skipped = true;
+    }
+
+    /// Ends the traversal once the hook returns, no other hook runs. Edits made so far are still
+    /// applied.
+    pub fn stop(&mut self) {
+        self.stopped = true;
+    }
+}
+
+struct Traverser<'v, T: Traverse + ?Sized + 'v> {
+    visitor: &'v mut T,
+    ancestors: Vec<Ancestor>,
+    /// Edits of the statements being visited, innermost last.
+    edits: Vec<Edits>,
+    stopped: bool,
+}
+
+impl<'v, T: Traverse + ?Sized + 'v> Traverser<'v, T> {
+    /// Visits a statement with its own edits, returning them. Replaced statements are not walked.
+    fn statement(&mut self, statement: &mut StatementNode, previous: &[StatementNode], next: &[StatementNode]) -> Edits {
+        self.edits.push(Edits::new());
+
+        let location = statement.location.clone();
+        let skipped = {
+            let mut path = NodePath { ancestors: &self.ancestors, location: location.clone(), previous, next, edits: self.edits.last_mut().unwrap(), skipped: false, stopped: false };
+            self.visitor.enter_statement(statement, &mut path);
+            self.stopped |= path.stopped;
+            path.skipped
+        };
+
+        let replaced = self.edits.last().unwrap().replacement.is_some();
+        if !replaced && !skipped && !self.stopped {
+            self.ancestors.push(Ancestor { kind: AncestorKind::Statement, location: location.clone() });
+            self.walk_statement(statement);
+            self.ancestors.pop();
+        }
+
+        if !replaced && !self.stopped {
+            let mut path = NodePath { ancestors: &self.ancestors, location, previous, next, edits: self.edits.last_mut().unwrap(), skipped: false, stopped: false };
+            self.visitor.exit_statement(statement, &mut path);
+            self.stopped |= path.stopped;
+        }
+
+        return self.edits.pop().unwrap();
+    }
+
+    /// Visits a statement standing alone, like the body of a loop. Edits turn it into a block.
+    fn nested_statement(&mut self, statement: &mut StatementNode) {
+        let edits = self.statement(statement, &[], &[]);
+        if edits.is_empty() {
+            return;
+        }
+
+        let Edits { before, after, replacement } = edits;
+        let mut original = mem::replace(statement, StatementNode::new(Statement::Empty, NodeTrivia::new(), StatementTerminator::Semicolon));
+        let prefix = mem::replace(&mut original.trivia.prefix, String::new());
+
+        let mut statements = before;
+        statements.extend(replacement.unwrap_or_else(|| vec![original]));
+        statements.extend(after);
+        match statements.len() {
+            0 => {}
+            1 => *statement = statements.remove(0),
+            _ => {
+                let mut body = BodyNode::new(Vec::new());
+                body.insert_statements(0, statements);
+                if let Some(first) = body.content.first_mut() {
+                    first.trivia.prefix.insert(0, '\n');
+                }
+                let block = Statement::Block { body, trivia: BlockTrivia { body_suffix: String::from("\n") } };
+                *statement = StatementNode::new(block, NodeTrivia::new(), StatementTerminator::Block);
+            }
+        }
+        statement.trivia.prefix = prefix;
+    }
+
+    /// Walks the children of a statement. Statements in the head of a `for` loop or exported by an
+    /// `export` are part of it, so their edits apply to the loop or export.
+    fn walk_statement(&mut self, statement: &mut StatementNode) {
+        use self::Statement::*;
+
+        match statement.statement {
+            Empty | Import { .. } => {}
+            Expression { ref mut expression } => self.visit_expression(expression),
+            Declaration { ref mut declarations, .. } => {
+                for declaration in declarations {
+                    self.visit_declaration(declaration);
+                }
+            }
+            Return { ref mut expression } => {
+                if let Some(ref mut expression) = *expression {
+                    self.visit_expression(expression);
+                }
+            }
+            Export { ref mut declaration, .. } => {
+                match *declaration {
+                    ExportDeclaration::Default(ref mut expression) => self.visit_expression(expression),
+                    ExportDeclaration::Statement(ref mut statement) => self.head_statement(statement),
+                    ExportDeclaration::Named(_) => {}
+                }
+            }
+            With { ref mut object, ref mut body, .. } => {
+                self.visit_expression(object);
+                self.nested_statement(body);
+            }
+            Block { ref mut body, .. } => self.visit_body(body),
+            For { ref mut init, ref mut test, ref mut update, ref mut body, .. } => {
+                if let Some(ref mut init) = *init {
+                    self.head_statement(init);
+                }
+                if let Some(ref mut test) = *test {
+                    self.visit_expression(test);
+                }
+                if let Some(ref mut update) = *update {
+                    self.visit_expression(update);
+                }
+                self.nested_statement(body);
+            }
+            ForIn { ref mut left, ref mut right, ref mut body, .. } | ForOf { ref mut left, ref mut right, ref mut body, .. } => {
+                self.head_statement(left);
+                self.visit_expression(right);
+                self.nested_statement(body);
+            }
+        }
+    }
+
+    /// Visits a statement which is part of another, sharing its edits.
+    fn head_statement(&mut self, statement: &mut StatementNode) {
+        let edits = self.statement(statement, &[], &[]);
+        let parent = self.edits.last_mut().unwrap();
+        parent.before.extend(edits.before);
+        parent.after.extend(edits.after);
+        if let Some(replacement) = edits.replacement {
+            parent.replacement = Some(replacement);
+        }
+    }
+}
+
+impl<'v, T: Traverse + ?Sized + 'v> VisitMut for Traverser<'v, T> {
+    fn visit_body(&mut self, body: &mut BodyNode) {
+        let mut index = 0;
+        while index < body.content.len() && !self.stopped {
+            let edits = {
+                let (previous, rest) = body.content.split_at_mut(index);
+                let (statement, next) = rest.split_first_mut().unwrap();
+                self.statement(statement, previous, next)
+            };
+
+            let Edits { before, after, replacement } = edits;
+            let mut count = 1;
+            if let Some(mut replacement) = replacement {
+                let removed = body.content.remove(index);
+                count = replacement.len();
+                match replacement.first_mut() {
+                    Some(first) => first.trivia.prefix = removed.trivia.prefix,
+                    None if index == 0 && !removed.trivia.prefix.contains('\n') => {
+                        if let Some(next) = body.content.first_mut() {
+                            next.trivia.prefix = next.trivia.prefix.trim_start_matches('\n').to_owned();
+                        }
+                    }
+                    None => {}
+                }
+                body.insert_statements(index, replacement);
+            }
+            let inserted = before.len() + after.len();
+            body.insert_statements(index + count, after);
+            body.insert_statements(index, before);
+            index += count + inserted;
+        }
+    }
+
+    fn visit_statement(&mut self, statement: &mut StatementNode) {
+        if !self.stopped {
+            self.nested_statement(statement);
+        }
+    }
+
+    fn visit_expression(&mut self, expression: &mut ExpressionNode) {
+        if self.stopped {
+            return;
+        }
+
+        let location = expression.location.clone();
+        let skipped = {
+            let mut path = NodePath { ancestors: &self.ancestors, location: location.clone(), previous: &[], next: &[], edits: self.edits.last_mut().unwrap(), skipped: false, stopped: false };
+            self.visitor.enter_expression(expression, &mut path);
+            self.stopped |= path.stopped;
+            path.skipped
+        };
+
+        if !skipped && !self.stopped {
+            let kind = match expression.expression {
+                Expression::Function { .. } | Expression::ArrowFunction { .. } => AncestorKind::Function,
+                Expression::Class { .. } => AncestorKind::Class,
+                _ => AncestorKind::Expression
+            };
+            self.ancestors.push(Ancestor { kind, location: location.clone() });
+            walk_expression(self, expression);
+            self.ancestors.pop();
+        }
+
+        if self.stopped {
+            return;
+        }
+        let mut path = NodePath { ancestors: &self.ancestors, location, previous: &[], next: &[], edits: self.edits.last_mut().unwrap(), skipped: false, stopped: false };
+        self.visitor.exit_expression(expression, &mut path);
+        self.stopped |= path.stopped;
+    }
+
+    fn visit_property(&mut self, property: &mut PropertyNode) {
+        if self.stopped {
+            return;
+        }
+        self.ancestors.push(Ancestor { kind: AncestorKind::Property, location: property.location.clone() });
+        walk_property(self, property);
+        self.ancestors.pop();
+    }
+}
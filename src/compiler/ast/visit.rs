@@ -0,0 +1,320 @@
+//! Read-only traversal of a syntax tree. Every node type has an `enter_*` and `exit_*` hook, called
+//! before and after its children, and a `visit_*` method which calls both around the matching
+//! `walk_*` function. Override the hooks to observe nodes, or `visit_*` to change how a subtree is
+//! walked, for example to skip it.
+//!
+//! `VisitMut` in `visit_mut` is the same traversal over mutable references.
+
+use super::{SyntaxTree, Parameter};
+use super::body::BodyNode;
+use super::directive::DirectiveNode;
+use super::statement::{StatementNode, Statement, ImportDeclaration, ExportDeclaration, ModuleSpecifier};
+use super::declaration::DeclarationNode;
+use super::expression::{ExpressionNode, Expression};
+use super::property::{PropertyNode, PropertyKey};
+use super::jsx::{JsxElement, JsxAttribute, JsxAttributeValue, JsxChild};
+
+pub trait Visit {
+    fn enter_syntax_tree(&mut self, _tree: &SyntaxTree) {}
+    fn exit_syntax_tree(&mut self, _tree: &SyntaxTree) {}
+    fn visit_syntax_tree(&mut self, tree: &SyntaxTree) {
+        self.enter_syntax_tree(tree);
+        walk_syntax_tree(self, tree);
+        self.exit_syntax_tree(tree);
+    }
+
+    fn enter_body(&mut self, _body: &BodyNode) {}
+    fn exit_body(&mut self, _body: &BodyNode) {}
+    fn visit_body(&mut self, body: &BodyNode) {
+        self.enter_body(body);
+        walk_body(self, body);
+        self.exit_body(body);
+    }
+
+    fn enter_directive(&mut self, _directive: &DirectiveNode) {}
+    fn exit_directive(&mut self, _directive: &DirectiveNode) {}
+    fn visit_directive(&mut self, directive: &DirectiveNode) {
+        self.enter_directive(directive);
+        self.exit_directive(directive);
+    }
+
+    fn enter_statement(&mut self, _statement: &StatementNode) {}
+    fn exit_statement(&mut self, _statement: &StatementNode) {}
+    fn visit_statement(&mut self, statement: &StatementNode) {
+        self.enter_statement(statement);
+        walk_statement(self, statement);
+        self.exit_statement(statement);
+    }
+
+    fn enter_declaration(&mut self, _declaration: &DeclarationNode) {}
+    fn exit_declaration(&mut self, _declaration: &DeclarationNode) {}
+    fn visit_declaration(&mut self, declaration: &DeclarationNode) {
+        self.enter_declaration(declaration);
+        walk_declaration(self, declaration);
+        self.exit_declaration(declaration);
+    }
+
+    fn enter_module_specifier(&mut self, _specifier: &ModuleSpecifier) {}
+    fn exit_module_specifier(&mut self, _specifier: &ModuleSpecifier) {}
+    fn visit_module_specifier(&mut self, specifier: &ModuleSpecifier) {
+        self.enter_module_specifier(specifier);
+        self.exit_module_specifier(specifier);
+    }
+
+    fn enter_expression(&mut self, _expression: &ExpressionNode) {}
+    fn exit_expression(&mut self, _expression: &ExpressionNode) {}
+    fn visit_expression(&mut self, expression: &ExpressionNode) {
+        self.enter_expression(expression);
+        walk_expression(self, expression);
+        self.exit_expression(expression);
+    }
+
+    fn enter_parameter(&mut self, _parameter: &Parameter) {}
+    fn exit_parameter(&mut self, _parameter: &Parameter) {}
+    fn visit_parameter(&mut self, parameter: &Parameter) {
+        self.enter_parameter(parameter);
+        self.exit_parameter(parameter);
+    }
+
+    fn enter_property(&mut self, _property: &PropertyNode) {}
+    fn exit_property(&mut self, _property: &PropertyNode) {}
+    fn visit_property(&mut self, property: &PropertyNode) {
+        self.enter_property(property);
+        walk_property(self, property);
+        self.exit_property(property);
+    }
+
+    fn enter_property_key(&mut self, _key: &PropertyKey) {}
+    fn exit_property_key(&mut self, _key: &PropertyKey) {}
+    fn visit_property_key(&mut self, key: &PropertyKey) {
+        self.enter_property_key(key);
+        walk_property_key(self, key);
+        self.exit_property_key(key);
+    }
+
+    fn enter_jsx_element(&mut self, _element: &JsxElement) {}
+    fn exit_jsx_element(&mut self, _element: &JsxElement) {}
+    fn visit_jsx_element(&mut self, element: &JsxElement) {
+        self.enter_jsx_element(element);
+        walk_jsx_element(self, element);
+        self.exit_jsx_element(element);
+    }
+
+    fn enter_jsx_attribute(&mut self, _attribute: &JsxAttribute) {}
+    fn exit_jsx_attribute(&mut self, _attribute: &JsxAttribute) {}
+    fn visit_jsx_attribute(&mut self, attribute: &JsxAttribute) {
+        self.enter_jsx_attribute(attribute);
+        walk_jsx_attribute(self, attribute);
+        self.exit_jsx_attribute(attribute);
+    }
+
+    fn enter_jsx_child(&mut self, _child: &JsxChild) {}
+    fn exit_jsx_child(&mut self, _child: &JsxChild) {}
+    fn visit_jsx_child(&mut self, child: &JsxChild) {
+        self.enter_jsx_child(child);
+        walk_jsx_child(self, child);
+        self.exit_jsx_child(child);
+    }
+}
+
+pub fn walk_syntax_tree<V: Visit + ?Sized>(visitor: &mut V, tree: &SyntaxTree) {
+    visitor.visit_body(&tree.base_node);
+}
+
+pub fn walk_body<V: Visit + ?Sized>(visitor: &mut V, body: &BodyNode) {
+    for directive in &body.directives {
+        visitor.visit_directive(directive);
+    }
+    for statement in &body.content {
+        visitor.visit_statement(statement);
+    }
+}
+
+pub fn walk_statement<V: Visit + ?Sized>(visitor: &mut V, statement: &StatementNode) {
+    use self::Statement::*;
+
+    match statement.statement {
+        Empty => {}
+        Expression { ref expression } => visitor.visit_expression(expression),
+        Declaration { ref declarations, .. } => {
+            for declaration in declarations {
+                visitor.visit_declaration(declaration);
+            }
+        }
+        Return { ref expression } => {
+            if let Some(ref expression) = *expression {
+                visitor.visit_expression(expression);
+            }
+        }
+        Import { ref declaration, .. } => {
+            if let ImportDeclaration::Multiple(ref specifiers) = *declaration {
+                for specifier in specifiers {
+                    visitor.visit_module_specifier(specifier);
+                }
+            }
+        }
+        Export { ref declaration, .. } => {
+            match *declaration {
+                ExportDeclaration::Default(ref expression) => visitor.visit_expression(expression),
+                ExportDeclaration::Statement(ref statement) => visitor.visit_statement(statement),
+                ExportDeclaration::Named(ref specifiers) => {
+                    for specifier in specifiers {
+                        visitor.visit_module_specifier(specifier);
+                    }
+                }
+            }
+        }
+        With { ref object, ref body, .. } => {
+            visitor.visit_expression(object);
+            visitor.visit_statement(body);
+        }
+        Block { ref body, .. } => visitor.visit_body(body),
+        For { ref init, ref test, ref update, ref body, .. } => {
+            if let Some(ref init) = *init {
+                visitor.visit_statement(init);
+            }
+            if let Some(ref test) = *test {
+                visitor.visit_expression(test);
+            }
+            if let Some(ref update) = *update {
+                visitor.visit_expression(update);
+            }
+            visitor.visit_statement(body);
+        }
+        ForIn { ref left, ref right, ref body, .. } | ForOf { ref left, ref right, ref body, .. } => {
+            visitor.visit_statement(left);
+            visitor.visit_expression(right);
+            visitor.visit_statement(body);
+        }
+    }
+}
+
+pub fn walk_declaration<V: Visit + ?Sized>(visitor: &mut V, declaration: &DeclarationNode) {
+    if let Some(ref expression) = declaration.expression {
+        visitor.visit_expression(expression);
+    }
+}
+
+pub fn walk_expression<V: Visit + ?Sized>(visitor: &mut V, expression: &ExpressionNode) {
+    use self::Expression::*;
+
+    match expression.expression {
+        Void | This | Super | Identifier(_) | Literal(_) => {}
+        Bracketed { ref expression, .. } => visitor.visit_expression(expression),
+        Function { ref parameters, ref body, .. } | ArrowFunction { ref parameters, ref body, .. } => {
+            for parameter in parameters {
+                visitor.visit_parameter(parameter);
+            }
+            visitor.visit_body(body);
+        }
+        Call { ref callee, ref parameters, .. } => {
+            visitor.visit_expression(callee);
+            for parameter in parameters {
+                visitor.visit_expression(parameter);
+            }
+        }
+        New { ref callee, ref parameters, .. } => {
+            visitor.visit_expression(callee);
+            if let Some(ref parameters) = *parameters {
+                for parameter in parameters {
+                    visitor.visit_expression(parameter);
+                }
+            }
+        }
+        Member { ref object, .. } => visitor.visit_expression(object),
+        ComputedMember { ref object, ref property, .. } => {
+            visitor.visit_expression(object);
+            visitor.visit_expression(property);
+        }
+        Unary { ref argument, .. } | Update { ref argument, .. } | Await { ref argument } => visitor.visit_expression(argument),
+        Binary { ref left, ref right, .. } | Assignment { ref left, ref right, .. } => {
+            visitor.visit_expression(left);
+            visitor.visit_expression(right);
+        }
+        Yield { ref argument, .. } => {
+            if let Some(ref argument) = *argument {
+                visitor.visit_expression(argument);
+            }
+        }
+        Object { ref properties, .. } => {
+            for property in properties {
+                visitor.visit_property(property);
+            }
+        }
+        Array { ref elements, .. } => {
+            for element in elements {
+                if let Some(ref element) = *element {
+                    visitor.visit_expression(element);
+                }
+            }
+        }
+        Class { ref super_class, ref members, .. } => {
+            if let Some(ref super_class) = *super_class {
+                visitor.visit_expression(super_class);
+            }
+            for member in members {
+                visitor.visit_property(member);
+            }
+        }
+        Conditional { ref test, ref consequent, ref alternate, .. } => {
+            visitor.visit_expression(test);
+            visitor.visit_expression(consequent);
+            visitor.visit_expression(alternate);
+        }
+        Jsx(ref element) => visitor.visit_jsx_element(element),
+    }
+}
+
+pub fn walk_property<V: Visit + ?Sized>(visitor: &mut V, property: &PropertyNode) {
+    visitor.visit_property_key(&property.key);
+    if let Some(ref value) = property.value {
+        visitor.visit_expression(value);
+    }
+}
+
+pub fn walk_property_key<V: Visit + ?Sized>(visitor: &mut V, key: &PropertyKey) {
+    if let PropertyKey::Computed(ref expression) = *key {
+        visitor.visit_expression(expression);
+    }
+}
+
+pub fn walk_jsx_element<V: Visit + ?Sized>(visitor: &mut V, element: &JsxElement) {
+    for attribute in &element.attributes {
+        visitor.visit_jsx_attribute(attribute);
+    }
+    if let Some(ref children) = element.children {
+        for child in children {
+            visitor.visit_jsx_child(child);
+        }
+    }
+}
+
+pub fn walk_jsx_attribute<V: Visit + ?Sized>(visitor: &mut V, attribute: &JsxAttribute) {
+    match *attribute {
+        JsxAttribute::Named { ref value, .. } => {
+            match *value {
+                Some(JsxAttributeValue::Container(ref container)) => {
+                    if let Some(ref expression) = container.expression {
+                        visitor.visit_expression(expression);
+                    }
+                }
+                Some(JsxAttributeValue::Element(ref element)) => visitor.visit_expression(element),
+                Some(JsxAttributeValue::String(..)) | None => {}
+            }
+        }
+        JsxAttribute::Spread { ref argument, .. } => visitor.visit_expression(argument),
+    }
+}
+
+pub fn walk_jsx_child<V: Visit + ?Sized>(visitor: &mut V, child: &JsxChild) {
+    match *child {
+        JsxChild::Text(_) => {}
+        JsxChild::Container(ref container) => {
+            if let Some(ref expression) = container.expression {
+                visitor.visit_expression(expression);
+            }
+        }
+        JsxChild::Spread { ref argument, .. } => visitor.visit_expression(argument),
+        JsxChild::Element(ref element) => visitor.visit_expression(element),
+    }
+}
@@ -0,0 +1,317 @@
+//! Mutable traversal of a syntax tree, the same as `Visit` in `visit` but over mutable references so
+//! hooks may rewrite the nodes they are given. `exit_*` hooks run after the children of a node have
+//! been visited, so replacing a node there works bottom-up.
+
+use super::{SyntaxTree, Parameter};
+use super::body::BodyNode;
+use super::directive::DirectiveNode;
+use super::statement::{StatementNode, Statement, ImportDeclaration, ExportDeclaration, ModuleSpecifier};
+use super::declaration::DeclarationNode;
+use super::expression::{ExpressionNode, Expression};
+use super::property::{PropertyNode, PropertyKey};
+use super::jsx::{JsxElement, JsxAttribute, JsxAttributeValue, JsxChild};
+
+pub trait VisitMut {
+    fn enter_syntax_tree(&mut self, _tree: &mut SyntaxTree) {}
+    fn exit_syntax_tree(&mut self, _tree: &mut SyntaxTree) {}
+    fn visit_syntax_tree(&mut self, tree: &mut SyntaxTree) {
+        self.enter_syntax_tree(tree);
+        walk_syntax_tree(self, tree);
+        self.exit_syntax_tree(tree);
+    }
+
+    fn enter_body(&mut self, _body: &mut BodyNode) {}
+    fn exit_body(&mut self, _body: &mut BodyNode) {}
+    fn visit_body(&mut self, body: &mut BodyNode) {
+        self.enter_body(body);
+        walk_body(self, body);
+        self.exit_body(body);
+    }
+
+    fn enter_directive(&mut self, _directive: &mut DirectiveNode) {}
+    fn exit_directive(&mut self, _directive: &mut DirectiveNode) {}
+    fn visit_directive(&mut self, directive: &mut DirectiveNode) {
+        self.enter_directive(directive);
+        self.exit_directive(directive);
+    }
+
+    fn enter_statement(&mut self, _statement: &mut StatementNode) {}
+    fn exit_statement(&mut self, _statement: &mut StatementNode) {}
+    fn visit_statement(&mut self, statement: &mut StatementNode) {
+        self.enter_statement(statement);
+        walk_statement(self, statement);
+        self.exit_statement(statement);
+    }
+
+    fn enter_declaration(&mut self, _declaration: &mut DeclarationNode) {}
+    fn exit_declaration(&mut self, _declaration: &mut DeclarationNode) {}
+    fn visit_declaration(&mut self, declaration: &mut DeclarationNode) {
+        self.enter_declaration(declaration);
+        walk_declaration(self, declaration);
+        self.exit_declaration(declaration);
+    }
+
+    fn enter_module_specifier(&mut self, _specifier: &mut ModuleSpecifier) {}
+    fn exit_module_specifier(&mut self, _specifier: &mut ModuleSpecifier) {}
+    fn visit_module_specifier(&mut self, specifier: &mut ModuleSpecifier) {
+        self.enter_module_specifier(specifier);
+        self.exit_module_specifier(specifier);
+    }
+
+    fn enter_expression(&mut self, _expression: &mut ExpressionNode) {}
+    fn exit_expression(&mut self, _expression: &mut ExpressionNode) {}
+    fn visit_expression(&mut self, expression: &mut ExpressionNode) {
+        self.enter_expression(expression);
+        walk_expression(self, expression);
+        self.exit_expression(expression);
+    }
+
+    fn enter_parameter(&mut self, _parameter: &mut Parameter) {}
+    fn exit_parameter(&mut self, _parameter: &mut Parameter) {}
+    fn visit_parameter(&mut self, parameter: &mut Parameter) {
+        self.enter_parameter(parameter);
+        self.exit_parameter(parameter);
+    }
+
+    fn enter_property(&mut self, _property: &mut PropertyNode) {}
+    fn exit_property(&mut self, _property: &mut PropertyNode) {}
+    fn visit_property(&mut self, property: &mut PropertyNode) {
+        self.enter_property(property);
+        walk_property(self, property);
+        self.exit_property(property);
+    }
+
+    fn enter_property_key(&mut self, _key: &mut PropertyKey) {}
+    fn exit_property_key(&mut self, _key: &mut PropertyKey) {}
+    fn visit_property_key(&mut self, key: &mut PropertyKey) {
+        self.enter_property_key(key);
+        walk_property_key(self, key);
+        self.exit_property_key(key);
+    }
+
+    fn enter_jsx_element(&mut self, _element: &mut JsxElement) {}
+    fn exit_jsx_element(&mut self, _element: &mut JsxElement) {}
+    fn visit_jsx_element(&mut self, element: &mut JsxElement) {
+        self.enter_jsx_element(element);
+        walk_jsx_element(self, element);
+        self.exit_jsx_element(element);
+    }
+
+    fn enter_jsx_attribute(&mut self, _attribute: &mut JsxAttribute) {}
+    fn exit_jsx_attribute(&mut self, _attribute: &mut JsxAttribute) {}
+    fn visit_jsx_attribute(&mut self, attribute: &mut JsxAttribute) {
+        self.enter_jsx_attribute(attribute);
+        walk_jsx_attribute(self, attribute);
+        self.exit_jsx_attribute(attribute);
+    }
+
+    fn enter_jsx_child(&mut self, _child: &mut JsxChild) {}
+    fn exit_jsx_child(&mut self, _child: &mut JsxChild) {}
+    fn visit_jsx_child(&mut self, child: &mut JsxChild) {
+        self.enter_jsx_child(child);
+        walk_jsx_child(self, child);
+        self.exit_jsx_child(child);
+    }
+}
+
+pub fn walk_syntax_tree<V: VisitMut + ?Sized>(visitor: &mut V, tree: &mut SyntaxTree) {
+    visitor.visit_body(&mut tree.base_node);
+}
+
+pub fn walk_body<V: VisitMut + ?Sized>(visitor: &mut V, body: &mut BodyNode) {
+    for directive in &mut body.directives {
+        visitor.visit_directive(directive);
+    }
+    for statement in &mut body.content {
+        visitor.visit_statement(statement);
+    }
+}
+
+pub fn walk_statement<V: VisitMut + ?Sized>(visitor: &mut V, statement: &mut StatementNode) {
+    use self::Statement::*;
+
+    match statement.statement {
+        Empty => {}
+        Expression { ref mut expression } => visitor.visit_expression(expression),
+        Declaration { ref mut declarations, .. } => {
+            for declaration in declarations {
+                visitor.visit_declaration(declaration);
+            }
+        }
+        Return { ref mut expression } => {
+            if let Some(ref mut expression) = *expression {
+                visitor.visit_expression(expression);
+            }
+        }
+        Import { ref mut declaration, .. } => {
+            if let ImportDeclaration::Multiple(ref mut specifiers) = *declaration {
+                for specifier in specifiers {
+                    visitor.visit_module_specifier(specifier);
+                }
+            }
+        }
+        Export { ref mut declaration, .. } => {
+            match *declaration {
+                ExportDeclaration::Default(ref mut expression) => visitor.visit_expression(expression),
+                ExportDeclaration::Statement(ref mut statement) => visitor.visit_statement(statement),
+                ExportDeclaration::Named(ref mut specifiers) => {
+                    for specifier in specifiers {
+                        visitor.visit_module_specifier(specifier);
+                    }
+                }
+            }
+        }
+        With { ref mut object, ref mut body, .. } => {
+            visitor.visit_expression(object);
+            visitor.visit_statement(body);
+        }
+        Block { ref mut body, .. } => visitor.visit_body(body),
+        For { ref mut init, ref mut test, ref mut update, ref mut body, .. } => {
+            if let Some(ref mut init) = *init {
+                visitor.visit_statement(init);
+            }
+            if let Some(ref mut test) = *test {
+                visitor.visit_expression(test);
+            }
+            if let Some(ref mut update) = *update {
+                visitor.visit_expression(update);
+            }
+            visitor.visit_statement(body);
+        }
+        ForIn { ref mut left, ref mut right, ref mut body, .. } | ForOf { ref mut left, ref mut right, ref mut body, .. } => {
+            visitor.visit_statement(left);
+            visitor.visit_expression(right);
+            visitor.visit_statement(body);
+        }
+    }
+}
+
+pub fn walk_declaration<V: VisitMut + ?Sized>(visitor: &mut V, declaration: &mut DeclarationNode) {
+    if let Some(ref mut expression) = declaration.expression {
+        visitor.visit_expression(expression);
+    }
+}
+
+pub fn walk_expression<V: VisitMut + ?Sized>(visitor: &mut V, expression: &mut ExpressionNode) {
+    use self::Expression::*;
+
+    match expression.expression {
+        Void | This | Super | Identifier(_) | Literal(_) => {}
+        Bracketed { ref mut expression, .. } => visitor.visit_expression(expression),
+        Function { ref mut parameters, ref mut body, .. } | ArrowFunction { ref mut parameters, ref mut body, .. } => {
+            for parameter in parameters {
+                visitor.visit_parameter(parameter);
+            }
+            visitor.visit_body(body);
+        }
+        Call { ref mut callee, ref mut parameters, .. } => {
+            visitor.visit_expression(callee);
+            for parameter in parameters {
+                visitor.visit_expression(parameter);
+            }
+        }
+        New { ref mut callee, ref mut parameters, .. } => {
+            visitor.visit_expression(callee);
+            if let Some(ref mut parameters) = *parameters {
+                for parameter in parameters {
+                    visitor.visit_expression(parameter);
+                }
+            }
+        }
+        Member { ref mut object, .. } => visitor.visit_expression(object),
+        ComputedMember { ref mut object, ref mut property, .. } => {
+            visitor.visit_expression(object);
+            visitor.visit_expression(property);
+        }
+        Unary { ref mut argument, .. } | Update { ref mut argument, .. } | Await { ref mut argument } => visitor.visit_expression(argument),
+        Binary { ref mut left, ref mut right, .. } | Assignment { ref mut left, ref mut right, .. } => {
+            visitor.visit_expression(left);
+            visitor.visit_expression(right);
+        }
+        Yield { ref mut argument, .. } => {
+            if let Some(ref mut argument) = *argument {
+                visitor.visit_expression(argument);
+            }
+        }
+        Object { ref mut properties, .. } => {
+            for property in properties {
+                visitor.visit_property(property);
+            }
+        }
+        Array { ref mut elements, .. } => {
+            for element in elements {
+                if let Some(ref mut element) = *element {
+                    visitor.visit_expression(element);
+                }
+            }
+        }
+        Class { ref mut super_class, ref mut members, .. } => {
+            if let Some(ref mut super_class) = *super_class {
+                visitor.visit_expression(super_class);
+            }
+            for member in members {
+                visitor.visit_property(member);
+            }
+        }
+        Conditional { ref mut test, ref mut consequent, ref mut alternate, .. } => {
+            visitor.visit_expression(test);
+            visitor.visit_expression(consequent);
+            visitor.visit_expression(alternate);
+        }
+        Jsx(ref mut element) => visitor.visit_jsx_element(element),
+    }
+}
+
+pub fn walk_property<V: VisitMut + ?Sized>(visitor: &mut V, property: &mut PropertyNode) {
+    visitor.visit_property_key(&mut property.key);
+    if let Some(ref mut value) = property.value {
+        visitor.visit_expression(value);
+    }
+}
+
+pub fn walk_property_key<V: VisitMut + ?Sized>(visitor: &mut V, key: &mut PropertyKey) {
+    if let PropertyKey::Computed(ref mut expression) = *key {
+        visitor.visit_expression(expression);
+    }
+}
+
+pub fn walk_jsx_element<V: VisitMut + ?Sized>(visitor: &mut V, element: &mut JsxElement) {
+    for attribute in &mut element.attributes {
+        visitor.visit_jsx_attribute(attribute);
+    }
+    if let Some(ref mut children) = element.children {
+        for child in children {
+            visitor.visit_jsx_child(child);
+        }
+    }
+}
+
+pub fn walk_jsx_attribute<V: VisitMut + ?Sized>(visitor: &mut V, attribute: &mut JsxAttribute) {
+    match *attribute {
+        JsxAttribute::Named { ref mut value, .. } => {
+            match *value {
+                Some(JsxAttributeValue::Container(ref mut container)) => {
+                    if let Some(ref mut expression) = container.expression {
+                        visitor.visit_expression(expression);
+                    }
+                }
+                Some(JsxAttributeValue::Element(ref mut element)) => visitor.visit_expression(element),
+                Some(JsxAttributeValue::String(..)) | None => {}
+            }
+        }
+        JsxAttribute::Spread { ref mut argument, .. } => visitor.visit_expression(argument),
+    }
+}
+
+pub fn walk_jsx_child<V: VisitMut + ?Sized>(visitor: &mut V, child: &mut JsxChild) {
+    match *child {
+        JsxChild::Text(_) => {}
+        JsxChild::Container(ref mut container) => {
+            if let Some(ref mut expression) = container.expression {
+                visitor.visit_expression(expression);
+            }
+        }
+        JsxChild::Spread { ref mut argument, .. } => visitor.visit_expression(argument),
+        JsxChild::Element(ref mut element) => visitor.visit_expression(element),
+    }
+}
@@ -1,10 +1,12 @@
 use super::lexicon;
 use super::module::Dependencies;
-use super::ast::SyntaxTree;
+use super::ast::{self, SyntaxTree};
+use super::SourceMap;
 
 use std::fs::File;
 use std::io::{Error, ErrorKind};
-use std::path::PathBuf;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
 use std::io::prelude::*;
 
 use std::hash::{Hash, Hasher};
@@ -12,27 +14,56 @@ use std::collections::hash_map::DefaultHasher;
 
 pub type ChunkId = u64;
 
+/// Extensions kept as-is when resolving a chunk. Anything else is treated as a `.js` file, unless only
+/// a `.jsx`, `.ts` or `.tsx` file of that name exists.
+const SOURCE_EXTENSIONS: [&str; 8] = ["js", "mjs", "cjs", "jsx", "ts", "tsx", "mts", "cts"];
+
+fn with_source_extension(path: PathBuf) -> PathBuf {
+    let known = match path.extension().and_then(|extension| extension.to_str()) {
+        Some(extension) => SOURCE_EXTENSIONS.contains(&extension),
+        None => false
+    };
+    if known {
+        return path;
+    }
+    for extension in ["js", "jsx", "ts", "tsx"].iter() {
+        let candidate = path.with_extension(extension);
+        if candidate.is_file() {
+            return candidate;
+        }
+    }
+    return path.with_extension("js");
+}
+
 #[derive(Debug,Clone)]
 pub struct ChunkLocation {
     pub path: PathBuf,
+    /// Virtual chunks have no file, their path is the id they are imported by and their source
+    /// comes from a plugin.
+    pub is_virtual: bool,
 }
 
 impl ChunkLocation {
     pub fn absolute(mut path: PathBuf) -> Result<Self, Error> {
-        path = path.with_extension("js");
+        path = with_source_extension(path);
         if !path.is_absolute() || !path.exists() {
             return Err(Error::from(ErrorKind::NotFound));
         }
-        return Ok(ChunkLocation { path });
+        return Ok(ChunkLocation { path, is_virtual: false });
     }
 
     pub fn relative(source: &ChunkLocation, mut path: PathBuf) -> Result<Self, Error> {
         let from_dir = source.path.parent().unwrap();
-        path = from_dir.join(path).with_extension("js");
+        path = with_source_extension(from_dir.join(path));
         if !path.is_absolute() || !path.exists() {
             return Err(Error::from(ErrorKind::NotFound));
         }
-        return Ok(ChunkLocation { path });
+        return Ok(ChunkLocation { path, is_virtual: false });
+    }
+
+    /// A chunk which only exists in memory, such as `virtual:routes`.
+    pub fn virtual_module(id: &str) -> Self {
+        return ChunkLocation { path: PathBuf::from(id), is_virtual: true };
     }
 
     pub fn generate_id(&self) -> ChunkId {
@@ -49,6 +80,8 @@ pub struct Chunk {
     pub loaded: bool,
     pub index: usize,
     pub source: String,
+    pub bom: bool,
+    pub hashbang: Option<String>,
     pub syntax_tree: Option<SyntaxTree>,
     pub dependencies: Dependencies,
 }
@@ -60,6 +93,8 @@ impl<'a> Chunk {
             loaded:false,
             location,
             source: String::new(),
+            bom: false,
+            hashbang: None,
             index: 0,
             syntax_tree: None,
             dependencies: Dependencies::new()
@@ -88,9 +123,28 @@ impl<'a> Chunk {
         };
 
         //TODO: Do we need to copy the memory?
+        self.set_source(src);
+        return Ok(&self.source);
+    }
+
+    /// Replaces the source of the chunk. A leading UTF-8 BOM is removed and a `#!` line is kept aside,
+    /// both are restored when the chunk is emitted.
+    pub fn set_source(&mut self, mut src: String) {
+        self.bom = src.starts_with('\u{FEFF}');
+        if self.bom {
+            src.remove(0);
+        }
+
+        self.index = 0;
+        self.hashbang = None;
+        if src.starts_with("#!") {
+            let end = src.find(|char| char == '\n' || char == '\r').unwrap_or(src.len());
+            self.hashbang = Some(src[..end].to_owned());
+            self.index = end;
+        }
+
         self.source = src;
         self.loaded = true;
-        return Ok(&self.source);
     }
 
     pub fn peek_char(&self) -> char {
@@ -131,11 +185,83 @@ impl<'a> Chunk {
     }
 
     pub fn bump_char(&mut self) -> usize {
-        self.index += 1;
+        if !self.is_eof() {
+            self.index += self.peek_char().len_utf8();
+        }
         return self.index;
     }
 
+    /// The URL of the source map of the source, from a `//# sourceMappingURL=` comment left by the
+    /// tool which generated it. Only the last such comment counts.
+    pub fn source_mapping_url(&self) -> Option<&str> {
+        for line in self.source.lines().rev() {
+            let line = line.trim();
+            if line.starts_with("//# sourceMappingURL=") || line.starts_with("//@ sourceMappingURL=") {
+                return Some(line["//# sourceMappingURL=".len()..].trim());
+            }
+        }
+        return None;
+    }
+
+    /// Loads the source map named by the `sourceMappingURL` of the source, from a data URL or a
+    /// file next to the chunk. Its sources are made absolute, unless they are URLs. `None` when
+    /// the source names no map.
+    pub fn load_input_source_map(&self) -> Result<Option<SourceMap>, String> {
+        let url = match self.source_mapping_url() {
+            Some(url) => url,
+            None => return Ok(None)
+        };
+        if url.starts_with("data:") {
+            let mut map = SourceMap::from_data_url(url)?;
+            if !self.location.is_virtual {
+                Chunk::resolve_sources(&mut map, self.location.path.parent().unwrap());
+            }
+            return Ok(Some(map));
+        }
+        if self.location.is_virtual {
+            return Err(format!("Cannot load source map '{}' of a virtual module", url));
+        }
+
+        let path = self.location.path.parent().unwrap().join(url.split(&['?', '#'][..]).next().unwrap());
+        let text = match fs::read_to_string(&path) {
+            Ok(text) => text,
+            Err(err) => return Err(format!("Cannot read source map {}: {}", path.display(), err))
+        };
+        let mut map = match SourceMap::from_json(&text) {
+            Ok(map) => map,
+            Err(err) => return Err(format!("Invalid source map {}: {}", path.display(), err))
+        };
+        Chunk::resolve_sources(&mut map, path.parent().unwrap());
+        return Ok(Some(map));
+    }
+
+    fn resolve_sources(map: &mut SourceMap, dir: &Path) {
+        for source in &mut map.sources {
+            if source.contains("://") {
+                continue;
+            }
+            // Sources are compared by their components later, so `..` is taken out here.
+            let mut path = PathBuf::new();
+            for component in dir.join(&*source).components() {
+                match component {
+                    Component::ParentDir => {
+                        path.pop();
+                    }
+                    Component::CurDir => {}
+                    component => path.push(component.as_os_str())
+                }
+            }
+            *source = path.to_string_lossy().into_owned();
+        }
+    }
+
+    /// Character starting at the given byte index of the source.
     pub fn char_at(&self, index: usize) -> char {
-        return self.source.chars().nth(index).unwrap();
+        return self.source[index..].chars().next().unwrap();
+    }
+
+    /// One-based line and column of the given byte index of the source. Columns count characters.
+    pub fn line_column(&self, index: usize) -> (usize, usize) {
+        return ast::line_column(&self.source, index);
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,183 @@
+use super::{CommentMap, CommentAttachment, CommentPlacement};
+
+use super::super::ast::{Comment, CommentKind, Parameter, SourceLocation, SyntaxTree};
+use super::super::ast::directive::DirectiveNode;
+use super::super::ast::statement::StatementNode;
+use super::super::ast::expression::ExpressionNode;
+use super::super::ast::property::PropertyNode;
+use super::super::ast::visit::Visit;
+
+use std::cmp::Ordering;
+use std::mem;
+
+struct LocatedNode {
+    location: SourceLocation,
+    /// The located nodes directly inside this one, by their start.
+    children: Vec<usize>,
+}
+
+/// Builds a `CommentMap`. The located nodes of the tree are collected first, then every comment is
+/// attached relative to the nodes next to it inside the smallest node enclosing it:
+///
+/// - a comment alone on its line leads the node following it,
+/// - a comment ending a line, or followed by a comma, trails the node preceding it,
+/// - any other comment leads the node following it,
+///
+/// falling back to the node on the other side, or to the enclosing node itself when it has no
+/// children next to the comment. Nodes separated from the comment by a bracket are not next to it.
+///
+/// Siblings never overlap, so the enclosing node and the nodes next to the comment are found by
+/// searching the children of each node by their start.
+pub struct CommentAttacher<'a> {
+    source: &'a str,
+    /// Located nodes in the order they are entered, the whole source first.
+    nodes: Vec<LocatedNode>,
+    stack: Vec<usize>,
+}
+
+impl<'a> CommentAttacher<'a> {
+    pub fn new(source: &'a str) -> Self {
+        let root = LocatedNode { location: SourceLocation::new(0, source.len()), children: Vec::new() };
+        return CommentAttacher { source, nodes: vec![root], stack: vec![0] };
+    }
+
+    pub fn attach(mut self, tree: &SyntaxTree) -> CommentMap {
+        self.visit_syntax_tree(tree);
+        // Plugins may have moved nodes out of source order.
+        for id in 0..self.nodes.len() {
+            let mut children = mem::replace(&mut self.nodes[id].children, Vec::new());
+            children.sort_by_key(|&child| self.nodes[child].location.start);
+            self.nodes[id].children = children;
+        }
+
+        let attachments = tree.comments.iter().map(|comment| self.attachment(comment)).collect();
+        return CommentMap { comments: tree.comments.clone(), attachments };
+    }
+
+    fn attachment(&self, comment: &Comment) -> CommentAttachment {
+        let location = &comment.location;
+
+        // The last child starting before the comment is the only one which can contain it.
+        let mut enclosing = 0;
+        let mut index = self.children_before(enclosing, location.start);
+        while index > 0 {
+            let child = self.nodes[enclosing].children[index - 1];
+            if self.nodes[child].location.end < location.end {
+                break;
+            }
+            enclosing = child;
+            index = self.children_before(enclosing, location.start);
+        }
+
+        let children = &self.nodes[enclosing].children;
+        let preceding = match index {
+            0 => None,
+            _ => Some(&self.nodes[children[index - 1]])
+        };
+        let following = children.get(index).map(|&child| &self.nodes[child]);
+
+        // A bracket opened or closed between the comment and a node puts them in different lists, as
+        // in `call(/* comment */)`.
+        let preceding = preceding.filter(|node| !self.gap_contains(node.location.end, location.start, &['(', '[', '{']));
+        let following = following.filter(|node| !self.gap_contains(location.end, node.location.start, &[')', ']', '}']));
+
+        let ends_item = self.ends_line(comment) || self.source[location.end..].trim_start().starts_with(',');
+        let (placement, node) = match (self.starts_line(comment), ends_item, preceding, following) {
+            (false, true, Some(preceding), _) => (CommentPlacement::Trailing, preceding),
+            (_, _, _, Some(following)) => (CommentPlacement::Leading, following),
+            (_, _, Some(preceding), None) => (CommentPlacement::Trailing, preceding),
+            (_, _, None, None) => (CommentPlacement::Inner, &self.nodes[enclosing]),
+        };
+        return CommentAttachment { placement, node: node.location.clone() };
+    }
+
+    /// How many children of a node start before an offset.
+    fn children_before(&self, node: usize, offset: usize) -> usize {
+        let children = &self.nodes[node].children;
+        return children.binary_search_by(|&child| match self.nodes[child].location.start < offset {
+            true => Ordering::Less,
+            false => Ordering::Greater
+        }).unwrap_err();
+    }
+
+    /// Whether the source between two offsets holds one of the characters.
+    fn gap_contains(&self, start: usize, end: usize, chars: &[char]) -> bool {
+        return self.source.get(start..end).map_or(false, |gap| gap.contains(chars));
+    }
+
+    /// Whether only whitespace precedes the comment on its line.
+    fn starts_line(&self, comment: &Comment) -> bool {
+        let before = &self.source[..comment.location.start];
+        return before.rsplit(&['\n', '\r'][..]).next().map_or(true, |line| line.trim().is_empty());
+    }
+
+    /// Whether only whitespace follows the comment on its line.
+    fn ends_line(&self, comment: &Comment) -> bool {
+        if comment.kind == CommentKind::Line {
+            return true;
+        }
+        let after = &self.source[comment.location.end..];
+        return after.split(&['\n', '\r'][..]).next().map_or(true, |line| line.trim().is_empty());
+    }
+
+    /// Enters a located node. Nodes without a location, and nodes located like their parent, are
+    /// treated as part of their parent.
+    fn enter(&mut self, location: &SourceLocation) {
+        let parent = *self.stack.last().unwrap();
+        if location.start == location.end || self.nodes[parent].location == *location {
+            self.stack.push(parent);
+            return;
+        }
+
+        self.nodes.push(LocatedNode { location: location.clone(), children: Vec::new() });
+        let id = self.nodes.len() - 1;
+        self.nodes[parent].children.push(id);
+        self.stack.push(id);
+    }
+
+    fn exit(&mut self) {
+        self.stack.pop();
+    }
+}
+
+impl<'a> Visit for CommentAttacher<'a> {
+    fn enter_directive(&mut self, directive: &DirectiveNode) {
+        self.enter(&directive.location);
+    }
+
+    fn exit_directive(&mut self, _directive: &DirectiveNode) {
+        self.exit();
+    }
+
+    fn enter_statement(&mut self, statement: &StatementNode) {
+        self.enter(&statement.location);
+    }
+
+    fn exit_statement(&mut self, _statement: &StatementNode) {
+        self.exit();
+    }
+
+    fn enter_expression(&mut self, expression: &ExpressionNode) {
+        self.enter(&expression.location);
+    }
+
+    fn exit_expression(&mut self, _expression: &ExpressionNode) {
+        self.exit();
+    }
+
+    fn enter_parameter(&mut self, parameter: &Parameter) {
+        self.enter(&parameter.location);
+    }
+
+    fn exit_parameter(&mut self, _parameter: &Parameter) {
+        self.exit();
+    }
+
+    fn enter_property(&mut self, property: &PropertyNode) {
+        self.enter(&property.location);
+    }
+
+    fn exit_property(&mut self, _property: &PropertyNode) {
+        self.exit();
+    }
+}
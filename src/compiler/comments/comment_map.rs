@@ -0,0 +1,75 @@
+use super::super::ast::{Comment, SourceLocation, SyntaxTree};
+use super::attacher::CommentAttacher;
+
+pub type CommentId = usize;
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum CommentPlacement {
+    /// Before the node, like the JSDoc of a function.
+    Leading,
+    /// After the node, usually at the end of its line.
+    Trailing,
+    /// Inside a node with no children next to the comment, like an empty block or argument list.
+    Inner,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct CommentAttachment {
+    pub placement: CommentPlacement,
+    /// The location of the node the comment belongs to. Comments belonging to no node belong to
+    /// the whole source.
+    pub node: SourceLocation,
+}
+
+/// The comments of a syntax tree with the node each of them is attached to.
+#[derive(Debug, Clone)]
+pub struct CommentMap {
+    pub comments: Vec<Comment>,
+    /// The attachment of every comment, by its id.
+    pub attachments: Vec<CommentAttachment>,
+}
+
+impl CommentMap {
+    /// Attaches the comments of a tree. The source is the one the tree was parsed from, used to
+    /// tell which comments end a line.
+    pub fn attach(tree: &SyntaxTree, source: &str) -> Self {
+        return CommentAttacher::new(source).attach(tree);
+    }
+
+    pub fn comment(&self, id: CommentId) -> &Comment {
+        return &self.comments[id];
+    }
+
+    pub fn attachment(&self, id: CommentId) -> &CommentAttachment {
+        return &self.attachments[id];
+    }
+
+    pub fn leading(&self, node: &SourceLocation) -> Vec<&Comment> {
+        return self.placed(node, CommentPlacement::Leading);
+    }
+
+    pub fn trailing(&self, node: &SourceLocation) -> Vec<&Comment> {
+        return self.placed(node, CommentPlacement::Trailing);
+    }
+
+    pub fn inner(&self, node: &SourceLocation) -> Vec<&Comment> {
+        return self.placed(node, CommentPlacement::Inner);
+    }
+
+    /// Whether the node is preceded by a `/*#__PURE__*/` annotation.
+    pub fn is_pure(&self, node: &SourceLocation) -> bool {
+        return self.leading(node).iter().any(|comment| comment.is_pure_annotation());
+    }
+
+    /// The JSDoc comment closest to the node, if it has one.
+    pub fn jsdoc(&self, node: &SourceLocation) -> Option<&Comment> {
+        return self.leading(node).into_iter().rev().find(|comment| comment.is_jsdoc());
+    }
+
+    fn placed(&self, node: &SourceLocation, placement: CommentPlacement) -> Vec<&Comment> {
+        return self.attachments.iter().enumerate()
+            .filter(|&(_, attachment)| attachment.placement == placement && attachment.node == *node)
+            .map(|(id, _)| &self.comments[id])
+            .collect();
+    }
+}
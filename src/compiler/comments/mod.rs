@@ -0,0 +1,11 @@
+//! Attachment of comments to the nodes they belong to, so transforms can find the JSDoc of a
+//! function, a `/*#__PURE__*/` annotation or a `// @ts-ignore` directive by the node it applies to.
+//!
+//! Nodes are identified by their location, so a node sharing its location with its parent, like
+//! the expression of a statement without a semicolon, shares its comments too.
+
+mod comment_map;
+mod attacher;
+
+pub use self::comment_map::*;
+pub use self::attacher::CommentAttacher;
@@ -3,17 +3,26 @@ extern crate rayon;
 use self::rayon::prelude::*;
 
 use super::{Chunk, ChunkId, Generator, ChunkLocation, CompilerError, CompilerOptions};
-use super::module::Resolver;
-use super::parser::{JsParser, ParserError, ParserErrorKind, ParserResult, Parser, ParserOptions};
-use super::ast::SourceLocation;
+use super::{SourceMap, SourceMapBuilder, SourceMapMode, SourceMapOptions};
+use super::module::{Resolver, ResolverError};
+use super::parser::{JsParser, TsParser, Parser, ParserOptions, SourceType};
+use super::diagnostics::{Diagnostic, SourceFile};
+use super::ast::{SourceLocation, SyntaxTree};
+use super::ast::statement::Statement;
 
-use super::transform::PluginManager;
+use super::transform::{PluginManager, PluginDiagnostic, PluginRegistry, Bundle};
+use super::ConfigError;
+
+use plugins;
+use plugins::{JsxPlugin, VirtualModulesPlugin};
 
 use std::io::prelude::*;
+use std::io;
 use std::io::{Write, BufWriter};
 use std::fs::File;
 use std::fs;
-use std::path::PathBuf;
+use std::env;
+use std::path::{Path, PathBuf};
 use std::collections::HashMap;
 use std::time::{Duration, Instant};
 
@@ -21,11 +30,18 @@ pub struct CompileResult {
     pub overall_duration: Duration,
     pub emit_duration: Duration,
     pub parse_duration: Duration,
+    pub transform_duration: Duration,
+    /// The number of chunks written.
     pub emit_count: i16,
+    /// The number of files written, counting source maps and files added by plugins.
+    pub file_count: i16,
+    /// Warnings reported by plugins, to be shown once the build is done.
+    pub warnings: Vec<Diagnostic>,
 }
 
 pub struct EmitResult {
     pub count: i16,
+    pub file_count: i16,
     pub duration: Duration,
 }
 
@@ -33,19 +49,33 @@ pub struct Compiler {
     pub resolver: Resolver,
     chunk_map: HashMap<ChunkId, Chunk>,
     options: Option<CompilerOptions>,
-    parser: Box<Parser>
+    parser: Box<Parser>,
+    ts_parser: Box<Parser>,
+    tsx_parser: Box<Parser>,
+    registry: PluginRegistry,
 }
 
 impl Compiler {
     pub fn new() -> Self {
+        let mut registry = PluginRegistry::new();
+        plugins::register_builtins(&mut registry);
+
         return Compiler {
             resolver: Resolver {},
             chunk_map: HashMap::new(),
             options: None,
-            parser: Box::new(JsParser::new())
+            parser: Box::new(JsParser::new()),
+            ts_parser: Box::new(TsParser::new()),
+            tsx_parser: Box::new(TsParser::with_jsx()),
+            registry,
         };
     }
 
+    /// Plugins which can be enabled by name from the `"plugins"` section of `maxwell.json`.
+    pub fn registry_mut(&mut self) -> &mut PluginRegistry {
+        return &mut self.registry;
+    }
+
 
     pub fn push_chunk(&mut self, chunk: Chunk) {
         let id = chunk.get_id();
@@ -53,17 +83,90 @@ impl Compiler {
     }
 
     pub fn compile(&mut self, plugin_manager: PluginManager) -> Result<CompileResult, CompilerError> {
-        let start = Instant::now();
-
-        let options = match CompilerOptions::load() {
-            Ok(options) => options,
-            Err(err) => return Err(CompilerError::InvalidConfig(err))
+        return match CompilerOptions::load() {
+            Ok(options) => self.compile_with(options, plugin_manager),
+            Err(err) => Err(CompilerError::InvalidConfig(err))
         };
+    }
+
+    /// Builds with options which were already loaded, such as from `CompilerOptions::load_from`.
+    pub fn compile_with(&mut self, options: CompilerOptions, mut plugin_manager: PluginManager) -> Result<CompileResult, CompilerError> {
+        let start = Instant::now();
 
         if options.entries.len() == 0 {
             return Err(CompilerError::NoEntries);
         }
 
+        // JSX is lowered before any other plugin sees the tree.
+        plugin_manager.add_plugin_first(JsxPlugin::new(options.jsx.clone()));
+        if options.virtual_modules.len() > 0 {
+            plugin_manager.add_plugin_first(VirtualModulesPlugin::new(options.virtual_modules.clone()));
+        }
+
+        for path in &options.native_plugins {
+            if let Err(err) = self.registry.load_native(path) {
+                return Err(CompilerError::InvalidConfig(ConfigError::InvalidConfigOption(String::from("nativePlugins"), err.to_string())));
+            }
+        }
+        for path in &options.wasm_plugins {
+            if let Err(err) = self.registry.load_wasm(path) {
+                return Err(CompilerError::InvalidConfig(ConfigError::InvalidConfigOption(String::from("wasmPlugins"), err.to_string())));
+            }
+        }
+
+        // Plugins enabled in the config run after the ones given to the compiler, in config order.
+        for config in &options.plugins {
+            let plugin = match self.registry.create(&config.name, &config.options) {
+                Some(Ok(plugin)) => plugin,
+                Some(Err(reason)) => return Err(CompilerError::InvalidConfig(ConfigError::InvalidConfigOption(format!("plugins.{}", config.name), reason))),
+                None => {
+                    let reason = format!("Unknown plugin, expected one of {}", self.registry.names().join(", "));
+                    return Err(CompilerError::InvalidConfig(ConfigError::InvalidConfigOption(format!("plugins.{}", config.name), reason)));
+                }
+            };
+            plugin_manager.add_boxed_plugin(plugin);
+        }
+
+        let mut diagnostics = Vec::new();
+        plugin_manager.build_start(&options, &mut diagnostics);
+        let result = match self.check_diagnostics(&diagnostics) {
+            Ok(_) => self.build(&options, &plugin_manager, &mut diagnostics, start),
+            Err(err) => Err(err)
+        };
+        plugin_manager.build_end(result.as_ref().err(), &mut diagnostics);
+
+        return match result {
+            Ok(mut result) => {
+                self.check_diagnostics(&diagnostics)?;
+                result.warnings = self.report(&diagnostics);
+                Ok(result)
+            }
+            Err(CompilerError::PluginError(_)) => Err(CompilerError::PluginError(self.report(&diagnostics))),
+            Err(err) if !diagnostics.is_empty() => Err(CompilerError::WithPluginDiagnostics(Box::new(err), self.report(&diagnostics))),
+            Err(err) => Err(err)
+        };
+    }
+
+    /// Fails with every diagnostic when any of them is an error.
+    fn check_diagnostics(&self, diagnostics: &[PluginDiagnostic]) -> Result<(), CompilerError> {
+        if diagnostics.iter().any(|diagnostic| diagnostic.is_error()) {
+            return Err(CompilerError::PluginError(self.report(diagnostics)));
+        }
+        return Ok(());
+    }
+
+    /// Diagnostics of plugins as they are reported, with the source of the chunk each points into.
+    fn report(&self, diagnostics: &[PluginDiagnostic]) -> Vec<Diagnostic> {
+        return diagnostics.iter().map(|diagnostic| {
+            let chunk = match diagnostic.path {
+                Some(ref path) => self.chunk_map.values().find(|chunk| chunk.location.path == *path),
+                None => None
+            };
+            diagnostic.to_diagnostic(chunk.map(|chunk| chunk.source.as_str()))
+        }).collect();
+    }
+
+    fn build(&mut self, options: &CompilerOptions, plugin_manager: &PluginManager, diagnostics: &mut Vec<PluginDiagnostic>, start: Instant) -> Result<CompileResult, CompilerError> {
         let mut parse_duration = Duration::default();
 
         for entry in &options.entries {
@@ -71,52 +174,121 @@ impl Compiler {
             if location.is_err() {
                 return Err(CompilerError::EntryNotValid(entry.to_owned()));
             }
-            match self.compile_chunk(&location.unwrap(), &plugin_manager) {
-                Ok(result) => {
-                    parse_duration += result.duration;
-                }
-                Err(err) => {
-                    eprintln!("{:?}", err);
-                }
-            }
+            parse_duration += self.compile_chunk(&location.unwrap(), options.source_type, plugin_manager, diagnostics)?;
         };
+        self.check_diagnostics(diagnostics)?;
 
+        let transform_start = Instant::now();
+        plugin_manager.transform(&mut self.chunk_map, diagnostics);
+        let transform_duration = transform_start.elapsed();
+        self.check_diagnostics(diagnostics)?;
 
-        return match self.emit(&options) {
+        self.rewrite_virtual_imports(options);
+        if options.source_map.is_some() {
+            self.remove_source_mapping_urls();
+        }
+        let result = self.emit(options, plugin_manager, diagnostics)?;
+        return Ok(CompileResult {
+            overall_duration: start.elapsed(),
+            parse_duration,
+            transform_duration,
+            emit_duration: result.duration,
+            emit_count: result.count,
+            file_count: result.file_count,
+            warnings: Vec::new()
+        });
+    }
+
+    /// Parses a single file on its own, without resolving its imports or running any plugins.
+    pub fn parse_file(&mut self, path: &Path, default_source_type: SourceType) -> Result<Chunk, CompilerError> {
+        let absolute_path = match env::current_dir() {
+            Ok(dir) => dir.join(path),
+            Err(_) => path.to_path_buf()
+        };
+        let location = match ChunkLocation::absolute(absolute_path) {
+            Ok(location) => location,
+            Err(_) => return Err(CompilerError::EntryNotValid(path.display().to_string()))
+        };
+        let file_name = location.path.file_name().and_then(|name| name.to_str()).unwrap_or_default().to_owned();
+        let mut chunk = Chunk::new(file_name, location.clone());
+        if chunk.load_source().is_err() {
+            return Err(CompilerError::EntryNotValid(path.display().to_string()));
+        }
+
+        let source_type = SourceType::from_path(&location.path, default_source_type);
+        return match self.parser_for(&location.path).parse(ParserOptions::new(&mut chunk, source_type)) {
             Ok(result) => {
-                Ok(CompileResult {
-                    overall_duration: start.elapsed(),
-                    parse_duration,
-                    emit_duration: result.duration,
-                    emit_count: result.count
-                })
+                chunk.syntax_tree = Some(result.syntax_tree);
+                Ok(chunk)
             }
-            Err(err) => Err(err)
+            Err(err) => Err(CompilerError::ParserError(err))
+        };
+    }
+
+    fn parser_for(&mut self, path: &Path) -> &mut Box<Parser> {
+        return match path.extension().and_then(|extension| extension.to_str()) {
+            Some("ts") | Some("mts") | Some("cts") => &mut self.ts_parser,
+            Some("tsx") => &mut self.tsx_parser,
+            _ => &mut self.parser
         };
     }
 
-    fn compile_chunk(&mut self, location: &ChunkLocation, plugin_manager: &PluginManager) -> Result<ParserResult, ParserError> {
+    /// Parses a chunk and every chunk it imports which was not parsed yet.
+    fn compile_chunk(&mut self, location: &ChunkLocation, default_source_type: SourceType, plugin_manager: &PluginManager, diagnostics: &mut Vec<PluginDiagnostic>) -> Result<Duration, CompilerError> {
         let id = location.generate_id();
 
         if self.chunk_map.contains_key(&id) {
-            return Err(ParserError::new(ParserErrorKind::Syntax, format!("Chunk already exists"), SourceLocation::default()));
+            return Ok(Duration::default());
         }
 
         let file_name = location.path.file_name().unwrap().to_str().unwrap().to_owned();
         let mut chunk = Chunk::new(file_name, location.clone());
 
-        chunk.load_source();
+        // A plugin failing to load the chunk explains why it is missing better than the fallbacks.
+        let loaded = plugin_manager.load(location, diagnostics);
+        self.check_diagnostics(diagnostics)?;
+        match loaded {
+            Some(source) => chunk.set_source(source),
+            None if location.is_virtual => {
+                let id = location.path.to_string_lossy().into_owned();
+                return Err(CompilerError::ResolverError(ResolverError::VirtualModuleNotLoaded(id), None));
+            }
+            None => {
+                if let Err(err) = chunk.load_source() {
+                    return Err(CompilerError::SourceUnreadable(location.path.clone(), err));
+                }
+            }
+        }
 
-        let parse_result = self.parser.parse(ParserOptions::new(&mut chunk, &plugin_manager));
+        let source_type = SourceType::from_path(&location.path, default_source_type);
+        let parse_result = self.parser_for(&location.path).parse(ParserOptions::new(&mut chunk, source_type));
         return match parse_result {
             Ok(result) => {
-                let required_chunks = self.resolver.resolve(&mut chunk, &result.requires).unwrap();
-                chunk.syntax_tree = Some(result.syntax_tree.clone());
+                let mut duration = result.duration;
+                let mut required_chunks = Vec::new();
+                for import in &result.requires {
+                    let resolved = plugin_manager.resolve_id(import, &chunk.location, diagnostics);
+                    self.check_diagnostics(diagnostics)?;
+                    let required_location = match resolved {
+                        Some(location) => location,
+                        None => match self.resolver.resolve_import(&chunk.location, import) {
+                            Ok(location) => location,
+                            Err(err) => {
+                                let importer = SourceFile::new(chunk.location.path.clone(), chunk.source.clone());
+                                let import_location = Compiler::import_location(&result.syntax_tree, import);
+                                return Err(CompilerError::ResolverError(err, Some((importer, import_location))));
+                            }
+                        }
+                    };
+                    chunk.dependencies.imports.push((import.to_owned(), required_location.generate_id()));
+                    required_chunks.push(required_location);
+                }
+                chunk.syntax_tree = Some(result.syntax_tree);
 
                 for required_location in &required_chunks {
                     let required_id = required_location.generate_id();
 
-                    self.compile_chunk(&required_location, plugin_manager);
+                    duration += self.compile_chunk(&required_location, default_source_type, plugin_manager, diagnostics)?;
 
                     let mut required_chunk = self.chunk_map.get_mut(&required_id).unwrap();
                     required_chunk.dependencies.used_by.push(id.clone());
@@ -125,43 +297,368 @@ impl Compiler {
 
                 self.push_chunk(chunk);
 
-                Ok(result)
+                Ok(duration)
             }
-            Err(err) => Err(err),
+            Err(err) => Err(CompilerError::ParserError(err)),
         };
     }
 
-    fn emit<'a>(&'a self, options: &'a CompilerOptions) -> Result<EmitResult, CompilerError> {
+    /// Location of the import statement of `import`, where a failure to resolve it is shown.
+    fn import_location(tree: &SyntaxTree, import: &str) -> SourceLocation {
+        for statement in &tree.base_node.content {
+            if let Statement::Import { ref source, .. } = statement.statement {
+                if source == import {
+                    return statement.location.clone();
+                }
+            }
+        }
+        return SourceLocation::default();
+    }
+
+    /// Writes every chunk to the out directory. Chunks are streamed to their files as they are
+    /// generated, unless a plugin renders chunks or changes the bundle, which needs the code in memory.
+    fn emit<'a>(&'a self, options: &'a CompilerOptions, plugin_manager: &PluginManager, diagnostics: &mut Vec<PluginDiagnostic>) -> Result<EmitResult, CompilerError> {
         let start = Instant::now();
+
+        if let Err(err) = fs::create_dir_all(&options.out_dir) {
+            return Err(CompilerError::OutputNotWritten(vec![(options.out_dir.clone(), err)]));
+        }
+
+        let generator = match options.output {
+            Some(ref output) => Generator::with_options(output.clone()),
+            None => Generator::new()
+        };
+        let (count, file_count, failures) = match plugin_manager.handles_output() {
+            true => self.emit_bundle(options, &generator, plugin_manager, diagnostics)?,
+            false => self.stream_chunks(options, &generator, diagnostics)?
+        };
+        if !failures.is_empty() {
+            return Err(CompilerError::OutputNotWritten(failures));
+        }
+
+        return Ok(EmitResult { count, file_count, duration: start.elapsed() });
+    }
+
+    /// Generates every chunk straight into its file, with its source map next to it. Returns how
+    /// many chunks and files were written.
+    fn stream_chunks(&self, options: &CompilerOptions, generator: &Generator, diagnostics: &mut Vec<PluginDiagnostic>) -> Result<(i16, i16, Vec<(PathBuf, io::Error)>), CompilerError> {
         let mut count = 0;
+        let mut file_count = 0;
+        let mut failures = Vec::new();
+
+        for (_, chunk) in self.chunk_map.iter() {
+            let tree = match chunk.syntax_tree {
+                Some(ref tree) => tree,
+                None => continue
+            };
+            let out_path = self.get_relative_out_path(chunk, options);
+            let out_file = options.out_dir.join(&out_path);
+            let map = match Compiler::stream_chunk(generator, tree, chunk, &out_path, &out_file, options, diagnostics) {
+                Ok(map) => map,
+                Err(err) => {
+                    failures.push((out_file, err));
+                    continue;
+                }
+            };
+            count += 1;
+            file_count += 1;
+
+            if let Some(map) = map {
+                let map_file = options.out_dir.join(Compiler::map_path(&out_path));
+                match Compiler::write_file(&map_file, map.to_json().as_bytes()) {
+                    Ok(_) => file_count += 1,
+                    Err(err) => failures.push((map_file, err))
+                }
+            }
+        }
+        self.check_diagnostics(diagnostics)?;
 
-        if !options.out_dir.exists() {
-            println!("{:?}", fs::create_dir_all(options.out_dir.to_str().unwrap()));
+        return Ok((count, file_count, failures));
+    }
+
+    /// Generates a chunk into its file, returning its source map when it goes in a file of its own.
+    fn stream_chunk(generator: &Generator, tree: &SyntaxTree, chunk: &Chunk, out_path: &PathBuf, out_file: &PathBuf, options: &CompilerOptions, diagnostics: &mut Vec<PluginDiagnostic>) -> io::Result<Option<SourceMap>> {
+        let mut writer = Compiler::create_file(out_file)?;
+        if chunk.bom {
+            writer.write_all("\u{FEFF}".as_bytes())?;
+        }
+
+        let source_map = match options.source_map {
+            Some(ref source_map) => source_map,
+            None => {
+                generator.stream_tree_from(tree, &chunk.source, &mut writer)?;
+                writer.flush()?;
+                return Ok(None);
+            }
+        };
+        let mut builder = Compiler::source_map_builder(chunk, out_path);
+        let end = generator.stream_tree_mapped(tree, &chunk.source, &mut writer, &mut builder, 0)?;
+        let mut map = Compiler::finish_source_map(builder, chunk, source_map, diagnostics);
+        Compiler::relative_sources(&mut map, out_file);
+
+        if let Some(url) = Compiler::source_mapping_url(&map, out_path, source_map) {
+            if end.column > 0 {
+                writer.write_all(b"\n")?;
+            }
+            write!(writer, "//# sourceMappingURL={}\n", url)?;
         }
+        writer.flush()?;
 
-        let generator = Generator {};
+        return Ok(match source_map.mode {
+            SourceMapMode::Inline => None,
+            _ => Some(map)
+        });
+    }
+
+    /// Renders every chunk into the bundle, lets plugins change the bundle and writes it out.
+    /// Returns how many chunks were rendered and how many files were written.
+    fn emit_bundle(&self, options: &CompilerOptions, generator: &Generator, plugin_manager: &PluginManager, diagnostics: &mut Vec<PluginDiagnostic>) -> Result<(i16, i16, Vec<(PathBuf, io::Error)>), CompilerError> {
+        let mut bundle = Bundle::new();
+        let mut count = 0;
 
         for (_, chunk) in self.chunk_map.iter() {
-            let out_path = Compiler::get_out_path(&chunk.location.path, &options.src_base_path, &options.out_dir);
-
-            match chunk.syntax_tree {
-                Some(ref node) => {
-                    let src = generator.generate(node);
-
-                    match fs::OpenOptions::new().create(true).write(true).open(out_path) {
-                        Ok(ref file) => {
-                            let mut buf = BufWriter::new(file);
-                            buf.write_all(src.as_bytes());
-                            count += 1;
-                        },
-                        Err(err) => {},
-                    };
+            let tree = match chunk.syntax_tree {
+                Some(ref tree) => tree,
+                None => continue
+            };
+            let out_path = self.get_relative_out_path(chunk, options);
+
+            let (mut src, mut map) = match options.source_map {
+                Some(ref source_map) => {
+                    let mut builder = Compiler::source_map_builder(chunk, &out_path);
+                    let src = generator.generate_tree_mapped(tree, &chunk.source, &mut builder, 0);
+                    (src, Some(Compiler::finish_source_map(builder, chunk, source_map, diagnostics)))
+                }
+                None => (generator.generate_tree_from(tree, &chunk.source), None)
+            };
+            plugin_manager.render_chunk(chunk, &mut src, map.as_mut(), diagnostics);
+
+            if let (Some(mut map), Some(source_map)) = (map, options.source_map.as_ref()) {
+                Compiler::relative_sources(&mut map, &options.out_dir.join(&out_path));
+                // A plugin may have rendered the comment naming the map of its own code.
+                Compiler::remove_source_mapping_url(&mut src);
+
+                if let Some(url) = Compiler::source_mapping_url(&map, &out_path, source_map) {
+                    if !src.is_empty() && !src.ends_with('\n') {
+                        src.push('\n');
+                    }
+                    src += &format!("//# sourceMappingURL={}\n", url);
+                }
+                if source_map.mode != SourceMapMode::Inline {
+                    bundle.insert(Compiler::map_path(&out_path), map.to_json().into_bytes());
                 }
-                None => {}
             }
+
+            if chunk.bom {
+                src.insert(0, '\u{FEFF}');
+            }
+            bundle.insert(out_path, src.into_bytes());
+            count += 1;
+        }
+        self.check_diagnostics(diagnostics)?;
+
+        plugin_manager.generate_bundle(&mut bundle, diagnostics);
+        self.check_diagnostics(diagnostics)?;
+
+        let mut file_count = 0;
+        let mut failures = Vec::new();
+        for (path, contents) in &bundle {
+            let out_path = options.out_dir.join(path);
+            match Compiler::write_file(&out_path, contents) {
+                Ok(_) => file_count += 1,
+                Err(err) => failures.push((out_path, err))
+            }
+        }
+        return Ok((count, file_count, failures));
+    }
+
+    /// Writes a file of the bundle, creating the directories it is in.
+    fn write_file(path: &Path, contents: &[u8]) -> io::Result<()> {
+        let mut writer = Compiler::create_file(path)?;
+        writer.write_all(contents)?;
+        return writer.flush();
+    }
+
+    /// Opens a file of the bundle for writing, creating the directories it is in.
+    fn create_file(path: &Path) -> io::Result<BufWriter<File>> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let file = fs::OpenOptions::new().create(true).write(true).truncate(true).open(path)?;
+        return Ok(BufWriter::new(file));
+    }
+
+    /// Path a chunk is written to, relative to the out directory. Virtual chunks go to `_virtual/`,
+    /// named by their id. An id which is not a file name as it is gets a hash of it appended, so
+    /// ids such as `virtual:a` and `virtual_a` are not written to the same file.
+    fn get_relative_out_path(&self, chunk: &Chunk, options: &CompilerOptions) -> PathBuf {
+        if chunk.location.is_virtual {
+            let id = chunk.location.path.to_string_lossy();
+            let name: String = id.chars()
+                .map(|char| if char.is_alphanumeric() || char == '-' || char == '_' || char == '.' { char } else { '_' })
+                .collect();
+            if name == id && name.ends_with(".js") {
+                return PathBuf::from("_virtual").join(name);
+            }
+            let stem = name.trim_start_matches('_').trim_end_matches(".js");
+            return PathBuf::from("_virtual").join(format!("{}-{:08x}.js", stem, chunk.get_id() as u32));
+        }
+
+        let out_path = Compiler::get_out_path(&chunk.location.path, &options.src_base_path, &options.out_dir);
+        return match out_path.strip_prefix(&options.out_dir) {
+            Ok(path) => path.to_path_buf(),
+            Err(_) => out_path.clone()
         };
+    }
 
-        return Ok(EmitResult { count, duration: start.elapsed() });
+    /// Virtual modules have no file to be imported from, so their imports are pointed at the file
+    /// they are written to. The trees are rewritten in place once the transform hooks have run.
+    fn rewrite_virtual_imports(&mut self, options: &CompilerOptions) {
+        let mut rewrites = Vec::new();
+        for (id, chunk) in self.chunk_map.iter() {
+            let out_path = self.get_relative_out_path(chunk, options);
+            let mut specifiers = HashMap::new();
+            for &(ref specifier, ref imported_id) in &chunk.dependencies.imports {
+                if let Some(imported) = self.chunk_map.get(imported_id) {
+                    if imported.location.is_virtual {
+                        let imported_path = self.get_relative_out_path(imported, options);
+                        specifiers.insert(specifier.to_owned(), Compiler::relative_specifier(&out_path, &imported_path));
+                    }
+                }
+            }
+            if specifiers.len() > 0 {
+                rewrites.push((*id, specifiers));
+            }
+        }
+
+        for (id, specifiers) in rewrites {
+            let tree = match self.chunk_map.get_mut(&id).and_then(|chunk| chunk.syntax_tree.as_mut()) {
+                Some(tree) => tree,
+                None => continue
+            };
+            for statement in &mut tree.base_node.content {
+                if let Statement::Import { ref mut source, .. } = statement.statement {
+                    if let Some(rewritten) = specifiers.get(source) {
+                        *source = rewritten.to_owned();
+                    }
+                }
+            }
+        }
+    }
+
+    /// Removes the `sourceMappingURL` comments the sources end with, which name the maps of the
+    /// sources rather than the ones written for the chunks. Trailing comments are in the suffix.
+    fn remove_source_mapping_urls(&mut self) {
+        for chunk in self.chunk_map.values_mut() {
+            if let Some(ref mut tree) = chunk.syntax_tree {
+                Compiler::remove_source_mapping_url(&mut tree.suffix);
+            }
+        }
+    }
+
+    /// A source map builder for the code of a chunk, with the chunk as its only source.
+    fn source_map_builder(chunk: &Chunk, out_path: &PathBuf) -> SourceMapBuilder {
+        let file_name = out_path.file_name().map(|name| name.to_string_lossy().into_owned());
+        let mut builder = SourceMapBuilder::new(file_name);
+        let source = match chunk.location.is_virtual {
+            true => chunk.location.path.to_string_lossy().trim_start_matches('\0').to_owned(),
+            false => chunk.location.path.to_string_lossy().into_owned()
+        };
+        builder.add_source(source, Some(chunk.source.clone()));
+        return builder;
+    }
+
+    /// The map of the code of a chunk to its source. When the source was generated by another
+    /// tool and names the source map it was given, the map leads through it to the sources of
+    /// that tool.
+    fn finish_source_map(builder: SourceMapBuilder, chunk: &Chunk, options: &SourceMapOptions, diagnostics: &mut Vec<PluginDiagnostic>) -> SourceMap {
+        let mut map = match chunk.load_input_source_map() {
+            Ok(Some(input)) => builder.build().compose(&input),
+            Ok(None) => builder.build(),
+            Err(message) => {
+                let mut diagnostic = PluginDiagnostic::warning(message, SourceLocation::default());
+                diagnostic.plugin = String::from("source-map");
+                diagnostic.path = Some(chunk.location.path.clone());
+                diagnostics.push(diagnostic);
+                builder.build()
+            }
+        };
+        if !options.sources_content {
+            for content in &mut map.sources_content {
+                *content = None;
+            }
+        }
+        return map;
+    }
+
+    /// Makes the sources of a map written next to `out_file` relative to it.
+    fn relative_sources(map: &mut SourceMap, out_file: &PathBuf) {
+        for source in &mut map.sources {
+            if Path::new(source.as_str()).is_absolute() {
+                *source = Compiler::relative_source(out_file, Path::new(source.as_str()));
+            }
+        }
+    }
+
+    /// Path the source map of a chunk is written to, relative to the out directory.
+    fn map_path(out_path: &PathBuf) -> PathBuf {
+        let mut map_path = out_path.clone().into_os_string();
+        map_path.push(".map");
+        return PathBuf::from(map_path);
+    }
+
+    /// URL the code of a chunk names its source map by, unless the map is hidden.
+    fn source_mapping_url(map: &SourceMap, out_path: &PathBuf, options: &SourceMapOptions) -> Option<String> {
+        return match options.mode {
+            SourceMapMode::External => Compiler::map_path(out_path).file_name().map(|name| name.to_string_lossy().into_owned()),
+            SourceMapMode::Inline => Some(map.to_data_url()),
+            SourceMapMode::Hidden => None
+        };
+    }
+
+    /// A source of a source map, relative to the chunk the map is written next to.
+    fn relative_source(out_file: &PathBuf, source: &Path) -> String {
+        let specifier = Compiler::relative_specifier(out_file, &source.to_path_buf());
+        return match specifier.starts_with("./") {
+            true => specifier[2..].to_owned(),
+            false => specifier
+        };
+    }
+
+    /// Removes a `sourceMappingURL` comment the code ends with.
+    fn remove_source_mapping_url(src: &mut String) {
+        let trimmed = src.trim_end().len();
+        let line_start = src[..trimmed].rfind('\n').map_or(0, |index| index + 1);
+        let line = src[line_start..trimmed].trim_start();
+        if line.starts_with("//# sourceMappingURL=") || line.starts_with("//@ sourceMappingURL=") {
+            src.truncate(line_start);
+        }
+    }
+
+    /// Specifier importing `to` from `from`, both relative to the out directory.
+    fn relative_specifier(from: &PathBuf, to: &PathBuf) -> String {
+        let from_dir: Vec<_> = match from.parent() {
+            Some(parent) => parent.components().collect(),
+            None => Vec::new()
+        };
+        let to_components: Vec<_> = to.components().collect();
+
+        let mut common = 0;
+        while common < from_dir.len() && common < to_components.len() - 1 && from_dir[common] == to_components[common] {
+            common += 1;
+        }
+
+        let mut parts = Vec::new();
+        for _ in common..from_dir.len() {
+            parts.push(String::from(".."));
+        }
+        for component in &to_components[common..] {
+            parts.push(component.as_os_str().to_string_lossy().into_owned());
+        }
+        if parts[0] != ".." {
+            parts.insert(0, String::from("."));
+        }
+        return parts.join("/");
     }
 
     fn get_out_path(file_path: &PathBuf, src_dir: &PathBuf, out_dir: &PathBuf) -> PathBuf {
@@ -185,6 +682,17 @@ impl Compiler {
             }
         }
 
+        // TypeScript and JSX sources are emitted as the JavaScript they compile to.
+        let extension = match out_path.extension().and_then(|extension| extension.to_str()) {
+            Some("ts") | Some("tsx") | Some("jsx") => Some("js"),
+            Some("mts") => Some("mjs"),
+            Some("cts") => Some("cjs"),
+            _ => None
+        };
+        if let Some(extension) = extension {
+            out_path.set_extension(extension);
+        }
+
         return out_path;
     }
 }
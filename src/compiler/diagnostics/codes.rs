@@ -0,0 +1,32 @@
+//! Codes of the diagnostics reported by Maxwell itself. Diagnostics of plugins use the plugin name.
+//!
+//! `M00xx` are config errors, `M01xx` parser errors, `M02xx` resolver errors and `M03xx` errors
+//! writing the bundle.
+
+/// The project directory or `maxwell.json` does not exist.
+pub const CONFIG_NOT_FOUND: &str = "M0001";
+/// `maxwell.json` cannot be read.
+pub const CONFIG_UNREADABLE: &str = "M0002";
+/// `maxwell.json` is not valid JSON.
+pub const CONFIG_INVALID_JSON: &str = "M0003";
+/// An option of `maxwell.json` has a value it cannot have.
+pub const CONFIG_INVALID_OPTION: &str = "M0004";
+/// `maxwell.json` lists no entries.
+pub const NO_ENTRIES: &str = "M0005";
+/// An entry of `maxwell.json` is not a file.
+pub const ENTRY_NOT_FOUND: &str = "M0006";
+
+/// The source does not match the grammar.
+pub const SYNTAX_ERROR: &str = "M0101";
+/// The source matches the grammar but breaks one of its early error rules.
+pub const EARLY_ERROR: &str = "M0102";
+
+/// Neither a plugin nor the resolver found an import.
+pub const IMPORT_NOT_FOUND: &str = "M0201";
+/// A plugin resolved an import to a virtual module which no plugin loaded.
+pub const VIRTUAL_MODULE_NOT_LOADED: &str = "M0202";
+/// The file an import resolved to cannot be read.
+pub const SOURCE_UNREADABLE: &str = "M0203";
+
+/// A file of the bundle cannot be written to the out directory.
+pub const OUTPUT_NOT_WRITTEN: &str = "M0301";
@@ -0,0 +1,132 @@
+use super::super::ast::{self, SourceLocation};
+use super::Renderer;
+
+use std::fmt;
+use std::path::PathBuf;
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum DiagnosticLevel {
+    Error,
+    Warning,
+}
+
+impl ToString for DiagnosticLevel {
+    fn to_string(&self) -> String {
+        return match *self {
+            DiagnosticLevel::Error => String::from("error"),
+            DiagnosticLevel::Warning => String::from("warning"),
+        };
+    }
+}
+
+/// A file a diagnostic points into, with the source its spans are byte offsets of.
+#[derive(Debug, PartialEq, Clone)]
+pub struct SourceFile {
+    pub path: PathBuf,
+    pub source: String,
+}
+
+impl SourceFile {
+    pub fn new(path: PathBuf, source: String) -> Self {
+        return SourceFile { path, source };
+    }
+
+    /// One-based line and column of the given byte index of the source. Columns count characters.
+    pub fn line_column(&self, index: usize) -> (usize, usize) {
+        return ast::line_column(&self.source, index);
+    }
+
+    /// Text of a one-based line, without its line break.
+    pub fn line(&self, number: usize) -> &str {
+        let line = self.source.split('\n').nth(number - 1).unwrap_or("");
+        return line.trim_end_matches('\r');
+    }
+}
+
+/// A span of the file of a diagnostic. The primary label shows where the problem is, secondary
+/// labels show code which takes part in it, such as an earlier declaration of a name.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Label {
+    pub location: SourceLocation,
+    pub message: String,
+    pub primary: bool,
+}
+
+impl Label {
+    pub fn primary(location: SourceLocation, message: String) -> Self {
+        return Label { location, message, primary: true };
+    }
+
+    pub fn secondary(location: SourceLocation, message: String) -> Self {
+        return Label { location, message, primary: false };
+    }
+}
+
+/// A problem found in a build. Only the level and message are required, a diagnostic without a
+/// file is about the build as a whole, and labels are only shown when it has one.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Diagnostic {
+    pub level: DiagnosticLevel,
+    /// A code from `codes`, or the name of the plugin which reported it.
+    pub code: Option<String>,
+    pub message: String,
+    pub file: Option<SourceFile>,
+    pub labels: Vec<Label>,
+    pub notes: Vec<String>,
+    pub help: Option<String>,
+}
+
+impl Diagnostic {
+    pub fn new(level: DiagnosticLevel, message: String) -> Self {
+        return Diagnostic { level, code: None, message, file: None, labels: Vec::new(), notes: Vec::new(), help: None };
+    }
+
+    pub fn error(code: &str, message: String) -> Self {
+        return Diagnostic::new(DiagnosticLevel::Error, message).with_code(code);
+    }
+
+    pub fn warning(code: &str, message: String) -> Self {
+        return Diagnostic::new(DiagnosticLevel::Warning, message).with_code(code);
+    }
+
+    pub fn with_code(mut self, code: &str) -> Self {
+        self.code = Some(code.to_owned());
+        return self;
+    }
+
+    pub fn with_file(mut self, file: SourceFile) -> Self {
+        self.file = Some(file);
+        return self;
+    }
+
+    pub fn with_label(mut self, label: Label) -> Self {
+        self.labels.push(label);
+        return self;
+    }
+
+    pub fn with_note(mut self, note: String) -> Self {
+        self.notes.push(note);
+        return self;
+    }
+
+    pub fn with_help(mut self, help: String) -> Self {
+        self.help = Some(help);
+        return self;
+    }
+
+    pub fn is_error(&self) -> bool {
+        return self.level == DiagnosticLevel::Error;
+    }
+
+    /// The first primary label, which the diagnostic is reported at.
+    pub fn primary_label(&self) -> Option<&Label> {
+        return self.labels.iter().find(|label| label.primary);
+    }
+}
+
+/// The diagnostic rendered without colour.
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&Renderer::new().render(self))
+    }
+}
@@ -0,0 +1,11 @@
+//! Problems found in a build, as shown to the developer. Every error of the config, the parser, the
+//! resolver and plugins becomes a `Diagnostic`: a code, a message, the spans of the file it is about
+//! and notes on how to fix it, which the `Renderer` prints as a code frame.
+
+mod diagnostic;
+mod renderer;
+
+pub mod codes;
+
+pub use self::diagnostic::*;
+pub use self::renderer::Renderer;
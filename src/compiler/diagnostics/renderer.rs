@@ -0,0 +1,205 @@
+use super::{Diagnostic, DiagnosticLevel, Label, SourceFile};
+
+use std::env;
+use std::path::{Path, PathBuf};
+
+const RESET: &str = "\x1b[0m";
+const BOLD: &str = "\x1b[1m";
+const RED: &str = "\x1b[1;31m";
+const YELLOW: &str = "\x1b[1;33m";
+const BLUE: &str = "\x1b[1;34m";
+
+/// Columns a tab is shown as in a code frame.
+const TAB_WIDTH: usize = 4;
+
+/// Prints diagnostics for a terminal. The lines their labels point at are shown with line numbers,
+/// each label underlined with `^` when it is primary and `-` otherwise:
+///
+/// ```text
+/// error[M0101]: Expected ')' but found ';'.
+///  --> src/main.js:3:18
+///   |
+/// 3 |     let total = (a + ;
+///   |                      ^
+/// ```
+pub struct Renderer {
+    colour: bool,
+}
+
+impl Renderer {
+    pub fn new() -> Self {
+        return Renderer { colour: false };
+    }
+
+    /// Colours the output with ANSI escape codes.
+    pub fn with_colour(mut self, colour: bool) -> Self {
+        self.colour = colour;
+        return self;
+    }
+
+    pub fn render(&self, diagnostic: &Diagnostic) -> String {
+        let level_colour = match diagnostic.level {
+            DiagnosticLevel::Error => RED,
+            DiagnosticLevel::Warning => YELLOW
+        };
+        let mut output = self.paint(level_colour, &diagnostic.level.to_string());
+        if let Some(ref code) = diagnostic.code {
+            output += &self.paint(level_colour, &format!("[{}]", code));
+        }
+        output += &self.paint(BOLD, &format!(": {}", diagnostic.message));
+
+        let file = match diagnostic.file {
+            Some(ref file) => file,
+            None => return output + &self.render_notes(diagnostic, "  ")
+        };
+
+        let lines = Renderer::shown_lines(file, &diagnostic.labels);
+        let width = lines.last().map(|line| line.to_string().len()).unwrap_or(1);
+        let gutter = " ".repeat(width);
+
+        output += &format!("\n{}{} {}", gutter, self.paint(BLUE, "-->"), Renderer::display_path(&file.path).display());
+        if let Some(label) = diagnostic.primary_label().or(diagnostic.labels.first()) {
+            let (line, column) = file.line_column(label.location.start);
+            output += &format!(":{}:{}", line, column);
+        }
+
+        if !lines.is_empty() {
+            output += &format!("\n{} {}", gutter, self.paint(BLUE, "|"));
+        }
+        let mut previous = None;
+        for &number in &lines {
+            if let Some(previous) = previous {
+                if number > previous + 1 {
+                    output += &format!("\n{}", self.paint(BLUE, "..."));
+                }
+            }
+            previous = Some(number);
+
+            let text = file.line(number);
+            output += &format!("\n{} {}", self.paint(BLUE, &format!("{:>width$} |", number, width = width)), Renderer::expand_tabs(text));
+            output += &self.render_labels(file, number, &diagnostic.labels, &gutter, level_colour);
+        }
+
+        if !diagnostic.notes.is_empty() || diagnostic.help.is_some() {
+            output += &format!("\n{} {}", gutter, self.paint(BLUE, "|"));
+        }
+        return output + &self.render_notes(diagnostic, &format!("{} ", gutter));
+    }
+
+    /// A row for each note and the help, after `indent`.
+    fn render_notes(&self, diagnostic: &Diagnostic, indent: &str) -> String {
+        let mut output = String::new();
+        for note in &diagnostic.notes {
+            output += &format!("\n{}{} {}: {}", indent, self.paint(BLUE, "="), self.paint(BOLD, "note"), note);
+        }
+        if let Some(ref help) = diagnostic.help {
+            output += &format!("\n{}{} {}: {}", indent, self.paint(BLUE, "="), self.paint(BOLD, "help"), help);
+        }
+        return output;
+    }
+
+    /// Rows underlining the labels which start on a line. The message of the last label follows
+    /// its underline, the messages of the others get a row each.
+    fn render_labels(&self, file: &SourceFile, number: usize, labels: &[Label], gutter: &str, level_colour: &str) -> String {
+        let text = file.line(number);
+        let mut spans: Vec<(usize, usize, &Label)> = Vec::new();
+        for label in labels {
+            let (start_line, start_column) = file.line_column(label.location.start);
+            if start_line != number {
+                continue;
+            }
+            let (end_line, end_column) = file.line_column(label.location.end);
+            let end_column = if end_line == start_line { end_column } else { text.chars().count() + 1 };
+            let start = Renderer::display_column(text, start_column);
+            let end = Renderer::display_column(text, end_column).max(start + 1);
+            spans.push((start, end, label));
+        }
+        if spans.is_empty() {
+            return String::new();
+        }
+        spans.sort_by_key(|&(start, _, _)| start);
+
+        let length = spans.iter().map(|&(_, end, _)| end).max().unwrap();
+        let mut marks = vec![' '; length];
+        // Primary labels are drawn last, so they show where they overlap secondary ones.
+        for &(start, end, _) in spans.iter().filter(|&&(_, _, label)| !label.primary) {
+            for mark in &mut marks[start..end] {
+                *mark = '-';
+            }
+        }
+        for &(start, end, _) in spans.iter().filter(|&&(_, _, label)| label.primary) {
+            for mark in &mut marks[start..end] {
+                *mark = '^';
+            }
+        }
+
+        let mut underline = String::new();
+        let mut index = 0;
+        while index < marks.len() {
+            let mark = marks[index];
+            let run = marks[index..].iter().take_while(|&&other| other == mark).count();
+            let part: String = marks[index..index + run].iter().collect();
+            underline += &match mark {
+                '^' => self.paint(level_colour, &part),
+                '-' => self.paint(BLUE, &part),
+                _ => part
+            };
+            index += run;
+        }
+
+        let &(_, _, last) = spans.last().unwrap();
+        if !last.message.is_empty() {
+            let colour = if last.primary { level_colour } else { BLUE };
+            underline += &format!(" {}", self.paint(colour, &last.message));
+        }
+        let mut output = format!("\n{} {} {}", gutter, self.paint(BLUE, "|"), underline.trim_end());
+
+        for &(start, _, label) in spans[..spans.len() - 1].iter().rev() {
+            if label.message.is_empty() {
+                continue;
+            }
+            let colour = if label.primary { level_colour } else { BLUE };
+            output += &format!("\n{} {} {}{}", gutter, self.paint(BLUE, "|"), " ".repeat(start), self.paint(colour, &label.message));
+        }
+        return output;
+    }
+
+    /// Line numbers the labels start on, in order.
+    fn shown_lines(file: &SourceFile, labels: &[Label]) -> Vec<usize> {
+        let mut lines: Vec<usize> = labels.iter().map(|label| file.line_column(label.location.start).0).collect();
+        lines.sort();
+        lines.dedup();
+        return lines;
+    }
+
+    /// Zero-based column a one-based character column is shown at, once tabs are expanded.
+    fn display_column(text: &str, column: usize) -> usize {
+        let mut display = 0;
+        for char in text.chars().take(column - 1) {
+            display += if char == '\t' { TAB_WIDTH } else { 1 };
+        }
+        return display + (column - 1).saturating_sub(text.chars().count());
+    }
+
+    fn expand_tabs(text: &str) -> String {
+        return text.replace('\t', &" ".repeat(TAB_WIDTH));
+    }
+
+    /// The path relative to the working directory when it is inside it.
+    fn display_path(path: &Path) -> PathBuf {
+        return match env::current_dir() {
+            Ok(dir) => match path.strip_prefix(&dir) {
+                Ok(relative) => relative.to_path_buf(),
+                Err(_) => path.to_path_buf()
+            },
+            Err(_) => path.to_path_buf()
+        };
+    }
+
+    fn paint(&self, colour: &str, text: &str) -> String {
+        if !self.colour {
+            return text.to_owned();
+        }
+        return format!("{}{}{}", colour, text, RESET);
+    }
+}
@@ -1,29 +1,84 @@
 use super::parser::ParserError;
 use super::ConfigError;
+use super::ast::SourceLocation;
+use super::diagnostics::{codes, Diagnostic, Label, SourceFile};
+use super::module::ResolverError;
+
+use std::error::Error;
+use std::fmt;
+use std::io;
+use std::path::PathBuf;
 
 #[derive(Debug)]
 pub enum CompilerError {
     InvalidConfig(ConfigError),
     ParserError(ParserError),
+    /// An import which could not be resolved, with the file importing it and where, when known.
+    ResolverError(ResolverError, Option<(SourceFile, SourceLocation)>),
+    /// Diagnostics of a phase in which a plugin reported an error, warnings included.
+    PluginError(Vec<Diagnostic>),
+    /// An error along with the diagnostics plugins reported before it, which may explain it.
+    WithPluginDiagnostics(Box<CompilerError>, Vec<Diagnostic>),
     EntryNotValid(String),
-    NoEntries
+    NoEntries,
+    /// A resolved import whose file could not be read.
+    SourceUnreadable(PathBuf, io::Error),
+    /// Files of the bundle which could not be written, each with the reason.
+    OutputNotWritten(Vec<(PathBuf, io::Error)>),
 }
 
-impl ToString for CompilerError {
-    fn to_string(&self) -> String {
+impl CompilerError {
+    /// The error as diagnostics to report, one for each problem found.
+    pub fn diagnostics(&self) -> Vec<Diagnostic> {
         use self::CompilerError::*;
-        use self::ConfigError::*;
 
-        match self {
-            &InvalidConfig(ref config_err) => match config_err {
-                &FileNotFound(ref path) => format!(" File not found at path {}", path),
-                &JsonInvalid => " Invalid json contents".to_owned(),
-                &FileContentsInvalid => " File contents are invalid".to_owned(),
-                &InvalidConfigOption(ref option, ref reason) => format!(" Invalid config option '{}': {}", option, reason),
-            },
-            &ParserError(ref parser_err) => format!(" {:?}", parser_err),
-            &EntryNotValid(ref entry_name) => format!(" Invalid entry file {}", entry_name),
-            &NoEntries => format!("{}", " No entries defined")
-        }
+        return match *self {
+            InvalidConfig(ref config_err) => vec![config_err.to_diagnostic()],
+            ParserError(ref parser_err) => vec![parser_err.to_diagnostic()],
+            ResolverError(ref resolver_err, ref importer) => {
+                let diagnostic = resolver_err.to_diagnostic();
+                vec![match *importer {
+                    Some((ref file, ref location)) => diagnostic.with_file(file.clone()).with_label(Label::primary(location.clone(), String::from("imported here"))),
+                    None => diagnostic
+                }]
+            }
+            PluginError(ref diagnostics) => diagnostics.clone(),
+            WithPluginDiagnostics(ref err, ref diagnostics) => diagnostics.iter().cloned().chain(err.diagnostics()).collect(),
+            EntryNotValid(ref entry_name) => {
+                vec![Diagnostic::error(codes::ENTRY_NOT_FOUND, format!("Invalid entry file {}", entry_name))
+                    .with_note(String::from("Entries are resolved from the directory of maxwell.json"))]
+            }
+            NoEntries => {
+                vec![Diagnostic::error(codes::NO_ENTRIES, String::from("No entries defined"))
+                    .with_help(String::from("List the files to build in \"src.entry\" of maxwell.json"))]
+            }
+            SourceUnreadable(ref path, ref err) => {
+                vec![Diagnostic::error(codes::SOURCE_UNREADABLE, format!("Cannot read {}: {}", path.display(), err))]
+            }
+            OutputNotWritten(ref failures) => {
+                failures.iter().map(|&(ref path, ref err)| Diagnostic::error(codes::OUTPUT_NOT_WRITTEN, format!("Cannot write {}: {}", path.display(), err))).collect()
+            }
+        };
+    }
+}
+
+/// Every diagnostic of the error, rendered without colour.
+impl fmt::Display for CompilerError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let rendered: Vec<String> = self.diagnostics().iter().map(|diagnostic| diagnostic.to_string()).collect();
+        f.write_str(&rendered.join("\n\n"))
     }
-}
\ No newline at end of file
+}
+
+impl Error for CompilerError {
+    fn source(&self) -> Option<&(Error + 'static)> {
+        return match *self {
+            CompilerError::InvalidConfig(ref err) => Some(err),
+            CompilerError::ParserError(ref err) => Some(err),
+            CompilerError::ResolverError(ref err, _) => Some(err),
+            CompilerError::SourceUnreadable(_, ref err) => Some(err),
+            CompilerError::WithPluginDiagnostics(ref err, _) => err.source(),
+            _ => None
+        };
+    }
+}
@@ -1,9 +1,156 @@
-use super::ast::Node;
+use super::ast::{Node, SyntaxTree, CodeWriter, Position};
+use super::GeneratorOptions;
+use super::comments::CommentMap;
 
-pub struct Generator {}
+mod printer;
+mod source_map;
+
+use self::printer::Printer;
+
+pub use self::source_map::{SourceMap, SourceMapBuilder, Mapping};
+
+use std::fmt;
+use std::io;
+
+/// Writes syntax trees as code. Without options the trivia of every node is written back, so the
+/// code keeps the layout and comments of its source. With options the trivia is ignored and the
+/// code is laid out as they ask, keeping the comments the format allows when the source of the
+/// tree is given.
+pub struct Generator {
+    options: Option<GeneratorOptions>,
+}
 
 impl Generator {
+    pub fn new() -> Self {
+        return Generator { options: None };
+    }
+
+    pub fn with_options(options: GeneratorOptions) -> Self {
+        return Generator { options: Some(options) };
+    }
+
+    /// Writes a node with its trivia.
     pub fn generate(&self, node: &Node) -> String {
         return node.generate();
     }
-}
\ No newline at end of file
+
+    pub fn generate_tree(&self, tree: &SyntaxTree) -> String {
+        let mut code = String::new();
+        // Writing to a `String` cannot fail.
+        self.write_tree(tree, &mut CodeWriter::new(&mut code)).unwrap();
+        return code;
+    }
+
+    pub fn write_tree(&self, tree: &SyntaxTree, output: &mut CodeWriter) -> fmt::Result {
+        return match self.options {
+            Some(ref options) => Printer::new(options).print(tree, output).map(|_| ()),
+            None => tree.write(output)
+        };
+    }
+
+    pub fn generate_tree_from(&self, tree: &SyntaxTree, source: &str) -> String {
+        let mut code = String::new();
+        self.write_tree_from(tree, source, &mut CodeWriter::new(&mut code)).unwrap();
+        return code;
+    }
+
+    /// Writes a tree parsed from `source`. Its comments are attached to their nodes against the
+    /// source, so they can be written with the nodes when the trivia is ignored.
+    pub fn write_tree_from(&self, tree: &SyntaxTree, source: &str, output: &mut CodeWriter) -> fmt::Result {
+        return match self.options {
+            Some(ref options) => {
+                let comments = CommentMap::attach(tree, source);
+                Printer::new(options).with_comments(&comments).print(tree, output).map(|_| ())
+            }
+            None => tree.write(output)
+        };
+    }
+
+    /// Writes a tree into a file or any other writer as it is generated, returning where the code
+    /// ended.
+    pub fn stream_tree<W: io::Write>(&self, tree: &SyntaxTree, writer: W) -> io::Result<Position> {
+        let mut sink = IoSink { writer, error: None };
+        let (result, end) = {
+            let mut output = CodeWriter::new(&mut sink);
+            (self.write_tree(tree, &mut output), output.position())
+        };
+        return sink.finish(result).map(|_| end);
+    }
+
+    /// Writes a tree parsed from `source` into a writer as `write_tree_from` does.
+    pub fn stream_tree_from<W: io::Write>(&self, tree: &SyntaxTree, source: &str, writer: W) -> io::Result<Position> {
+        let mut sink = IoSink { writer, error: None };
+        let (result, end) = {
+            let mut output = CodeWriter::new(&mut sink);
+            (self.write_tree_from(tree, source, &mut output), output.position())
+        };
+        return sink.finish(result).map(|_| end);
+    }
+
+    /// Writes a tree parsed from `source` into a writer as it is generated, adding the mappings of
+    /// the code to `map` as `write_tree_mapped` does. Returns where the code ended.
+    pub fn stream_tree_mapped<W: io::Write>(&self, tree: &SyntaxTree, source: &str, writer: W, map: &mut SourceMapBuilder, source_index: usize) -> io::Result<Position> {
+        let mut sink = IoSink { writer, error: None };
+        let (result, end) = {
+            let mut output = CodeWriter::new(&mut sink);
+            (self.write_tree_mapped(tree, source, &mut output, map, source_index), output.position())
+        };
+        return sink.finish(result).map(|_| end);
+    }
+
+    pub fn generate_tree_mapped(&self, tree: &SyntaxTree, source: &str, map: &mut SourceMapBuilder, source_index: usize) -> String {
+        let mut code = String::new();
+        self.write_tree_mapped(tree, source, &mut CodeWriter::new(&mut code), map, source_index).unwrap();
+        return code;
+    }
+
+    /// Writes a tree parsed from `source`, adding the mappings of the code to the source at index
+    /// `source_index` of `map`. The start of every located node is mapped, and identifiers which
+    /// were renamed keep the name they have in the source.
+    pub fn write_tree_mapped<'a>(&self, tree: &SyntaxTree, source: &'a str, output: &mut CodeWriter<'a>, map: &mut SourceMapBuilder, source_index: usize) -> fmt::Result {
+        let marks = match self.options {
+            Some(ref options) => {
+                let comments = CommentMap::attach(tree, source);
+                Printer::new(options).with_source(source).with_comments(&comments).print(tree, output)?
+            }
+            None => {
+                output.mark_source(Some(source));
+                let result = tree.write(output);
+                output.mark_source(None);
+                result?;
+                output.take_marks()
+            }
+        };
+        map.add_marks(source_index, source, &marks);
+        return Ok(());
+    }
+}
+
+/// Hands code to an `io::Write`, keeping the error it failed with as `fmt::Error` carries none.
+struct IoSink<W: io::Write> {
+    writer: W,
+    error: Option<io::Error>,
+}
+
+impl<W: io::Write> IoSink<W> {
+    /// The result of writing into the sink, with the error of the writer it failed on.
+    fn finish(self, result: fmt::Result) -> io::Result<()> {
+        return match (result, self.error) {
+            (Ok(_), _) => Ok(()),
+            (Err(_), Some(err)) => Err(err),
+            (Err(_), None) => Err(io::Error::new(io::ErrorKind::Other, "Cannot write the code"))
+        };
+    }
+}
+
+impl<W: io::Write> fmt::Write for IoSink<W> {
+    fn write_str(&mut self, code: &str) -> fmt::Result {
+        return match self.writer.write_all(code.as_bytes()) {
+            Ok(_) => Ok(()),
+            Err(err) => {
+                self.error = Some(err);
+                Err(fmt::Error)
+            }
+        };
+    }
+}
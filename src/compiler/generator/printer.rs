@@ -0,0 +1,1173 @@
+use super::super::{Literal, QuoteKind, OperatorKind, GeneratorOptions, OutputFormat, QuoteStyle};
+use super::super::ast::{SyntaxTree, Parameter, SourceLocation, CodeWriter, Mark, Position, Comment, CommentKind};
+use super::super::ast::body::BodyNode;
+use super::super::ast::directive::DirectiveNode;
+use super::super::ast::statement::{StatementNode, Statement, StatementTerminator, ImportDeclaration, ExportDeclaration, ModuleSpecifier};
+use super::super::ast::declaration::{DeclarationKind, DeclarationNode};
+use super::super::ast::expression::{ExpressionNode, Expression};
+use super::super::ast::expression::{ASSIGNMENT_PRECEDENCE as ASSIGNMENT, UNARY_PRECEDENCE as UNARY, POSTFIX_PRECEDENCE as POSTFIX, MEMBER_PRECEDENCE as MEMBER, PRIMARY_PRECEDENCE as PRIMARY};
+use super::super::ast::property::{PropertyNode, PropertyKey, PropertyKind};
+use super::super::ast::jsx::{JsxElement, JsxAttribute, JsxAttributeValue, JsxChild, JsxContainer};
+use super::super::comments::{CommentMap, CommentPlacement};
+
+use std::collections::HashMap;
+use std::fmt;
+use std::mem;
+
+/// Writes a syntax tree from its syntax alone, ignoring trivia. Code is handed to the output a
+/// top-level statement at a time, as brackets and semicolons may still be put in front of the
+/// statement being written.
+pub struct Printer<'a> {
+    options: &'a GeneratorOptions,
+    /// Code not handed to the output yet. It keeps the last characters handed over, which decide
+    /// whether the next token needs a space.
+    output: String,
+    depth: usize,
+    /// Set in the head of a `for` loop, where an `in` operator would be read as a `for-in` loop.
+    no_in: bool,
+    /// Source of the tree, when the code written is marked for a source map.
+    source: Option<&'a str>,
+    /// Marks of the code handed to the output.
+    marks: Vec<Mark>,
+    /// Marks of the code in `output`, by their offset in it.
+    buffered_marks: Vec<(usize, Mark)>,
+    /// Mark of the node being written, made at its first token.
+    pending: Option<Mark>,
+    /// Comments kept in the output which were not written yet, by the node they are attached to.
+    comments: HashMap<SourceLocation, Vec<(CommentPlacement, &'a Comment)>>,
+    /// Set after a line comment, which the next code must not follow on the same line.
+    in_line_comment: bool,
+    /// The statement being written. An expression located like it leaves its trailing comments to
+    /// the statement, so they follow the semicolon.
+    statement: SourceLocation,
+    /// The node being written, whose inner comments go in the first empty brackets written for it.
+    node: SourceLocation,
+}
+
+impl<'a> Printer<'a> {
+    pub fn new(options: &'a GeneratorOptions) -> Self {
+        return Printer {
+            options,
+            output: String::new(),
+            depth: 0,
+            no_in: false,
+            source: None,
+            marks: Vec::new(),
+            buffered_marks: Vec::new(),
+            pending: None,
+            comments: HashMap::new(),
+            in_line_comment: false,
+            statement: SourceLocation::default(),
+            node: SourceLocation::default(),
+        };
+    }
+
+    /// Marks the start of every located node, so the code can be mapped back to `source`.
+    pub fn with_source(mut self, source: &'a str) -> Self {
+        self.source = Some(source);
+        return self;
+    }
+
+    /// Writes the comments of the map next to the nodes they are attached to. Pretty output keeps
+    /// every comment, compact output only license comments and `/*#__PURE__*/` annotations.
+    pub fn with_comments(mut self, comments: &'a CommentMap) -> Self {
+        let is_pretty = self.is_pretty();
+        for (comment, attachment) in comments.comments.iter().zip(comments.attachments.iter()) {
+            if is_pretty || comment.is_license() || comment.is_pure_annotation() {
+                self.comments.entry(attachment.node.clone()).or_default().push((attachment.placement, comment));
+            }
+        }
+        return self;
+    }
+
+    /// Writes a tree into the output, returning the marks of its nodes when there is a source.
+    pub fn print(mut self, tree: &SyntaxTree, output: &mut CodeWriter) -> Result<Vec<Mark>, fmt::Error> {
+        if let Some(ref hashbang) = tree.hashbang {
+            self.output += hashbang;
+            self.output.push('\n');
+        }
+
+        let body = &tree.base_node;
+        for (i, directive) in body.directives.iter().enumerate() {
+            self.body_directive(directive, i == 0);
+            self.flush(output, true)?;
+        }
+        for (i, statement) in body.content.iter().enumerate() {
+            self.body_statement(statement, i == 0 && body.directives.is_empty());
+            self.flush(output, true)?;
+        }
+        // Comments of a source without any code belong to the whole source.
+        let nodes: Vec<SourceLocation> = self.comments.keys().filter(|node| node.start == 0).cloned().collect();
+        for node in nodes {
+            for (placement, comment) in self.comments.remove(&node).unwrap() {
+                if placement != CommentPlacement::Inner {
+                    continue;
+                }
+                if !self.output.is_empty() {
+                    self.separate();
+                }
+                self.comment(comment);
+                self.in_line_comment = comment.kind == CommentKind::Line;
+            }
+        }
+        self.flush(output, false)?;
+        return Ok(self.marks);
+    }
+
+    /// Hands the code written so far to the output. Unless it is the last of it, the last two
+    /// characters are kept for `needs_space`.
+    fn flush(&mut self, output: &mut CodeWriter, keep_tail: bool) -> fmt::Result {
+        let end = match keep_tail {
+            true => self.output.char_indices().rev().nth(1).map_or(0, |(index, _)| index),
+            false => self.output.len()
+        };
+
+        let mut position = output.position();
+        let mut offset = 0;
+        for (mark_offset, mut mark) in mem::replace(&mut self.buffered_marks, Vec::new()) {
+            if mark_offset >= end {
+                self.buffered_marks.push((mark_offset - end, mark));
+                continue;
+            }
+            position.advance(&self.output[offset..mark_offset]);
+            offset = mark_offset;
+            mark.generated = position;
+            self.marks.push(mark);
+        }
+
+        output.write(&self.output[..end])?;
+        self.output.drain(..end);
+        return Ok(());
+    }
+
+    /// Marks the next token written as the start of a node. Nodes built by plugins have no
+    /// location and are left to the mark before them.
+    fn mark(&mut self, location: &SourceLocation, name: Option<&str>) {
+        let source = match self.source {
+            Some(source) if Mark::is_located(source, location) => source,
+            _ => return
+        };
+        self.pending = Some(Mark::new(Position::new(), source, location, name));
+    }
+
+    /// Inserts a character into code already written, moving the marks after it along.
+    fn insert(&mut self, offset: usize, char: char) {
+        self.output.insert(offset, char);
+        for &mut (ref mut mark_offset, _) in self.buffered_marks.iter_mut().rev() {
+            if *mark_offset < offset {
+                break;
+            }
+            *mark_offset += char.len_utf8();
+        }
+    }
+
+    fn is_pretty(&self) -> bool {
+        return self.options.format == OutputFormat::Pretty;
+    }
+
+    /// Writes a token, separated from the one before it where they would otherwise be read as one.
+    fn write(&mut self, token: &str) {
+        self.end_comment_line();
+        if let Some(next) = token.chars().next() {
+            if needs_space(&self.output, next) {
+                self.output.push(' ');
+            }
+        }
+        if let Some(mark) = self.pending.take() {
+            self.buffered_marks.push((self.output.len(), mark));
+        }
+        self.output += token;
+    }
+
+    /// A space which only pretty output has.
+    fn space(&mut self) {
+        if self.is_pretty() && !self.in_line_comment {
+            self.output.push(' ');
+        }
+    }
+
+    /// A line break which only pretty output has.
+    fn newline(&mut self) {
+        if self.is_pretty() {
+            self.in_line_comment = false;
+            self.output.push('\n');
+            for _ in 0..self.depth * self.options.indent {
+                self.output.push(' ');
+            }
+        }
+    }
+
+    /// Separates two statements, by a line break unless semicolons do so in compact output.
+    fn separate(&mut self) {
+        match self.is_pretty() {
+            true => self.newline(),
+            false if !self.options.semicolons => {
+                self.in_line_comment = false;
+                self.output.push('\n');
+            }
+            false => {}
+        }
+    }
+
+    /// Breaks the line after a line comment, before anything else is written on it.
+    fn end_comment_line(&mut self) {
+        if mem::replace(&mut self.in_line_comment, false) {
+            match self.is_pretty() {
+                true => self.newline(),
+                false => self.output.push('\n')
+            }
+        }
+    }
+
+    /// Writes the comments attached to a node in a placement. Comments leading a node written on a
+    /// line of its own are put on lines of their own in pretty output.
+    fn attached_comments(&mut self, node: &SourceLocation, placement: CommentPlacement, own_line: bool) {
+        let comments = match self.comments.get_mut(node) {
+            Some(comments) => comments,
+            None => return
+        };
+        let (written, kept) = comments.drain(..).partition(|&(comment_placement, _)| comment_placement == placement);
+        *comments = kept;
+        if comments.is_empty() {
+            self.comments.remove(node);
+        }
+
+        for (_, comment) in written {
+            if placement != CommentPlacement::Leading && !self.output.ends_with('(') {
+                self.space();
+            }
+            self.comment(comment);
+            match comment.kind {
+                CommentKind::Line => self.in_line_comment = true,
+                CommentKind::Block if placement != CommentPlacement::Leading => {}
+                CommentKind::Block if own_line && self.is_pretty() => self.newline(),
+                CommentKind::Block => self.space()
+            }
+        }
+    }
+
+    /// Writes empty brackets, such as an empty block or argument list, with the inner comments of the
+    /// node being written between them.
+    fn empty(&mut self, open: &str, close: &str) {
+        self.write(open);
+        let node = self.node.clone();
+        let length = self.output.len();
+        self.attached_comments(&node, CommentPlacement::Inner, false);
+        if self.output.len() > length && open == "{" {
+            self.space();
+        }
+        self.write(close);
+    }
+
+    fn leading_comments(&mut self, node: &SourceLocation, own_line: bool) {
+        self.attached_comments(node, CommentPlacement::Leading, own_line);
+    }
+
+    /// Writes the comments after a node, with the ones inside of it which have no node to go with.
+    fn trailing_comments(&mut self, node: &SourceLocation) {
+        self.attached_comments(node, CommentPlacement::Inner, false);
+        self.attached_comments(node, CommentPlacement::Trailing, false);
+    }
+
+    /// Writes a comment as it was written. Comments never take the mark of a node.
+    fn comment(&mut self, comment: &Comment) {
+        self.end_comment_line();
+        if comment.text.starts_with(|next| needs_space(&self.output, next)) {
+            self.output.push(' ');
+        }
+        match self.options.ascii_only {
+            true => self.output += &escape_non_ascii(&comment.text, false),
+            false => self.output += &comment.text
+        }
+    }
+
+    fn terminate(&mut self) {
+        if self.options.semicolons {
+            self.write(";");
+        }
+    }
+
+    /// Puts brackets around everything written since `start`.
+    fn bracket_from(&mut self, start: usize) {
+        self.end_comment_line();
+        let start = self.output.len() - self.output[start..].trim_start().len();
+        self.insert(start, '(');
+        self.output.push(')');
+    }
+
+    fn statements(&mut self, body: &BodyNode) {
+        for (i, directive) in body.directives.iter().enumerate() {
+            self.body_directive(directive, i == 0);
+        }
+        for (i, statement) in body.content.iter().enumerate() {
+            self.body_statement(statement, i == 0 && body.directives.is_empty());
+        }
+    }
+
+    fn body_directive(&mut self, directive: &DirectiveNode, first: bool) {
+        if !first {
+            self.separate();
+        }
+        self.leading_comments(&directive.location, true);
+        self.directive(directive);
+    }
+
+    fn body_statement(&mut self, statement: &StatementNode, first: bool) {
+        if !first {
+            self.separate();
+        }
+        self.leading_comments(&statement.location, true);
+        let start = self.output.len();
+        self.statement(statement);
+
+        // Without a semicolon before it, such a statement would continue the previous one.
+        if !first && !self.options.semicolons && self.output[start..].starts_with(&['(', '[', '+', '-', '/', '`', '<'][..]) {
+            self.insert(start, ';');
+        }
+    }
+
+    fn block(&mut self, body: &BodyNode) {
+        if body.directives.is_empty() && body.content.is_empty() {
+            self.empty("{", "}");
+            return;
+        }
+
+        self.write("{");
+        self.depth += 1;
+        self.newline();
+        self.statements(body);
+        self.depth -= 1;
+        self.newline();
+        self.write("}");
+    }
+
+    fn directive(&mut self, directive: &DirectiveNode) {
+        self.mark(&directive.location, None);
+        let string = self.string(&directive.value, &directive.quote_kind);
+        self.write(&string);
+        self.terminate();
+        self.trailing_comments(&directive.location);
+    }
+
+    fn statement(&mut self, node: &StatementNode) {
+        self.leading_comments(&node.location, false);
+        let statement = mem::replace(&mut self.statement, node.location.clone());
+        self.statement_syntax(node);
+        self.statement = statement;
+        self.trailing_comments(&node.location);
+    }
+
+    fn statement_syntax(&mut self, node: &StatementNode) {
+        let parent = mem::replace(&mut self.node, node.location.clone());
+        self.statement_kind(node);
+        self.node = parent;
+    }
+
+    fn statement_kind(&mut self, node: &StatementNode) {
+        self.mark(&node.location, None);
+        match node.statement {
+            Statement::Empty => self.write(";"),
+            Statement::Expression { ref expression } => {
+                if node.terminator == StatementTerminator::Block && expression.expression.is_declaration() {
+                    self.expression(expression, 0);
+                    return;
+                }
+                let start = self.output.len();
+                self.expression(expression, 0);
+                if is_string(expression) || starts_ambiguously(&self.output[start..]) {
+                    self.bracket_from(start);
+                }
+                self.terminate();
+            }
+            Statement::Declaration { ref kind, ref declarations } => {
+                self.declaration(kind, declarations);
+                self.terminate();
+            }
+            Statement::Return { ref expression } => {
+                self.write("return");
+                if let Some(ref expression) = *expression {
+                    self.space();
+                    self.expression(expression, 0);
+                }
+                self.terminate();
+            }
+            Statement::Import { ref alias, ref declaration, ref source, ref trivia } => {
+                self.write("import");
+                self.space();
+                match *declaration {
+                    ImportDeclaration::All => self.write("*"),
+                    ImportDeclaration::Single(ref name) => self.identifier(name),
+                    ImportDeclaration::Multiple(ref specifiers) => self.specifiers(specifiers),
+                }
+                if let Some(ref alias) = *alias {
+                    self.space();
+                    self.write("as");
+                    self.space();
+                    self.identifier(alias);
+                }
+                self.space();
+                self.write("from");
+                self.space();
+                let source = self.string(source, &trivia.quote_kind);
+                self.write(&source);
+                self.terminate();
+            }
+            Statement::Export { ref declaration, .. } => {
+                self.write("export");
+                self.space();
+                match *declaration {
+                    ExportDeclaration::Default(ref expression) => {
+                        self.write("default");
+                        self.space();
+                        match expression.expression {
+                            Expression::Function { .. } | Expression::Class { .. } => self.expression(expression, ASSIGNMENT),
+                            _ => {
+                                let start = self.output.len();
+                                self.expression(expression, ASSIGNMENT);
+                                if starts_ambiguously(&self.output[start..]) {
+                                    self.bracket_from(start);
+                                }
+                                self.terminate();
+                            }
+                        }
+                    }
+                    ExportDeclaration::Statement(ref statement) => self.statement(statement),
+                    ExportDeclaration::Named(ref specifiers) => {
+                        self.specifiers(specifiers);
+                        self.terminate();
+                    }
+                }
+            }
+            Statement::With { ref object, ref body, .. } => {
+                self.write("with");
+                self.space();
+                self.write("(");
+                self.expression(object, 0);
+                self.write(")");
+                self.nested(body);
+            }
+            Statement::Block { ref body, .. } => self.block(body),
+            Statement::For { ref init, ref test, ref update, ref body, .. } => {
+                self.write("for");
+                self.space();
+                self.write("(");
+                if let Some(ref init) = *init {
+                    self.head(init, false);
+                }
+                self.write(";");
+                if let Some(ref test) = *test {
+                    self.space();
+                    self.expression(test, 0);
+                }
+                self.write(";");
+                if let Some(ref update) = *update {
+                    self.space();
+                    self.expression(update, 0);
+                }
+                self.write(")");
+                self.nested(body);
+            }
+            Statement::ForIn { ref left, ref right, ref body, .. } => {
+                self.write("for");
+                self.space();
+                self.write("(");
+                self.head(left, false);
+                self.space();
+                self.write("in");
+                self.space();
+                self.expression(right, 0);
+                self.write(")");
+                self.nested(body);
+            }
+            Statement::ForOf { ref left, ref right, ref body, is_await, .. } => {
+                self.write("for");
+                if is_await {
+                    self.write("await");
+                }
+                self.space();
+                self.write("(");
+                self.head(left, true);
+                self.space();
+                self.write("of");
+                self.space();
+                self.expression(right, ASSIGNMENT);
+                self.write(")");
+                self.nested(body);
+            }
+        }
+    }
+
+    /// Writes the body of a loop or `with` statement on the same line.
+    fn nested(&mut self, statement: &StatementNode) {
+        if statement.statement != Statement::Empty {
+            self.space();
+        }
+        self.statement(statement);
+    }
+
+    /// Writes the declaration or expression which starts the head of a `for` loop.
+    fn head(&mut self, statement: &StatementNode, is_of: bool) {
+        let no_in = mem::replace(&mut self.no_in, true);
+        match statement.statement {
+            Statement::Declaration { ref kind, ref declarations } => self.declaration(kind, declarations),
+            Statement::Expression { ref expression } => {
+                let start = self.output.len();
+                self.expression(expression, 0);
+                let code = self.output[start..].trim_start();
+                if starts_with_word(code, "let") || (is_of && starts_with_word(code, "async")) {
+                    self.bracket_from(start);
+                }
+            }
+            _ => self.statement(statement)
+        }
+        self.no_in = no_in;
+    }
+
+    fn declaration(&mut self, kind: &DeclarationKind, declarations: &[DeclarationNode]) {
+        self.write(&kind.to_string());
+        self.space();
+        for (i, declaration) in declarations.iter().enumerate() {
+            if i > 0 {
+                self.write(",");
+                self.space();
+            }
+            self.mark(&declaration.location, Some(&declaration.name));
+            self.identifier(&declaration.name);
+            if let Some(ref expression) = declaration.expression {
+                self.space();
+                self.write("=");
+                self.space();
+                self.expression(expression, ASSIGNMENT);
+            }
+        }
+    }
+
+    fn specifiers(&mut self, specifiers: &[ModuleSpecifier]) {
+        if specifiers.is_empty() {
+            self.write("{}");
+            return;
+        }
+
+        self.write("{");
+        self.space();
+        for (i, specifier) in specifiers.iter().enumerate() {
+            if i > 0 {
+                self.write(",");
+                self.space();
+            }
+            self.mark(&specifier.location, Some(&specifier.name));
+            self.identifier(&specifier.name);
+            if let Some(ref alias) = specifier.alias {
+                self.space();
+                self.write("as");
+                self.space();
+                self.identifier(alias);
+            }
+        }
+        self.space();
+        self.write("}");
+    }
+
+    /// Writes an expression with its comments, in brackets when it binds more loosely than
+    /// `precedence`.
+    fn expression(&mut self, node: &ExpressionNode, precedence: u8) {
+        self.leading_comments(&node.location, false);
+        self.expression_syntax(node, precedence);
+        if node.location != self.statement {
+            self.trailing_comments(&node.location);
+        }
+    }
+
+    fn expression_syntax(&mut self, node: &ExpressionNode, precedence: u8) {
+        let parent = mem::replace(&mut self.node, node.location.clone());
+        self.expression_kind(node, precedence);
+        self.node = parent;
+    }
+
+    fn expression_kind(&mut self, node: &ExpressionNode, precedence: u8) {
+        match node.expression {
+            Expression::Identifier(ref name) => self.mark(&node.location, Some(name)),
+            _ => self.mark(&node.location, None)
+        }
+        let is_in = match node.expression {
+            Expression::Binary { operator: OperatorKind::In, .. } => true,
+            _ => false
+        };
+        if node.expression.precedence() < precedence || (self.no_in && is_in) {
+            let no_in = mem::replace(&mut self.no_in, false);
+            self.write("(");
+            self.expression_kind(node, 0);
+            self.write(")");
+            self.no_in = no_in;
+            return;
+        }
+
+        match node.expression {
+            Expression::Void => {}
+            Expression::This => self.write("this"),
+            Expression::Super => self.write("super"),
+            Expression::Bracketed { ref expression, .. } => self.expression(expression, precedence),
+            Expression::Identifier(ref name) => self.identifier(name),
+            Expression::Literal(ref literal) => self.literal(literal),
+            Expression::Function { ref name, is_async, is_generator, ref parameters, ref body, .. } => {
+                if is_async {
+                    self.write("async");
+                    self.space();
+                }
+                self.write("function");
+                if is_generator {
+                    self.write("*");
+                }
+                self.space();
+                self.identifier(name);
+                self.function_tail(parameters, body);
+            }
+            Expression::ArrowFunction { ref parameters, ref body, .. } => {
+                self.parameters(parameters);
+                self.space();
+                self.write("=>");
+                self.space();
+                self.block(body);
+            }
+            Expression::Call { ref callee, ref parameters, .. } => {
+                self.expression(callee, MEMBER);
+                self.arguments(parameters);
+            }
+            Expression::New { ref callee, ref parameters, .. } => {
+                self.write("new");
+                self.space();
+                // A call in the callee would take the arguments of `new` for its own.
+                match callee.expression.contains_call() {
+                    true => {
+                        self.write("(");
+                        self.expression(callee, 0);
+                        self.write(")");
+                    }
+                    false => self.expression(callee, MEMBER)
+                }
+                match *parameters {
+                    Some(ref parameters) => self.arguments(parameters),
+                    None => self.arguments(&[])
+                }
+            }
+            Expression::Member { ref object, ref property, .. } => {
+                // The dot would be read as the decimal point of an integer.
+                match object.expression.is_integer() {
+                    true => {
+                        self.write("(");
+                        self.expression(object, 0);
+                        self.write(")");
+                    }
+                    false => self.expression(object, MEMBER)
+                }
+                self.write(".");
+                self.identifier(property);
+            }
+            Expression::ComputedMember { ref object, ref property, .. } => {
+                self.expression(object, MEMBER);
+                self.write("[");
+                self.expression(property, 0);
+                self.write("]");
+            }
+            Expression::Unary { ref operator, ref argument } => {
+                self.write(&operator.to_string());
+                if operator.is_word() {
+                    self.space();
+                }
+                match *operator {
+                    OperatorKind::Spread => self.expression(argument, ASSIGNMENT),
+                    _ => self.expression(argument, UNARY)
+                }
+            }
+            Expression::Update { ref operator, ref argument, prefix, .. } => {
+                if prefix {
+                    self.write(&operator.to_string());
+                }
+                self.expression(argument, MEMBER);
+                if !prefix {
+                    self.write(&operator.to_string());
+                }
+            }
+            Expression::Binary { ref left, ref operator, ref right, .. } => {
+                let precedence = operator.precedence();
+                // `**` groups to the right and its left operand must not be a unary expression.
+                let (left_precedence, right_precedence) = match *operator {
+                    OperatorKind::Exponent => (POSTFIX, precedence),
+                    _ => (precedence, precedence + 1)
+                };
+                self.expression(left, left_precedence);
+                self.space();
+                self.write(&operator.to_string());
+                self.space();
+                self.expression(right, right_precedence);
+            }
+            Expression::Assignment { ref left, ref operator, ref right, .. } => {
+                self.expression(left, MEMBER);
+                self.space();
+                self.write(&operator.to_string());
+                self.space();
+                self.expression(right, ASSIGNMENT);
+            }
+            Expression::Await { ref argument } => {
+                self.write("await");
+                self.space();
+                self.expression(argument, UNARY);
+            }
+            Expression::Yield { ref argument, delegate, .. } => {
+                self.write("yield");
+                if delegate {
+                    self.write("*");
+                }
+                if let Some(ref argument) = *argument {
+                    self.space();
+                    self.expression(argument, ASSIGNMENT);
+                }
+            }
+            Expression::Object { ref properties, .. } => self.object(properties),
+            Expression::Array { ref elements, .. } => {
+                self.write("[");
+                for (i, element) in elements.iter().enumerate() {
+                    if i > 0 {
+                        self.write(",");
+                    }
+                    if let Some(ref element) = *element {
+                        if i > 0 {
+                            self.space();
+                        }
+                        self.expression(element, ASSIGNMENT);
+                    }
+                }
+                // A hole at the end needs a comma of its own.
+                if elements.last().map_or(false, |element| element.is_none()) {
+                    self.write(",");
+                }
+                self.write("]");
+            }
+            Expression::Class { ref name, ref super_class, ref members, .. } => {
+                self.write("class");
+                if !name.is_empty() {
+                    self.space();
+                    self.identifier(name);
+                }
+                if let Some(ref super_class) = *super_class {
+                    self.space();
+                    self.write("extends");
+                    self.space();
+                    self.expression(super_class, MEMBER);
+                }
+                self.space();
+                match members.is_empty() {
+                    true => self.empty("{", "}"),
+                    false => {
+                        self.write("{");
+                        self.depth += 1;
+                        for member in members {
+                            self.newline();
+                            self.property(member);
+                            self.trailing_comments(&member.location);
+                        }
+                        self.depth -= 1;
+                        self.newline();
+                        self.write("}");
+                    }
+                }
+            }
+            Expression::Conditional { ref test, ref consequent, ref alternate, .. } => {
+                self.expression(test, OperatorKind::LogicalOr.precedence());
+                self.space();
+                self.write("?");
+                self.space();
+                self.expression(consequent, ASSIGNMENT);
+                self.space();
+                self.write(":");
+                self.space();
+                self.expression(alternate, ASSIGNMENT);
+            }
+            Expression::Jsx(ref element) => self.jsx(element),
+        }
+    }
+
+    fn function_tail(&mut self, parameters: &[Parameter], body: &BodyNode) {
+        self.parameters(parameters);
+        self.space();
+        self.block(body);
+    }
+
+    /// Writes the parameters of a function, with the comments trailing a parameter after its comma.
+    fn parameters(&mut self, parameters: &[Parameter]) {
+        self.write("(");
+        for (i, parameter) in parameters.iter().enumerate() {
+            self.leading_comments(&parameter.location, false);
+            self.identifier(&parameter.name);
+            if let Some(ref default) = parameter.default {
+                self.space();
+                self.write("=");
+                self.space();
+                self.literal(default);
+            }
+            if i + 1 < parameters.len() {
+                self.write(",");
+            }
+            self.trailing_comments(&parameter.location);
+            if i + 1 < parameters.len() {
+                self.space();
+            }
+        }
+        self.write(")");
+    }
+
+    /// Writes the arguments of a call, with the comments trailing an argument after its comma.
+    fn arguments(&mut self, arguments: &[ExpressionNode]) {
+        if arguments.is_empty() {
+            self.empty("(", ")");
+            return;
+        }
+
+        self.write("(");
+        for (i, argument) in arguments.iter().enumerate() {
+            self.leading_comments(&argument.location, false);
+            self.expression_syntax(argument, ASSIGNMENT);
+            if i + 1 < arguments.len() {
+                self.write(",");
+            }
+            self.trailing_comments(&argument.location);
+            if i + 1 < arguments.len() {
+                self.space();
+            }
+        }
+        self.write(")");
+    }
+
+    /// Writes an object literal, with a property per line in pretty output.
+    fn object(&mut self, properties: &[PropertyNode]) {
+        if properties.is_empty() {
+            self.empty("{", "}");
+            return;
+        }
+
+        self.write("{");
+        self.depth += 1;
+        for (i, property) in properties.iter().enumerate() {
+            self.newline();
+            self.property(property);
+            if i + 1 < properties.len() || (self.is_pretty() && self.options.trailing_commas) {
+                self.write(",");
+            }
+            self.trailing_comments(&property.location);
+        }
+        self.depth -= 1;
+        self.newline();
+        self.write("}");
+    }
+
+    /// Writes a property of an object literal or a member of a class body with its leading
+    /// comments. Its trailing comments follow the comma after it.
+    fn property(&mut self, property: &PropertyNode) {
+        self.leading_comments(&property.location, true);
+        self.mark(&property.location, None);
+        if property.is_static {
+            self.write("static");
+            self.space();
+        }
+
+        let function = match property.value {
+            Some(ExpressionNode { expression: Expression::Function { is_async, is_generator, ref parameters, ref body, .. }, .. }) => {
+                match property.kind {
+                    PropertyKind::Method | PropertyKind::Get | PropertyKind::Set => Some((is_async, is_generator, parameters, body)),
+                    _ => None
+                }
+            }
+            _ => None
+        };
+
+        match (&property.kind, function) {
+            (&PropertyKind::Get, _) => {
+                self.write("get");
+                self.space();
+            }
+            (&PropertyKind::Set, _) => {
+                self.write("set");
+                self.space();
+            }
+            (_, Some((is_async, is_generator, _, _))) => {
+                if is_async {
+                    self.write("async");
+                    self.space();
+                }
+                if is_generator {
+                    self.write("*");
+                }
+            }
+            _ => {}
+        }
+
+        match property.key {
+            PropertyKey::Identifier(ref name) => {
+                self.mark(&property.key_location, Some(name));
+                self.identifier(name);
+            }
+            PropertyKey::Literal(ref literal) => {
+                self.mark(&property.key_location, None);
+                self.literal(literal);
+            }
+            PropertyKey::Computed(ref expression) => {
+                self.write("[");
+                self.expression(expression, ASSIGNMENT);
+                self.write("]");
+            }
+        }
+
+        if let Some((_, _, parameters, body)) = function {
+            self.function_tail(parameters, body);
+            return;
+        }
+        match property.kind {
+            PropertyKind::Field => {
+                if let Some(ref value) = property.value {
+                    self.space();
+                    self.write("=");
+                    self.space();
+                    self.expression(value, ASSIGNMENT);
+                }
+                self.write(";");
+            }
+            _ => {
+                if let Some(ref value) = property.value {
+                    self.write(":");
+                    self.space();
+                    self.expression(value, ASSIGNMENT);
+                }
+            }
+        }
+    }
+
+    fn jsx(&mut self, element: &JsxElement) {
+        let name = match element.name {
+            Some(ref name) => name.as_str(),
+            None => ""
+        };
+
+        self.write("<");
+        self.output += name;
+        for attribute in &element.attributes {
+            self.output.push(' ');
+            match *attribute {
+                JsxAttribute::Named { ref name, ref value, .. } => {
+                    self.output += name;
+                    match *value {
+                        Some(JsxAttributeValue::String(ref value, ref quote)) => {
+                            let value = self.jsx_text(value);
+                            self.output += &format!("={}{}{}", quote.to_string(), value, quote.to_string());
+                        }
+                        Some(JsxAttributeValue::Container(ref container)) => {
+                            self.output.push('=');
+                            self.jsx_container(container);
+                        }
+                        Some(JsxAttributeValue::Element(ref element)) => {
+                            self.output.push('=');
+                            self.expression(element, PRIMARY);
+                        }
+                        None => {}
+                    }
+                }
+                JsxAttribute::Spread { ref argument, .. } => self.jsx_spread(argument),
+            }
+        }
+
+        let children = match element.children {
+            Some(ref children) => children,
+            None => {
+                self.space();
+                self.output += "/>";
+                return;
+            }
+        };
+        self.output.push('>');
+        for child in children {
+            match *child {
+                JsxChild::Text(ref text) => {
+                    let text = self.jsx_text(text);
+                    self.output += &text;
+                }
+                JsxChild::Container(ref container) => self.jsx_container(container),
+                JsxChild::Spread { ref argument, .. } => self.jsx_spread(argument),
+                JsxChild::Element(ref element) => self.expression(element, PRIMARY),
+            }
+        }
+        self.output += "</";
+        self.output += name;
+        self.output.push('>');
+    }
+
+    fn jsx_container(&mut self, container: &JsxContainer) {
+        self.output.push('{');
+        if let Some(ref expression) = container.expression {
+            self.expression(expression, ASSIGNMENT);
+        }
+        self.end_comment_line();
+        self.output.push('}');
+    }
+
+    fn jsx_spread(&mut self, argument: &ExpressionNode) {
+        self.output += "{...";
+        self.expression(argument, ASSIGNMENT);
+        self.end_comment_line();
+        self.output.push('}');
+    }
+
+    /// JSX text has no escapes, characters outside of ASCII are written as character references.
+    fn jsx_text(&self, text: &str) -> String {
+        if !self.options.ascii_only {
+            return text.to_owned();
+        }
+        let mut string = String::new();
+        for char in text.chars() {
+            match char.is_ascii() {
+                true => string.push(char),
+                false => string += &format!("&#x{:X};", char as u32)
+            }
+        }
+        return string;
+    }
+
+    fn identifier(&mut self, name: &str) {
+        match self.options.ascii_only {
+            true => self.write(&escape_non_ascii(name, true)),
+            false => self.write(name)
+        }
+    }
+
+    fn literal(&mut self, literal: &Literal) {
+        match *literal {
+            Literal::String(ref value, ref quote) => {
+                let string = self.string(value, quote);
+                self.write(&string);
+            }
+            _ => self.write(&literal.to_string())
+        }
+    }
+
+    /// A string literal of the raw value, in the quotes the options ask for.
+    fn string(&self, value: &str, quote: &QuoteKind) -> String {
+        let target = match self.options.quotes {
+            QuoteStyle::Double => QuoteKind::SpeechMark,
+            QuoteStyle::Single => QuoteKind::Apostrophe,
+            QuoteStyle::Preserve => quote.clone(),
+        };
+        let mut value = match *quote == target {
+            true => value.to_owned(),
+            false => requote(value, quote, &target)
+        };
+        if self.options.ascii_only {
+            value = escape_non_ascii(&value, false);
+        }
+        return format!("{}{}{}", target.to_string(), value, target.to_string());
+    }
+}
+
+/// Whether a token starting with `next` needs a space to stay apart from the code before it.
+fn needs_space(output: &str, next: char) -> bool {
+    let last = match output.chars().next_back() {
+        Some(last) => last,
+        None => return false
+    };
+    return (is_word_char(last) && is_word_char(next))
+        || ((last == '+' || last == '-') && next == last)
+        || (last == '/' && (next == '/' || next == '*'))
+        || (last == '<' && next == '!')
+        || (output.ends_with("--") && next == '>');
+}
+
+fn is_word_char(char: char) -> bool {
+    return char.is_alphanumeric() || char == '_' || char == '$' || char == '\\' || !char.is_ascii();
+}
+
+fn starts_with_word(code: &str, word: &str) -> bool {
+    return code.starts_with(word) && !code[word.len()..].starts_with(is_word_char);
+}
+
+/// Whether an expression statement starting with this code would be read as a declaration or a
+/// block, or in the case of `let [`, as a lexical declaration.
+fn starts_ambiguously(code: &str) -> bool {
+    let code = code.trim_start();
+    return code.starts_with('{')
+        || starts_with_word(code, "function")
+        || starts_with_word(code, "class")
+        || (starts_with_word(code, "async") && starts_with_word(code[5..].trim_start(), "function"))
+        || (starts_with_word(code, "let") && code[3..].trim_start().starts_with('['));
+}
+
+/// Whether an expression is a string literal, which as a statement could be read as a directive.
+fn is_string(expression: &ExpressionNode) -> bool {
+    return match expression.expression {
+        Expression::Bracketed { ref expression, .. } => is_string(expression),
+        Expression::Literal(Literal::String(..)) => true,
+        _ => false
+    };
+}
+
+fn quote_char(quote: &QuoteKind) -> char {
+    return match *quote {
+        QuoteKind::SpeechMark => '"',
+        QuoteKind::Apostrophe => '\'',
+    };
+}
+
+/// Rewrites the raw value of a string literal for other quotes. Escaped old quotes no longer need
+/// their backslash, the new quotes need one.
+fn requote(value: &str, from: &QuoteKind, to: &QuoteKind) -> String {
+    let (from, to) = (quote_char(from), quote_char(to));
+    let mut string = String::new();
+    let mut chars = value.chars();
+    while let Some(char) = chars.next() {
+        match char {
+            '\\' => match chars.next() {
+                Some(escaped) if escaped == from => string.push(escaped),
+                Some(escaped) => {
+                    string.push('\\');
+                    string.push(escaped);
+                }
+                None => string.push('\\')
+            },
+            _ if char == to => {
+                string.push('\\');
+                string.push(char);
+            }
+            _ => string.push(char)
+        }
+    }
+    return string;
+}
+
+/// Escapes characters outside of ASCII as `\uXXXX`. Identifiers cannot hold surrogate pairs, so
+/// characters outside of the basic plane are written as `\u{XXXXX}` in them.
+fn escape_non_ascii(value: &str, is_identifier: bool) -> String {
+    let mut string = String::new();
+    let mut escaped = false;
+    for char in value.chars() {
+        if char.is_ascii() {
+            escaped = char == '\\' && !escaped;
+            string.push(char);
+            continue;
+        }
+
+        // An escaped character stands for itself, a line continuation for nothing.
+        if escaped {
+            string.pop();
+            escaped = false;
+            if char == '\u{2028}' || char == '\u{2029}' {
+                continue;
+            }
+        }
+
+        if is_identifier && char as u32 > 0xFFFF {
+            string += &format!("\\u{{{:X}}}", char as u32);
+            continue;
+        }
+        let mut units = [0; 2];
+        for unit in char.encode_utf16(&mut units) {
+            string += &format!("\\u{:04X}", unit);
+        }
+    }
+    return string;
+}
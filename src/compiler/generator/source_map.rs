@@ -0,0 +1,445 @@
+extern crate json;
+
+use super::super::ast::{Mark, Position};
+
+use std::collections::HashMap;
+
+const BASE64: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// A position in generated code mapped to a position in a source. Lines and columns start at zero
+/// and columns count UTF-16 code units, as source maps do.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Mapping {
+    pub generated_line: usize,
+    pub generated_column: usize,
+    pub source: usize,
+    pub original_line: usize,
+    pub original_column: usize,
+    /// Index in `names` of the name the code had in the source, where it was renamed.
+    pub name: Option<usize>,
+}
+
+/// A version 3 source map.
+#[derive(Debug, Clone)]
+pub struct SourceMap {
+    /// Name of the generated file.
+    pub file: Option<String>,
+    /// Sources, relative to the map. A `sourceRoot` of a parsed map is joined with its sources.
+    pub sources: Vec<String>,
+    /// Contents of the sources, `None` where they are not included.
+    pub sources_content: Vec<Option<String>>,
+    pub names: Vec<String>,
+    pub mappings: Vec<Mapping>,
+}
+
+impl SourceMap {
+    /// A map without sources, of code which was generated from nothing.
+    pub fn new(file: Option<String>) -> Self {
+        return SourceMap { file, sources: Vec::new(), sources_content: Vec::new(), names: Vec::new(), mappings: Vec::new() };
+    }
+
+    /// Reads a map written by another tool. Index maps, which are made of sections, are not
+    /// supported.
+    pub fn from_json(text: &str) -> Result<Self, String> {
+        let map = match json::parse(text) {
+            Ok(map) => map,
+            Err(err) => return Err(format!("Invalid JSON: {}", err))
+        };
+        if map["version"].as_u32() != Some(3) {
+            return Err(String::from("Only version 3 source maps are supported"));
+        }
+        if !map["sections"].is_null() {
+            return Err(String::from("Index maps are not supported"));
+        }
+        let mappings = match map["mappings"].as_str() {
+            Some(mappings) => mappings,
+            None => return Err(String::from("Expected the mappings as a string"))
+        };
+
+        let source_root = match map["sourceRoot"].as_str() {
+            Some(root) if !root.is_empty() && !root.ends_with('/') => format!("{}/", root),
+            Some(root) => root.to_owned(),
+            None => String::new()
+        };
+        let sources: Vec<String> = map["sources"].members()
+            .map(|source| format!("{}{}", source_root, source.as_str().unwrap_or_default()))
+            .collect();
+        let sources_content = (0..sources.len())
+            .map(|i| map["sourcesContent"][i].as_str().map(|content| content.to_owned()))
+            .collect();
+        let names: Vec<String> = map["names"].members().map(|name| name.as_str().unwrap_or_default().to_owned()).collect();
+
+        let mappings = decode_mappings(mappings, sources.len(), names.len())?;
+        return Ok(SourceMap {
+            file: map["file"].as_str().map(|file| file.to_owned()),
+            sources,
+            sources_content,
+            names,
+            mappings,
+        });
+    }
+
+    /// Reads the map of a `data:` URL, as inline maps are given.
+    pub fn from_data_url(url: &str) -> Result<Self, String> {
+        let (header, data) = match url.find(',') {
+            Some(comma) if url.starts_with("data:") => (&url[5..comma], &url[comma + 1..]),
+            _ => return Err(String::from("Expected a data URL"))
+        };
+        if !header.starts_with("application/json") {
+            return Err(format!("Unexpected media type '{}'", header));
+        }
+        if !header.ends_with(";base64") {
+            return SourceMap::from_json(data);
+        }
+        let text = match decode_base64(data).map(String::from_utf8) {
+            Some(Ok(text)) => text,
+            _ => return Err(String::from("Invalid base64 data"))
+        };
+        return SourceMap::from_json(&text);
+    }
+
+    /// Maps the code of this map through `input`, a map of the code this map was made from to
+    /// other sources, so the code maps straight to those sources. The sources of this map are all
+    /// taken to be the code `input` maps. Positions `input` does not map are dropped.
+    pub fn compose(&self, input: &SourceMap) -> SourceMap {
+        let mut map = SourceMap::new(self.file.clone());
+        map.sources = input.sources.clone();
+        map.sources_content = input.sources_content.clone();
+        let mut name_indices = HashMap::new();
+
+        for mapping in &self.mappings {
+            let original = match input.find(mapping.original_line, mapping.original_column) {
+                Some(original) => original,
+                None => continue
+            };
+            // The name in the source, unless the code in between renamed it. The name of an input
+            // mapping belongs to the code at its start only.
+            let exact = original.generated_column == mapping.original_column;
+            let name = match (original.name, mapping.name) {
+                (Some(index), _) if exact => Some(&input.names[index]),
+                (_, Some(index)) => Some(&self.names[index]),
+                _ => None
+            };
+            let name = name.map(|name| *name_indices.entry(name.clone()).or_insert_with(|| {
+                map.names.push(name.clone());
+                map.names.len() - 1
+            }));
+            map.mappings.push(Mapping {
+                generated_line: mapping.generated_line,
+                generated_column: mapping.generated_column,
+                source: original.source,
+                original_line: original.original_line,
+                original_column: original.original_column,
+                name,
+            });
+        }
+        return map;
+    }
+
+    /// The mapping a generated position falls in: the last one starting on its line at or before
+    /// its column.
+    pub fn find(&self, line: usize, column: usize) -> Option<&Mapping> {
+        let next = match self.mappings.binary_search_by(|mapping| (mapping.generated_line, mapping.generated_column).cmp(&(line, column))) {
+            Ok(index) => return Some(&self.mappings[index]),
+            Err(next) => next
+        };
+        return match next {
+            0 => None,
+            _ if self.mappings[next - 1].generated_line == line => Some(&self.mappings[next - 1]),
+            _ => None
+        };
+    }
+
+    /// Drops every mapping, for code which was changed without a map of the change.
+    pub fn clear_mappings(&mut self) {
+        self.mappings.clear();
+        self.names.clear();
+    }
+
+    pub fn to_json(&self) -> String {
+        let mut map = json::JsonValue::new_object();
+        map["version"] = 3.into();
+        if let Some(ref file) = self.file {
+            map["file"] = file.as_str().into();
+        }
+        map["sources"] = json::JsonValue::Array(self.sources.iter().map(|source| source.as_str().into()).collect());
+        if self.sources_content.iter().any(|content| content.is_some()) {
+            map["sourcesContent"] = json::JsonValue::Array(self.sources_content.iter().map(|content| match *content {
+                Some(ref content) => content.as_str().into(),
+                None => json::JsonValue::Null
+            }).collect());
+        }
+        map["names"] = json::JsonValue::Array(self.names.iter().map(|name| name.as_str().into()).collect());
+        map["mappings"] = self.encode_mappings().into();
+        return map.dump();
+    }
+
+    /// The map as a `data:` URL, for a `sourceMappingURL` comment of an inline map.
+    pub fn to_data_url(&self) -> String {
+        return format!("data:application/json;charset=utf-8;base64,{}", encode_base64(self.to_json().as_bytes()));
+    }
+
+    /// The `mappings` field: lines separated by `;`, segments by `,`, every field a VLQ relative to
+    /// the same field of the segment before it. Generated columns are relative within a line only.
+    fn encode_mappings(&self) -> String {
+        let mut mappings = String::new();
+        let mut line = 0;
+        let mut column = 0;
+        let mut source = 0;
+        let mut original_line = 0;
+        let mut original_column = 0;
+        let mut name = 0;
+
+        for (i, mapping) in self.mappings.iter().enumerate() {
+            if mapping.generated_line > line {
+                while line < mapping.generated_line {
+                    mappings.push(';');
+                    line += 1;
+                }
+                column = 0;
+            } else if i > 0 {
+                mappings.push(',');
+            }
+
+            encode_vlq(&mut mappings, mapping.generated_column as i64 - column as i64);
+            encode_vlq(&mut mappings, mapping.source as i64 - source as i64);
+            encode_vlq(&mut mappings, mapping.original_line as i64 - original_line as i64);
+            encode_vlq(&mut mappings, mapping.original_column as i64 - original_column as i64);
+            if let Some(index) = mapping.name {
+                encode_vlq(&mut mappings, index as i64 - name as i64);
+                name = index;
+            }
+            column = mapping.generated_column;
+            source = mapping.source;
+            original_line = mapping.original_line;
+            original_column = mapping.original_column;
+        }
+        return mappings;
+    }
+}
+
+/// Collects the mappings of a chunk while it is generated.
+pub struct SourceMapBuilder {
+    map: SourceMap,
+    name_indices: HashMap<String, usize>,
+}
+
+impl SourceMapBuilder {
+    pub fn new(file: Option<String>) -> Self {
+        return SourceMapBuilder { map: SourceMap::new(file), name_indices: HashMap::new() };
+    }
+
+    /// Adds a source, returning the index mappings refer to it by.
+    pub fn add_source(&mut self, source: String, content: Option<String>) -> usize {
+        self.map.sources.push(source);
+        self.map.sources_content.push(content);
+        return self.map.sources.len() - 1;
+    }
+
+    /// Maps a position of the generated code to a position in a source. Mappings are added in the
+    /// order of the generated code. A later mapping of the same generated position replaces the
+    /// one before it, as it belongs to a node nested in the one before.
+    pub fn add_mapping(&mut self, generated: Position, source: usize, original_line: usize, original_column: usize, name: Option<&str>) {
+        let name = name.map(|name| self.name_index(name));
+        let mapping = Mapping {
+            generated_line: generated.line,
+            generated_column: generated.column,
+            source,
+            original_line,
+            original_column,
+            name,
+        };
+
+        if let Some(last) = self.map.mappings.last_mut() {
+            if last.generated_line == generated.line && last.generated_column == generated.column {
+                *last = mapping;
+                return;
+            }
+        }
+        self.map.mappings.push(mapping);
+    }
+
+    /// Adds the mappings of marks made while generating code from `text`, the source at index
+    /// `source`.
+    pub fn add_marks(&mut self, source: usize, text: &str, marks: &[Mark]) {
+        let lines = LineIndex::new(text);
+        for mark in marks {
+            let (line, column) = lines.position(mark.original);
+            self.add_mapping(mark.generated, source, line, column, mark.name.as_deref());
+        }
+    }
+
+    pub fn build(self) -> SourceMap {
+        return self.map;
+    }
+
+    fn name_index(&mut self, name: &str) -> usize {
+        if let Some(&index) = self.name_indices.get(name) {
+            return index;
+        }
+        self.map.names.push(name.to_owned());
+        self.name_indices.insert(name.to_owned(), self.map.names.len() - 1);
+        return self.map.names.len() - 1;
+    }
+}
+
+/// Finds the line and column of byte offsets of a source.
+pub struct LineIndex<'a> {
+    source: &'a str,
+    line_starts: Vec<usize>,
+}
+
+impl<'a> LineIndex<'a> {
+    pub fn new(source: &'a str) -> Self {
+        let mut line_starts = vec![0];
+        for (offset, char) in source.char_indices() {
+            if char == '\n' {
+                line_starts.push(offset + 1);
+            }
+        }
+        return LineIndex { source, line_starts };
+    }
+
+    /// Zero-based line and column of an offset, columns counting UTF-16 code units.
+    pub fn position(&self, offset: usize) -> (usize, usize) {
+        let offset = offset.min(self.source.len());
+        let line = match self.line_starts.binary_search(&offset) {
+            Ok(line) => line,
+            Err(next) => next - 1
+        };
+        let start = self.line_starts[line];
+        let column = match self.source.get(start..offset) {
+            Some(text) => text.encode_utf16().count(),
+            None => offset - start
+        };
+        return (line, column);
+    }
+}
+
+fn encode_vlq(output: &mut String, value: i64) {
+    let mut rest = match value < 0 {
+        true => ((-value) << 1) | 1,
+        false => value << 1
+    };
+    loop {
+        let mut digit = rest & 0b11111;
+        rest >>= 5;
+        if rest > 0 {
+            digit |= 0b100000;
+        }
+        output.push(BASE64[digit as usize] as char);
+        if rest == 0 {
+            break;
+        }
+    }
+}
+
+/// Reads the `mappings` field, checking the sources and names it refers to exist. Segments of a
+/// generated column alone map nothing and are left out.
+fn decode_mappings(text: &str, source_count: usize, name_count: usize) -> Result<Vec<Mapping>, String> {
+    let mut mappings = Vec::new();
+    let mut source = 0;
+    let mut original_line = 0;
+    let mut original_column = 0;
+    let mut name = 0;
+
+    for (line, segments) in text.split(';').enumerate() {
+        let mut column = 0;
+        for segment in segments.split(',').filter(|segment| !segment.is_empty()) {
+            let fields = match decode_vlq(segment) {
+                Some(fields) => fields,
+                None => return Err(format!("Invalid mapping '{}'", segment))
+            };
+            column += fields[0];
+            match fields.len() {
+                1 => continue,
+                4 | 5 => {}
+                _ => return Err(format!("Invalid mapping '{}'", segment))
+            }
+            source += fields[1];
+            original_line += fields[2];
+            original_column += fields[3];
+            let has_name = fields.len() == 5;
+            if has_name {
+                name += fields[4];
+            }
+
+            if column < 0 || source < 0 || source as usize >= source_count || original_line < 0 || original_column < 0
+                || (has_name && (name < 0 || name as usize >= name_count)) {
+                return Err(format!("Invalid mapping '{}'", segment));
+            }
+            mappings.push(Mapping {
+                generated_line: line,
+                generated_column: column as usize,
+                source: source as usize,
+                original_line: original_line as usize,
+                original_column: original_column as usize,
+                name: match has_name {
+                    true => Some(name as usize),
+                    false => None
+                },
+            });
+        }
+    }
+    mappings.sort_by_key(|mapping| (mapping.generated_line, mapping.generated_column));
+    return Ok(mappings);
+}
+
+fn decode_vlq(segment: &str) -> Option<Vec<i64>> {
+    let mut fields = Vec::new();
+    let mut value = 0;
+    let mut shift = 0;
+    for byte in segment.bytes() {
+        let digit = BASE64.iter().position(|&char| char == byte)? as i64;
+        value += (digit & 0b11111) << shift;
+        if digit & 0b100000 != 0 {
+            shift += 5;
+            if shift > 60 {
+                return None;
+            }
+            continue;
+        }
+        fields.push(match value & 1 {
+            1 => -(value >> 1),
+            _ => value >> 1
+        });
+        value = 0;
+        shift = 0;
+    }
+    if shift > 0 {
+        return None;
+    }
+    return Some(fields);
+}
+
+fn decode_base64(text: &str) -> Option<Vec<u8>> {
+    let mut bytes = Vec::new();
+    let mut value: u32 = 0;
+    let mut bits = 0;
+    for byte in text.bytes().filter(|&byte| byte != b'=' && !(byte as char).is_whitespace()) {
+        value = value << 6 | BASE64.iter().position(|&char| char == byte)? as u32;
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            bytes.push((value >> bits) as u8);
+            value &= (1 << bits) - 1;
+        }
+    }
+    return Some(bytes);
+}
+
+fn encode_base64(bytes: &[u8]) -> String {
+    let mut output = String::new();
+    for group in bytes.chunks(3) {
+        let value = (group[0] as u32) << 16
+            | (*group.get(1).unwrap_or(&0) as u32) << 8
+            | *group.get(2).unwrap_or(&0) as u32;
+        for i in 0..4 {
+            match i <= group.len() {
+                true => output.push(BASE64[(value >> (18 - i * 6)) as usize & 0b111111] as char),
+                false => output.push('=')
+            }
+        }
+    }
+    return output;
+}
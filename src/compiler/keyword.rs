@@ -8,23 +8,19 @@ pub enum Keyword {
     Finally,
     With,
     Switch,
-    Yield,
     Break,
     Do,
-    In,
-    Of,
     Class,
     Extends,
     Return,
     Import,
     Export,
     Try,
-    As,
     Catch,
     If,
-    From,
-    Async,
-    Await,
+    This,
+    Super,
+    For,
     Declaration(DeclarationKind),
 }
 
@@ -33,29 +29,25 @@ impl ToString for Keyword {
         use self::Keyword::*;
 
         let string = match *self {
-            From => "from".to_owned(),
             Function => "function".to_owned(),
             Break => "break".to_owned(),
             Catch => "catch".to_owned(),
             If => "if".to_owned(),
             Class => "class".to_owned(),
             Default => "default".to_owned(),
-            Yield => "yield".to_owned(),
-            Async => "async".to_owned(),
             While => "while".to_owned(),
-            As => String::from("as"),
             Finally => "finally".to_owned(),
             Switch => "switch".to_owned(),
             Return => "return".to_owned(),
             Extends => "extends".to_owned(),
             Try => "try".to_owned(),
-            Await => "await".to_owned(),
             Do => "do".to_owned(),
             Export => "export".to_owned(),
             Import => "import".to_owned(),
             With => "with".to_owned(),
-            In => String::from("in"),
-            Of => String::from("of"),
+            This => String::from("this"),
+            Super => String::from("super"),
+            For => "for".to_owned(),
 
             Declaration(ref declaration) => declaration.to_string(),
         };
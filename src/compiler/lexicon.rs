@@ -30,5 +30,17 @@ mod ident_lookup {
 }
 
 pub fn is_ident(char : char) -> bool {
+    if char as u32 > 0xFF {
+        return char.is_alphanumeric();
+    }
     return ident_lookup::TABLE[char as usize];
-}
\ No newline at end of file
+}
+
+/// Whether a name would be read back as a single identifier.
+pub fn is_identifier(name: &str) -> bool {
+    let is_part = |char: char| is_ident(char) && char != '\\';
+    return match name.chars().next() {
+        Some(first) => !first.is_ascii_digit() && name.chars().all(is_part),
+        None => false
+    };
+}
@@ -39,4 +39,60 @@ impl ToString for Literal {
         };
         return str;
     }
-}
\ No newline at end of file
+}
+impl Literal {
+    /// A string literal holding the given value, escaped for the quotes it is written with.
+    pub fn quoted(value: &str, quote: QuoteKind) -> Self {
+        let quote_char = match quote {
+            QuoteKind::SpeechMark => '"',
+            QuoteKind::Apostrophe => '\'',
+        };
+
+        let mut string = String::new();
+        for char in value.chars() {
+            match char {
+                '\\' => string += "\\\\",
+                '\n' => string += "\\n",
+                '\r' => string += "\\r",
+                '\u{A0}' => string += "\\u00A0",
+                _ if char == quote_char => {
+                    string.push('\\');
+                    string.push(char);
+                }
+                _ => string.push(char)
+            }
+        }
+        return Literal::String(string, quote);
+    }
+
+    /// Legacy octal numbers (`017`, `08`) and octal escapes (`'\17'`), which strict mode code forbids.
+    pub fn is_legacy_octal(&self) -> bool {
+        use self::Literal::*;
+
+        return match *self {
+            Number(ref value) => {
+                let mut chars = value.chars();
+                chars.next() == Some('0') && chars.next().map_or(false, |char| char.is_ascii_digit())
+            }
+            String(ref value, _) => {
+                let mut chars = value.chars().peekable();
+                while let Some(char) = chars.next() {
+                    if char != '\\' {
+                        continue;
+                    }
+                    match chars.next() {
+                        Some('0') => {
+                            if chars.peek().map_or(false, |char| char.is_ascii_digit()) {
+                                return true;
+                            }
+                        }
+                        Some('1'..='9') => return true,
+                        _ => {}
+                    }
+                }
+                false
+            }
+            _ => false,
+        };
+    }
+}
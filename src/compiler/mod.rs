@@ -9,19 +9,25 @@ mod generator;
 mod compiler;
 mod error;
 mod options;
+mod symbol;
 
 pub mod transform;
 pub mod ast;
 pub mod module;
 pub mod parser;
+pub mod scope;
+pub mod comments;
+pub mod diagnostics;
 
-pub use self::compiler::Compiler;
+pub use self::compiler::{Compiler, CompileResult};
 pub use self::generator::Generator;
 pub use self::chunk::{Chunk, ChunkLocation,ChunkId};
-pub use self::tokenizer::Tokenizer;
+pub use self::tokenizer::{Tokenizer, TokenizerError};
 pub use self::token::Token;
 pub use self::literal::*;
 pub use self::keyword::Keyword;
 pub use self::operator::OperatorKind;
+pub use self::symbol::{Symbol, SymbolTable};
 pub use self::error::CompilerError;
-pub use self::options::{CompilerOptions,ConfigError};
\ No newline at end of file
+pub use self::options::{CompilerOptions,ConfigError,JsxOptions,JsxRuntime,PluginConfig,GeneratorOptions,OutputFormat,QuoteStyle,SourceMapOptions,SourceMapMode};
+pub use self::generator::{SourceMap, SourceMapBuilder, Mapping};
\ No newline at end of file
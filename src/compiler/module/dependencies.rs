@@ -3,14 +3,17 @@ use super::super::{ChunkId};
 #[derive(Debug)]
 pub struct Dependencies {
     pub requires: Vec<ChunkId>,
-    pub used_by: Vec<ChunkId>
+    pub used_by: Vec<ChunkId>,
+    /// The import specifiers of the chunk with the chunks they resolved to.
+    pub imports: Vec<(String, ChunkId)>,
 }
 
 impl Dependencies {
     pub fn new() -> Self {
         return Dependencies {
             requires: Vec::new(),
-            used_by: Vec::new()
+            used_by: Vec::new(),
+            imports: Vec::new(),
         };
     }
 
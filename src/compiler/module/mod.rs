@@ -1,5 +1,5 @@
 mod dependencies;
 mod resolver;
 
-pub use self::resolver::Resolver;
+pub use self::resolver::{Resolver, ResolverError};
 pub use self::dependencies::Dependencies;
\ No newline at end of file
@@ -1,25 +1,64 @@
 use super::super::{Chunk, ChunkLocation};
+use super::super::diagnostics::{codes, Diagnostic};
 
+use std::error::Error;
+use std::fmt;
 use std::path::PathBuf;
 
 #[derive(Debug)]
 pub enum ResolverError {
-    ImportNotFound(String)
+    ImportNotFound(String),
+    /// A plugin resolved the import to a virtual module, but no plugin loaded its source.
+    VirtualModuleNotLoaded(String),
 }
 
+impl ResolverError {
+    pub fn to_diagnostic(&self) -> Diagnostic {
+        return match *self {
+            ResolverError::ImportNotFound(ref import) => {
+                let diagnostic = Diagnostic::error(codes::IMPORT_NOT_FOUND, format!("Cannot resolve '{}'", import))
+                    .with_note(String::from("Imports are resolved from the directory of the importing file, bare imports included"));
+                if import.starts_with('.') || import.starts_with('/') {
+                    diagnostic
+                } else {
+                    diagnostic.with_help(String::from("Packages are not looked up in node_modules, add it to \"virtualModules\" in maxwell.json or enable a plugin which resolves it"))
+                }
+            }
+            ResolverError::VirtualModuleNotLoaded(ref id) => {
+                Diagnostic::error(codes::VIRTUAL_MODULE_NOT_LOADED, format!("No plugin loaded virtual module '{}'", id))
+                    .with_help(String::from("The plugin resolving a virtual module must also give its source from its load hook"))
+            }
+        };
+    }
+}
+
+impl fmt::Display for ResolverError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ResolverError::ImportNotFound(ref import) => write!(f, "Cannot resolve '{}'", import),
+            ResolverError::VirtualModuleNotLoaded(ref id) => write!(f, "No plugin loaded virtual module '{}'", id)
+        }
+    }
+}
+
+impl Error for ResolverError {}
+
 pub struct Resolver {}
 
 impl Resolver {
     pub fn resolve(&self, chunk: &Chunk, requires: &Vec<String>) -> Result<Vec<ChunkLocation>, ResolverError> {
         let mut new_chunks = Vec::new();
         for import in requires {
-            let location = ChunkLocation::relative(&chunk.location, PathBuf::from(import));
-            if location.is_ok() {
-                new_chunks.push(location.unwrap());
-            } else {
-                return Err(ResolverError::ImportNotFound(import.clone()));
-            }
+            new_chunks.push(self.resolve_import(&chunk.location, import)?);
         }
         return Ok(new_chunks);
     }
+
+    /// Resolves a single import relative to the chunk importing it.
+    pub fn resolve_import(&self, importer: &ChunkLocation, import: &str) -> Result<ChunkLocation, ResolverError> {
+        return match ChunkLocation::relative(importer, PathBuf::from(import)) {
+            Ok(location) => Ok(location),
+            Err(_) => Err(ResolverError::ImportNotFound(import.to_owned()))
+        };
+    }
 }
\ No newline at end of file
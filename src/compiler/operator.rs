@@ -108,3 +108,71 @@ impl ToString for OperatorKind {
     }
 }
 
+impl OperatorKind {
+    /// Binding power of the operator when used in a binary position, higher binds tighter.
+    pub fn precedence(&self) -> u8 {
+        return match *self {
+            Assign | AddAssign | SubtractAssign | ExponentAssign | MultiplyAssign | DivideAssign
+            | RemainderAssign | BSLAssign | BSRAssign | UBSRAssign | BitAndAssign | BitXorAssign
+            | BitOrAssign | Spread => 2,
+            Conditional => 3,
+            LogicalOr => 4,
+            LogicalAnd => 5,
+            BitwiseOr => 6,
+            BitwiseXor => 7,
+            BitwiseAnd => 8,
+            Equality | Inequality | StrictEquality | StrictInequality => 9,
+            Lesser | LesserEquals | Greater | GreaterEquals | InstanceOf | In => 10,
+            BitShiftLeft | BitShiftRight | UBitShiftRight => 11,
+            Addition | Subtraction => 12,
+            Multiplication | Division | Remainder => 13,
+            Exponent => 14,
+            LogicalNot | BitwiseNot | Typeof | Void | Delete => 15,
+            Increment | Decrement => 16,
+            New => 17,
+            Accessor => 18,
+        };
+    }
+
+    pub fn is_binary(&self) -> bool {
+        return match *self {
+            LogicalOr | LogicalAnd | BitwiseOr | BitwiseXor | BitwiseAnd | Equality | Inequality
+            | StrictEquality | StrictInequality | Lesser | LesserEquals | Greater | GreaterEquals
+            | InstanceOf | In | BitShiftLeft | BitShiftRight | UBitShiftRight | Addition
+            | Subtraction | Multiplication | Division | Remainder | Exponent => true,
+            _ => false,
+        };
+    }
+
+    pub fn is_unary(&self) -> bool {
+        return match *self {
+            LogicalNot | BitwiseNot | Typeof | Void | Delete | Addition | Subtraction => true,
+            _ => false,
+        };
+    }
+
+    pub fn is_update(&self) -> bool {
+        return *self == Increment || *self == Decrement;
+    }
+
+    pub fn is_assignment(&self) -> bool {
+        return match *self {
+            Assign | AddAssign | SubtractAssign | ExponentAssign | MultiplyAssign | DivideAssign
+            | RemainderAssign | BSLAssign | BSRAssign | UBSRAssign | BitAndAssign | BitXorAssign
+            | BitOrAssign => true,
+            _ => false,
+        };
+    }
+
+    pub fn is_right_associative(&self) -> bool {
+        return *self == Exponent || self.is_assignment();
+    }
+
+    /// Word operators need whitespace to stay separate from an adjacent identifier.
+    pub fn is_word(&self) -> bool {
+        return match *self {
+            New | Typeof | Void | Delete | InstanceOf | In => true,
+            _ => false,
+        };
+    }
+}
@@ -1,11 +1,18 @@
 extern crate json;
 
+use super::parser::SourceType;
+use super::ast::SourceLocation;
+use super::diagnostics::{codes, Diagnostic, Label, SourceFile};
+
 use std::io::prelude::*;
 use std::fs::File;
 
 use std::path::PathBuf;
+use std::collections::HashMap;
 
 use std::env;
+use std::error::Error;
+use std::fmt;
 use std::ops::Index;
 
 //TODO: Cleanup move env vars away from this struct.
@@ -14,8 +21,158 @@ use std::ops::Index;
 pub enum ConfigError {
     FileNotFound(String),
     FileContentsInvalid,
+    /// The name of the option, dotted for nested ones, and what it should have been.
     InvalidConfigOption(String, String),
-    JsonInvalid,
+    /// The config with the reason it is not JSON, and where reading it stopped when known.
+    JsonInvalid(SourceFile, String, Option<SourceLocation>),
+}
+
+impl ConfigError {
+    pub fn to_diagnostic(&self) -> Diagnostic {
+        return match *self {
+            ConfigError::FileNotFound(ref path) => {
+                Diagnostic::error(codes::CONFIG_NOT_FOUND, format!("File not found at path {}", path))
+                    .with_help(String::from("Run maxwell in a directory with a maxwell.json, or pass the directory or config as its first argument"))
+            }
+            ConfigError::FileContentsInvalid => {
+                Diagnostic::error(codes::CONFIG_UNREADABLE, String::from("File contents are invalid"))
+                    .with_note(String::from("maxwell.json must be UTF-8 text"))
+            }
+            ConfigError::InvalidConfigOption(ref option, ref reason) => {
+                Diagnostic::error(codes::CONFIG_INVALID_OPTION, format!("Invalid config option '{}'", option))
+                    .with_note(String::from("Options are read from maxwell.json"))
+                    .with_help(reason.clone())
+            }
+            ConfigError::JsonInvalid(ref file, ref reason, ref location) => {
+                let diagnostic = Diagnostic::error(codes::CONFIG_INVALID_JSON, format!("Invalid json contents: {}", reason))
+                    .with_file(file.clone());
+                match *location {
+                    Some(ref location) => diagnostic.with_label(Label::primary(location.clone(), String::new())),
+                    None => diagnostic
+                }
+            }
+        };
+    }
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ConfigError::FileNotFound(ref path) => write!(f, "File not found at path {}", path),
+            ConfigError::FileContentsInvalid => f.write_str("File contents are invalid"),
+            ConfigError::InvalidConfigOption(ref option, ref reason) => write!(f, "Invalid config option '{}': {}", option, reason),
+            ConfigError::JsonInvalid(ref file, ref reason, _) => write!(f, "Invalid json contents in {}: {}", file.path.display(), reason)
+        }
+    }
+}
+
+impl Error for ConfigError {}
+
+/// How JSX is lowered. The classic runtime calls the factory, `React.createElement` by default, the
+/// automatic runtime calls `jsx`/`jsxs` imported from `<importSource>/jsx-runtime`.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum JsxRuntime {
+    Classic,
+    Automatic,
+}
+
+#[derive(Debug, Clone)]
+pub struct JsxOptions {
+    pub runtime: JsxRuntime,
+    pub factory: String,
+    pub fragment: String,
+    pub import_source: String,
+}
+
+impl JsxOptions {
+    pub fn new() -> Self {
+        return JsxOptions {
+            runtime: JsxRuntime::Classic,
+            factory: String::from("React.createElement"),
+            fragment: String::from("React.Fragment"),
+            import_source: String::from("react"),
+        };
+    }
+}
+
+/// How code is laid out by a generator which ignores trivia.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum OutputFormat {
+    /// As few characters and lines as the code allows. Only license comments and `/*#__PURE__*/`
+    /// annotations are kept.
+    Compact,
+    /// A statement or property per line, indented by nesting, with every comment.
+    Pretty,
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum QuoteStyle {
+    Double,
+    Single,
+    /// Every string keeps the quotes it was written with.
+    Preserve,
+}
+
+/// Options of the `"output"` section. When given, chunks are generated from their syntax alone
+/// and the layout of the source is dropped.
+#[derive(Debug, Clone)]
+pub struct GeneratorOptions {
+    pub format: OutputFormat,
+    /// Spaces per level of indentation of pretty output.
+    pub indent: usize,
+    pub quotes: QuoteStyle,
+    /// Whether statements end in semicolons. Without them, a statement which would continue the
+    /// one before it starts with a semicolon.
+    pub semicolons: bool,
+    /// Whether the last property of an object written over several lines is followed by a comma.
+    pub trailing_commas: bool,
+    /// Escapes characters outside of ASCII in strings, identifiers and JSX text.
+    pub ascii_only: bool,
+}
+
+impl GeneratorOptions {
+    pub fn new() -> Self {
+        return GeneratorOptions {
+            format: OutputFormat::Pretty,
+            indent: 2,
+            quotes: QuoteStyle::Double,
+            semicolons: true,
+            trailing_commas: false,
+            ascii_only: false,
+        };
+    }
+}
+
+/// Where the source map of a chunk is written.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum SourceMapMode {
+    /// A `.map` file next to the chunk, named by a `sourceMappingURL` comment.
+    External,
+    /// A `sourceMappingURL` comment holding the map as a data URL.
+    Inline,
+    /// A `.map` file without a comment, for tools which are given the map themselves.
+    Hidden,
+}
+
+/// Options of the `"sourceMap"` setting.
+#[derive(Debug, Clone)]
+pub struct SourceMapOptions {
+    pub mode: SourceMapMode,
+    /// Whether the map holds the sources it maps to, so they can be shown without being served.
+    pub sources_content: bool,
+}
+
+impl SourceMapOptions {
+    pub fn new() -> Self {
+        return SourceMapOptions { mode: SourceMapMode::External, sources_content: true };
+    }
+}
+
+/// A plugin enabled in the `"plugins"` section, with the options object given to it.
+#[derive(Debug, Clone)]
+pub struct PluginConfig {
+    pub name: String,
+    pub options: json::JsonValue,
 }
 
 #[derive(Debug, Clone)]
@@ -28,7 +185,20 @@ pub struct CompilerOptions {
     pub entries: Vec<String>,
 
     pub out_dir: PathBuf,
-    plugins: Vec<String>,
+    pub source_type: SourceType,
+    pub jsx: JsxOptions,
+    /// How chunks are laid out, `None` to reproduce the layout of their source.
+    pub output: Option<GeneratorOptions>,
+    /// How source maps of chunks are written, `None` to write none.
+    pub source_map: Option<SourceMapOptions>,
+    /// Plugins in the order they are listed in the config.
+    pub plugins: Vec<PluginConfig>,
+    /// Shared libraries of native plugins, loaded before the plugins are created.
+    pub native_plugins: Vec<PathBuf>,
+    /// WebAssembly plugin modules, loaded before the plugins are created.
+    pub wasm_plugins: Vec<PathBuf>,
+    /// Sources of virtual modules by the specifier they are imported by.
+    pub virtual_modules: HashMap<String, String>,
 }
 
 impl CompilerOptions {
@@ -37,7 +207,11 @@ impl CompilerOptions {
         if env::args().len() >= 2 {
             dir_path.push(PathBuf::from(env::args().nth(1).unwrap()));
         }
+        return CompilerOptions::load_from(dir_path);
+    }
 
+    /// Loads the config at a path, either a directory holding `maxwell.json` or a config file.
+    pub fn load_from(mut dir_path: PathBuf) -> Result<Self, ConfigError> {
         match dir_path.canonicalize() {
             Ok(path) => dir_path = path,
             Err(_) => {
@@ -53,7 +227,14 @@ impl CompilerOptions {
             out_dir: PathBuf::default(),
             entries: Vec::new(),
             src_base_path: PathBuf::default(),
+            source_type: SourceType::Module,
+            jsx: JsxOptions::new(),
+            output: None,
+            source_map: None,
             plugins: Vec::new(),
+            native_plugins: Vec::new(),
+            wasm_plugins: Vec::new(),
+            virtual_modules: HashMap::new(),
         };
 
         if !options.dir_path.is_dir() {
@@ -61,7 +242,11 @@ impl CompilerOptions {
             options.dir_path = options.dir_path.parent().unwrap().to_path_buf();
         }
 
-        options.config_path = options.dir_path.join(PathBuf::from(&options.config_name)).canonicalize().unwrap();
+        let config_path = options.dir_path.join(PathBuf::from(&options.config_name));
+        options.config_path = match config_path.canonicalize() {
+            Ok(path) => path,
+            Err(_) => return Err(ConfigError::FileNotFound(config_path.to_string_lossy().into_owned()))
+        };
 
         match options.read_json() {
             Ok(config) => {
@@ -76,9 +261,12 @@ impl CompilerOptions {
     }
 
     fn parse_json(&mut self, config: json::JsonValue) -> Result<(), ConfigError> {
-        let out_element = config.index("out");
+        let out_element = match config.index("out").as_str() {
+            Some(out) => out,
+            None => return Err(ConfigError::InvalidConfigOption(String::from("out"), String::from("Expected the out directory as a string")))
+        };
         let mut out_dir = self.dir_path.clone();
-        for item in out_element.as_str().unwrap().split("/") {
+        for item in out_element.split("/") {
             out_dir.push(item);
         }
 
@@ -88,14 +276,61 @@ impl CompilerOptions {
 
         self.out_dir = out_dir;
 
-        let base_dir_element = config.index("src").index("base");
+        let base_dir_element = match config.index("src").index("base").as_str() {
+            Some(base) => base,
+            None => return Err(ConfigError::InvalidConfigOption(String::from("src.base"), String::from("Expected the base directory as a string")))
+        };
         let mut base_dir = self.dir_path.clone();
-        base_dir.push(PathBuf::from(base_dir_element.as_str().unwrap()));
+        base_dir.push(PathBuf::from(base_dir_element));
         self.src_base_path = base_dir;
         if !self.src_base_path.is_absolute() {
             return Err(ConfigError::InvalidConfigOption(String::from("src.base"), String::from("Cannot find base directory")));
         }
 
+        let source_type_element = config.index("src").index("sourceType");
+        if !source_type_element.is_null() {
+            self.source_type = match source_type_element.as_str().and_then(SourceType::from_name) {
+                Some(source_type) => source_type,
+                None => return Err(ConfigError::InvalidConfigOption(String::from("src.sourceType"), String::from("Expected \"script\" or \"module\"")))
+            };
+        }
+
+        let jsx_element = config.index("jsx");
+        if !jsx_element.is_null() {
+            self.parse_jsx(jsx_element)?;
+        }
+
+        let output_element = config.index("output");
+        if !output_element.is_null() {
+            self.output = Some(CompilerOptions::parse_output(output_element)?);
+        }
+
+        let source_map_element = config.index("sourceMap");
+        if !source_map_element.is_null() {
+            self.source_map = CompilerOptions::parse_source_map(source_map_element)?;
+        }
+
+        self.native_plugins = self.parse_paths(&config, "nativePlugins")?;
+        self.wasm_plugins = self.parse_paths(&config, "wasmPlugins")?;
+
+        let virtual_modules_element = config.index("virtualModules");
+        if !virtual_modules_element.is_null() {
+            if !virtual_modules_element.is_object() {
+                return Err(ConfigError::InvalidConfigOption(String::from("virtualModules"), String::from("Expected an object of specifiers to sources")));
+            }
+            for (id, source) in virtual_modules_element.entries() {
+                match source.as_str() {
+                    Some(source) => self.virtual_modules.insert(id.to_owned(), source.to_owned()),
+                    None => return Err(ConfigError::InvalidConfigOption(format!("virtualModules.{}", id), String::from("Expected the source as a string")))
+                };
+            }
+        }
+
+        let plugins_element = config.index("plugins");
+        if !plugins_element.is_null() {
+            self.parse_plugins(plugins_element)?;
+        }
+
         let entry_element = config.index("src").index("entry");
         if entry_element.is_string() {
             self.entries.push(entry_element.as_str().unwrap().to_owned());
@@ -104,6 +339,157 @@ impl CompilerOptions {
         return Ok(());
     }
 
+    /// Reads an optional array of paths relative to the config.
+    fn parse_paths(&self, config: &json::JsonValue, name: &str) -> Result<Vec<PathBuf>, ConfigError> {
+        let element = config.index(name);
+        let mut paths = Vec::new();
+        if element.is_null() {
+            return Ok(paths);
+        }
+        if !element.is_array() {
+            return Err(ConfigError::InvalidConfigOption(name.to_owned(), String::from("Expected an array of paths")));
+        }
+        for member in element.members() {
+            match member.as_str() {
+                Some(path) => paths.push(self.dir_path.join(path)),
+                None => return Err(ConfigError::InvalidConfigOption(name.to_owned(), String::from("Expected an array of paths")))
+            }
+        }
+        return Ok(paths);
+    }
+
+    fn parse_plugins(&mut self, config: &json::JsonValue) -> Result<(), ConfigError> {
+        if !config.is_object() {
+            return Err(ConfigError::InvalidConfigOption(String::from("plugins"), String::from("Expected an object of plugin names to options")));
+        }
+        for (name, options) in config.entries() {
+            if !options.is_object() {
+                return Err(ConfigError::InvalidConfigOption(format!("plugins.{}", name), String::from("Expected an options object")));
+            }
+            self.plugins.push(PluginConfig { name: name.to_owned(), options: options.clone() });
+        }
+        return Ok(());
+    }
+
+    fn parse_jsx(&mut self, config: &json::JsonValue) -> Result<(), ConfigError> {
+        let runtime_element = config.index("runtime");
+        if !runtime_element.is_null() {
+            self.jsx.runtime = match runtime_element.as_str() {
+                Some("classic") => JsxRuntime::Classic,
+                Some("automatic") => JsxRuntime::Automatic,
+                _ => return Err(ConfigError::InvalidConfigOption(String::from("jsx.runtime"), String::from("Expected \"classic\" or \"automatic\"")))
+            };
+        }
+
+        if let Some(factory) = CompilerOptions::parse_jsx_string(config, "factory")? {
+            self.jsx.factory = factory;
+        }
+        if let Some(fragment) = CompilerOptions::parse_jsx_string(config, "fragment")? {
+            self.jsx.fragment = fragment;
+        }
+        if let Some(import_source) = CompilerOptions::parse_jsx_string(config, "importSource")? {
+            self.jsx.import_source = import_source;
+        }
+        return Ok(());
+    }
+
+    fn parse_jsx_string(config: &json::JsonValue, name: &str) -> Result<Option<String>, ConfigError> {
+        let element = config.index(name);
+        if element.is_null() {
+            return Ok(None);
+        }
+        return match element.as_str() {
+            Some(value) if !value.is_empty() => Ok(Some(value.to_owned())),
+            _ => Err(ConfigError::InvalidConfigOption(format!("jsx.{}", name), String::from("Expected a non-empty string")))
+        };
+    }
+
+    fn parse_output(config: &json::JsonValue) -> Result<GeneratorOptions, ConfigError> {
+        let mut options = GeneratorOptions::new();
+
+        let format_element = config.index("format");
+        if !format_element.is_null() {
+            options.format = match format_element.as_str() {
+                Some("compact") => OutputFormat::Compact,
+                Some("pretty") => OutputFormat::Pretty,
+                _ => return Err(ConfigError::InvalidConfigOption(String::from("output.format"), String::from("Expected \"compact\" or \"pretty\"")))
+            };
+        }
+
+        let indent_element = config.index("indent");
+        if !indent_element.is_null() {
+            options.indent = match indent_element.as_usize() {
+                Some(indent) => indent,
+                None => return Err(ConfigError::InvalidConfigOption(String::from("output.indent"), String::from("Expected a number of spaces")))
+            };
+        }
+
+        let quotes_element = config.index("quotes");
+        if !quotes_element.is_null() {
+            options.quotes = match quotes_element.as_str() {
+                Some("double") => QuoteStyle::Double,
+                Some("single") => QuoteStyle::Single,
+                Some("preserve") => QuoteStyle::Preserve,
+                _ => return Err(ConfigError::InvalidConfigOption(String::from("output.quotes"), String::from("Expected \"double\", \"single\" or \"preserve\"")))
+            };
+        }
+
+        if let Some(semicolons) = CompilerOptions::parse_output_flag(config, "semicolons")? {
+            options.semicolons = semicolons;
+        }
+        if let Some(trailing_commas) = CompilerOptions::parse_output_flag(config, "trailingCommas")? {
+            options.trailing_commas = trailing_commas;
+        }
+        if let Some(ascii_only) = CompilerOptions::parse_output_flag(config, "asciiOnly")? {
+            options.ascii_only = ascii_only;
+        }
+        return Ok(options);
+    }
+
+    fn parse_output_flag(config: &json::JsonValue, name: &str) -> Result<Option<bool>, ConfigError> {
+        let element = config.index(name);
+        if element.is_null() {
+            return Ok(None);
+        }
+        return match element.as_bool() {
+            Some(value) => Ok(Some(value)),
+            None => Err(ConfigError::InvalidConfigOption(format!("output.{}", name), String::from("Expected true or false")))
+        };
+    }
+
+    /// `true` for external maps, a mode name, or an object of `mode` and `sourcesContent`.
+    fn parse_source_map(config: &json::JsonValue) -> Result<Option<SourceMapOptions>, ConfigError> {
+        let mut options = SourceMapOptions::new();
+        if let Some(enabled) = config.as_bool() {
+            return Ok(match enabled {
+                true => Some(options),
+                false => None
+            });
+        }
+
+        let mode_element = match config.is_object() {
+            true => config.index("mode"),
+            false => config
+        };
+        if !mode_element.is_null() {
+            options.mode = match mode_element.as_str() {
+                Some("external") => SourceMapMode::External,
+                Some("inline") => SourceMapMode::Inline,
+                Some("hidden") => SourceMapMode::Hidden,
+                _ => return Err(ConfigError::InvalidConfigOption(String::from("sourceMap"), String::from("Expected true, \"external\", \"inline\", \"hidden\" or an object")))
+            };
+        }
+
+        let sources_content_element = config.index("sourcesContent");
+        if !sources_content_element.is_null() {
+            options.sources_content = match sources_content_element.as_bool() {
+                Some(sources_content) => sources_content,
+                None => return Err(ConfigError::InvalidConfigOption(String::from("sourceMap.sourcesContent"), String::from("Expected true or false")))
+            };
+        }
+        return Ok(Some(options));
+    }
+
     fn read_json(&self) -> Result<json::JsonValue, ConfigError> {
         let config_path = &self.config_path;
         let config_file_result = File::open(config_path);
@@ -117,7 +503,25 @@ impl CompilerOptions {
 
         return match json::parse(&contents) {
             Ok(config) => Ok(config),
-            Err(_) => Err(ConfigError::JsonInvalid)
+            Err(err) => {
+                let location = match err {
+                    json::Error::UnexpectedCharacter { line, column, .. } => Some(CompilerOptions::json_location(&contents, line, column)),
+                    json::Error::UnexpectedEndOfJson => Some(SourceLocation::new(contents.len(), contents.len())),
+                    _ => None
+                };
+                Err(ConfigError::JsonInvalid(SourceFile::new(config_path.clone(), contents), err.to_string(), location))
+            }
+        };
+    }
+
+    /// Location of the character at a one-based line and column of the config.
+    fn json_location(contents: &str, line: usize, column: usize) -> SourceLocation {
+        let line_start = contents.split('\n').take(line - 1).map(|line| line.len() + 1).sum::<usize>().min(contents.len());
+        let start = match contents[line_start..].char_indices().nth(column - 1) {
+            Some((index, _)) => line_start + index,
+            None => contents.len()
         };
+        let end = contents[start..].chars().next().map(|char| start + char.len_utf8()).unwrap_or(start);
+        return SourceLocation::new(start, end);
     }
 }
\ No newline at end of file
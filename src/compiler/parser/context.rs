@@ -0,0 +1,156 @@
+use super::SourceType;
+use super::super::ast::SourceLocation;
+
+use std::collections::HashMap;
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+enum ScopeKind {
+    Program,
+    Function,
+    Block,
+}
+
+#[derive(Debug)]
+struct DeclaredNames {
+    kind: ScopeKind,
+    /// Names by where they were first declared.
+    lexical: HashMap<String, SourceLocation>,
+    var: HashMap<String, SourceLocation>,
+}
+
+impl DeclaredNames {
+    fn new(kind: ScopeKind) -> Self {
+        return DeclaredNames { kind, lexical: HashMap::new(), var: HashMap::new() };
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct FunctionFlags {
+    is_async: bool,
+    is_generator: bool,
+}
+
+/// Tracks the state early errors depend on while a chunk is parsed: strictness, whether `await` and
+/// `yield` are keywords in the current function, and the names declared in each enclosing scope.
+#[derive(Debug)]
+pub struct ParserContext {
+    pub source_type: SourceType,
+    strict: Vec<bool>,
+    functions: Vec<FunctionFlags>,
+    scopes: Vec<DeclaredNames>,
+}
+
+impl ParserContext {
+    pub fn new(source_type: SourceType) -> Self {
+        return ParserContext {
+            source_type,
+            strict: vec![source_type == SourceType::Module],
+            // Modules allow `await` at the top level.
+            functions: vec![FunctionFlags { is_async: source_type == SourceType::Module, is_generator: false }],
+            scopes: vec![DeclaredNames::new(ScopeKind::Program)],
+        };
+    }
+
+    pub fn is_module(&self) -> bool {
+        return self.source_type == SourceType::Module;
+    }
+
+    pub fn is_strict(&self) -> bool {
+        return *self.strict.last().unwrap();
+    }
+
+    /// Called when a `'use strict'` directive is found in the current function or program prologue.
+    pub fn set_strict(&mut self) {
+        *self.strict.last_mut().unwrap() = true;
+    }
+
+    pub fn is_top_level(&self) -> bool {
+        return self.scopes.len() == 1;
+    }
+
+    /// Whether `await` starts an await expression rather than being an identifier.
+    pub fn is_async(&self) -> bool {
+        return self.functions.last().unwrap().is_async;
+    }
+
+    /// Whether `yield` starts a yield expression rather than being an identifier.
+    pub fn is_generator(&self) -> bool {
+        return self.functions.last().unwrap().is_generator;
+    }
+
+    pub fn enter_function(&mut self, is_async: bool, is_generator: bool) {
+        let strict = self.is_strict();
+        self.strict.push(strict);
+        self.functions.push(FunctionFlags { is_async, is_generator });
+        self.scopes.push(DeclaredNames::new(ScopeKind::Function));
+    }
+
+    pub fn exit_function(&mut self) {
+        self.strict.pop();
+        self.functions.pop();
+        self.scopes.pop();
+    }
+
+    /// Class bodies are always strict mode code.
+    pub fn enter_class(&mut self) {
+        self.strict.push(true);
+    }
+
+    pub fn exit_class(&mut self) {
+        self.strict.pop();
+    }
+
+    pub fn enter_block(&mut self) {
+        self.scopes.push(DeclaredNames::new(ScopeKind::Block));
+    }
+
+    pub fn exit_block(&mut self) {
+        self.scopes.pop();
+    }
+
+    /// Declares a `let`/`const` binding. If the name already exists in the current scope, returns
+    /// where it was first declared.
+    pub fn declare_lexical(&mut self, name: &str, location: SourceLocation) -> Result<(), SourceLocation> {
+        let scope = self.scopes.last_mut().unwrap();
+        if let Some(first) = scope.lexical.get(name).or(scope.var.get(name)) {
+            return Err(first.clone());
+        }
+        scope.lexical.insert(name.to_owned(), location);
+        return Ok(());
+    }
+
+    /// Declares a `var` binding or parameter. The name is hoisted through every block up to the
+    /// closest function, so it conflicts with a lexical binding in any of those scopes.
+    pub fn declare_var(&mut self, name: &str, location: SourceLocation) -> Result<(), SourceLocation> {
+        for scope in self.scopes.iter_mut().rev() {
+            if let Some(first) = scope.lexical.get(name) {
+                return Err(first.clone());
+            }
+            scope.var.entry(name.to_owned()).or_insert_with(|| location.clone());
+            if scope.kind != ScopeKind::Block {
+                break;
+            }
+        }
+        return Ok(());
+    }
+
+    /// Function declarations behave like `var` at the top of a function or script, but are lexical
+    /// at the top of a module and inside blocks of strict code.
+    pub fn declare_function(&mut self, name: &str, location: SourceLocation) -> Result<(), SourceLocation> {
+        let kind = self.scopes.last().unwrap().kind;
+        let lexical = match kind {
+            ScopeKind::Program => self.is_module(),
+            ScopeKind::Function => false,
+            ScopeKind::Block => self.is_strict(),
+        };
+        if lexical {
+            return self.declare_lexical(name, location);
+        }
+        let scope = self.scopes.last_mut().unwrap();
+        if let Some(first) = scope.lexical.get(name) {
+            return Err(first.clone());
+        }
+        scope.var.entry(name.to_owned()).or_insert(location);
+        return Ok(());
+    }
+}
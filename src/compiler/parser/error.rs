@@ -1,8 +1,13 @@
 use super::super::ast::SourceLocation;
+use super::super::diagnostics::{codes, Diagnostic, Label, SourceFile};
 
-#[derive(Debug)]
+use std::error::Error;
+use std::fmt;
+
+#[derive(Debug, PartialEq, Clone, Copy)]
 pub enum ParserErrorKind {
     Syntax,
+    EarlyError,
 }
 
 #[derive(Debug)]
@@ -10,10 +15,67 @@ pub struct ParserError {
     kind: ParserErrorKind,
     description: String,
     location: SourceLocation,
+    /// Other code taking part in the error, such as an earlier declaration of a name.
+    labels: Vec<Label>,
+    file: Option<SourceFile>,
 }
 
 impl ParserError {
     pub fn new(kind: ParserErrorKind, description: String, location: SourceLocation) -> Self {
-        return ParserError { kind, description, location };
+        return ParserError { kind, description, location, labels: Vec::new(), file: None };
+    }
+
+    pub fn with_label(mut self, label: Label) -> Self {
+        self.labels.push(label);
+        return self;
+    }
+
+    /// The error with the file it was found in, so its diagnostic can show the code.
+    pub fn in_file(mut self, file: SourceFile) -> Self {
+        self.file = Some(file);
+        return self;
     }
-}
\ No newline at end of file
+
+    pub fn kind(&self) -> ParserErrorKind {
+        return self.kind;
+    }
+
+    pub fn description(&self) -> &str {
+        return &self.description;
+    }
+
+    pub fn location(&self) -> &SourceLocation {
+        return &self.location;
+    }
+
+    pub fn file(&self) -> Option<&SourceFile> {
+        return self.file.as_ref();
+    }
+
+    pub fn to_diagnostic(&self) -> Diagnostic {
+        let diagnostic = match self.kind {
+            ParserErrorKind::Syntax => Diagnostic::error(codes::SYNTAX_ERROR, self.description.clone()),
+            ParserErrorKind::EarlyError => Diagnostic::error(codes::EARLY_ERROR, self.description.clone())
+        };
+        let mut diagnostic = match self.file {
+            Some(ref file) => diagnostic.with_file(file.clone()).with_label(Label::primary(self.location.clone(), String::new())),
+            None => return diagnostic
+        };
+        diagnostic.labels.extend(self.labels.iter().cloned());
+        return diagnostic;
+    }
+}
+
+impl fmt::Display for ParserError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.file {
+            Some(ref file) => {
+                let (line, column) = file.line_column(self.location.start);
+                write!(f, "{} ({}:{}:{})", self.description, file.path.display(), line, column)
+            }
+            None => f.write_str(&self.description)
+        }
+    }
+}
+
+impl Error for ParserError {}
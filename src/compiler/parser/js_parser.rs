@@ -1,40 +1,60 @@
-use super::{Parser, ParserError, ParserErrorKind, ParserResult, ParserOptions};
+use super::{Parser, ParserError, ParserErrorKind, ParserResult, ParserOptions, ParserContext, SourceType};
 
-use super::super::{Token, Keyword, OperatorKind, Literal, Tokenizer};
-use super::super::transform::PluginPass;
+use super::super::{Token, Keyword, OperatorKind, Literal, Symbol, Tokenizer, TokenizerError};
 use super::super::ast::declaration::{DeclarationKind, DeclarationNode, DeclarationTrivia};
-use super::super::ast::expression::{FunctionTrivia, ExpressionNode, Expression};
-use super::super::ast::statement::{StatementNode, Statement, StatementTerminator, ImportDeclaration, ImportTrivia};
+use super::super::ast::expression::{FunctionTrivia, ExpressionNode, Expression, OperatorTrivia, MemberTrivia, ComputedMemberTrivia, CallTrivia, ConditionalTrivia, ObjectTrivia, ArrayTrivia, ClassTrivia, BracketedTrivia};
+use super::super::ast::statement::{StatementNode, Statement, StatementTerminator, ImportDeclaration, ImportTrivia, ExportDeclaration, ModuleSpecifier, ModuleSpecifierTrivia, ExportTrivia, WithTrivia, BlockTrivia, ForTrivia};
+use super::super::ast::property::{PropertyNode, PropertyKind, PropertyKey, PropertyTrivia};
 use super::super::ast::{NodeTrivia, Parameter, ParameterTrivia, SyntaxTree, SourceLocation};
 
 use super::super::ast::body::BodyNode;
+use super::super::ast::directive::DirectiveNode;
+use super::super::diagnostics::{Label, SourceFile};
 
+use std::collections::HashMap;
+use std::mem;
 use std::time::Instant;
 
+mod typescript;
+mod jsx;
+
 pub struct JsParser {
     tokenizer: Tokenizer,
     requires: Vec<String>,
+    context: ParserContext,
+    typescript: bool,
+    jsx: bool,
 }
 
 impl Parser for JsParser {
     fn parse(&mut self, mut options: ParserOptions) -> Result<ParserResult, ParserError> {
-        use self::Token::*;
-
         let start = Instant::now();
 
-        let mut contents = Vec::new();
+        self.context = ParserContext::new(options.source_type);
+        self.tokenizer.take_comments();
+        self.tokenizer.take_error();
 
-        loop {
-            let token = self.tokenizer.peek_token(options.chunk).unwrap();
-            if token == EndOfFile {
-                break;
-            }
-            match self.parse_statement(&mut options) {
-                Ok(statement_node) => contents.push(statement_node),
-                Err(err) => return Err(err)
-            }
+        let mut parsed = self.parse_body(&mut options, Token::EndOfFile, true);
+        // The parser reads on from a token the tokenizer failed on as if the file ended there, so the
+        // tokenizer error is the cause of any error which follows.
+        if let Some(err) = self.tokenizer.take_error() {
+            parsed = Err(self.tokenizer_error(err));
         }
-        let tree = SyntaxTree::new(BodyNode::new(contents));
+        let (mut body, suffix) = match parsed {
+            Ok(body) => body,
+            Err(err) => {
+                self.requires.clear();
+                let chunk = &options.chunk;
+                return Err(err.in_file(SourceFile::new(chunk.location.path.clone(), chunk.source.clone())));
+            }
+        };
+        body.location = SourceLocation::new(0, options.chunk.source.len());
+        let mut tree = SyntaxTree::new(body);
+        tree.hashbang = options.chunk.hashbang.clone();
+        tree.suffix = suffix;
+        tree.source_type = options.source_type;
+        tree.comments = self.tokenizer.take_comments();
+
         let requires = self.requires.clone();
         self.requires.clear();
 
@@ -48,448 +68,1707 @@ impl JsParser {
         return JsParser {
             tokenizer: Tokenizer::new(),
             requires: Vec::new(),
+            context: ParserContext::new(SourceType::Module),
+            typescript: false,
+            jsx: true,
+        };
+    }
+
+    /// A parser accepting TypeScript syntax, see `TsParser`. JSX is only read when `jsx` is set, as
+    /// `.ts` files use `<T>value` for type assertions instead.
+    pub fn with_typescript(jsx: bool) -> Self {
+        let mut parser = JsParser::new();
+        parser.typescript = true;
+        parser.jsx = jsx;
+        return parser;
+    }
+
+    fn syntax_error(&self, description: String) -> ParserError {
+        let location = SourceLocation::new(self.tokenizer.token_start(), self.tokenizer.token_end());
+        return ParserError::new(ParserErrorKind::Syntax, description, location);
+    }
+
+    fn redeclaration_error(&self, name: &str, location: SourceLocation, first: SourceLocation) -> ParserError {
+        let error = self.early_error(format!("Identifier '{}' has already been declared.", name), location);
+        return error.with_label(Label::secondary(first, String::from("first declared here")));
+    }
+
+    fn tokenizer_error(&self, err: TokenizerError) -> ParserError {
+        let kind = if err.is_early() { ParserErrorKind::EarlyError } else { ParserErrorKind::Syntax };
+        return ParserError::new(kind, err.description().to_owned(), err.location().clone());
+    }
+
+    fn early_error(&self, description: String, location: SourceLocation) -> ParserError {
+        return ParserError::new(ParserErrorKind::EarlyError, description, location);
+    }
+
+    /// Parses statements until the closing token, returning them with the padding found before it.
+    /// When `directives` is set, string literal statements at the start of the body are collected as
+    /// its directive prologue, and a `'use strict'` directive switches the current function to strict mode.
+    fn parse_body(&mut self, options: &mut ParserOptions, closing: Token, directives: bool) -> Result<(BodyNode, String), ParserError> {
+        let mut prologue = Vec::new();
+        let mut content = Vec::new();
+        let mut in_prologue = directives;
+
+        loop {
+            let (token, suffix) = self.tokenizer.peek_ignore_padding(options.chunk);
+            if token == closing {
+                self.tokenizer.pop_ignore_padding(options.chunk);
+                return Ok((BodyNode::with_directives(prologue, content), suffix));
+            }
+            if token == Token::EndOfFile {
+                return Err(self.syntax_error(format!("Unexpected end of file, expected '{}'.", closing.to_string())));
+            }
+
+            let statement_node = self.parse_statement(options)?;
+            if JsParser::is_stripped(&statement_node) {
+                continue;
+            }
+            if in_prologue {
+                match JsParser::into_directive(statement_node) {
+                    Ok(directive) => {
+                        if directive.value == "use strict" {
+                            self.context.set_strict();
+                        }
+                        prologue.push(directive);
+                    }
+                    Err(statement_node) => {
+                        in_prologue = false;
+                        content.push(statement_node);
+                    }
+                }
+            } else {
+                content.push(statement_node);
+            }
+        }
+    }
+
+    /// Converts a statement consisting of only a string literal into a directive.
+    fn into_directive(statement_node: StatementNode) -> Result<DirectiveNode, StatementNode> {
+        let StatementNode { statement, location, trivia, terminator, requires } = statement_node;
+        return match statement {
+            Statement::Expression { expression: ExpressionNode { expression: Expression::Literal(Literal::String(value, quote_kind)), .. } } => {
+                Ok(DirectiveNode { value, quote_kind, location, trivia, terminator })
+            }
+            statement => Err(StatementNode { statement, location, trivia, terminator, requires })
+        };
+    }
+
+    /// Whether the next token is `word`, used for contextual keywords which the tokenizer reads as identifiers.
+    fn is_contextual(token: &Token, word: &str) -> bool {
+        return match *token {
+            Token::Identifier(ref name) => name == word,
+            _ => false
+        };
+    }
+
+    /// Returns the token after the next one and the padding before it, without consuming anything.
+    fn peek_second(&mut self, options: &mut ParserOptions) -> (Token, String) {
+        let index = options.chunk.index;
+        self.tokenizer.pop_ignore_padding(options.chunk);
+        let result = self.tokenizer.peek_ignore_padding(options.chunk);
+        options.chunk.index = index;
+        return result;
+    }
+
+    /// `let` starts a declaration when a binding follows it, otherwise it is an identifier.
+    fn is_let_declaration(&mut self, options: &mut ParserOptions) -> bool {
+        return match self.peek_second(options).0 {
+            Token::Identifier(_) | Token::SquareBracketOpen | Token::BraceOpen => true,
+            _ => false
         };
     }
 
-    fn parse_function(&mut self, options: &mut ParserOptions) -> Result<(Expression, Option<StatementTerminator>), ParserError> {
+    /// `async` only marks a function when `function` follows it on the same line.
+    fn is_async_function(&mut self, options: &mut ParserOptions) -> bool {
+        let (token, gap) = self.peek_second(options);
+        return token == Token::Keyword(Keyword::Function) && !gap.contains('\n');
+    }
+
+    /// Parses a function after the `function` keyword. `async_gap` holds the padding after `async`
+    /// for async functions.
+    fn parse_function(&mut self, options: &mut ParserOptions, is_declaration: bool, async_gap: Option<String>) -> Result<Expression, ParserError> {
         use self::Token::*;
 
         let mut trivia = FunctionTrivia::default();
+        let is_async = async_gap.is_some();
+        trivia.async_gap = async_gap.unwrap_or_default();
+
+        let (generator_token, generator_gap) = self.tokenizer.peek_ignore_padding(options.chunk);
+        let is_generator = generator_token == Operator(OperatorKind::Multiplication);
+        if is_generator {
+            self.tokenizer.pop_ignore_padding(options.chunk);
+            trivia.generator_gap = generator_gap;
+        }
 
         let (identifier_token, identifier_gap) = self.tokenizer.peek_ignore_padding(options.chunk);
-        self.tokenizer.pop_ignore_padding(options.chunk);
+        let identifier_location = SourceLocation::new(self.tokenizer.token_start(), self.tokenizer.token_end());
         let identifier = match identifier_token {
-            Identifier(ref name) => name.to_owned(),
-            _ => return Err(ParserError::new(ParserErrorKind::Syntax, String::from("Function has no identifier."), SourceLocation::default()))
+            Identifier(ref name) => {
+                self.tokenizer.pop_ignore_padding(options.chunk);
+                trivia.identifier_gap = identifier_gap;
+                name.clone()
+            }
+            _ if is_declaration => return Err(self.syntax_error(String::from("Function has no identifier."))),
+            _ => Symbol::new("")
         };
-        trivia.identifier_gap = identifier_gap;
+        if self.typescript {
+            self.skip_type_parameters(options)?;
+        }
+
+        let (parameters, body) = self.parse_function_tail(options, &identifier, identifier_location, is_async, is_generator, &mut trivia)?;
+
+        return Ok(Expression::Function {
+            name: identifier,
+            is_async,
+            is_generator,
+            parameters,
+            body,
+            trivia
+        });
+    }
 
+    /// Parses the parameters and body of a function or method.
+    fn parse_function_tail(&mut self, options: &mut ParserOptions, name: &str, name_location: SourceLocation, is_async: bool, is_generator: bool, trivia: &mut FunctionTrivia) -> Result<(Vec<Parameter>, BodyNode), ParserError> {
+        use self::Token::*;
 
         let (bracket_token, bracket_gap) = self.tokenizer.peek_ignore_padding(options.chunk);
         self.tokenizer.pop_ignore_padding(options.chunk);
         match bracket_token {
             BracketOpen => {}
-            _ => return Err(ParserError::new(ParserErrorKind::Syntax, format!("Function params must begin with bracket."), SourceLocation::default()))
+            _ => return Err(self.syntax_error(format!("Function params must begin with bracket.")))
         }
         trivia.parameters_gap = bracket_gap;
 
+        self.context.enter_function(is_async, is_generator);
+
         let mut parameters = Vec::new();
+        let mut parameter_properties = Vec::new();
         loop {
+            let mut is_property = false;
+            let mut modifiers_prefix = None;
+            if self.typescript {
+                if self.skip_this_parameter(options)? {
+                    continue;
+                }
+                let (_, prefix) = self.tokenizer.peek_ignore_padding(options.chunk);
+                is_property = self.skip_member_modifiers(options).len() > 0;
+                if is_property {
+                    modifiers_prefix = Some(prefix);
+                }
+            }
+
             let (parameter_token, parameter_gap) = self.tokenizer.peek_ignore_padding(options.chunk);
             self.tokenizer.pop_ignore_padding(options.chunk);
+            // The padding before stripped modifiers stands in for the padding after them.
+            let parameter_gap = modifiers_prefix.unwrap_or(parameter_gap);
             match parameter_token {
                 BracketClose => {
                     if parameters.len() == 0 {
                         trivia.parameters_padding += &parameter_gap;
                         break;
                     }
-                    return Err(ParserError::new(ParserErrorKind::Syntax, format!("Unexpected end of params."), SourceLocation::default()));
+                    return Err(self.syntax_error(format!("Unexpected end of params.")));
                 }
                 Identifier(ref ident) => {
+                    let location = SourceLocation::new(self.tokenizer.token_start(), self.tokenizer.token_end());
+                    self.check_identifier(ident, location.clone())?;
+                    if is_property {
+                        parameter_properties.push(ident.to_string());
+                    }
+                    if self.typescript {
+                        self.skip_parameter_type(options)?;
+                    }
+
                     let (next_token, suffix) = self.tokenizer.peek_ignore_padding(options.chunk);
                     self.tokenizer.pop_ignore_padding(options.chunk);
 
                     parameters.push(Parameter {
-                        name: ident.to_owned(),
+                        name: ident.clone(),
                         default: None,
+                        location,
                         trivia: ParameterTrivia { prefix: parameter_gap, suffix }
                     });
 
                     match next_token {
                         Comma => continue,
                         BracketClose => break,
-                        _ => return Err(ParserError::new(ParserErrorKind::Syntax, format!("Function params contains invalid token."), SourceLocation::default())),
+                        _ => return Err(self.syntax_error(format!("Function params contains invalid token."))),
                     }
                 }
-                _ => return Err(ParserError::new(ParserErrorKind::Syntax, format!("Invalid function."), SourceLocation::default())),
+                _ => return Err(self.syntax_error(format!("Invalid function."))),
             }
         }
 
+        if self.typescript {
+            self.skip_type_annotation(options)?;
+        }
+
         let (body_token, body_gap) = self.tokenizer.peek_ignore_padding(options.chunk);
 
         if body_token != BraceOpen {
-            return Err(ParserError::new(ParserErrorKind::Syntax, format!("Function body must begin with brace."), SourceLocation::default()));
+            return Err(self.syntax_error(format!("Function body must begin with brace.")));
         }
         self.tokenizer.pop_ignore_padding(options.chunk);
         trivia.body_gap = body_gap;
+        let body_start = self.tokenizer.token_start();
 
-        let mut body = Vec::new();
-        loop {
-            let (token, prefix) = self.tokenizer.peek_ignore_padding(options.chunk);
-            match token {
-                BraceClose => {
-                    trivia.body_suffix = prefix;
-                    self.tokenizer.pop_ignore_padding(options.chunk);
-                    break;
-                }
-                _ => match self.parse_statement(options) {
-                    Ok(mut statement_node) => {
-                        statement_node.trivia.prefix = prefix;
-                        self.apply_plugin(options, PluginPass::StatementNodeEmit(&mut statement_node));
-                        body.push(statement_node)
-                    }
-                    Err(err) => return Err(err)
+        for parameter in &parameters {
+            // Parameters may share a name in sloppy mode code, which is checked below in strict mode code.
+            let _ = self.context.declare_var(&parameter.name, parameter.location.clone());
+        }
+
+        let (mut body, body_suffix) = self.parse_body(options, BraceClose, true)?;
+        trivia.body_suffix = body_suffix;
+        let body_location = SourceLocation::new(body_start, self.tokenizer.token_end());
+        if parameter_properties.len() > 0 {
+            body = self.assign_parameter_properties(options, body, &parameter_properties)?;
+        }
+        body.location = body_location;
+
+        // A 'use strict' directive in the body applies to the function's own name and parameters too.
+        if self.context.is_strict() {
+            if !name.is_empty() {
+                self.check_binding(name, name_location)?;
+            }
+            let mut names = HashMap::new();
+            for parameter in &parameters {
+                self.check_binding(&parameter.name, parameter.location.clone())?;
+                if let Some(first) = names.insert(parameter.name.as_str(), parameter.location.clone()) {
+                    let error = self.early_error(format!("Duplicate parameter '{}' is not allowed in strict mode code.", parameter.name), parameter.location.clone());
+                    return Err(error.with_label(Label::secondary(first, String::from("first declared here"))));
                 }
             }
         }
+        self.context.exit_function();
 
-        return Ok((Expression::Function {
-            name: identifier,
-            parameters,
-            body: BodyNode::new(body),
-            trivia
-        }, Some(StatementTerminator::Block)));
+        return Ok((parameters, body));
     }
 
-    fn apply_plugin(&self, options: &mut ParserOptions, pass: PluginPass) {
-        options.plugin_manager.apply_plugin(pass);
+    /// Wraps a parsed expression in a node.
+    fn finish_expression(&self, expression: Expression, trivia: NodeTrivia, location: SourceLocation) -> ExpressionNode {
+        let mut node = ExpressionNode::new(expression, trivia);
+        node.location = location;
+        return node;
     }
 
-    fn parse_bracket_expression(&mut self, options: &mut ParserOptions) -> Result<Expression, ParserError> {
-        use self::Token::*;
+    /// Moves the leading padding of the first child onto the node that is about to wrap it.
+    fn take_prefix(node: &mut ExpressionNode) -> NodeTrivia {
+        let mut trivia = NodeTrivia::new();
+        trivia.prefix = mem::replace(&mut node.trivia.prefix, String::new());
+        return trivia;
+    }
+
+    /// Rejects identifiers which are reserved words in the current context.
+    fn check_identifier(&self, name: &str, location: SourceLocation) -> Result<(), ParserError> {
+        let reserved = match name {
+            "await" => self.context.is_module() || self.context.is_async(),
+            "yield" => self.context.is_strict() || self.context.is_generator(),
+            "let" | "static" | "implements" | "interface" | "package" | "private" | "protected" | "public" => self.context.is_strict(),
+            _ => false
+        };
+        if reserved {
+            return Err(self.early_error(format!("Unexpected reserved word '{}'.", name), location));
+        }
+        return Ok(());
+    }
 
-        let mut expressions = Vec::new();
+    fn check_binding(&self, name: &str, location: SourceLocation) -> Result<(), ParserError> {
+        self.check_identifier(name, location.clone())?;
+        if self.context.is_strict() && (name == "eval" || name == "arguments") {
+            return Err(self.early_error(format!("Binding '{}' is not allowed in strict mode code.", name), location));
+        }
+        return Ok(());
+    }
 
-        loop {
-            let (token, prefix) = self.tokenizer.peek_ignore_padding(options.chunk);
-            match token {
-                BracketClose => {
-                    self.tokenizer.pop_ignore_padding(options.chunk);
-                    break;
-                }
-                Comma => {
-                    self.tokenizer.pop_ignore_padding(options.chunk);
-                }
-                _ => {
-                    match self.parse_expression(options) {
-                        Ok((child_expression, _)) => {
-                            expressions.push(child_expression);
-                        }
-                        Err(err) => return Err(err)
-                    }
+    fn check_assignment_target(&self, node: &ExpressionNode) -> Result<(), ParserError> {
+        return match node.expression {
+            Expression::Identifier(ref name) => {
+                if self.context.is_strict() && (name == "eval" || name == "arguments") {
+                    return Err(self.early_error(format!("Assignment to '{}' is not allowed in strict mode code.", name), node.location.clone()));
                 }
+                Ok(())
             }
+            Expression::Member { .. } | Expression::ComputedMember { .. } => Ok(()),
+            Expression::Bracketed { ref expression, .. } => self.check_assignment_target(expression),
+            _ => Err(self.early_error(String::from("Invalid assignment target."), node.location.clone()))
+        };
+    }
+
+    fn parse_bracket_expression(&mut self, options: &mut ParserOptions) -> Result<Expression, ParserError> {
+        use self::Token::*;
+
+        let expression = self.parse_expression(options)?;
+
+        let (token, expression_suffix) = self.tokenizer.peek_ignore_padding(options.chunk);
+        if token != BracketClose {
+            return Err(self.syntax_error(format!("Expected ')' but found '{}'.", token.to_string())));
         }
-        let (possible_fat_arrow, arrow_prefix) = self.tokenizer.peek_ignore_padding(options.chunk);
+        self.tokenizer.pop_ignore_padding(options.chunk);
+
+        let (possible_fat_arrow, _) = self.tokenizer.peek_ignore_padding(options.chunk);
         if possible_fat_arrow == FatArrow {
-            return Err(ParserError::new(ParserErrorKind::Syntax, String::from("ASDasd"), SourceLocation::default()));
-        } else {
-            let expression = expressions.remove(0);
-            return Ok(Expression::Bracketed { expression: Box::new(expression) });
+            return Err(self.syntax_error(String::from("Arrow functions are not supported.")));
         }
+        return Ok(Expression::Bracketed { expression: Box::new(expression), trivia: BracketedTrivia { expression_suffix } });
     }
 
+    fn parse_expression(&mut self, options: &mut ParserOptions) -> Result<ExpressionNode, ParserError> {
+        return self.parse_assignment(options);
+    }
 
-    fn parse_expression(&mut self, options: &mut ParserOptions) -> Result<(ExpressionNode, Option<StatementTerminator>), ParserError> {
-        let mut trivia = NodeTrivia::new();
-        let mut expression_option: Option<Expression> = None;
-        let mut terminator_option: Option<StatementTerminator> = None;
+    fn parse_assignment(&mut self, options: &mut ParserOptions) -> Result<ExpressionNode, ParserError> {
+        let (token, _) = self.tokenizer.peek_ignore_padding(options.chunk);
+        if self.context.is_generator() && JsParser::is_contextual(&token, "yield") {
+            return self.parse_yield(options);
+        }
 
-        let (token, prefix) = self.tokenizer.peek_ignore_padding(options.chunk);
-        trivia.prefix = prefix;
-        match token {
-            Token::BracketOpen => {
-                self.tokenizer.pop_ignore_padding(options.chunk);
-                match self.parse_bracket_expression(options) {
-                    Ok(expression) => {
-                        expression_option = Some(expression);
-                    }
-                    Err(err) => return Err(err)
-                }
-            }
-            Token::Literal(ref literal) => {
-                expression_option = Some(Expression::Literal(literal.clone()));
-                self.tokenizer.pop_ignore_padding(options.chunk);
-            }
-            Token::Keyword(keyword) => {
-                match keyword {
-                    Keyword::Function => {
-                        self.tokenizer.pop_ignore_padding(options.chunk);
-                        match self.parse_function(options) {
-                            Ok((expression, terminator)) => {
-                                expression_option = Some(expression);
-                                terminator_option = terminator;
-                            }
-                            Err(err) => return Err(err)
-                        }
-                    }
-                    _ => return Err(ParserError::new(ParserErrorKind::Syntax, format!("Keyword '{:?}' not supported.", keyword), SourceLocation::default()))
-                }
-            }
-            Token::Identifier(ref name) => {
+        let mut left = self.parse_conditional(options)?;
+
+        let (token, operator_prefix) = self.tokenizer.peek_ignore_padding(options.chunk);
+        return match token {
+            Token::Operator(operator) if operator.is_assignment() => {
+                self.check_assignment_target(&left)?;
                 self.tokenizer.pop_ignore_padding(options.chunk);
-                let (next_token, _) = self.tokenizer.peek_ignore_padding(options.chunk);
-                match next_token {
-                    Token::BracketOpen => {
-                        self.tokenizer.pop_ignore_padding(options.chunk);
-                        match self.parse_call(options, name.to_owned()) {
-                            Ok((expression, terminator)) => {
-                                expression_option = Some(expression);
-                                terminator_option = terminator;
-                            }
-                            Err(err) => return Err(err)
-                        }
-                    }
-                    _ => {
-                        expression_option = Some(Expression::Identifier(name.to_owned()));
-                    }
-                }
+
+                let right = self.parse_assignment(options)?;
+                let location = SourceLocation::new(left.location.start, right.location.end);
+                let trivia = JsParser::take_prefix(&mut left);
+                Ok(self.finish_expression(Expression::Assignment {
+                    left: Box::new(left),
+                    operator,
+                    right: Box::new(right),
+                    trivia: OperatorTrivia { operator_prefix }
+                }, trivia, location))
             }
-            _ => {}
-        }
+            _ => Ok(left)
+        };
+    }
 
-        if expression_option == None {
-            return Err(ParserError::new(ParserErrorKind::Syntax, format!("Expression could not be parsed. Current token: {:?} at index {}", self.tokenizer.peek_token(options.chunk), options.chunk.index), SourceLocation::default()));
-        }
+    fn parse_yield(&mut self, options: &mut ParserOptions) -> Result<ExpressionNode, ParserError> {
+        use self::Token::*;
 
-        let mut node = ExpressionNode::new(expression_option.unwrap(), trivia);
-        self.apply_plugin(options, PluginPass::ExpressionNodeEmit(&mut node));
-        return Ok((node, terminator_option));
-    }
+        let (_, prefix) = self.tokenizer.peek_ignore_padding(options.chunk);
+        let start = self.tokenizer.token_start();
+        self.tokenizer.pop_ignore_padding(options.chunk);
 
-    fn parse_call(&mut self, options: &mut ParserOptions, identifier: String) -> Result<(Expression, Option<StatementTerminator>), ParserError> {
-        let mut parameters = Vec::new();
-        loop {
-            let (token, _) = self.tokenizer.peek_ignore_padding(options.chunk);
-            match token {
-                Token::Comma => continue,
-                Token::BracketClose => break,
-                _ => match self.parse_expression(options) {
-                    Ok((expression_node, _)) => {
-                        parameters.push(expression_node)
-                    }
-                    Err(err) => return Err(err)
-                }
-            }
+        let mut trivia = OperatorTrivia::new();
+        let (token, operator_prefix) = self.tokenizer.peek_ignore_whitespace(options.chunk);
+        let delegate = token == Operator(OperatorKind::Multiplication);
+        if delegate {
+            self.tokenizer.pop_ignore_whitespace(options.chunk);
+            trivia.operator_prefix = operator_prefix;
         }
 
-        let token = self.tokenizer.peek_token(options.chunk).unwrap();
-        match token {
-            Token::BracketClose => self.tokenizer.pop_token(options.chunk),
-            _ => return Err(ParserError::new(ParserErrorKind::Syntax, format!("Call not closed with bracket"), SourceLocation::default()))
+        // Without `*`, a yield followed by a line break or the end of its enclosing expression has no argument.
+        let (token, _) = self.tokenizer.peek_ignore_whitespace(options.chunk);
+        let argument = match token {
+            Newline | BracketClose | SquareBracketClose | BraceClose | Comma | Semicolon | Colon | EndOfFile if !delegate => None,
+            _ => Some(Box::new(self.parse_assignment(options)?))
         };
 
-        return Ok((Expression::Call { identifier, parameters }, None));
+        let mut node_trivia = NodeTrivia::new();
+        node_trivia.prefix = prefix;
+        let location = SourceLocation::new(start, options.chunk.index);
+        return Ok(self.finish_expression(Expression::Yield { argument, delegate, trivia }, node_trivia, location));
     }
 
-    fn parse_declaration(&mut self, options: &mut ParserOptions, kind: &DeclarationKind) -> Result<(Statement, Option<StatementTerminator>), ParserError> {
-        use self::Token::*;
+    fn parse_conditional(&mut self, options: &mut ParserOptions) -> Result<ExpressionNode, ParserError> {
+        let mut test = self.parse_binary(options, 0)?;
 
-        let mut declarations = Vec::new();
+        let (token, question_prefix) = self.tokenizer.peek_ignore_padding(options.chunk);
+        if token != Token::Operator(OperatorKind::Conditional) {
+            return Ok(test);
+        }
+        self.tokenizer.pop_ignore_padding(options.chunk);
+
+        let consequent = self.parse_assignment(options)?;
+
+        let (token, colon_prefix) = self.tokenizer.peek_ignore_padding(options.chunk);
+        if token != Token::Colon {
+            return Err(self.syntax_error(format!("Expected ':' in conditional expression but found '{}'.", token.to_string())));
+        }
+        self.tokenizer.pop_ignore_padding(options.chunk);
+
+        let alternate = self.parse_assignment(options)?;
+        let location = SourceLocation::new(test.location.start, alternate.location.end);
+        let trivia = JsParser::take_prefix(&mut test);
+
+        return Ok(self.finish_expression(Expression::Conditional {
+            test: Box::new(test),
+            consequent: Box::new(consequent),
+            alternate: Box::new(alternate),
+            trivia: ConditionalTrivia { question_prefix, colon_prefix }
+        }, trivia, location));
+    }
+
+    fn parse_binary(&mut self, options: &mut ParserOptions, min_precedence: u8) -> Result<ExpressionNode, ParserError> {
+        let mut left = self.parse_unary(options)?;
 
         loop {
-            let (identifier_token, identifier_prefix) = self.tokenizer.peek_ignore_whitespace(options.chunk);
+            let (token, operator_prefix) = self.tokenizer.peek_ignore_padding(options.chunk);
 
-            match identifier_token {
-                Identifier(ref name) => {
-                    self.tokenizer.pop_ignore_whitespace(options.chunk);
-                    let mut trivia = DeclarationTrivia::new();
-                    let expression;
+            // `x as T` and `x satisfies T` bind like relational operators and leave just `x`.
+            let is_cast = JsParser::is_contextual(&token, "as") || JsParser::is_contextual(&token, "satisfies");
+            if self.typescript && is_cast && !operator_prefix.contains('\n') && OperatorKind::InstanceOf.precedence() >= min_precedence {
+                self.tokenizer.pop_ignore_padding(options.chunk);
+                self.skip_type(options)?;
+                continue;
+            }
 
-                    let (next_token, next_prefix) = self.tokenizer.peek_ignore_padding(options.chunk);
-                    match next_token {
-                        Operator(OperatorKind::Assign) => {
-                            self.tokenizer.pop_ignore_padding(options.chunk);
-                            trivia.assign_prefix = next_prefix;
-                            expression = match self.parse_expression(options) {
-                                Ok((expression_node, _)) => Some(expression_node),
-                                Err(err) => return Err(err),
-                            };
-                        }
-                        _ => return Err(ParserError::new(ParserErrorKind::Syntax, format!("Declaration is not valid."), SourceLocation::default()))
-                    }
-                    declarations.push(DeclarationNode::new(name.to_owned(), expression, trivia))
-                }
-                Comma => {
-                    self.tokenizer.pop_ignore_whitespace(options.chunk);
-                }
-                Newline => {
-                    self.tokenizer.pop_ignore_whitespace(options.chunk);
-                    options.chunk.index -=1;
-                    break;
-                }
-                Semicolon => {
-                    self.tokenizer.pop_ignore_whitespace(options.chunk);
-                    options.chunk.index -=1;
-                    break;
+            let operator = match token {
+                Token::Operator(operator) if operator.is_binary() => operator,
+                _ => break
+            };
+
+            let precedence = operator.precedence();
+            if precedence < min_precedence {
+                break;
+            }
+            self.tokenizer.pop_ignore_padding(options.chunk);
+
+            // `-a ** 2` could mean `(-a) ** 2` or `-(a ** 2)`, the operand needs brackets.
+            if operator == OperatorKind::Exponent {
+                if let Expression::Unary { .. } | Expression::Await { .. } = left.expression {
+                    return Err(self.syntax_error(String::from("Unary expressions before '**' must be bracketed.")));
                 }
-                _ => return Err(ParserError::new(ParserErrorKind::Syntax, format!("Declaration does not end correctly."), SourceLocation::default()))
             }
+
+            let next_precedence = match operator.is_right_associative() {
+                true => precedence,
+                false => precedence + 1
+            };
+            let right = self.parse_binary(options, next_precedence)?;
+            let location = SourceLocation::new(left.location.start, right.location.end);
+            let trivia = JsParser::take_prefix(&mut left);
+
+            left = self.finish_expression(Expression::Binary {
+                left: Box::new(left),
+                operator,
+                right: Box::new(right),
+                trivia: OperatorTrivia { operator_prefix }
+            }, trivia, location);
         }
 
-        return Ok((Statement::Declaration { kind: kind.clone(), declarations }, None));
+        return Ok(left);
     }
 
-    fn parse_return(&mut self, options: &mut ParserOptions) -> Result<(Statement, Option<StatementTerminator>), ParserError> {
-        let mut expression_option = None;
-        let mut terminator_option = None;
-        match self.parse_expression(options) {
-            Ok((expression_node, terminator)) => {
-                expression_option = Some(expression_node);
-                terminator_option = terminator;
+    fn parse_unary(&mut self, options: &mut ParserOptions) -> Result<ExpressionNode, ParserError> {
+        let (token, prefix) = self.tokenizer.peek_ignore_padding(options.chunk);
+        let start = self.tokenizer.token_start();
+
+        if self.context.is_async() && JsParser::is_contextual(&token, "await") {
+            self.tokenizer.pop_ignore_padding(options.chunk);
+            let argument = self.parse_unary(options)?;
+            let location = SourceLocation::new(start, argument.location.end);
+            let mut trivia = NodeTrivia::new();
+            trivia.prefix = prefix;
+            return Ok(self.finish_expression(Expression::Await { argument: Box::new(argument) }, trivia, location));
+        }
+
+        // A `<T>value` type assertion leaves just the value, written where the assertion started.
+        if self.typescript && !self.jsx && token == Token::Operator(OperatorKind::Lesser) {
+            self.skip_type_arguments(options)?;
+            let mut argument = self.parse_unary(options)?;
+            argument.trivia.prefix = prefix;
+            return Ok(argument);
+        }
+
+        let operator = match token {
+            Token::Operator(operator) if operator.is_unary() || operator.is_update() => operator,
+            _ => return self.parse_postfix(options)
+        };
+        self.tokenizer.pop_ignore_padding(options.chunk);
+
+        let argument = self.parse_unary(options)?;
+        let location = SourceLocation::new(start, argument.location.end);
+        let mut trivia = NodeTrivia::new();
+        trivia.prefix = prefix;
+
+        let expression = match operator.is_update() {
+            true => {
+                self.check_assignment_target(&argument)?;
+                Expression::Update { operator, argument: Box::new(argument), prefix: true, trivia: OperatorTrivia::new() }
             }
-            Err(_) => {}
+            false => Expression::Unary { operator, argument: Box::new(argument) }
         };
 
-        return Ok((Statement::Return { expression: expression_option }, terminator_option));
+        return Ok(self.finish_expression(expression, trivia, location));
     }
 
-    fn parse_import(&mut self, options: &mut ParserOptions) -> Result<(Statement, Option<StatementTerminator>), ParserError> {
-        let mut trivia = ImportTrivia::new();
+    fn parse_postfix(&mut self, options: &mut ParserOptions) -> Result<ExpressionNode, ParserError> {
+        let mut argument = self.parse_member(options, true)?;
 
-        let (declaration_token, declaration_prefix) = self.tokenizer.peek_ignore_padding(options.chunk);
-        trivia.declaration_prefix = declaration_prefix;
+        // A line break before a postfix operator ends the expression instead.
+        let (token, operator_prefix) = self.tokenizer.peek_ignore_whitespace(options.chunk);
+        return match token {
+            Token::Operator(operator) if operator.is_update() => {
+                self.check_assignment_target(&argument)?;
+                self.tokenizer.pop_ignore_whitespace(options.chunk);
 
-        let declaration = match declaration_token {
-            Token::Identifier(name) => {
-                match name == "*" {
-                    true => ImportDeclaration::All,
-                    false => ImportDeclaration::Single(name.to_owned())
-                }
+                let location = SourceLocation::new(argument.location.start, options.chunk.index);
+                let trivia = JsParser::take_prefix(&mut argument);
+                Ok(self.finish_expression(Expression::Update {
+                    operator,
+                    argument: Box::new(argument),
+                    prefix: false,
+                    trivia: OperatorTrivia { operator_prefix }
+                }, trivia, location))
             }
-            _ => return Err(ParserError::new(ParserErrorKind::Syntax, format!("Invalid import."), SourceLocation::default()))
+            _ => Ok(argument)
         };
+    }
 
-        let mut alias = None;
-        let source;
+    /// Parses member accesses and, when allowed, calls following a primary expression.
+    fn parse_member(&mut self, options: &mut ParserOptions, allow_call: bool) -> Result<ExpressionNode, ParserError> {
+        let (token, _) = self.tokenizer.peek_ignore_padding(options.chunk);
+        let mut object = match token {
+            Token::Operator(OperatorKind::New) => self.parse_new(options)?,
+            _ => self.parse_primary(options)?
+        };
 
         loop {
-            self.tokenizer.pop_ignore_padding(options.chunk);
-            let (next_token, next_prefix) = self.tokenizer.peek_ignore_padding(options.chunk);
-            match next_token {
-                Token::Keyword(Keyword::As) => {
+            let (token, token_prefix) = self.tokenizer.peek_ignore_padding(options.chunk);
+            let expression = match token {
+                Token::Operator(OperatorKind::Accessor) => {
                     self.tokenizer.pop_ignore_padding(options.chunk);
-                    if alias.is_some() {
-                        return Err(ParserError::new(ParserErrorKind::Syntax, format!("Invalid import."), SourceLocation::default()));
-                    }
-                    trivia.as_prefix = next_prefix;
-                    let (identifier_token, identifier_prefix) = self.tokenizer.peek_ignore_padding(options.chunk);
-                    match identifier_token {
-                        Token::Identifier(ref name) => {
-                            trivia.alias_prefix = identifier_prefix;
-                            alias = Some(name.to_owned());
-                        }
-                        _ => return Err(ParserError::new(ParserErrorKind::Syntax, format!("Invalid import."), SourceLocation::default()))
+
+                    let (property_token, property_prefix) = self.tokenizer.peek_ignore_padding(options.chunk);
+                    let property = match property_token {
+                        Token::Identifier(name) => name,
+                        Token::Keyword(keyword) => keyword.to_string().into(),
+                        Token::Operator(operator) if operator.is_word() => operator.to_string().into(),
+                        Token::Literal(Literal::Null) | Token::Literal(Literal::Undefined) | Token::Literal(Literal::Boolean(_)) => property_token.to_string().into(),
+                        _ => return Err(self.syntax_error(format!("Unexpected token '{}' after '.'.", property_token.to_string())))
+                    };
+                    self.tokenizer.pop_ignore_padding(options.chunk);
+
+                    let trivia = MemberTrivia { accessor_prefix: token_prefix, property_prefix };
+                    Expression::Member { object: Box::new(object), property, trivia }
+                }
+                Token::SquareBracketOpen => {
+                    self.tokenizer.pop_ignore_padding(options.chunk);
+                    let property = self.parse_expression(options)?;
+
+                    let (close_token, close_prefix) = self.tokenizer.peek_ignore_padding(options.chunk);
+                    if close_token != Token::SquareBracketClose {
+                        return Err(self.syntax_error(format!("Expected ']' but found '{}'.", close_token.to_string())));
                     }
+                    self.tokenizer.pop_ignore_padding(options.chunk);
+
+                    let trivia = ComputedMemberTrivia { open_prefix: token_prefix, close_prefix };
+                    Expression::ComputedMember { object: Box::new(object), property: Box::new(property), trivia }
                 }
-                Token::Keyword(Keyword::From) => {
+                Token::BracketOpen if allow_call => {
                     self.tokenizer.pop_ignore_padding(options.chunk);
-                    trivia.from_prefix = next_prefix;
-                    let (source_token, source_prefix) = self.tokenizer.peek_ignore_padding(options.chunk);
+                    let (parameters, trivia) = self.parse_arguments(options, token_prefix)?;
+                    Expression::Call { callee: Box::new(object), parameters, trivia }
+                }
+                // Non-null assertions, `x!`, and type arguments, `f<T>()`, are dropped.
+                Token::Operator(OperatorKind::LogicalNot) if self.typescript && !token_prefix.contains('\n') => {
                     self.tokenizer.pop_ignore_padding(options.chunk);
-                    match source_token {
-                        Token::Literal(Literal::String(ref name, ref quote)) => {
-                            trivia.source_prefix = source_prefix;
-                            trivia.quote_kind = quote.clone();
-                            source = Some(name.to_owned());
-                        }
-                        _ => return Err(ParserError::new(ParserErrorKind::Syntax, format!("Invalid import."), SourceLocation::default()))
-                    };
-                    break;
+                    continue;
                 }
-                _ => return Err(ParserError::new(ParserErrorKind::Syntax, format!("Invalid import."), SourceLocation::default()))
-            }
-        }
+                Token::Operator(OperatorKind::Lesser) if self.typescript && self.try_skip_type_arguments(options) => continue,
+                _ => break
+            };
 
-        //TODO: Cleanup, should this be here?
-        let import = source.unwrap();
-        self.requires.push(import.clone());
+            object = self.wrap_member(options, expression);
+        }
 
-        return Ok((Statement::Import { source: import, alias, declaration, trivia }, None));
+        return Ok(object);
     }
 
-    fn parse_keyword(&mut self, options: &mut ParserOptions, keyword: Keyword) -> Result<(Statement, Option<StatementTerminator>), ParserError> {
-        use self::Keyword::*;
-
-        return match keyword {
-            Return => {
-                self.tokenizer.pop_ignore_padding(options.chunk);
-                self.parse_return(options)
-            }
-            Declaration(kind) => {
-                self.tokenizer.pop_ignore_padding(options.chunk);
-                self.parse_declaration(options, &kind)
-            }
-            Import => {
-                self.tokenizer.pop_ignore_padding(options.chunk);
-                self.parse_import(options)
+    /// Builds the node for a member access or call, moving the object's leading padding onto it.
+    fn wrap_member(&self, options: &mut ParserOptions, mut expression: Expression) -> ExpressionNode {
+        let (trivia, start) = match expression {
+            Expression::Member { ref mut object, .. } | Expression::ComputedMember { ref mut object, .. } | Expression::Call { callee: ref mut object, .. } => {
+                (JsParser::take_prefix(object), object.location.start)
             }
-            _ => Err(ParserError::new(ParserErrorKind::Syntax, format!("Statement keyword '{:?}' not found.", keyword), SourceLocation::default()))
+            _ => (NodeTrivia::new(), self.tokenizer.token_start())
         };
+        let location = SourceLocation::new(start, options.chunk.index);
+        return self.finish_expression(expression, trivia, location);
     }
 
+    fn parse_new(&mut self, options: &mut ParserOptions) -> Result<ExpressionNode, ParserError> {
+        let (_, prefix) = self.tokenizer.peek_ignore_padding(options.chunk);
+        let start = self.tokenizer.token_start();
+        self.tokenizer.pop_ignore_padding(options.chunk);
 
-    fn parse_statement(&mut self, options: &mut ParserOptions) -> Result<StatementNode, ParserError> {
-        use self::Token::*;
+        let callee = self.parse_member(options, false)?;
 
-        let mut trivia = NodeTrivia::new();
-        let mut statement_option: Option<Statement> = None;
-        let mut terminator_option: Option<StatementTerminator> = None;
+        let (token, open_prefix) = self.tokenizer.peek_ignore_padding(options.chunk);
+        let (parameters, call_trivia) = match token {
+            Token::BracketOpen => {
+                self.tokenizer.pop_ignore_padding(options.chunk);
+                let (parameters, call_trivia) = self.parse_arguments(options, open_prefix)?;
+                (Some(parameters), call_trivia)
+            }
+            _ => (None, CallTrivia::new())
+        };
+
+        let mut trivia = NodeTrivia::new();
+        trivia.prefix = prefix;
+        let location = SourceLocation::new(start, options.chunk.index);
+        return Ok(self.finish_expression(Expression::New { callee: Box::new(callee), parameters, trivia: call_trivia }, trivia, location));
+    }
+
+    /// Parses the arguments of a call after its `(`, given the padding before that bracket.
+    fn parse_arguments(&mut self, options: &mut ParserOptions, open_prefix: String) -> Result<(Vec<ExpressionNode>, CallTrivia), ParserError> {
+        let mut trivia = CallTrivia::new();
+        trivia.open_prefix = open_prefix;
+
+        let mut parameters = Vec::new();
+        loop {
+            let (token, prefix) = self.tokenizer.peek_ignore_padding(options.chunk);
+            if token == Token::BracketClose {
+                self.tokenizer.pop_ignore_padding(options.chunk);
+                trivia.trailing_comma = parameters.len() > 0;
+                trivia.arguments_suffix = prefix;
+                break;
+            }
+
+            let mut parameter = self.parse_expression(options)?;
+
+            let (token, suffix) = self.tokenizer.peek_ignore_padding(options.chunk);
+            self.tokenizer.pop_ignore_padding(options.chunk);
+            parameter.trivia.suffix = suffix;
+            parameters.push(parameter);
+            match token {
+                Token::Comma => continue,
+                Token::BracketClose => break,
+                _ => return Err(self.syntax_error(format!("Call not closed with bracket")))
+            }
+        }
+        return Ok((parameters, trivia));
+    }
+
+    fn parse_primary(&mut self, options: &mut ParserOptions) -> Result<ExpressionNode, ParserError> {
+        let mut trivia = NodeTrivia::new();
 
         let (token, prefix) = self.tokenizer.peek_ignore_padding(options.chunk);
+        let start = self.tokenizer.token_start();
         trivia.prefix = prefix;
-        match token {
-            Keyword(keyword) => {
-                match self.parse_keyword(options, keyword) {
-                    Ok((statement, terminator)) => {
-                        statement_option = Some(statement);
-                        terminator_option = terminator;
+
+        let expression = match token {
+            Token::BracketOpen => {
+                self.tokenizer.pop_ignore_padding(options.chunk);
+                self.parse_bracket_expression(options)?
+            }
+            Token::Literal(literal) => {
+                self.tokenizer.pop_ignore_padding(options.chunk);
+                if self.context.is_strict() && literal.is_legacy_octal() {
+                    let location = SourceLocation::new(start, options.chunk.index);
+                    return Err(self.early_error(String::from("Octal literals are not allowed in strict mode code."), location));
+                }
+                Expression::Literal(literal)
+            }
+            Token::BraceOpen => {
+                self.tokenizer.pop_ignore_padding(options.chunk);
+                self.parse_object(options)?
+            }
+            Token::SquareBracketOpen => {
+                self.tokenizer.pop_ignore_padding(options.chunk);
+                self.parse_array(options)?
+            }
+            Token::Operator(OperatorKind::Lesser) if self.jsx => self.parse_jsx_element(options)?,
+            Token::Keyword(Keyword::Function) => {
+                self.tokenizer.pop_ignore_padding(options.chunk);
+                self.parse_function(options, false, None)?
+            }
+            Token::Keyword(Keyword::Class) => {
+                self.tokenizer.pop_ignore_padding(options.chunk);
+                self.parse_class(options, false)?
+            }
+            Token::Keyword(Keyword::This) => {
+                self.tokenizer.pop_ignore_padding(options.chunk);
+                Expression::This
+            }
+            Token::Keyword(Keyword::Super) => {
+                self.tokenizer.pop_ignore_padding(options.chunk);
+                Expression::Super
+            }
+            Token::Keyword(keyword) => return Err(self.syntax_error(format!("Keyword '{:?}' not supported.", keyword))),
+            Token::Identifier(ref name) if name == "async" && self.is_async_function(options) => {
+                self.tokenizer.pop_ignore_padding(options.chunk);
+                let (_, async_gap) = self.tokenizer.peek_ignore_padding(options.chunk);
+                self.tokenizer.pop_ignore_padding(options.chunk);
+                self.parse_function(options, false, Some(async_gap))?
+            }
+            Token::Identifier(name) => {
+                self.tokenizer.pop_ignore_padding(options.chunk);
+                self.check_identifier(&name, SourceLocation::new(start, options.chunk.index))?;
+                Expression::Identifier(name)
+            }
+            token => return Err(self.syntax_error(format!("Expected an expression but found '{}'.", token.to_string())))
+        };
+
+        let location = SourceLocation::new(start, options.chunk.index);
+        return Ok(self.finish_expression(expression, trivia, location));
+    }
+
+    fn parse_object(&mut self, options: &mut ParserOptions) -> Result<Expression, ParserError> {
+        use self::Token::*;
+
+        let mut properties = Vec::new();
+        let mut trivia = ObjectTrivia::new();
+
+        loop {
+            let (token, prefix) = self.tokenizer.peek_ignore_padding(options.chunk);
+            if token == BraceClose {
+                self.tokenizer.pop_ignore_padding(options.chunk);
+                trivia.trailing_comma = properties.len() > 0;
+                trivia.properties_suffix = prefix;
+                break;
+            }
+
+            if let Some(property) = self.parse_property(options, false)? {
+                properties.push(property);
+            }
+
+            let (separator, _) = self.tokenizer.peek_ignore_padding(options.chunk);
+            self.tokenizer.pop_ignore_padding(options.chunk);
+            match separator {
+                Comma => continue,
+                BraceClose => break,
+                _ => return Err(self.syntax_error(format!("Expected ',' or '}}' in object literal but found '{}'.", separator.to_string())))
+            }
+        }
+
+        return Ok(Expression::Object { properties, trivia });
+    }
+
+    fn parse_array(&mut self, options: &mut ParserOptions) -> Result<Expression, ParserError> {
+        use self::Token::*;
+
+        let mut elements = Vec::new();
+        let mut trivia = ArrayTrivia::new();
+        let mut after_comma = false;
+
+        loop {
+            let (token, prefix) = self.tokenizer.peek_ignore_padding(options.chunk);
+            match token {
+                SquareBracketClose => {
+                    self.tokenizer.pop_ignore_padding(options.chunk);
+                    trivia.trailing_comma = after_comma;
+                    trivia.elements_suffix = prefix;
+                    break;
+                }
+                Comma => {
+                    self.tokenizer.pop_ignore_padding(options.chunk);
+                    elements.push(None);
+                    trivia.hole_prefixes.push(prefix);
+                    after_comma = true;
+                    continue;
+                }
+                _ => {
+                    elements.push(Some(self.parse_assignment(options)?));
+                    after_comma = false;
+                }
+            }
+
+            let (separator, separator_prefix) = self.tokenizer.peek_ignore_padding(options.chunk);
+            match separator {
+                Comma => {
+                    self.tokenizer.pop_ignore_padding(options.chunk);
+                    if let Some(&mut Some(ref mut element)) = elements.last_mut() {
+                        element.trivia.suffix = separator_prefix;
                     }
-                    Err(_) => {}
+                    after_comma = true;
                 }
+                SquareBracketClose => {}
+                _ => return Err(self.syntax_error(format!("Expected ',' or ']' in array literal but found '{}'.", separator.to_string())))
             }
-            _ => {
-                statement_option = None;
+        }
+
+        return Ok(Expression::Array { elements, trivia });
+    }
+
+    fn parse_class(&mut self, options: &mut ParserOptions, is_declaration: bool) -> Result<Expression, ParserError> {
+        use self::Token::*;
+
+        self.context.enter_class();
+        let mut trivia = ClassTrivia::new();
+
+        let (token, identifier_gap) = self.tokenizer.peek_ignore_padding(options.chunk);
+        let name = match token {
+            Identifier(name) => {
+                self.tokenizer.pop_ignore_padding(options.chunk);
+                let location = SourceLocation::new(self.tokenizer.token_start(), self.tokenizer.token_end());
+                self.check_binding(&name, location)?;
+                trivia.identifier_gap = identifier_gap;
+                name
             }
+            _ if is_declaration => return Err(self.syntax_error(String::from("Class has no identifier."))),
+            _ => Symbol::new("")
+        };
+        if self.typescript {
+            self.skip_type_parameters(options)?;
         }
-        if statement_option.is_none() {
-            match self.parse_expression(options) {
-                Ok((mut expression_node, terminator)) => {
-                    expression_node.trivia.prefix = String::new();
-                    statement_option = Some(Statement::Expression { expression: expression_node });
-                    terminator_option = terminator;
+
+        let (token, extends_prefix) = self.tokenizer.peek_ignore_padding(options.chunk);
+        let super_class = match token {
+            Keyword(self::Keyword::Extends) => {
+                self.tokenizer.pop_ignore_padding(options.chunk);
+                trivia.extends_prefix = extends_prefix;
+                let super_class = self.parse_member(options, true)?;
+                if self.typescript && self.tokenizer.peek_ignore_padding(options.chunk).0 == Operator(OperatorKind::Lesser) {
+                    self.skip_type_arguments(options)?;
                 }
-                Err(err) => return Err(err)
+                Some(Box::new(super_class))
             }
+            _ => None
+        };
+        if self.typescript {
+            self.skip_implements(options)?;
         }
-        if terminator_option.is_none() {
-            loop {
-                let token = self.tokenizer.peek_token(options.chunk).unwrap();
-                 match token {
-                    Whitespace(count) => {
-                        for _ in 0..count {
-                            trivia.suffix += " ";
-                        }
-                        self.tokenizer.pop_token(options.chunk);
+
+        let (token, body_gap) = self.tokenizer.peek_ignore_padding(options.chunk);
+        if token != BraceOpen {
+            return Err(self.syntax_error(format!("Expected '{{' before class body but found '{}'.", token.to_string())));
+        }
+        self.tokenizer.pop_ignore_padding(options.chunk);
+        trivia.body_gap = body_gap;
+
+        // Semicolons between members carry no meaning, they are kept as padding of the next member.
+        let mut members = Vec::new();
+        let mut padding = String::new();
+        loop {
+            let (token, prefix) = self.tokenizer.peek_ignore_padding(options.chunk);
+            match token {
+                BraceClose => {
+                    self.tokenizer.pop_ignore_padding(options.chunk);
+                    trivia.body_suffix = padding + &prefix;
+                    break;
+                }
+                Semicolon => {
+                    self.tokenizer.pop_ignore_padding(options.chunk);
+                    padding += &prefix;
+                    padding += ";";
+                }
+                EndOfFile => return Err(self.syntax_error(String::from("Unexpected end of file, expected '}'."))),
+                _ => {
+                    if let Some(mut member) = self.parse_property(options, true)? {
+                        member.trivia.prefix = mem::replace(&mut padding, String::new()) + &member.trivia.prefix;
+                        members.push(member);
                     }
-                    Newline => {
-                        self.tokenizer.pop_token(options.chunk);
-                        let (next_proper_token, pad) = self.tokenizer.peek_ignore_whitespace(options.chunk);
-
-                        if next_proper_token == Newline || next_proper_token == Semicolon {
-                            trivia.suffix += "\n";
-                        } else {
-                            terminator_option = Some(StatementTerminator::Newline);
-                            break;
-                        }
+                }
+            }
+        }
+
+        self.context.exit_class();
+        return Ok(Expression::Class { name, super_class, members, trivia });
+    }
+
+    /// Whether the next token is the modifier `word` (`static`, `async`, `get` or `set`) rather than
+    /// the name of the property itself.
+    fn is_property_modifier(&mut self, options: &mut ParserOptions, word: &str) -> bool {
+        use self::Token::*;
+
+        let (token, _) = self.tokenizer.peek_ignore_padding(options.chunk);
+        if !JsParser::is_contextual(&token, word) {
+            return false;
+        }
+        let (next_token, gap) = self.peek_second(options);
+        if word == "async" && gap.contains('\n') {
+            return false;
+        }
+        return match next_token {
+            BracketOpen | Colon | Comma | BraceClose | Semicolon | EndOfFile => false,
+            Operator(OperatorKind::Assign) | Operator(OperatorKind::Conditional) | Operator(OperatorKind::LogicalNot) => false,
+            _ => true
+        };
+    }
+
+    /// Parses a property of an object literal or, when `in_class` is set, a member of a class body.
+    /// Returns `None` for TypeScript members which only exist in the type system.
+    fn parse_property(&mut self, options: &mut ParserOptions, in_class: bool) -> Result<Option<PropertyNode>, ParserError> {
+        use self::Token::*;
+
+        let mut trivia = PropertyTrivia::new();
+        let (_, prefix) = self.tokenizer.peek_ignore_padding(options.chunk);
+        let start = self.tokenizer.token_start();
+        trivia.prefix = prefix;
+
+        let typescript_member = self.typescript && in_class;
+        let mut modifiers = Vec::new();
+        if typescript_member {
+            modifiers = self.skip_member_modifiers(options);
+        }
+
+        let is_static = in_class && self.is_property_modifier(options, "static");
+        if is_static {
+            self.tokenizer.pop_ignore_padding(options.chunk);
+            trivia.static_gap = self.tokenizer.peek_ignore_padding(options.chunk).1;
+        }
+
+        if typescript_member {
+            modifiers.extend(self.skip_member_modifiers(options));
+            if self.skip_index_signature(options)? {
+                return Ok(None);
+            }
+        }
+        let type_only = modifiers.contains(&"declare") || modifiers.contains(&"abstract");
+
+        let is_async = self.is_property_modifier(options, "async");
+        if is_async {
+            self.tokenizer.pop_ignore_padding(options.chunk);
+            trivia.async_gap = self.tokenizer.peek_ignore_padding(options.chunk).1;
+        }
+
+        let is_generator = self.tokenizer.peek_ignore_padding(options.chunk).0 == Operator(OperatorKind::Multiplication);
+        if is_generator {
+            self.tokenizer.pop_ignore_padding(options.chunk);
+            trivia.generator_gap = self.tokenizer.peek_ignore_padding(options.chunk).1;
+        }
+
+        let mut kind = match in_class {
+            true => PropertyKind::Field,
+            false => PropertyKind::Init
+        };
+        if !is_async && !is_generator {
+            if self.is_property_modifier(options, "get") {
+                kind = PropertyKind::Get;
+            } else if self.is_property_modifier(options, "set") {
+                kind = PropertyKind::Set;
+            }
+            if kind == PropertyKind::Get || kind == PropertyKind::Set {
+                self.tokenizer.pop_ignore_padding(options.chunk);
+                trivia.accessor_gap = self.tokenizer.peek_ignore_padding(options.chunk).1;
+            }
+        }
+
+        let (token, _) = self.tokenizer.peek_ignore_padding(options.chunk);
+        let key_start = self.tokenizer.token_start();
+        let key = match token {
+            Identifier(name) => PropertyKey::Identifier(name),
+            Keyword(keyword) => PropertyKey::Identifier(keyword.to_string().into()),
+            Operator(operator) if operator.is_word() => PropertyKey::Identifier(operator.to_string().into()),
+            Literal(literal) => PropertyKey::Literal(literal),
+            SquareBracketOpen => {
+                self.tokenizer.pop_ignore_padding(options.chunk);
+                let expression = self.parse_assignment(options)?;
+
+                let (close_token, close_prefix) = self.tokenizer.peek_ignore_padding(options.chunk);
+                if close_token != SquareBracketClose {
+                    return Err(self.syntax_error(format!("Expected ']' but found '{}'.", close_token.to_string())));
+                }
+                trivia.computed_suffix = close_prefix;
+                PropertyKey::Computed(Box::new(expression))
+            }
+            token => return Err(self.syntax_error(format!("Unexpected token '{}' in property name.", token.to_string())))
+        };
+        self.tokenizer.pop_ignore_padding(options.chunk);
+        let key_location = SourceLocation::new(key_start, self.tokenizer.token_end());
+
+        if typescript_member {
+            let (token, _) = self.tokenizer.peek_ignore_padding(options.chunk);
+            if token == Operator(OperatorKind::Conditional) || token == Operator(OperatorKind::LogicalNot) {
+                self.tokenizer.pop_ignore_padding(options.chunk);
+            }
+            if self.skip_method_signature(options)? {
+                return Ok(None);
+            }
+            self.skip_type_annotation(options)?;
+        }
+        if self.typescript {
+            self.skip_type_parameters(options)?;
+        }
+
+        let (token, value_prefix) = self.tokenizer.peek_ignore_padding(options.chunk);
+        let has_modifier = is_async || is_generator || kind == PropertyKind::Get || kind == PropertyKind::Set;
+        let value = match token {
+            BracketOpen => {
+                let value_start = self.tokenizer.token_start();
+                let mut function_trivia = FunctionTrivia::default();
+                let (parameters, body) = self.parse_function_tail(options, "", SourceLocation::new(start, start), is_async, is_generator, &mut function_trivia)?;
+
+                let location = SourceLocation::new(start, options.chunk.index);
+                match kind {
+                    PropertyKind::Get if parameters.len() != 0 => {
+                        return Err(self.early_error(String::from("Getter must not have any formal parameters."), location));
                     }
-                    Semicolon => {
-                        terminator_option = Some(StatementTerminator::Semicolon);
-                        self.tokenizer.pop_token(options.chunk);
-                        break;
+                    PropertyKind::Set if parameters.len() != 1 => {
+                        return Err(self.early_error(String::from("Setter must have exactly one formal parameter."), location));
                     }
-                    BraceClose => {
-                        terminator_option = Some(StatementTerminator::Block);
-                        self.tokenizer.pop_token(options.chunk);
-                        break;
+                    PropertyKind::Get | PropertyKind::Set => {}
+                    _ => kind = PropertyKind::Method
+                }
+
+                let function = Expression::Function { name: Symbol::new(""), is_async, is_generator, parameters, body, trivia: function_trivia };
+                let location = SourceLocation::new(value_start, options.chunk.index);
+                Some(self.finish_expression(function, NodeTrivia::new(), location))
+            }
+            _ if has_modifier => return Err(self.syntax_error(format!("Expected '(' after method name but found '{}'.", token.to_string()))),
+            Colon if !in_class => {
+                self.tokenizer.pop_ignore_padding(options.chunk);
+                trivia.value_prefix = value_prefix;
+                Some(self.parse_assignment(options)?)
+            }
+            Operator(OperatorKind::Assign) if in_class => {
+                self.tokenizer.pop_ignore_padding(options.chunk);
+                trivia.value_prefix = value_prefix;
+                Some(self.parse_assignment(options)?)
+            }
+            _ if in_class => None,
+            _ => match key {
+                PropertyKey::Identifier(ref name) => {
+                    self.check_identifier(name, SourceLocation::new(start, options.chunk.index))?;
+                    kind = PropertyKind::Shorthand;
+                    None
+                }
+                _ => return Err(self.syntax_error(format!("Expected ':' after property name but found '{}'.", token.to_string())))
+            }
+        };
+        let location = SourceLocation::new(start, options.chunk.index);
+
+        // Object properties end at the next separator, class members only at a semicolon.
+        let (token, suffix) = self.tokenizer.peek_ignore_padding(options.chunk);
+        if !in_class {
+            trivia.suffix = suffix;
+        } else if token == Semicolon {
+            self.tokenizer.pop_ignore_padding(options.chunk);
+            trivia.suffix = suffix + ";";
+        }
+
+        if type_only {
+            return Ok(None);
+        }
+        return Ok(Some(PropertyNode { key, value, kind, is_static, location, key_location, trivia }));
+    }
+
+    fn parse_declaration(&mut self, options: &mut ParserOptions, kind: &DeclarationKind) -> Result<(Statement, Option<StatementTerminator>), ParserError> {
+        use self::Token::*;
+
+        let mut declarations = Vec::new();
+
+        loop {
+            let (identifier_token, name_prefix) = self.tokenizer.peek_ignore_padding(options.chunk);
+
+            let name = match identifier_token {
+                Identifier(name) => name,
+                _ => return Err(self.syntax_error(format!("Declaration is not valid.")))
+            };
+            self.tokenizer.pop_ignore_padding(options.chunk);
+
+            let location = SourceLocation::new(self.tokenizer.token_start(), self.tokenizer.token_end());
+            self.check_binding(&name, location.clone())?;
+            let declared = match *kind {
+                DeclarationKind::Var => self.context.declare_var(&name, location.clone()),
+                _ => self.context.declare_lexical(&name, location.clone())
+            };
+            if let Err(first) = declared {
+                return Err(self.redeclaration_error(&name, location, first));
+            }
+
+            if self.typescript {
+                if self.tokenizer.peek_ignore_padding(options.chunk).0 == Operator(OperatorKind::LogicalNot) {
+                    self.tokenizer.pop_ignore_padding(options.chunk);
+                }
+                self.skip_type_annotation(options)?;
+            }
+
+            let mut trivia = DeclarationTrivia::new();
+            trivia.prefix = name_prefix;
+            let mut expression = None;
+
+            let (next_token, next_prefix) = self.tokenizer.peek_ignore_padding(options.chunk);
+            match next_token {
+                Operator(OperatorKind::Assign) => {
+                    self.tokenizer.pop_ignore_padding(options.chunk);
+                    trivia.assign_prefix = next_prefix;
+                    expression = Some(self.parse_expression(options)?);
+                }
+                // The binding of a for-in/of head has no initializer.
+                Operator(OperatorKind::In) => {}
+                Identifier(ref name) if name == "of" => {}
+                _ if *kind == DeclarationKind::Const => {
+                    return Err(self.syntax_error(format!("Missing initializer in const declaration '{}'.", name)));
+                }
+                _ => {}
+            }
+
+            let end = match expression {
+                Some(ref expression) => expression.location.end,
+                None => location.end
+            };
+            let (separator, separator_prefix) = self.tokenizer.peek_ignore_padding(options.chunk);
+            let is_last = separator != Comma;
+            if !is_last {
+                self.tokenizer.pop_ignore_padding(options.chunk);
+                trivia.suffix = separator_prefix;
+            }
+
+            let mut declaration = DeclarationNode::new(name, expression, trivia);
+            declaration.location = SourceLocation::new(location.start, end);
+            declarations.push(declaration);
+            if is_last {
+                break;
+            }
+        }
+
+        return Ok((Statement::Declaration { kind: kind.clone(), declarations }, None));
+    }
+
+    fn parse_return(&mut self, options: &mut ParserOptions) -> Result<(Statement, Option<StatementTerminator>), ParserError> {
+        use self::Token::*;
+
+        // `return` followed by a line break returns nothing, the next line is a new statement.
+        let (token, _) = self.tokenizer.peek_ignore_whitespace(options.chunk);
+        let expression = match token {
+            Newline | Semicolon | BraceClose | EndOfFile => None,
+            _ => Some(self.parse_expression(options)?)
+        };
+
+        return Ok((Statement::Return { expression }, None));
+    }
+
+    fn check_module_item(&self, keyword: &str) -> Result<(), ParserError> {
+        let location = SourceLocation::new(self.tokenizer.token_start(), self.tokenizer.token_end());
+        if !self.context.is_module() {
+            return Err(self.early_error(format!("Cannot use '{}' outside a module.", keyword), location));
+        }
+        if !self.context.is_top_level() {
+            return Err(self.early_error(format!("'{}' may only appear at the top level of a module.", keyword), location));
+        }
+        return Ok(());
+    }
+
+    fn parse_import(&mut self, options: &mut ParserOptions) -> Result<(Statement, Option<StatementTerminator>), ParserError> {
+        if self.typescript && self.is_type_modifier(options) {
+            self.skip_type_module_item(options)?;
+            return Ok(JsParser::stripped());
+        }
+
+        let mut trivia = ImportTrivia::new();
+
+        let (declaration_token, declaration_prefix) = self.tokenizer.peek_ignore_padding(options.chunk);
+        trivia.declaration_prefix = declaration_prefix;
+        self.tokenizer.pop_ignore_padding(options.chunk);
+
+        let declaration = match declaration_token {
+            Token::Operator(OperatorKind::Multiplication) => ImportDeclaration::All,
+            Token::Identifier(name) => ImportDeclaration::Single(name),
+            Token::BraceOpen => {
+                let (specifiers, specifiers_suffix) = self.parse_specifiers(options, "import")?;
+                trivia.specifiers_suffix = specifiers_suffix;
+                ImportDeclaration::Multiple(specifiers)
+            }
+            _ => return Err(self.syntax_error(format!("Invalid import.")))
+        };
+
+        let mut alias = None;
+        if declaration == ImportDeclaration::All {
+            let (as_token, as_prefix) = self.tokenizer.peek_ignore_padding(options.chunk);
+            if !JsParser::is_contextual(&as_token, "as") {
+                return Err(self.syntax_error(format!("Expected 'as' after '*' in import.")));
+            }
+            self.tokenizer.pop_ignore_padding(options.chunk);
+            trivia.as_prefix = as_prefix;
+
+            let (identifier_token, identifier_prefix) = self.tokenizer.peek_ignore_padding(options.chunk);
+            match identifier_token {
+                Token::Identifier(name) => {
+                    self.tokenizer.pop_ignore_padding(options.chunk);
+                    trivia.alias_prefix = identifier_prefix;
+                    alias = Some(name);
+                }
+                _ => return Err(self.syntax_error(format!("Invalid import.")))
+            }
+        }
+
+        let (from_token, from_prefix) = self.tokenizer.peek_ignore_padding(options.chunk);
+        if !JsParser::is_contextual(&from_token, "from") {
+            return Err(self.syntax_error(format!("Expected 'from' in import but found '{}'.", from_token.to_string())));
+        }
+        self.tokenizer.pop_ignore_padding(options.chunk);
+        trivia.from_prefix = from_prefix;
+
+        let (source_token, source_prefix) = self.tokenizer.peek_ignore_padding(options.chunk);
+        self.tokenizer.pop_ignore_padding(options.chunk);
+        let source = match source_token {
+            Token::Literal(Literal::String(name, quote)) => {
+                trivia.source_prefix = source_prefix;
+                trivia.quote_kind = quote;
+                name
+            }
+            _ => return Err(self.syntax_error(format!("Invalid import.")))
+        };
+
+        //TODO: Cleanup, should this be here?
+        self.requires.push(source.clone());
+
+        return Ok((Statement::Import { source, alias, declaration, trivia }, None));
+    }
+
+    /// Parses `name as alias` pairs up to and including the closing brace, returning them with the
+    /// padding before the brace.
+    fn parse_specifiers(&mut self, options: &mut ParserOptions, statement: &str) -> Result<(Vec<ModuleSpecifier>, String), ParserError> {
+        use self::Token::*;
+
+        let mut specifiers = Vec::new();
+        loop {
+            let (name_token, name_prefix) = self.tokenizer.peek_ignore_padding(options.chunk);
+            let name = match name_token {
+                BraceClose => {
+                    self.tokenizer.pop_ignore_padding(options.chunk);
+                    return Ok((specifiers, name_prefix));
+                }
+                Identifier(_) if self.typescript && self.is_type_modifier(options) => {
+                    self.skip_type_specifier(options, statement)?;
+                    continue;
+                }
+                Identifier(name) => name,
+                Keyword(self::Keyword::Default) => self.tokenizer.intern("default"),
+                _ => return Err(self.syntax_error(format!("Invalid {}.", statement)))
+            };
+            self.tokenizer.pop_ignore_padding(options.chunk);
+            let start = self.tokenizer.token_start();
+            let mut end = self.tokenizer.token_end();
+
+            let mut specifier_trivia = ModuleSpecifierTrivia::new();
+            specifier_trivia.prefix = name_prefix;
+            let mut alias = None;
+
+            let (next_token, next_prefix) = self.tokenizer.peek_ignore_padding(options.chunk);
+            if JsParser::is_contextual(&next_token, "as") {
+                self.tokenizer.pop_ignore_padding(options.chunk);
+                specifier_trivia.as_prefix = next_prefix;
+
+                let (alias_token, alias_prefix) = self.tokenizer.peek_ignore_padding(options.chunk);
+                alias = match alias_token {
+                    Identifier(name) => Some(name),
+                    Keyword(self::Keyword::Default) => Some(self.tokenizer.intern("default")),
+                    _ => return Err(self.syntax_error(format!("Invalid {}.", statement)))
+                };
+                self.tokenizer.pop_ignore_padding(options.chunk);
+                specifier_trivia.alias_prefix = alias_prefix;
+                end = self.tokenizer.token_end();
+            }
+
+            let location = SourceLocation::new(start, end);
+            let (separator, suffix) = self.tokenizer.peek_ignore_padding(options.chunk);
+            specifier_trivia.suffix = suffix;
+            specifiers.push(ModuleSpecifier { name, alias, location, trivia: specifier_trivia });
+
+            self.tokenizer.pop_ignore_padding(options.chunk);
+            match separator {
+                Comma => continue,
+                BraceClose => return Ok((specifiers, String::new())),
+                _ => return Err(self.syntax_error(format!("Invalid {}.", statement)))
+            }
+        }
+    }
+
+    fn parse_export(&mut self, options: &mut ParserOptions) -> Result<(Statement, Option<StatementTerminator>), ParserError> {
+        use self::Token::*;
+
+        let mut trivia = ExportTrivia::new();
+
+        if self.typescript && self.is_type_modifier(options) {
+            self.skip_type_module_item(options)?;
+            return Ok(JsParser::stripped());
+        }
+
+        let (token, prefix) = self.tokenizer.peek_ignore_padding(options.chunk);
+        return match token {
+            Keyword(self::Keyword::Default) => {
+                self.tokenizer.pop_ignore_padding(options.chunk);
+                trivia.declaration_prefix = prefix;
+
+                let (next_token, function_prefix) = self.tokenizer.peek_ignore_padding(options.chunk);
+                if next_token != Keyword(self::Keyword::Function) {
+                    let expression = self.parse_expression(options)?;
+                    return Ok((Statement::Export { declaration: ExportDeclaration::Default(expression), trivia }, None));
+                }
+
+                let start = self.tokenizer.token_start();
+                self.tokenizer.pop_ignore_padding(options.chunk);
+                let function = self.parse_function(options, false, None)?;
+                if let Expression::Function { ref name, .. } = function {
+                    let location = SourceLocation::new(start, options.chunk.index);
+                    if !name.is_empty() {
+                        if let Err(first) = self.context.declare_function(name, location.clone()) {
+                            return Err(self.redeclaration_error(name, location, first));
+                        }
+                    }
+                }
+
+                let mut function_trivia = NodeTrivia::new();
+                function_trivia.prefix = function_prefix;
+                let location = SourceLocation::new(start, options.chunk.index);
+                let expression = self.finish_expression(function, function_trivia, location);
+                Ok((Statement::Export { declaration: ExportDeclaration::Default(expression), trivia }, Some(StatementTerminator::Block)))
+            }
+            BraceOpen => {
+                self.tokenizer.pop_ignore_padding(options.chunk);
+                trivia.declaration_prefix = prefix;
+
+                let (specifiers, specifiers_suffix) = self.parse_specifiers(options, "export")?;
+                trivia.specifiers_suffix = specifiers_suffix;
+                Ok((Statement::Export { declaration: ExportDeclaration::Named(specifiers), trivia }, None))
+            }
+            Keyword(self::Keyword::Declaration(_)) | Keyword(self::Keyword::Function) | Keyword(self::Keyword::Class) | Identifier(_) => {
+                let statement = self.parse_statement(options)?;
+                if JsParser::is_stripped(&statement) {
+                    return Ok(JsParser::stripped());
+                }
+                Ok((Statement::Export { declaration: ExportDeclaration::Statement(Box::new(statement)), trivia }, Some(StatementTerminator::Implicit)))
+            }
+            _ => Err(self.syntax_error(format!("Invalid export.")))
+        };
+    }
+
+    fn parse_with(&mut self, options: &mut ParserOptions) -> Result<(Statement, Option<StatementTerminator>), ParserError> {
+        let mut trivia = WithTrivia::new();
+
+        let (token, object_gap) = self.tokenizer.peek_ignore_padding(options.chunk);
+        if token != Token::BracketOpen {
+            return Err(self.syntax_error(format!("Expected '(' after 'with'.")));
+        }
+        self.tokenizer.pop_ignore_padding(options.chunk);
+        trivia.object_gap = object_gap;
+
+        let object = self.parse_expression(options)?;
+
+        let (token, object_suffix) = self.tokenizer.peek_ignore_padding(options.chunk);
+        if token != Token::BracketClose {
+            return Err(self.syntax_error(format!("Expected ')' but found '{}'.", token.to_string())));
+        }
+        self.tokenizer.pop_ignore_padding(options.chunk);
+        trivia.object_suffix = object_suffix;
+
+        let body = self.parse_statement(options)?;
+        return Ok((Statement::With { object, body: Box::new(body), trivia }, Some(StatementTerminator::Implicit)));
+    }
+
+    fn parse_block(&mut self, options: &mut ParserOptions) -> Result<(Statement, Option<StatementTerminator>), ParserError> {
+        let start = self.tokenizer.token_start();
+        self.context.enter_block();
+        let (mut body, body_suffix) = self.parse_body(options, Token::BraceClose, false)?;
+        self.context.exit_block();
+        body.location = SourceLocation::new(start, self.tokenizer.token_end());
+
+        return Ok((Statement::Block { body, trivia: BlockTrivia { body_suffix } }, Some(StatementTerminator::Block)));
+    }
+
+    /// Parses a function declaration starting at its `function` keyword, or at `async` for async functions.
+    fn parse_function_declaration(&mut self, options: &mut ParserOptions, is_async: bool) -> Result<(Statement, Option<StatementTerminator>), ParserError> {
+        self.tokenizer.pop_ignore_padding(options.chunk);
+        let start = self.tokenizer.token_start();
+
+        let async_gap = match is_async {
+            true => {
+                let (_, async_gap) = self.tokenizer.peek_ignore_padding(options.chunk);
+                self.tokenizer.pop_ignore_padding(options.chunk);
+                Some(async_gap)
+            }
+            false => None
+        };
+
+        let function = self.parse_function(options, true, async_gap)?;
+        let location = SourceLocation::new(start, options.chunk.index);
+
+        if let Expression::Function { ref name, .. } = function {
+            if let Err(first) = self.context.declare_function(name, location.clone()) {
+                return Err(self.redeclaration_error(name, location, first));
+            }
+        }
+
+        let expression = self.finish_expression(function, NodeTrivia::new(), location);
+        return Ok((Statement::Expression { expression }, Some(StatementTerminator::Block)));
+    }
+
+    fn parse_class_declaration(&mut self, options: &mut ParserOptions) -> Result<(Statement, Option<StatementTerminator>), ParserError> {
+        self.tokenizer.pop_ignore_padding(options.chunk);
+        let start = self.tokenizer.token_start();
+
+        let class = self.parse_class(options, true)?;
+        let location = SourceLocation::new(start, options.chunk.index);
+
+        if let Expression::Class { ref name, .. } = class {
+            if let Err(first) = self.context.declare_lexical(name, location.clone()) {
+                return Err(self.redeclaration_error(name, location, first));
+            }
+        }
+
+        let expression = self.finish_expression(class, NodeTrivia::new(), location);
+        return Ok((Statement::Expression { expression }, Some(StatementTerminator::Block)));
+    }
+
+    fn parse_for(&mut self, options: &mut ParserOptions) -> Result<(Statement, Option<StatementTerminator>), ParserError> {
+        use self::Token::*;
+
+        let mut trivia = ForTrivia::new();
+
+        let (token, await_prefix) = self.tokenizer.peek_ignore_padding(options.chunk);
+        let is_await = JsParser::is_contextual(&token, "await");
+        if is_await {
+            if !self.context.is_async() {
+                return Err(self.syntax_error(String::from("'for await' is only valid in async functions and modules.")));
+            }
+            self.tokenizer.pop_ignore_padding(options.chunk);
+            trivia.await_prefix = await_prefix;
+        }
+
+        let (token, head_gap) = self.tokenizer.peek_ignore_padding(options.chunk);
+        if token != BracketOpen {
+            return Err(self.syntax_error(format!("Expected '(' after 'for' but found '{}'.", token.to_string())));
+        }
+        self.tokenizer.pop_ignore_padding(options.chunk);
+        trivia.head_gap = head_gap;
+
+        self.context.enter_block();
+
+        let (token, init_prefix) = self.tokenizer.peek_ignore_padding(options.chunk);
+        let init_start = self.tokenizer.token_start();
+        let declaration_kind = match token {
+            Keyword(self::Keyword::Declaration(ref kind)) => Some(kind.clone()),
+            Identifier(ref name) if name == "let" && self.is_let_declaration(options) => Some(DeclarationKind::Let),
+            _ => None
+        };
+        let init = match declaration_kind {
+            Some(kind) => {
+                self.tokenizer.pop_ignore_padding(options.chunk);
+                Some(self.parse_declaration(options, &kind)?.0)
+            }
+            None if token == Semicolon => None,
+            None => {
+                // The left side of a for-in/of loop is a member expression, anything else is read
+                // again as a full expression since `in` would be taken as an operator.
+                let index = options.chunk.index;
+                let mut expression = self.parse_member(options, true)?;
+                let (next_token, _) = self.tokenizer.peek_ignore_padding(options.chunk);
+                if next_token != Operator(OperatorKind::In) && !JsParser::is_contextual(&next_token, "of") {
+                    options.chunk.index = index;
+                    expression = self.parse_expression(options)?;
+                }
+                expression.trivia.prefix = String::new();
+                Some(Statement::Expression { expression })
+            }
+        };
+        let init = init.map(|statement| {
+            let mut init_trivia = NodeTrivia::new();
+            init_trivia.prefix = init_prefix;
+            let mut node = StatementNode::new(statement, init_trivia, StatementTerminator::Implicit);
+            node.location = SourceLocation::new(init_start, options.chunk.index);
+            Box::new(node)
+        });
+
+        let (token, operator_prefix) = self.tokenizer.peek_ignore_padding(options.chunk);
+        let is_of = JsParser::is_contextual(&token, "of");
+        if is_await && !is_of {
+            return Err(self.syntax_error(String::from("'for await' requires an 'of' loop.")));
+        }
+
+        let statement = match init {
+            Some(left) if is_of || token == Operator(OperatorKind::In) => {
+                match left.statement {
+                    Statement::Declaration { ref declarations, .. } => {
+                        if declarations.len() != 1 || declarations[0].expression.is_some() {
+                            return Err(self.early_error(String::from("Invalid left-hand side in for loop head."), left.location.clone()));
+                        }
                     }
-                    _ => return Err(ParserError::new(ParserErrorKind::Syntax, format!("Statement '{:?}' is terminated with token '{:?}'", statement_option, token), SourceLocation::default())),
+                    Statement::Expression { ref expression } => self.check_assignment_target(expression)?,
+                    _ => {}
+                }
+                self.tokenizer.pop_ignore_padding(options.chunk);
+                trivia.operator_prefix = operator_prefix;
+
+                let right = match is_of {
+                    true => self.parse_assignment(options)?,
+                    false => self.parse_expression(options)?
+                };
+                trivia.head_suffix = self.expect_for_head_end(options)?;
+                let body = Box::new(self.parse_statement(options)?);
+
+                match is_of {
+                    true => Statement::ForOf { left, right, body, is_await, trivia },
+                    false => Statement::ForIn { left, right, body, trivia }
                 }
             }
+            init => {
+                if token != Semicolon {
+                    return Err(self.syntax_error(format!("Expected ';' in for loop head but found '{}'.", token.to_string())));
+                }
+                self.tokenizer.pop_ignore_padding(options.chunk);
+                trivia.init_suffix = operator_prefix;
+
+                let (token, test_prefix) = self.tokenizer.peek_ignore_padding(options.chunk);
+                let test = match token {
+                    Semicolon => None,
+                    _ => Some(self.parse_expression(options)?)
+                };
+                let (token, test_suffix) = self.tokenizer.peek_ignore_padding(options.chunk);
+                if token != Semicolon {
+                    return Err(self.syntax_error(format!("Expected ';' in for loop head but found '{}'.", token.to_string())));
+                }
+                self.tokenizer.pop_ignore_padding(options.chunk);
+                trivia.test_suffix = match test {
+                    Some(_) => test_suffix,
+                    None => test_prefix
+                };
+
+                let (token, _) = self.tokenizer.peek_ignore_padding(options.chunk);
+                let update = match token {
+                    BracketClose => None,
+                    _ => Some(self.parse_expression(options)?)
+                };
+                trivia.head_suffix = self.expect_for_head_end(options)?;
+                let body = Box::new(self.parse_statement(options)?);
+
+                Statement::For { init, test, update, body, trivia }
+            }
+        };
+
+        self.context.exit_block();
+        return Ok((statement, Some(StatementTerminator::Implicit)));
+    }
+
+    /// Consumes the `)` closing a for loop head, returning the padding before it.
+    fn expect_for_head_end(&mut self, options: &mut ParserOptions) -> Result<String, ParserError> {
+        let (token, suffix) = self.tokenizer.peek_ignore_padding(options.chunk);
+        if token != Token::BracketClose {
+            return Err(self.syntax_error(format!("Expected ')' but found '{}'.", token.to_string())));
         }
+        self.tokenizer.pop_ignore_padding(options.chunk);
+        return Ok(suffix);
+    }
 
-        let mut node = StatementNode::new(statement_option.unwrap(), trivia, terminator_option.unwrap());
-        self.apply_plugin(options, PluginPass::StatementNodeEmit(&mut node));
+    /// Parses statements introduced by a keyword. Keywords that begin an expression return `None`.
+    fn parse_keyword(&mut self, options: &mut ParserOptions, keyword: Keyword) -> Result<Option<(Statement, Option<StatementTerminator>)>, ParserError> {
+        use self::Keyword::*;
+
+        let result = match keyword {
+            Return => {
+                self.tokenizer.pop_ignore_padding(options.chunk);
+                self.parse_return(options)?
+            }
+            Declaration(kind) => {
+                self.tokenizer.pop_ignore_padding(options.chunk);
+                self.parse_declaration(options, &kind)?
+            }
+            Import => {
+                self.check_module_item("import")?;
+                self.tokenizer.pop_ignore_padding(options.chunk);
+                self.parse_import(options)?
+            }
+            Export => {
+                self.check_module_item("export")?;
+                self.tokenizer.pop_ignore_padding(options.chunk);
+                self.parse_export(options)?
+            }
+            With => {
+                if self.context.is_strict() {
+                    let location = SourceLocation::new(self.tokenizer.token_start(), self.tokenizer.token_end());
+                    return Err(self.early_error(String::from("'with' statements are not allowed in strict mode code."), location));
+                }
+                self.tokenizer.pop_ignore_padding(options.chunk);
+                self.parse_with(options)?
+            }
+            Function => self.parse_function_declaration(options, false)?,
+            Class => self.parse_class_declaration(options)?,
+            For => {
+                self.tokenizer.pop_ignore_padding(options.chunk);
+                self.parse_for(options)?
+            }
+            _ => return Ok(None)
+        };
+        return Ok(Some(result));
+    }
+
+
+    fn parse_statement(&mut self, options: &mut ParserOptions) -> Result<StatementNode, ParserError> {
+        use self::Token::*;
+
+        let mut trivia = NodeTrivia::new();
+
+        let (token, prefix) = self.tokenizer.peek_ignore_padding(options.chunk);
+        let start = self.tokenizer.token_start();
+        trivia.prefix = prefix;
+
+        let typescript_statement = match self.typescript {
+            true => self.parse_ts_statement(options, &token)?,
+            false => None
+        };
+
+        let keyword_statement = match token {
+            _ if typescript_statement.is_some() => typescript_statement,
+            Keyword(keyword) => self.parse_keyword(options, keyword)?,
+            Identifier(ref name) if name == "let" && self.is_let_declaration(options) => {
+                self.tokenizer.pop_ignore_padding(options.chunk);
+                Some(self.parse_declaration(options, &DeclarationKind::Let)?)
+            }
+            Identifier(ref name) if name == "async" && self.is_async_function(options) => Some(self.parse_function_declaration(options, true)?),
+            BraceOpen => {
+                self.tokenizer.pop_ignore_padding(options.chunk);
+                Some(self.parse_block(options)?)
+            }
+            Semicolon => {
+                self.tokenizer.pop_ignore_padding(options.chunk);
+                Some((Statement::Empty, Some(StatementTerminator::Semicolon)))
+            }
+            _ => None
+        };
+
+        let (statement, mut terminator_option) = match keyword_statement {
+            Some(result) => result,
+            None => {
+                let mut expression_node = self.parse_expression(options)?;
+                expression_node.trivia.prefix = String::new();
+                (Statement::Expression { expression: expression_node }, None)
+            }
+        };
+
+        // Padding and comments read while looking for the terminator are not part of the statement.
+        let mut end = options.chunk.index;
+        if terminator_option.is_none() {
+            let terminator = self.parse_terminator(options, &mut trivia)?;
+            if terminator == StatementTerminator::Semicolon {
+                end = self.tokenizer.token_end();
+            }
+            terminator_option = Some(terminator);
+        }
+
+        let mut node = StatementNode::new(statement, trivia, terminator_option.unwrap());
+        node.location = SourceLocation::new(start, end);
         return Ok(node);
     }
+
+    fn parse_terminator(&mut self, options: &mut ParserOptions, trivia: &mut NodeTrivia) -> Result<StatementTerminator, ParserError> {
+        use self::Token::*;
+
+        loop {
+            let token = self.tokenizer.peek_token(options.chunk).map_err(|err| self.tokenizer_error(err))?;
+            match token {
+                Whitespace(ref whitespace) => {
+                    trivia.suffix += whitespace;
+                    self.tokenizer.pop_token(options.chunk).unwrap();
+                }
+                Comment(ref comment) => {
+                    trivia.suffix += comment;
+                    self.tokenizer.pop_token(options.chunk).unwrap();
+                }
+                Newline => {
+                    let line_start = options.chunk.index;
+                    self.tokenizer.pop_token(options.chunk).unwrap();
+                    let line_break = options.chunk.source[line_start..options.chunk.index].to_owned();
+                    let (next_proper_token, _) = self.tokenizer.peek_ignore_whitespace(options.chunk);
+
+                    if next_proper_token == Newline || next_proper_token == Semicolon {
+                        trivia.suffix += &line_break;
+                    } else {
+                        return Ok(StatementTerminator::Newline(line_break));
+                    }
+                }
+                Semicolon => {
+                    self.tokenizer.pop_token(options.chunk).unwrap();
+                    return Ok(StatementTerminator::Semicolon);
+                }
+                BraceClose | EndOfFile => return Ok(StatementTerminator::Implicit),
+                _ => return Err(self.syntax_error(format!("Expected ';' or a line break but found '{}'.", token.to_string()))),
+            }
+        }
+    }
 }
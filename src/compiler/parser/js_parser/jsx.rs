@@ -0,0 +1,239 @@
+//! JSX syntax for `JsParser`. Inside elements the source is not made of JavaScript tokens, so text,
+//! names and attribute strings are read straight from the chunk with the JSX methods of the tokenizer.
+
+use super::JsParser;
+use super::super::{ParserError, ParserOptions};
+
+use super::super::super::{Token, OperatorKind};
+use super::super::super::lexicon;
+use super::super::super::ast::{NodeTrivia, SourceLocation};
+use super::super::super::ast::expression::{ExpressionNode, Expression};
+use super::super::super::ast::jsx::{JsxElement, JsxTrivia, JsxAttribute, JsxAttributeValue, JsxAttributeTrivia, JsxChild, JsxContainer, JsxSpreadTrivia};
+
+impl JsParser {
+    /// Parses an element or fragment starting at its `<`.
+    pub(super) fn parse_jsx_element(&mut self, options: &mut ParserOptions) -> Result<Expression, ParserError> {
+        use self::Token::*;
+
+        self.tokenizer.pop_ignore_padding(options.chunk);
+        let mut trivia = JsxTrivia::new();
+
+        let (token, name_prefix) = self.tokenizer.peek_ignore_padding(options.chunk);
+        trivia.name_prefix = name_prefix;
+        let name = match token {
+            _ if self.is_jsx_name_start(options) => Some(self.pop_jsx_name(options)),
+            _ if self.is_jsx_close(options) => None,
+            token => return Err(self.syntax_error(format!("Expected JSX element name but found '{}'.", token.to_string())))
+        };
+
+        let mut attributes = Vec::new();
+        if name.is_some() {
+            loop {
+                let (token, prefix) = self.tokenizer.peek_ignore_padding(options.chunk);
+                match token {
+                    BraceOpen => {
+                        let (argument, mut spread_trivia) = self.parse_jsx_spread(options)?;
+                        spread_trivia.prefix = prefix;
+                        attributes.push(JsxAttribute::Spread { argument, trivia: spread_trivia });
+                    }
+                    _ if self.is_jsx_name_start(options) => {
+                        let attribute_name = self.pop_jsx_name(options);
+                        let mut attribute_trivia = JsxAttributeTrivia::new();
+                        attribute_trivia.prefix = prefix;
+                        let value = self.parse_jsx_attribute_value(options, &mut attribute_trivia)?;
+                        attributes.push(JsxAttribute::Named { name: attribute_name, value, trivia: attribute_trivia });
+                    }
+                    _ => {
+                        trivia.attributes_suffix = prefix;
+                        break;
+                    }
+                }
+            }
+        }
+
+        if self.tokenizer.peek_ignore_padding(options.chunk).0 == Operator(OperatorKind::Division) && name.is_some() {
+            self.tokenizer.pop_ignore_padding(options.chunk);
+            self.expect_jsx_close(options)?;
+            return Ok(Expression::Jsx(Box::new(JsxElement { name, attributes, children: None, trivia })));
+        }
+        self.expect_jsx_close(options)?;
+
+        let mut children = Vec::new();
+        loop {
+            let text = self.tokenizer.pop_jsx_text(options.chunk);
+            if !text.is_empty() {
+                children.push(JsxChild::Text(text));
+            }
+
+            if options.chunk.is_eof() {
+                return Err(self.syntax_error(format!("Unterminated JSX element '{}'.", name.clone().unwrap_or_default())));
+            }
+
+            let start = options.chunk.index;
+            if options.chunk.peek_char() == '{' {
+                if self.is_jsx_spread(options) {
+                    let (argument, trivia) = self.parse_jsx_spread(options)?;
+                    children.push(JsxChild::Spread { argument, trivia });
+                } else {
+                    children.push(JsxChild::Container(self.parse_jsx_container(options)?));
+                }
+                continue;
+            }
+
+            // `</` closes this element, anything else after `<` is a child element.
+            options.chunk.bump_char();
+            let is_closing = !options.chunk.is_eof() && options.chunk.peek_char() == '/';
+            if !is_closing {
+                options.chunk.index = start;
+                let element = self.parse_jsx_element(options)?;
+                let location = SourceLocation::new(start, options.chunk.index);
+                children.push(JsxChild::Element(self.finish_expression(element, NodeTrivia::new(), location)));
+                continue;
+            }
+
+            options.chunk.bump_char();
+            trivia.closing_name_prefix = self.tokenizer.peek_ignore_padding(options.chunk).1;
+            let closing_name = match self.is_jsx_name_start(options) {
+                true => Some(self.pop_jsx_name(options)),
+                false => None
+            };
+            if closing_name != name {
+                let expected = name.unwrap_or_default();
+                return Err(self.syntax_error(format!("Expected corresponding JSX closing tag for '<{}>'.", expected)));
+            }
+            if closing_name.is_some() {
+                trivia.closing_suffix = self.tokenizer.peek_ignore_padding(options.chunk).1;
+            }
+            self.expect_jsx_close(options)?;
+            break;
+        }
+
+        return Ok(Expression::Jsx(Box::new(JsxElement { name, attributes, children: Some(children), trivia })));
+    }
+
+    /// Whether the next token after padding starts a JSX name. Names may be keywords such as `class`.
+    fn is_jsx_name_start(&mut self, options: &mut ParserOptions) -> bool {
+        self.tokenizer.peek_ignore_padding(options.chunk);
+        let start = self.tokenizer.token_start();
+        if start >= options.chunk.len() {
+            return false;
+        }
+        let char = options.chunk.char_at(start);
+        return char != '-' && char != ':' && char != '.' && !char.is_ascii_digit() && lexicon::is_ident(char);
+    }
+
+    fn pop_jsx_name(&mut self, options: &mut ParserOptions) -> String {
+        self.tokenizer.peek_ignore_padding(options.chunk);
+        options.chunk.index = self.tokenizer.token_start();
+        return self.tokenizer.pop_jsx_name(options.chunk);
+    }
+
+    fn is_jsx_close(&mut self, options: &mut ParserOptions) -> bool {
+        self.tokenizer.peek_ignore_padding(options.chunk);
+        let start = self.tokenizer.token_start();
+        return start < options.chunk.len() && options.chunk.char_at(start) == '>';
+    }
+
+    fn is_jsx_string_start(&self, options: &ParserOptions, start: usize) -> bool {
+        return start < options.chunk.len() && match options.chunk.char_at(start) {
+            '"' | '\'' => true,
+            _ => false
+        };
+    }
+
+    /// Consumes the `>` ending a tag. Only the one character is taken, as the tokenizer would read
+    /// `>=` or `>>` in `<a>=</a>` as a single operator.
+    fn expect_jsx_close(&mut self, options: &mut ParserOptions) -> Result<(), ParserError> {
+        if !self.is_jsx_close(options) {
+            let (token, _) = self.tokenizer.peek_ignore_padding(options.chunk);
+            return Err(self.syntax_error(format!("Expected '>' but found '{}'.", token.to_string())));
+        }
+        options.chunk.index = self.tokenizer.token_start() + 1;
+        return Ok(());
+    }
+
+    fn parse_jsx_attribute_value(&mut self, options: &mut ParserOptions, trivia: &mut JsxAttributeTrivia) -> Result<Option<JsxAttributeValue>, ParserError> {
+        use self::Token::*;
+
+        let (token, assign_prefix) = self.tokenizer.peek_ignore_padding(options.chunk);
+        if token != Operator(OperatorKind::Assign) {
+            return Ok(None);
+        }
+        self.tokenizer.pop_ignore_padding(options.chunk);
+        trivia.assign_prefix = assign_prefix;
+
+        let (token, value_prefix) = self.tokenizer.peek_ignore_padding(options.chunk);
+        trivia.value_prefix = value_prefix;
+        let start = self.tokenizer.token_start();
+        let value = match token {
+            // Read by the JSX rules, under which the tokenizer may fail on it as `\` escapes nothing.
+            _ if self.is_jsx_string_start(options, start) => {
+                options.chunk.index = start;
+                let (value, quote) = match self.tokenizer.pop_jsx_string(options.chunk) {
+                    Ok(string) => string,
+                    Err(err) => return Err(self.tokenizer_error(err))
+                };
+                JsxAttributeValue::String(value, quote)
+            }
+            BraceOpen => JsxAttributeValue::Container(self.parse_jsx_container(options)?),
+            Operator(OperatorKind::Lesser) => {
+                let element = self.parse_jsx_element(options)?;
+                let location = SourceLocation::new(start, options.chunk.index);
+                JsxAttributeValue::Element(self.finish_expression(element, NodeTrivia::new(), location))
+            }
+            token => return Err(self.syntax_error(format!("Invalid JSX attribute value '{}'.", token.to_string())))
+        };
+        return Ok(Some(value));
+    }
+
+    /// Parses `{expression}`, or `{}` which may only hold comments.
+    fn parse_jsx_container(&mut self, options: &mut ParserOptions) -> Result<JsxContainer, ParserError> {
+        self.tokenizer.pop_ignore_padding(options.chunk);
+
+        let (token, prefix) = self.tokenizer.peek_ignore_padding(options.chunk);
+        let expression = match token {
+            Token::BraceClose => None,
+            _ => Some(self.parse_expression(options)?)
+        };
+
+        let (token, suffix) = self.tokenizer.peek_ignore_padding(options.chunk);
+        if token != Token::BraceClose {
+            return Err(self.syntax_error(format!("Expected '}}' but found '{}'.", token.to_string())));
+        }
+        self.tokenizer.pop_ignore_padding(options.chunk);
+
+        let suffix = match expression {
+            Some(_) => suffix,
+            None => prefix
+        };
+        return Ok(JsxContainer { expression, suffix });
+    }
+
+    fn is_jsx_spread(&mut self, options: &mut ParserOptions) -> bool {
+        let (token, _) = self.peek_second(options);
+        return token == Token::Operator(OperatorKind::Spread);
+    }
+
+    /// Parses `{...argument}`, leaving the prefix before the brace to the caller.
+    fn parse_jsx_spread(&mut self, options: &mut ParserOptions) -> Result<(ExpressionNode, JsxSpreadTrivia), ParserError> {
+        let mut trivia = JsxSpreadTrivia::new();
+        self.tokenizer.pop_ignore_padding(options.chunk);
+
+        let (token, spread_prefix) = self.tokenizer.peek_ignore_padding(options.chunk);
+        if token != Token::Operator(OperatorKind::Spread) {
+            return Err(self.syntax_error(format!("Expected '...' but found '{}'.", token.to_string())));
+        }
+        self.tokenizer.pop_ignore_padding(options.chunk);
+        trivia.spread_prefix = spread_prefix;
+
+        let argument = self.parse_assignment(options)?;
+
+        let (token, suffix) = self.tokenizer.peek_ignore_padding(options.chunk);
+        if token != Token::BraceClose {
+            return Err(self.syntax_error(format!("Expected '}}' but found '{}'.", token.to_string())));
+        }
+        self.tokenizer.pop_ignore_padding(options.chunk);
+        trivia.suffix = suffix;
+        return Ok((argument, trivia));
+    }
+}
@@ -0,0 +1,1019 @@
+//! TypeScript syntax for `JsParser`. Types are read and thrown away as they are found, so the syntax
+//! tree only ever holds JavaScript. Enums and namespaces are lowered by generating the JavaScript
+//! `tsc` would emit for them and parsing that in their place.
+
+use super::JsParser;
+use super::super::{ParserError, ParserOptions, ParserContext};
+
+use super::super::super::{Token, Keyword, OperatorKind, Literal, Chunk, Symbol};
+use super::super::super::ast::{Node, NodeTrivia, SourceLocation};
+use super::super::super::ast::body::BodyNode;
+use super::super::super::ast::expression::{ExpressionNode, Expression, MemberTrivia, OperatorTrivia};
+use super::super::super::ast::statement::{StatementNode, Statement, StatementTerminator};
+use super::super::super::ast::property::{PropertyNode, PropertyKind, PropertyKey};
+use super::super::super::ast::visit_mut::{VisitMut, walk_expression};
+
+use std::collections::HashSet;
+
+/// Modifiers of class members and constructor parameters which only matter to the type checker.
+const MEMBER_MODIFIERS: [&str; 8] = ["public", "private", "protected", "readonly", "override", "abstract", "declare", "accessor"];
+
+/// Rewrites references to names which live on an object to reads off that object: earlier members
+/// of an enum, or variables exported from a namespace. Inside a function declaring one of the names
+/// itself, that name is left alone.
+struct MemberReferences<'a> {
+    name: &'a str,
+    members: &'a HashSet<String>,
+}
+
+impl<'a> MemberReferences<'a> {
+    /// The names a function binds itself: its own name, its parameters and its declarations.
+    fn shadowed(&self, node: &ExpressionNode) -> HashSet<String> {
+        let (name, parameters, body) = match node.expression {
+            Expression::Function { ref name, ref parameters, ref body, .. } => (name.as_str(), parameters, body),
+            Expression::ArrowFunction { ref parameters, ref body, .. } => ("", parameters, body),
+            _ => return HashSet::new()
+        };
+        let mut names: HashSet<String> = body.content.iter().flat_map(JsParser::declared_names).collect();
+        names.extend(parameters.iter().map(|parameter| parameter.name.to_string()));
+        names.insert(name.to_owned());
+        return names.into_iter().filter(|name| self.members.contains(name)).collect();
+    }
+}
+
+impl<'a> VisitMut for MemberReferences<'a> {
+    fn visit_expression(&mut self, node: &mut ExpressionNode) {
+        let shadowed = self.shadowed(node);
+        if shadowed.is_empty() {
+            self.enter_expression(node);
+            walk_expression(self, node);
+            self.exit_expression(node);
+            return;
+        }
+        let members = self.members.difference(&shadowed).cloned().collect();
+        walk_expression(&mut MemberReferences { name: self.name, members: &members }, node);
+    }
+
+    fn exit_property(&mut self, property: &mut PropertyNode) {
+        if property.kind != PropertyKind::Shorthand {
+            return;
+        }
+        let member = match property.key {
+            PropertyKey::Identifier(ref key) if self.members.contains(key.as_str()) => key.clone(),
+            _ => return
+        };
+        let mut value = ExpressionNode::new(Expression::Identifier(member), NodeTrivia::new());
+        value.trivia.prefix = String::from(" ");
+        self.exit_expression(&mut value);
+        property.kind = PropertyKind::Init;
+        property.value = Some(value);
+    }
+
+    fn exit_expression(&mut self, node: &mut ExpressionNode) {
+        let member = match node.expression {
+            Expression::Identifier(ref identifier) if self.members.contains(identifier.as_str()) => identifier.clone(),
+            _ => return
+        };
+        let object = ExpressionNode::new(Expression::Identifier(self.name.into()), NodeTrivia::new());
+        let trivia = MemberTrivia { accessor_prefix: String::new(), property_prefix: String::new() };
+        node.expression = Expression::Member { object: Box::new(object), property: member, trivia };
+    }
+}
+
+/// Whether an enum member is initialised to a string, which gets no reverse mapping: a string
+/// literal, an earlier string member of the enum, or strings joined with `+`.
+fn is_string_member(expression: &ExpressionNode, name: &str, string_members: &HashSet<String>) -> bool {
+    return match expression.expression {
+        Expression::Literal(Literal::String(..)) => true,
+        Expression::Bracketed { ref expression, .. } => is_string_member(expression, name, string_members),
+        Expression::Member { ref object, ref property, .. } => match object.expression {
+            Expression::Identifier(ref object) => object == name && string_members.contains(property.as_str()),
+            _ => false
+        },
+        Expression::ComputedMember { ref object, ref property, .. } => match (&object.expression, &property.expression) {
+            (&Expression::Identifier(ref object), &Expression::Literal(Literal::String(ref property, _))) => object == name && string_members.contains(property),
+            _ => false
+        },
+        Expression::Binary { operator: OperatorKind::Addition, ref left, ref right, .. } => {
+            is_string_member(left, name, string_members) || is_string_member(right, name, string_members)
+        }
+        _ => false
+    };
+}
+
+/// Where the value of the next enum member comes from when it has no initializer.
+enum EnumValue {
+    Number(f64),
+    After(String),
+}
+
+impl JsParser {
+    /// Statements which only exist in TypeScript are returned as an empty statement with an implicit
+    /// terminator, which `parse_body` leaves out of the tree.
+    pub(super) fn is_stripped(node: &StatementNode) -> bool {
+        return node.statement == Statement::Empty && node.terminator == StatementTerminator::Implicit;
+    }
+
+    pub(super) fn stripped() -> (Statement, Option<StatementTerminator>) {
+        return (Statement::Empty, Some(StatementTerminator::Implicit));
+    }
+
+    /// Whether the word after the next token is on the same line, as TypeScript declarations like
+    /// `type` and `namespace` require.
+    fn is_followed_on_line(&mut self, options: &mut ParserOptions) -> bool {
+        let (token, gap) = self.peek_second(options);
+        return match token {
+            Token::Identifier(_) | Token::Keyword(_) | Token::Literal(Literal::String(..)) => !gap.contains('\n'),
+            _ => false
+        };
+    }
+
+    /// Parses statements that only exist in TypeScript. Returns `None` when the statement is plain JavaScript.
+    pub(super) fn parse_ts_statement(&mut self, options: &mut ParserOptions, token: &Token) -> Result<Option<(Statement, Option<StatementTerminator>)>, ParserError> {
+        let word = match *token {
+            Token::Identifier(ref word) => word.to_owned(),
+            Token::Keyword(Keyword::Function) => {
+                if self.skip_function_signature(options)? {
+                    return Ok(Some(JsParser::stripped()));
+                }
+                return Ok(None);
+            }
+            Token::Keyword(Keyword::Declaration(_)) => {
+                if JsParser::is_contextual(&self.peek_second(options).0, "enum") {
+                    self.tokenizer.pop_ignore_padding(options.chunk);
+                    return Ok(Some(self.parse_ts_enum(options)?));
+                }
+                return Ok(None);
+            }
+            _ => return Ok(None)
+        };
+
+        if word == "async" && self.is_async_function(options) {
+            if self.skip_function_signature(options)? {
+                return Ok(Some(JsParser::stripped()));
+            }
+            return Ok(None);
+        }
+        if !self.is_followed_on_line(options) {
+            return Ok(None);
+        }
+
+        let result = match word.as_str() {
+            "interface" => {
+                self.skip_to_block(options)?;
+                self.skip_ts_terminator(options);
+                JsParser::stripped()
+            }
+            "type" => {
+                self.skip_type_alias(options)?;
+                JsParser::stripped()
+            }
+            "declare" => {
+                self.skip_declare(options)?;
+                JsParser::stripped()
+            }
+            "enum" => self.parse_ts_enum(options)?,
+            "namespace" | "module" => self.parse_ts_namespace(options)?,
+            "abstract" if self.peek_second(options).0 == Token::Keyword(Keyword::Class) => {
+                self.tokenizer.pop_ignore_padding(options.chunk);
+                self.parse_class_declaration(options)?
+            }
+            _ => return Ok(None)
+        };
+        return Ok(Some(result));
+    }
+
+    /// Consumes the semicolon ending a stripped statement, if there is one on the same line.
+    fn skip_ts_terminator(&mut self, options: &mut ParserOptions) {
+        let (token, _) = self.tokenizer.peek_ignore_whitespace(options.chunk);
+        if token == Token::Semicolon {
+            self.tokenizer.pop_ignore_whitespace(options.chunk);
+        }
+    }
+
+    fn expect_identifier(&mut self, options: &mut ParserOptions) -> Result<String, ParserError> {
+        let (token, _) = self.tokenizer.peek_ignore_padding(options.chunk);
+        return match token {
+            Token::Identifier(name) => {
+                self.tokenizer.pop_ignore_padding(options.chunk);
+                Ok(name.to_string())
+            }
+            token => Err(self.syntax_error(format!("Expected identifier but found '{}'.", token.to_string())))
+        };
+    }
+
+    fn expect_token(&mut self, options: &mut ParserOptions, expected: Token) -> Result<(), ParserError> {
+        let (token, _) = self.tokenizer.peek_ignore_padding(options.chunk);
+        if token != expected {
+            return Err(self.syntax_error(format!("Expected '{}' but found '{}'.", expected.to_string(), token.to_string())));
+        }
+        self.tokenizer.pop_ignore_padding(options.chunk);
+        return Ok(());
+    }
+
+    /// Skips a bracketed group starting at the next token, along with everything nested in it.
+    fn skip_balanced(&mut self, options: &mut ParserOptions) -> Result<(), ParserError> {
+        use self::Token::*;
+
+        let mut depth = 0;
+        loop {
+            let (token, _) = self.tokenizer.peek_ignore_padding(options.chunk);
+            self.tokenizer.pop_ignore_padding(options.chunk);
+            match token {
+                BracketOpen | SquareBracketOpen | BraceOpen => depth += 1,
+                BracketClose | SquareBracketClose | BraceClose => depth -= 1,
+                EndOfFile => return Err(self.syntax_error(String::from("Unexpected end of file."))),
+                _ => {}
+            }
+            if depth <= 0 {
+                return Ok(());
+            }
+        }
+    }
+
+    /// Skips a declaration header up to its body, then the body itself.
+    fn skip_to_block(&mut self, options: &mut ParserOptions) -> Result<(), ParserError> {
+        use self::Token::*;
+
+        loop {
+            let (token, _) = self.tokenizer.peek_ignore_padding(options.chunk);
+            match token {
+                BraceOpen => return self.skip_balanced(options),
+                BracketOpen | SquareBracketOpen => self.skip_balanced(options)?,
+                EndOfFile => return Err(self.syntax_error(String::from("Unexpected end of file, expected '{'."))),
+                _ => {
+                    self.tokenizer.pop_ignore_padding(options.chunk);
+                }
+            }
+        }
+    }
+
+    /// Consumes a `>` closing a type argument or parameter list. The tokenizer reads `>>` and friends
+    /// as one operator, so only their first character is taken.
+    fn skip_closing_angle(&mut self, options: &mut ParserOptions) -> Result<bool, ParserError> {
+        use self::OperatorKind::*;
+
+        let (token, _) = self.tokenizer.peek_ignore_padding(options.chunk);
+        return match token {
+            Token::Operator(Greater) | Token::Operator(GreaterEquals) | Token::Operator(BitShiftRight) | Token::Operator(UBitShiftRight) | Token::Operator(BSRAssign) | Token::Operator(UBSRAssign) => {
+                self.tokenizer.pop_ignore_padding(options.chunk);
+                options.chunk.index = self.tokenizer.token_start() + 1;
+                Ok(true)
+            }
+            _ => Ok(false)
+        };
+    }
+
+    /// Skips `: Type` if it follows.
+    pub(super) fn skip_type_annotation(&mut self, options: &mut ParserOptions) -> Result<(), ParserError> {
+        let (token, _) = self.tokenizer.peek_ignore_padding(options.chunk);
+        if token == Token::Colon {
+            self.tokenizer.pop_ignore_padding(options.chunk);
+            self.skip_type(options)?;
+        }
+        return Ok(());
+    }
+
+    pub(super) fn skip_type(&mut self, options: &mut ParserOptions) -> Result<(), ParserError> {
+        use self::Token::*;
+
+        self.skip_union_type(options)?;
+
+        let (token, _) = self.tokenizer.peek_ignore_padding(options.chunk);
+        if token == Keyword(self::Keyword::Extends) {
+            self.tokenizer.pop_ignore_padding(options.chunk);
+            self.skip_union_type(options)?;
+            self.expect_token(options, Operator(OperatorKind::Conditional))?;
+            self.skip_type(options)?;
+            self.expect_token(options, Colon)?;
+            self.skip_type(options)?;
+        }
+        return Ok(());
+    }
+
+    fn skip_union_type(&mut self, options: &mut ParserOptions) -> Result<(), ParserError> {
+        use self::Token::*;
+
+        let (token, _) = self.tokenizer.peek_ignore_padding(options.chunk);
+        if token == Operator(OperatorKind::BitwiseOr) || token == Operator(OperatorKind::BitwiseAnd) {
+            self.tokenizer.pop_ignore_padding(options.chunk);
+        }
+
+        loop {
+            self.skip_type_operand(options)?;
+            let (token, _) = self.tokenizer.peek_ignore_padding(options.chunk);
+            match token {
+                Operator(OperatorKind::BitwiseOr) | Operator(OperatorKind::BitwiseAnd) => {
+                    self.tokenizer.pop_ignore_padding(options.chunk);
+                }
+                _ => return Ok(())
+            }
+        }
+    }
+
+    fn skip_type_operand(&mut self, options: &mut ParserOptions) -> Result<(), ParserError> {
+        use self::Token::*;
+
+        let (token, _) = self.tokenizer.peek_ignore_padding(options.chunk);
+        match token {
+            Identifier(ref word) if (word == "keyof" || word == "readonly" || word == "unique") && self.is_followed_on_line(options) => {
+                self.tokenizer.pop_ignore_padding(options.chunk);
+                return self.skip_type_operand(options);
+            }
+            Identifier(ref word) if word == "infer" && self.is_followed_on_line(options) => {
+                self.tokenizer.pop_ignore_padding(options.chunk);
+                self.expect_identifier(options)?;
+                return Ok(());
+            }
+            Identifier(ref word) if word == "asserts" && self.is_followed_on_line(options) => {
+                self.tokenizer.pop_ignore_padding(options.chunk);
+                self.tokenizer.pop_ignore_padding(options.chunk);
+                self.skip_type_predicate(options)?;
+                return Ok(());
+            }
+            Identifier(_) => {
+                self.tokenizer.pop_ignore_padding(options.chunk);
+                self.skip_entity_name(options)?;
+                self.skip_type_predicate(options)?;
+            }
+            Operator(OperatorKind::Typeof) => {
+                self.tokenizer.pop_ignore_padding(options.chunk);
+                self.tokenizer.pop_ignore_padding(options.chunk);
+                self.skip_entity_name(options)?;
+            }
+            Keyword(self::Keyword::This) => {
+                self.tokenizer.pop_ignore_padding(options.chunk);
+                self.skip_type_predicate(options)?;
+            }
+            Literal(_) | Operator(OperatorKind::Void) | Keyword(self::Keyword::Declaration(_)) => {
+                self.tokenizer.pop_ignore_padding(options.chunk);
+            }
+            Operator(OperatorKind::Subtraction) => {
+                self.tokenizer.pop_ignore_padding(options.chunk);
+                self.tokenizer.pop_ignore_padding(options.chunk);
+            }
+            Operator(OperatorKind::New) | Operator(OperatorKind::Lesser) => {
+                if token == Operator(OperatorKind::New) {
+                    self.tokenizer.pop_ignore_padding(options.chunk);
+                }
+                self.skip_type_parameters(options)?;
+                self.skip_function_type(options)?;
+            }
+            BracketOpen => self.skip_function_type(options)?,
+            SquareBracketOpen | BraceOpen => self.skip_balanced(options)?,
+            token => return Err(self.syntax_error(format!("Expected type but found '{}'.", token.to_string())))
+        }
+
+        // Array types and indexed access, `T[]` and `T['key']`.
+        loop {
+            let (token, _) = self.tokenizer.peek_ignore_whitespace(options.chunk);
+            if token != SquareBracketOpen {
+                return Ok(());
+            }
+            self.skip_balanced(options)?;
+        }
+    }
+
+    /// Skips the rest of a dotted type name and its type arguments.
+    fn skip_entity_name(&mut self, options: &mut ParserOptions) -> Result<(), ParserError> {
+        loop {
+            let (token, _) = self.tokenizer.peek_ignore_padding(options.chunk);
+            if token != Token::Operator(OperatorKind::Accessor) {
+                break;
+            }
+            self.tokenizer.pop_ignore_padding(options.chunk);
+            self.tokenizer.pop_ignore_padding(options.chunk);
+        }
+
+        let (token, _) = self.tokenizer.peek_ignore_whitespace(options.chunk);
+        if token == Token::Operator(OperatorKind::Lesser) {
+            self.skip_type_arguments(options)?;
+        }
+        return Ok(());
+    }
+
+    /// Skips `is Type` following the subject of a type predicate.
+    fn skip_type_predicate(&mut self, options: &mut ParserOptions) -> Result<(), ParserError> {
+        let (token, gap) = self.tokenizer.peek_ignore_padding(options.chunk);
+        if JsParser::is_contextual(&token, "is") && !gap.contains('\n') {
+            self.tokenizer.pop_ignore_padding(options.chunk);
+            self.skip_type(options)?;
+        }
+        return Ok(());
+    }
+
+    /// Skips a parenthesized type, or the parameters and return type of a function type.
+    fn skip_function_type(&mut self, options: &mut ParserOptions) -> Result<(), ParserError> {
+        self.skip_balanced(options)?;
+        let (token, _) = self.tokenizer.peek_ignore_padding(options.chunk);
+        if token == Token::FatArrow {
+            self.tokenizer.pop_ignore_padding(options.chunk);
+            self.skip_type(options)?;
+        }
+        return Ok(());
+    }
+
+    /// Skips `<A, B>` type arguments.
+    pub(super) fn skip_type_arguments(&mut self, options: &mut ParserOptions) -> Result<(), ParserError> {
+        self.expect_token(options, Token::Operator(OperatorKind::Lesser))?;
+        loop {
+            self.skip_type(options)?;
+            if self.skip_closing_angle(options)? {
+                return Ok(());
+            }
+            self.expect_token(options, Token::Comma)?;
+        }
+    }
+
+    /// Skips type arguments of a call, `f<T>()`. Anything not followed by the call's arguments is a
+    /// comparison, in which case nothing is consumed.
+    pub(super) fn try_skip_type_arguments(&mut self, options: &mut ParserOptions) -> bool {
+        let index = options.chunk.index;
+        if self.skip_type_arguments(options).is_ok() && self.tokenizer.peek_ignore_padding(options.chunk).0 == Token::BracketOpen {
+            return true;
+        }
+        options.chunk.index = index;
+        return false;
+    }
+
+    /// Skips `<T extends U = V>` type parameters if they follow.
+    pub(super) fn skip_type_parameters(&mut self, options: &mut ParserOptions) -> Result<(), ParserError> {
+        use self::Token::*;
+
+        let (token, _) = self.tokenizer.peek_ignore_padding(options.chunk);
+        if token != Operator(OperatorKind::Lesser) {
+            return Ok(());
+        }
+        self.tokenizer.pop_ignore_padding(options.chunk);
+
+        loop {
+            if self.skip_closing_angle(options)? {
+                return Ok(());
+            }
+            let (token, _) = self.tokenizer.peek_ignore_padding(options.chunk);
+            let is_modifier = match token {
+                Keyword(self::Keyword::Declaration(_)) | Operator(OperatorKind::In) => true,
+                Identifier(ref word) => word == "out" && self.is_followed_on_line(options),
+                _ => false
+            };
+            if is_modifier {
+                self.tokenizer.pop_ignore_padding(options.chunk);
+            }
+            self.expect_identifier(options)?;
+
+            let (token, _) = self.tokenizer.peek_ignore_padding(options.chunk);
+            if token == Keyword(self::Keyword::Extends) {
+                self.tokenizer.pop_ignore_padding(options.chunk);
+                self.skip_type(options)?;
+            }
+            let (token, _) = self.tokenizer.peek_ignore_padding(options.chunk);
+            if token == Operator(OperatorKind::Assign) {
+                self.tokenizer.pop_ignore_padding(options.chunk);
+                self.skip_type(options)?;
+            }
+
+            let (token, _) = self.tokenizer.peek_ignore_padding(options.chunk);
+            if token == Comma {
+                self.tokenizer.pop_ignore_padding(options.chunk);
+            } else if !self.skip_closing_angle(options)? {
+                return Err(self.syntax_error(format!("Expected '>' but found '{}'.", token.to_string())));
+            } else {
+                return Ok(());
+            }
+        }
+    }
+
+    /// Skips `implements A, B<C>` in a class heading.
+    pub(super) fn skip_implements(&mut self, options: &mut ParserOptions) -> Result<(), ParserError> {
+        let (token, _) = self.tokenizer.peek_ignore_padding(options.chunk);
+        if !JsParser::is_contextual(&token, "implements") {
+            return Ok(());
+        }
+        self.tokenizer.pop_ignore_padding(options.chunk);
+        loop {
+            self.skip_type(options)?;
+            let (token, _) = self.tokenizer.peek_ignore_padding(options.chunk);
+            if token != Token::Comma {
+                return Ok(());
+            }
+            self.tokenizer.pop_ignore_padding(options.chunk);
+        }
+    }
+
+    fn skip_type_alias(&mut self, options: &mut ParserOptions) -> Result<(), ParserError> {
+        self.tokenizer.pop_ignore_padding(options.chunk);
+        self.expect_identifier(options)?;
+        self.skip_type_parameters(options)?;
+        self.expect_token(options, Token::Operator(OperatorKind::Assign))?;
+        self.skip_type(options)?;
+        self.skip_ts_terminator(options);
+        return Ok(());
+    }
+
+    /// Skips a function signature without a body, as used by overloads, `declare function` and
+    /// abstract methods. Returns false, consuming nothing, if the function has a body.
+    pub(super) fn skip_function_signature(&mut self, options: &mut ParserOptions) -> Result<bool, ParserError> {
+        use self::Token::*;
+
+        let index = options.chunk.index;
+        if JsParser::is_contextual(&self.tokenizer.peek_ignore_padding(options.chunk).0, "async") {
+            self.tokenizer.pop_ignore_padding(options.chunk);
+        }
+        self.expect_token(options, Keyword(self::Keyword::Function))?;
+        if self.tokenizer.peek_ignore_padding(options.chunk).0 == Operator(OperatorKind::Multiplication) {
+            self.tokenizer.pop_ignore_padding(options.chunk);
+        }
+        if let Identifier(_) = self.tokenizer.peek_ignore_padding(options.chunk).0 {
+            self.tokenizer.pop_ignore_padding(options.chunk);
+        }
+
+        if self.skip_method_signature(options)? {
+            return Ok(true);
+        }
+        options.chunk.index = index;
+        return Ok(false);
+    }
+
+    /// Skips type parameters, parameters and return type of a method without a body. Returns false,
+    /// consuming nothing, if a body follows them.
+    pub(super) fn skip_method_signature(&mut self, options: &mut ParserOptions) -> Result<bool, ParserError> {
+        let index = options.chunk.index;
+        self.skip_type_parameters(options)?;
+        if self.tokenizer.peek_ignore_padding(options.chunk).0 != Token::BracketOpen {
+            options.chunk.index = index;
+            return Ok(false);
+        }
+        self.skip_balanced(options)?;
+        self.skip_type_annotation(options)?;
+
+        if self.tokenizer.peek_ignore_padding(options.chunk).0 == Token::BraceOpen {
+            options.chunk.index = index;
+            return Ok(false);
+        }
+        self.skip_ts_terminator(options);
+        return Ok(true);
+    }
+
+    /// Whether the next token is `type` marking a type-only import, export or specifier, rather
+    /// than a binding named `type`.
+    pub(super) fn is_type_modifier(&mut self, options: &mut ParserOptions) -> bool {
+        use self::Token::*;
+
+        if !JsParser::is_contextual(&self.tokenizer.peek_ignore_padding(options.chunk).0, "type") {
+            return false;
+        }
+        return match self.peek_second(options).0 {
+            BraceOpen | Operator(OperatorKind::Multiplication) | Keyword(self::Keyword::Default) => true,
+            Identifier(ref word) => word != "from" && word != "as",
+            _ => false
+        };
+    }
+
+    /// Skips an `import type` or `export type` statement after its keyword, up to the end of the
+    /// statement or its source.
+    pub(super) fn skip_type_module_item(&mut self, options: &mut ParserOptions) -> Result<(), ParserError> {
+        use self::Token::*;
+
+        self.tokenizer.pop_ignore_padding(options.chunk);
+        loop {
+            let (token, _) = self.tokenizer.peek_ignore_whitespace(options.chunk);
+            match token {
+                BraceOpen => self.skip_balanced(options)?,
+                Literal(self::Literal::String(..)) => {
+                    self.tokenizer.pop_ignore_whitespace(options.chunk);
+                    break;
+                }
+                Newline | Semicolon | EndOfFile => break,
+                _ => {
+                    self.tokenizer.pop_ignore_whitespace(options.chunk);
+                }
+            }
+        }
+        self.skip_ts_terminator(options);
+        return Ok(());
+    }
+
+    /// Skips a `type Name as Alias` specifier along with the comma after it.
+    pub(super) fn skip_type_specifier(&mut self, options: &mut ParserOptions, statement: &str) -> Result<(), ParserError> {
+        use self::Token::*;
+
+        self.tokenizer.pop_ignore_padding(options.chunk);
+        self.tokenizer.pop_ignore_padding(options.chunk);
+        if JsParser::is_contextual(&self.tokenizer.peek_ignore_padding(options.chunk).0, "as") {
+            self.tokenizer.pop_ignore_padding(options.chunk);
+            self.tokenizer.pop_ignore_padding(options.chunk);
+        }
+        return match self.tokenizer.peek_ignore_padding(options.chunk).0 {
+            Comma => {
+                self.tokenizer.pop_ignore_padding(options.chunk);
+                Ok(())
+            }
+            BraceClose => Ok(()),
+            _ => Err(self.syntax_error(format!("Invalid {}.", statement)))
+        };
+    }
+
+    /// Skips an ambient `declare` statement.
+    fn skip_declare(&mut self, options: &mut ParserOptions) -> Result<(), ParserError> {
+        use self::Token::*;
+
+        self.tokenizer.pop_ignore_padding(options.chunk);
+        let (token, _) = self.tokenizer.peek_ignore_padding(options.chunk);
+        let is_variable = match token {
+            Keyword(self::Keyword::Declaration(_)) => !JsParser::is_contextual(&self.peek_second(options).0, "enum"),
+            Identifier(ref word) => word == "let",
+            _ => false
+        };
+
+        if is_variable {
+            self.tokenizer.pop_ignore_padding(options.chunk);
+            loop {
+                self.expect_identifier(options)?;
+                self.skip_type_annotation(options)?;
+                if self.tokenizer.peek_ignore_padding(options.chunk).0 == Operator(OperatorKind::Assign) {
+                    self.tokenizer.pop_ignore_padding(options.chunk);
+                    self.parse_assignment(options)?;
+                }
+                if self.tokenizer.peek_ignore_padding(options.chunk).0 != Comma {
+                    break;
+                }
+                self.tokenizer.pop_ignore_padding(options.chunk);
+            }
+            self.skip_ts_terminator(options);
+            return Ok(());
+        }
+
+        match token {
+            Keyword(self::Keyword::Function) => {
+                self.skip_function_signature(options)?;
+            }
+            Identifier(ref word) if word == "async" => {
+                self.skip_function_signature(options)?;
+            }
+            Identifier(ref word) if word == "type" => self.skip_type_alias(options)?,
+            _ => {
+                self.skip_to_block(options)?;
+                self.skip_ts_terminator(options);
+            }
+        }
+        return Ok(());
+    }
+
+    /// Skips accessibility and other type-only modifiers of a class member or constructor parameter,
+    /// returning the ones found.
+    pub(super) fn skip_member_modifiers(&mut self, options: &mut ParserOptions) -> Vec<&'static str> {
+        let mut found = Vec::new();
+        'modifiers: loop {
+            for modifier in MEMBER_MODIFIERS.iter() {
+                if self.is_property_modifier(options, modifier) {
+                    self.tokenizer.pop_ignore_padding(options.chunk);
+                    found.push(*modifier);
+                    continue 'modifiers;
+                }
+            }
+            return found;
+        }
+    }
+
+    /// Skips an index signature, `[key: string]: Type;`, in a class body.
+    pub(super) fn skip_index_signature(&mut self, options: &mut ParserOptions) -> Result<bool, ParserError> {
+        let index = options.chunk.index;
+        if self.tokenizer.peek_ignore_padding(options.chunk).0 != Token::SquareBracketOpen {
+            return Ok(false);
+        }
+        self.tokenizer.pop_ignore_padding(options.chunk);
+        self.tokenizer.pop_ignore_padding(options.chunk);
+        let is_signature = self.tokenizer.peek_ignore_padding(options.chunk).0 == Token::Colon;
+        options.chunk.index = index;
+        if !is_signature {
+            return Ok(false);
+        }
+
+        self.skip_balanced(options)?;
+        self.skip_type_annotation(options)?;
+        self.skip_ts_terminator(options);
+        return Ok(true);
+    }
+
+    /// Skips the type of a parameter, along with its `?` marker.
+    pub(super) fn skip_parameter_type(&mut self, options: &mut ParserOptions) -> Result<(), ParserError> {
+        if self.tokenizer.peek_ignore_padding(options.chunk).0 == Token::Operator(OperatorKind::Conditional) {
+            self.tokenizer.pop_ignore_padding(options.chunk);
+        }
+        return self.skip_type_annotation(options);
+    }
+
+    /// Skips a `this: Type` parameter and the comma after it, returning whether there was one.
+    pub(super) fn skip_this_parameter(&mut self, options: &mut ParserOptions) -> Result<bool, ParserError> {
+        if self.tokenizer.peek_ignore_padding(options.chunk).0 != Token::Keyword(Keyword::This) {
+            return Ok(false);
+        }
+        self.tokenizer.pop_ignore_padding(options.chunk);
+        self.skip_type_annotation(options)?;
+        if self.tokenizer.peek_ignore_padding(options.chunk).0 == Token::Comma {
+            self.tokenizer.pop_ignore_padding(options.chunk);
+        }
+        return Ok(true);
+    }
+
+    /// Parses JavaScript generated while lowering TypeScript syntax.
+    fn parse_fragment(&self, options: &mut ParserOptions, source: String) -> Result<Vec<StatementNode>, ParserError> {
+        let mut chunk = Chunk::new(options.chunk.name.clone(), options.chunk.location.clone());
+        chunk.set_source(source);
+
+        let mut parser = JsParser::new();
+        parser.context = ParserContext::new(options.source_type);
+        let mut fragment_options = ParserOptions::new(&mut chunk, options.source_type);
+        let (body, _) = parser.parse_body(&mut fragment_options, Token::EndOfFile, false)?;
+        return Ok(body.content);
+    }
+
+    /// Parses a single generated statement, keeping the terminator it was written with.
+    fn parse_lowered(&self, options: &mut ParserOptions, source: String) -> Result<(Statement, Option<StatementTerminator>), ParserError> {
+        let mut statements = self.parse_fragment(options, source)?;
+        let node = statements.remove(0);
+        return Ok((node.statement, Some(node.terminator)));
+    }
+
+    /// Assigns parameter properties, `constructor(private x)`, at the start of the constructor body,
+    /// after the `super()` call if there is one.
+    pub(super) fn assign_parameter_properties(&self, options: &mut ParserOptions, mut body: BodyNode, names: &Vec<String>) -> Result<BodyNode, ParserError> {
+        let mut index = 0;
+        for (i, statement) in body.content.iter().enumerate() {
+            if let Statement::Expression { expression: ExpressionNode { expression: Expression::Call { ref callee, .. }, .. } } = statement.statement {
+                if callee.expression == Expression::Super {
+                    index = i + 1;
+                    break;
+                }
+            }
+        }
+
+        let indent = match body.content.first() {
+            Some(statement) => JsParser::line_indent(&statement.trivia.prefix),
+            None => String::new()
+        };
+
+        let mut source = String::new();
+        for name in names {
+            source += &format!("{}this.{} = {};", indent, name, name);
+        }
+        let assignments = self.parse_fragment(options, source)?;
+        for (i, assignment) in assignments.into_iter().enumerate() {
+            body.content.insert(index + i, assignment);
+        }
+        return Ok(body);
+    }
+
+    /// The line break and indentation at the end of a statement prefix, used to lay out generated code.
+    fn line_indent(prefix: &str) -> String {
+        return match prefix.rfind('\n') {
+            Some(index) => prefix[index..].to_owned(),
+            None => String::from("\n")
+        };
+    }
+
+    /// Lowers `enum E { A, B = 2 }` to a function filling in the enum object, including the reverse
+    /// mapping from values to names for members which are not strings.
+    fn parse_ts_enum(&mut self, options: &mut ParserOptions) -> Result<(Statement, Option<StatementTerminator>), ParserError> {
+        use self::Token::*;
+
+        self.tokenizer.pop_ignore_padding(options.chunk);
+        let name = self.expect_identifier(options)?;
+        // Enums and namespaces merge with earlier declarations of the same name.
+        let location = SourceLocation::new(self.tokenizer.token_start(), self.tokenizer.token_end());
+        let _ = self.context.declare_var(&name, location);
+        self.expect_token(options, BraceOpen)?;
+
+        let mut members = HashSet::new();
+        let mut string_members = HashSet::new();
+        let mut lines = String::new();
+        let mut next = EnumValue::Number(0.0);
+        loop {
+            let (token, _) = self.tokenizer.peek_ignore_padding(options.chunk);
+            let member = match token {
+                BraceClose => {
+                    self.tokenizer.pop_ignore_padding(options.chunk);
+                    break;
+                }
+                Identifier(member) => member.to_string(),
+                Literal(self::Literal::String(member, _)) => member,
+                token => return Err(self.syntax_error(format!("Unexpected token '{}' in enum.", token.to_string())))
+            };
+            self.tokenizer.pop_ignore_padding(options.chunk);
+            let key = format!("\"{}\"", member);
+
+            let mut initializer = None;
+            if self.tokenizer.peek_ignore_padding(options.chunk).0 == Operator(OperatorKind::Assign) {
+                self.tokenizer.pop_ignore_padding(options.chunk);
+                let mut expression = self.parse_assignment(options)?;
+                MemberReferences { name: &name, members: &members }.visit_expression(&mut expression);
+                initializer = Some(expression);
+            }
+
+            let is_string = initializer.as_ref().map_or(false, |expression| is_string_member(expression, &name, &string_members));
+            let value = match initializer {
+                Some(ref expression) if is_string => expression.generate().trim().to_owned(),
+                Some(expression) => {
+                    let value = expression.generate().trim().to_owned();
+                    next = match value.parse::<f64>() {
+                        Ok(number) => EnumValue::Number(number + 1.0),
+                        Err(_) => EnumValue::After(key.clone())
+                    };
+                    value
+                }
+                None => {
+                    let value = match next {
+                        EnumValue::Number(number) => format!("{}", number),
+                        EnumValue::After(ref previous) => format!("{}[{}] + 1", name, previous)
+                    };
+                    next = match next {
+                        EnumValue::Number(number) => EnumValue::Number(number + 1.0),
+                        EnumValue::After(_) => EnumValue::After(key.clone())
+                    };
+                    value
+                }
+            };
+
+            lines += &match is_string {
+                true => format!("\n    {}[{}] = {};", name, key, value),
+                false => format!("\n    {0}[{0}[{1}] = {2}] = {1};", name, key, value)
+            };
+            if is_string {
+                string_members.insert(member.clone());
+            }
+            members.insert(member);
+
+            match self.tokenizer.peek_ignore_padding(options.chunk).0 {
+                Comma => {
+                    self.tokenizer.pop_ignore_padding(options.chunk);
+                }
+                BraceClose => {}
+                token => return Err(self.syntax_error(format!("Expected ',' or '}}' in enum but found '{}'.", token.to_string())))
+            }
+        }
+
+        return self.parse_lowered(options, format!("var {0} = (function ({0}) {{{1}\n    return {0};\n}})({0} || {{}});", name, lines));
+    }
+
+    /// Lowers `namespace N { ... }` to a function adding the exported declarations to the namespace
+    /// object. Namespaces holding only types are removed.
+    fn parse_ts_namespace(&mut self, options: &mut ParserOptions) -> Result<(Statement, Option<StatementTerminator>), ParserError> {
+        self.tokenizer.pop_ignore_padding(options.chunk);
+        return match self.lower_namespace(options)? {
+            Some((_, source)) => self.parse_lowered(options, source),
+            None => Ok(JsParser::stripped())
+        };
+    }
+
+    /// Reads a namespace name and body, returning the name with the generated `var` statement.
+    fn lower_namespace(&mut self, options: &mut ParserOptions) -> Result<Option<(String, String)>, ParserError> {
+        use self::Token::*;
+
+        let name = self.expect_identifier(options)?;
+        // Enums and namespaces merge with earlier declarations of the same name.
+        let location = SourceLocation::new(self.tokenizer.token_start(), self.tokenizer.token_end());
+        let _ = self.context.declare_var(&name, location);
+
+        // `namespace A.B {}` is `namespace A { export namespace B {} }`.
+        let mut statements = Vec::new();
+        let mut body_suffix = String::from("\n");
+        if self.tokenizer.peek_ignore_padding(options.chunk).0 == Operator(OperatorKind::Accessor) {
+            self.tokenizer.pop_ignore_padding(options.chunk);
+            self.context.enter_function(false, false);
+            let inner = self.lower_namespace(options)?;
+            self.context.exit_function();
+            if let Some((inner_name, source)) = inner {
+                statements.push((format!("\n    {}", source), String::from("\n    "), vec![inner_name]));
+            }
+        } else {
+            self.expect_token(options, BraceOpen)?;
+            self.context.enter_function(false, false);
+            let mut parsed = Vec::new();
+            loop {
+                let (token, prefix) = self.tokenizer.peek_ignore_padding(options.chunk);
+                match token {
+                    BraceClose => {
+                        self.tokenizer.pop_ignore_padding(options.chunk);
+                        body_suffix = prefix;
+                        break;
+                    }
+                    EndOfFile => return Err(self.syntax_error(String::from("Unexpected end of file, expected '}'."))),
+                    _ => {}
+                }
+
+                let is_export = token == Keyword(self::Keyword::Export);
+                if is_export {
+                    self.tokenizer.pop_ignore_padding(options.chunk);
+                }
+                let mut statement = self.parse_statement(options)?;
+                if JsParser::is_stripped(&statement) {
+                    continue;
+                }
+                if is_export {
+                    statement.trivia.prefix = prefix;
+                }
+                parsed.push((statement, is_export));
+            }
+            self.context.exit_function();
+
+            // Exported variables live on the namespace object alone, so assignments to them are seen
+            // from outside. Exported functions and classes are copied onto it after their declaration.
+            let variables: HashSet<String> = parsed.iter()
+                .filter(|&&(ref statement, is_export)| is_export && JsParser::is_variable_declaration(statement))
+                .flat_map(|&(ref statement, _)| JsParser::declared_names(statement))
+                .collect();
+            for (mut statement, is_export) in parsed {
+                MemberReferences { name: &name, members: &variables }.visit_statement(&mut statement);
+                let indent = JsParser::line_indent(&statement.trivia.prefix);
+                if is_export && JsParser::is_variable_declaration(&statement) {
+                    for assignment in JsParser::namespace_assignments(&name, statement, &indent) {
+                        statements.push((assignment.generate(), indent.clone(), Vec::new()));
+                    }
+                    continue;
+                }
+                let exported = match is_export {
+                    true => JsParser::declared_names(&statement),
+                    false => Vec::new()
+                };
+                statements.push((statement.generate(), indent, exported));
+            }
+        }
+
+        if statements.len() == 0 {
+            return Ok(None);
+        }
+
+        let mut body = String::new();
+        let mut indent = String::from("\n");
+        for (source, statement_indent, exported) in statements {
+            let ends_line = source.ends_with('\n');
+            body += &source;
+            for export in exported {
+                body += &match ends_line {
+                    true => format!("{}{}.{} = {};\n", &statement_indent[1..], name, export, export),
+                    false => format!("{}{}.{} = {};", statement_indent, name, export, export)
+                };
+            }
+            indent = statement_indent;
+        }
+
+        if body.ends_with('\n') {
+            indent.remove(0);
+        }
+        let source = format!("var {0} = (function ({0}) {{{1}{2}return {0};{3}}})({0} || {{}});", name, body, indent, body_suffix);
+        return Ok(Some((name, source)));
+    }
+
+    fn is_variable_declaration(statement: &StatementNode) -> bool {
+        return match statement.statement {
+            Statement::Declaration { .. } => true,
+            _ => false
+        };
+    }
+
+    /// Turns an exported `var a = 1, b;` in a namespace into `N.a = 1;`, the first assignment taking
+    /// the place of the declaration. Declarators without an initializer are dropped.
+    fn namespace_assignments(name: &str, statement: StatementNode, indent: &str) -> Vec<StatementNode> {
+        let declarations = match statement.statement {
+            Statement::Declaration { declarations, .. } => declarations,
+            _ => return Vec::new()
+        };
+
+        let mut assignments: Vec<StatementNode> = Vec::new();
+        for declaration in declarations {
+            let right = match declaration.expression {
+                Some(expression) => expression,
+                None => continue
+            };
+            let object = ExpressionNode::new(Expression::Identifier(Symbol::from(name)), NodeTrivia::new());
+            let trivia = MemberTrivia { accessor_prefix: String::new(), property_prefix: String::new() };
+            let left = ExpressionNode::new(Expression::Member { object: Box::new(object), property: declaration.name, trivia }, NodeTrivia::new());
+            let trivia = OperatorTrivia { operator_prefix: declaration.trivia.assign_prefix };
+            let assignment = Expression::Assignment { left: Box::new(left), operator: OperatorKind::Assign, right: Box::new(right), trivia };
+
+            let mut trivia = NodeTrivia::new();
+            trivia.prefix = match assignments.is_empty() {
+                true => statement.trivia.prefix.clone(),
+                false => indent.to_owned()
+            };
+            let expression = ExpressionNode::new(assignment, NodeTrivia::new());
+            assignments.push(StatementNode::new(Statement::Expression { expression }, trivia, StatementTerminator::Semicolon));
+        }
+        if let Some(last) = assignments.last_mut() {
+            last.terminator = statement.terminator;
+        }
+        return assignments;
+    }
+
+    /// Names bound by a declaration statement, used to export them from a namespace.
+    fn declared_names(statement: &StatementNode) -> Vec<String> {
+        return match statement.statement {
+            Statement::Declaration { ref declarations, .. } => declarations.iter().map(|declaration| declaration.name.to_string()).collect(),
+            Statement::Expression { ref expression } => match expression.expression {
+                Expression::Function { ref name, .. } | Expression::Class { ref name, .. } => vec![name.to_string()],
+                _ => Vec::new()
+            },
+            _ => Vec::new()
+        };
+    }
+}
@@ -1,7 +1,13 @@
 mod parser;
 mod js_parser;
+mod ts_parser;
 mod error;
+mod source_type;
+mod context;
 
 pub use self::error::*;
 pub use self::parser::*;
-pub use self::js_parser::*;
\ No newline at end of file
+pub use self::js_parser::*;
+pub use self::ts_parser::*;
+pub use self::source_type::SourceType;
+pub use self::context::ParserContext;
@@ -1,18 +1,17 @@
 use super::super::{Chunk};
 use super::super::ast::{SyntaxTree};
-use super::super::transform::PluginManager;
-use super::ParserError;
+use super::{ParserError, SourceType};
 
 use std::time::Duration;
 
 pub struct ParserOptions<'a> {
     pub chunk: &'a mut Chunk,
-    pub plugin_manager: &'a PluginManager,
+    pub source_type: SourceType,
 }
 
 impl<'a> ParserOptions<'a> {
-    pub fn new(chunk: &'a mut Chunk, plugin_manager: &'a PluginManager) -> Self {
-        return ParserOptions { chunk, plugin_manager };
+    pub fn new(chunk: &'a mut Chunk, source_type: SourceType) -> Self {
+        return ParserOptions { chunk, source_type };
     }
 }
 
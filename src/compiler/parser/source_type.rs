@@ -0,0 +1,36 @@
+use std::path::Path;
+
+/// The goal symbol a chunk is parsed with. Modules are always strict and may use `import`/`export`.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum SourceType {
+    Script,
+    Module,
+}
+
+impl SourceType {
+    /// `.mjs`/`.mts` files are always modules and `.cjs`/`.cts` files always scripts, anything else falls back to the configured type.
+    pub fn from_path(path: &Path, default: SourceType) -> Self {
+        return match path.extension().and_then(|extension| extension.to_str()) {
+            Some("mjs") | Some("mts") => SourceType::Module,
+            Some("cjs") | Some("cts") => SourceType::Script,
+            _ => default
+        };
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        return match name {
+            "script" => Some(SourceType::Script),
+            "module" => Some(SourceType::Module),
+            _ => None
+        };
+    }
+}
+
+impl ToString for SourceType {
+    fn to_string(&self) -> String {
+        return match *self {
+            SourceType::Script => "script",
+            SourceType::Module => "module",
+        }.to_owned();
+    }
+}
@@ -0,0 +1,24 @@
+use super::{Parser, ParserError, ParserResult, ParserOptions, JsParser};
+
+/// Parses TypeScript into a JavaScript syntax tree. Type annotations, interfaces and other type-only
+/// declarations are dropped, enums and namespaces are lowered to the JavaScript they compile to.
+pub struct TsParser {
+    parser: JsParser,
+}
+
+impl Parser for TsParser {
+    fn parse(&mut self, options: ParserOptions) -> Result<ParserResult, ParserError> {
+        return self.parser.parse(options);
+    }
+}
+
+impl TsParser {
+    pub fn new() -> Self {
+        return TsParser { parser: JsParser::with_typescript(false) };
+    }
+
+    /// A parser for `.tsx` files, which read JSX where `.ts` files would read `<T>value` type assertions.
+    pub fn with_jsx() -> Self {
+        return TsParser { parser: JsParser::with_typescript(true) };
+    }
+}
@@ -0,0 +1,354 @@
+use super::{ScopeTree, Scope, ScopeId, ScopeKind, Binding, BindingId, BindingKind, Reference};
+
+use super::super::OperatorKind;
+use super::super::parser::SourceType;
+use super::super::ast::{SyntaxTree, SourceLocation};
+use super::super::ast::body::BodyNode;
+use super::super::ast::statement::{StatementNode, Statement, ImportDeclaration, ExportDeclaration};
+use super::super::ast::declaration::DeclarationKind;
+use super::super::ast::expression::{ExpressionNode, Expression};
+use super::super::ast::property::{PropertyNode, PropertyKey, PropertyKind};
+use super::super::ast::visit::{Visit, walk_syntax_tree, walk_statement, walk_expression};
+
+use std::collections::HashMap;
+use std::mem;
+
+/// Builds a `ScopeTree`. Every declaration of a scope is bound when the scope is entered, `var`
+/// declarations in the function scope they are hoisted to, so references can be resolved as soon as
+/// they are visited.
+pub struct ScopeAnalyzer {
+    tree: ScopeTree,
+    current: ScopeId,
+    /// Set while the expression of a function or class declaration is visited. Its name is bound in
+    /// the enclosing scope, not in a scope of its own like the name of an expression.
+    in_declaration: bool,
+}
+
+impl ScopeAnalyzer {
+    pub fn new() -> Self {
+        let global = Scope { kind: ScopeKind::Global, parent: None, children: Vec::new(), bindings: HashMap::new(), location: SourceLocation::default() };
+        return ScopeAnalyzer {
+            tree: ScopeTree { scopes: vec![global], bindings: Vec::new(), references: Vec::new() },
+            current: 0,
+            in_declaration: false,
+        };
+    }
+
+    pub fn analyze(mut self, tree: &SyntaxTree) -> ScopeTree {
+        self.visit_syntax_tree(tree);
+        return self.tree;
+    }
+
+    fn push_scope(&mut self, kind: ScopeKind, location: SourceLocation) -> ScopeId {
+        let id = self.tree.scopes.len();
+        self.tree.scopes.push(Scope { kind, parent: Some(self.current), children: Vec::new(), bindings: HashMap::new(), location });
+        self.tree.scopes[self.current].children.push(id);
+        self.current = id;
+        return id;
+    }
+
+    fn pop_scope(&mut self) {
+        self.current = self.tree.scopes[self.current].parent.unwrap_or(0);
+    }
+
+    /// Binds a name in a scope. A name declared twice keeps its first binding, except that a function
+    /// declaration takes over a `var` of the same name.
+    fn declare(&mut self, scope: ScopeId, name: &str, kind: BindingKind, location: &SourceLocation) -> BindingId {
+        if let Some(&id) = self.tree.scopes[scope].bindings.get(name) {
+            if kind == BindingKind::Function && self.tree.bindings[id].kind == BindingKind::Var {
+                self.tree.bindings[id].kind = kind;
+                self.tree.bindings[id].location = location.clone();
+            }
+            return id;
+        }
+
+        let id = self.tree.bindings.len();
+        self.tree.bindings.push(Binding { name: name.to_owned(), kind, scope, location: location.clone(), references: Vec::new() });
+        self.tree.scopes[scope].bindings.insert(name.to_owned(), id);
+        return id;
+    }
+
+    fn reference(&mut self, name: &str, location: &SourceLocation, is_read: bool, is_write: bool) {
+        let id = self.tree.references.len();
+        let binding = self.tree.lookup(self.current, name);
+
+        let mut in_tdz = false;
+        if let Some(binding) = binding {
+            let declaration = &self.tree.bindings[binding];
+            in_tdz = declaration.kind.is_lexical()
+                && self.tree.var_scope(self.current) == self.tree.var_scope(declaration.scope)
+                && location.start < declaration.location.end;
+            self.tree.bindings[binding].references.push(id);
+        }
+
+        self.tree.references.push(Reference { name: name.to_owned(), location: location.clone(), scope: self.current, binding, is_read, is_write, in_tdz });
+    }
+
+    /// Binds the `var` declarations of a function body, including those in nested blocks and loops.
+    fn hoist_vars(&mut self, body: &BodyNode) {
+        let scope = self.current;
+        for statement in &body.content {
+            self.hoist_var_statement(statement, scope);
+        }
+    }
+
+    fn hoist_var_statement(&mut self, statement: &StatementNode, scope: ScopeId) {
+        use self::Statement::*;
+
+        match statement.statement {
+            Declaration { kind: DeclarationKind::Var, ref declarations } => {
+                for declaration in declarations {
+                    self.declare(scope, &declaration.name, BindingKind::Var, &statement.location);
+                }
+            }
+            Block { ref body, .. } => {
+                for statement in &body.content {
+                    self.hoist_var_statement(statement, scope);
+                }
+            }
+            For { ref init, ref body, .. } => {
+                if let Some(ref init) = *init {
+                    self.hoist_var_statement(init, scope);
+                }
+                self.hoist_var_statement(body, scope);
+            }
+            ForIn { ref left, ref body, .. } | ForOf { ref left, ref body, .. } => {
+                self.hoist_var_statement(left, scope);
+                self.hoist_var_statement(body, scope);
+            }
+            With { ref body, .. } => self.hoist_var_statement(body, scope),
+            Export { declaration: ExportDeclaration::Statement(ref statement), .. } => self.hoist_var_statement(statement, scope),
+            _ => {}
+        }
+    }
+
+    /// Binds the lexical declarations made directly in a body: `let`, `const`, classes, functions and imports.
+    fn declare_lexical(&mut self, body: &BodyNode) {
+        for statement in &body.content {
+            self.declare_lexical_statement(statement);
+        }
+    }
+
+    fn declare_lexical_statement(&mut self, statement: &StatementNode) {
+        let scope = self.current;
+        match statement.statement {
+            Statement::Declaration { ref kind, ref declarations } => {
+                let kind = match *kind {
+                    DeclarationKind::Let => BindingKind::Let,
+                    DeclarationKind::Const => BindingKind::Const,
+                    DeclarationKind::Var => return
+                };
+                for declaration in declarations {
+                    self.declare(scope, &declaration.name, kind, &statement.location);
+                }
+            }
+            Statement::Expression { ref expression } => self.declare_named(expression, &statement.location),
+            Statement::Import { ref alias, ref declaration, .. } => {
+                match *declaration {
+                    ImportDeclaration::All => {
+                        if let Some(ref alias) = *alias {
+                            self.declare(scope, alias, BindingKind::Import, &statement.location);
+                        }
+                    }
+                    ImportDeclaration::Single(ref name) => {
+                        self.declare(scope, name, BindingKind::Import, &statement.location);
+                    }
+                    ImportDeclaration::Multiple(ref specifiers) => {
+                        for specifier in specifiers {
+                            let local = specifier.alias.as_ref().unwrap_or(&specifier.name);
+                            self.declare(scope, local, BindingKind::Import, &statement.location);
+                        }
+                    }
+                }
+            }
+            Statement::Export { ref declaration, .. } => {
+                match *declaration {
+                    ExportDeclaration::Statement(ref statement) => self.declare_lexical_statement(statement),
+                    ExportDeclaration::Default(ref expression) => self.declare_named(expression, &statement.location),
+                    ExportDeclaration::Named(_) => {}
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Binds the name of a function or class declaration.
+    fn declare_named(&mut self, expression: &ExpressionNode, location: &SourceLocation) {
+        let scope = self.current;
+        match expression.expression {
+            Expression::Function { ref name, .. } if !name.is_empty() => {
+                self.declare(scope, name, BindingKind::Function, location);
+            }
+            Expression::Class { ref name, .. } if !name.is_empty() => {
+                self.declare(scope, name, BindingKind::Class, location);
+            }
+            _ => {}
+        }
+    }
+
+    fn is_lexical_declaration(statement: &StatementNode) -> bool {
+        return match statement.statement {
+            Statement::Declaration { ref kind, .. } => *kind != DeclarationKind::Var,
+            _ => false
+        };
+    }
+
+    /// The binding a JSX element name refers to. Lowercase and dashed names are intrinsic elements.
+    fn jsx_reference(name: &str) -> Option<&str> {
+        if name.contains(':') || name.contains('-') {
+            return None;
+        }
+        if let Some(index) = name.find('.') {
+            return Some(&name[..index]);
+        }
+        return match name.chars().next() {
+            Some(char) if char.is_lowercase() => None,
+            Some(_) => Some(name),
+            None => None
+        };
+    }
+}
+
+impl Visit for ScopeAnalyzer {
+    fn visit_syntax_tree(&mut self, tree: &SyntaxTree) {
+        if tree.source_type == SourceType::Module {
+            self.push_scope(ScopeKind::Module, SourceLocation::default());
+        }
+        self.hoist_vars(&tree.base_node);
+        self.declare_lexical(&tree.base_node);
+        walk_syntax_tree(self, tree);
+    }
+
+    fn visit_statement(&mut self, statement: &StatementNode) {
+        use self::Statement::*;
+
+        match statement.statement {
+            Block { ref body, .. } => {
+                self.push_scope(ScopeKind::Block, statement.location.clone());
+                self.declare_lexical(body);
+                walk_statement(self, statement);
+                self.pop_scope();
+            }
+            For { ref init, .. } => {
+                let scoped = init.as_ref().map_or(false, |init| ScopeAnalyzer::is_lexical_declaration(init));
+                if scoped {
+                    self.push_scope(ScopeKind::Block, statement.location.clone());
+                    self.declare_lexical_statement(init.as_ref().unwrap());
+                }
+                walk_statement(self, statement);
+                if scoped {
+                    self.pop_scope();
+                }
+            }
+            ForIn { ref left, ref right, ref body, .. } | ForOf { ref left, ref right, ref body, .. } => {
+                let scoped = ScopeAnalyzer::is_lexical_declaration(left);
+                if scoped {
+                    self.push_scope(ScopeKind::Block, statement.location.clone());
+                    self.declare_lexical_statement(left);
+                }
+                match left.statement {
+                    Expression { expression: ExpressionNode { expression: self::Expression::Identifier(ref name), ref location, .. } } => {
+                        self.reference(name, location, false, true);
+                    }
+                    _ => self.visit_statement(left)
+                }
+                self.visit_expression(right);
+                self.visit_statement(body);
+                if scoped {
+                    self.pop_scope();
+                }
+            }
+            Expression { ref expression } if expression.expression.is_declaration() => {
+                self.in_declaration = true;
+                self.visit_expression(expression);
+            }
+            Export { declaration: ExportDeclaration::Default(ref expression), .. } if expression.expression.is_declaration() => {
+                self.in_declaration = true;
+                self.visit_expression(expression);
+            }
+            Export { declaration: ExportDeclaration::Named(ref specifiers), .. } => {
+                for specifier in specifiers {
+                    self.reference(&specifier.name, &statement.location, true, false);
+                }
+            }
+            _ => walk_statement(self, statement)
+        }
+    }
+
+    fn visit_expression(&mut self, expression_node: &ExpressionNode) {
+        use self::Expression::*;
+
+        let is_declaration = mem::replace(&mut self.in_declaration, false);
+        let location = &expression_node.location;
+        match expression_node.expression {
+            Identifier(ref name) => self.reference(name, location, true, false),
+            Assignment { ref left, ref operator, ref right, .. } => {
+                match left.expression {
+                    Identifier(ref name) => self.reference(name, &left.location, *operator != OperatorKind::Assign, true),
+                    _ => self.visit_expression(left)
+                }
+                self.visit_expression(right);
+            }
+            Update { ref argument, .. } => {
+                match argument.expression {
+                    Identifier(ref name) => self.reference(name, &argument.location, true, true),
+                    _ => self.visit_expression(argument)
+                }
+            }
+            Function { ref name, ref parameters, ref body, .. } => {
+                let is_named = !name.is_empty() && !is_declaration;
+                if is_named {
+                    let scope = self.push_scope(ScopeKind::FunctionName, location.clone());
+                    self.declare(scope, name, BindingKind::ExpressionName, location);
+                }
+                self.push_scope(ScopeKind::Function, location.clone());
+                let scope = self.current;
+                for parameter in parameters {
+                    self.declare(scope, &parameter.name, BindingKind::Parameter, location);
+                }
+                self.hoist_vars(body);
+                self.declare_lexical(body);
+                walk_expression(self, expression_node);
+                self.pop_scope();
+                if is_named {
+                    self.pop_scope();
+                }
+            }
+            ArrowFunction { ref parameters, ref body, .. } => {
+                self.push_scope(ScopeKind::Function, location.clone());
+                let scope = self.current;
+                for parameter in parameters {
+                    self.declare(scope, &parameter.name, BindingKind::Parameter, location);
+                }
+                self.hoist_vars(body);
+                self.declare_lexical(body);
+                walk_expression(self, expression_node);
+                self.pop_scope();
+            }
+            Class { ref name, .. } => {
+                self.push_scope(ScopeKind::Class, location.clone());
+                if !name.is_empty() && !is_declaration {
+                    let scope = self.current;
+                    self.declare(scope, name, BindingKind::ExpressionName, location);
+                }
+                walk_expression(self, expression_node);
+                self.pop_scope();
+            }
+            Jsx(ref element) => {
+                if let Some(name) = element.name.as_ref().and_then(|name| ScopeAnalyzer::jsx_reference(name)) {
+                    self.reference(name, location, true, false);
+                }
+                walk_expression(self, expression_node);
+            }
+            _ => walk_expression(self, expression_node)
+        }
+    }
+
+    fn enter_property(&mut self, property: &PropertyNode) {
+        if property.kind == PropertyKind::Shorthand {
+            if let PropertyKey::Identifier(ref name) = property.key {
+                self.reference(name, &property.location, true, false);
+            }
+        }
+    }
+}
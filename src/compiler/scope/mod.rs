@@ -0,0 +1,8 @@
+//! Semantic analysis of a syntax tree: its scopes, the bindings declared in them and the binding
+//! every identifier reference resolves to.
+
+mod scope_tree;
+mod analyzer;
+
+pub use self::scope_tree::*;
+pub use self::analyzer::ScopeAnalyzer;
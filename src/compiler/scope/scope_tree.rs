@@ -0,0 +1,152 @@
+use super::super::ast::{SourceLocation, SyntaxTree};
+use super::analyzer::ScopeAnalyzer;
+
+use std::collections::HashMap;
+
+pub type ScopeId = usize;
+pub type BindingId = usize;
+pub type ReferenceId = usize;
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum ScopeKind {
+    Global,
+    Module,
+    Function,
+    /// The binding of the name of a function expression, between the enclosing scope and the scope
+    /// of the function, so parameters and declarations of the function shadow it.
+    FunctionName,
+    Block,
+    /// The parameter of a `catch` clause. Maxwell does not parse `try` statements yet.
+    Catch,
+    /// The inner binding of a class name and the scope of its heritage and members.
+    Class,
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum BindingKind {
+    Var,
+    Let,
+    Const,
+    /// A function declaration, hoisted with its value.
+    Function,
+    Class,
+    Parameter,
+    Import,
+    /// The name of a named function or class expression, only visible inside of it.
+    ExpressionName,
+}
+
+impl BindingKind {
+    /// Whether the binding is in its temporal dead zone until its declaration is evaluated.
+    pub fn is_lexical(&self) -> bool {
+        return match *self {
+            BindingKind::Let | BindingKind::Const | BindingKind::Class => true,
+            _ => false
+        };
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Scope {
+    pub kind: ScopeKind,
+    pub parent: Option<ScopeId>,
+    pub children: Vec<ScopeId>,
+    pub bindings: HashMap<String, BindingId>,
+    /// The node the scope belongs to, or the whole source for the global scope.
+    pub location: SourceLocation,
+}
+
+#[derive(Debug, Clone)]
+pub struct Binding {
+    pub name: String,
+    pub kind: BindingKind,
+    pub scope: ScopeId,
+    /// The statement or expression declaring the binding.
+    pub location: SourceLocation,
+    pub references: Vec<ReferenceId>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Reference {
+    pub name: String,
+    pub location: SourceLocation,
+    /// The scope the reference is made from.
+    pub scope: ScopeId,
+    /// `None` when no declaration is visible, which makes the reference one to a global.
+    pub binding: Option<BindingId>,
+    pub is_read: bool,
+    pub is_write: bool,
+    /// Whether the reference is evaluated before a `let`, `const` or `class` declaration it
+    /// resolves to, within the same function. References from closures are never in the dead zone
+    /// as they may run after the declaration.
+    pub in_tdz: bool,
+}
+
+/// Scopes of a syntax tree with every binding they declare and every identifier reference
+/// resolved to its binding. Scope `0` is the global scope.
+#[derive(Debug, Clone)]
+pub struct ScopeTree {
+    pub scopes: Vec<Scope>,
+    pub bindings: Vec<Binding>,
+    pub references: Vec<Reference>,
+}
+
+impl ScopeTree {
+    pub fn analyze(tree: &SyntaxTree) -> Self {
+        return ScopeAnalyzer::new().analyze(tree);
+    }
+
+    pub fn root(&self) -> ScopeId {
+        return 0;
+    }
+
+    pub fn scope(&self, id: ScopeId) -> &Scope {
+        return &self.scopes[id];
+    }
+
+    pub fn binding(&self, id: BindingId) -> &Binding {
+        return &self.bindings[id];
+    }
+
+    pub fn reference(&self, id: ReferenceId) -> &Reference {
+        return &self.references[id];
+    }
+
+    /// Finds the binding a name refers to from a scope, looking through its enclosing scopes.
+    pub fn lookup(&self, scope: ScopeId, name: &str) -> Option<BindingId> {
+        let mut current = Some(scope);
+        while let Some(id) = current {
+            let scope = &self.scopes[id];
+            if let Some(binding) = scope.bindings.get(name) {
+                return Some(*binding);
+            }
+            current = scope.parent;
+        }
+        return None;
+    }
+
+    /// The closest function, module or global scope enclosing a scope, the one `var` declarations
+    /// made in it are hoisted to.
+    pub fn var_scope(&self, scope: ScopeId) -> ScopeId {
+        let mut current = scope;
+        loop {
+            let scope = &self.scopes[current];
+            match (scope.kind, scope.parent) {
+                (ScopeKind::Function, _) | (ScopeKind::Module, _) | (ScopeKind::Global, _) | (_, None) => return current,
+                (_, Some(parent)) => current = parent
+            }
+        }
+    }
+
+    /// Whether a binding is referenced from a function other than the one declaring it.
+    pub fn is_captured(&self, binding: BindingId) -> bool {
+        let binding = &self.bindings[binding];
+        let declared_in = self.var_scope(binding.scope);
+        return binding.references.iter().any(|reference| self.var_scope(self.references[*reference].scope) != declared_in);
+    }
+
+    /// References which resolve to no binding, such as `window` or `console`.
+    pub fn unresolved(&self) -> Vec<&Reference> {
+        return self.references.iter().filter(|reference| reference.binding.is_none()).collect();
+    }
+}
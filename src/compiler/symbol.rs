@@ -0,0 +1,108 @@
+use std::borrow::Borrow;
+use std::collections::HashSet;
+use std::fmt;
+use std::ops::Deref;
+use std::rc::Rc;
+
+/// A name read from the source, such as an identifier or a property. Names interned by the same
+/// `SymbolTable` share their text, so a symbol is cheap to clone and trees holding it are cheap to
+/// copy.
+#[derive(Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Symbol(Rc<str>);
+
+impl Symbol {
+    /// A symbol which shares its text with no other. Names read by the tokenizer are interned.
+    pub fn new(name: &str) -> Self {
+        return Symbol(Rc::from(name));
+    }
+
+    pub fn as_str(&self) -> &str {
+        return &self.0;
+    }
+}
+
+impl Deref for Symbol {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        return &self.0;
+    }
+}
+
+impl Borrow<str> for Symbol {
+    fn borrow(&self) -> &str {
+        return &self.0;
+    }
+}
+
+impl fmt::Debug for Symbol {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(&*self.0, f)
+    }
+}
+
+impl fmt::Display for Symbol {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl PartialEq<str> for Symbol {
+    fn eq(&self, other: &str) -> bool {
+        return &*self.0 == other;
+    }
+}
+
+impl<'a> PartialEq<&'a str> for Symbol {
+    fn eq(&self, other: &&'a str) -> bool {
+        return &*self.0 == *other;
+    }
+}
+
+impl PartialEq<String> for Symbol {
+    fn eq(&self, other: &String) -> bool {
+        return &*self.0 == other.as_str();
+    }
+}
+
+impl<'a> From<&'a str> for Symbol {
+    fn from(name: &'a str) -> Self {
+        return Symbol::new(name);
+    }
+}
+
+impl From<String> for Symbol {
+    fn from(name: String) -> Self {
+        return Symbol(Rc::from(name));
+    }
+}
+
+/// Interns names, so every occurrence of a name in the chunks read by a tokenizer shares one
+/// allocation.
+#[derive(Debug, Default)]
+pub struct SymbolTable {
+    symbols: HashSet<Symbol>,
+}
+
+impl SymbolTable {
+    pub fn new() -> Self {
+        return SymbolTable { symbols: HashSet::new() };
+    }
+
+    pub fn intern(&mut self, name: &str) -> Symbol {
+        if let Some(symbol) = self.symbols.get(name) {
+            return symbol.clone();
+        }
+        let symbol = Symbol::new(name);
+        self.symbols.insert(symbol.clone());
+        return symbol;
+    }
+
+    pub fn len(&self) -> usize {
+        return self.symbols.len();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        return self.symbols.is_empty();
+    }
+}
@@ -1,6 +1,7 @@
 use super::Literal;
 use super::Keyword;
 use super::OperatorKind;
+use super::Symbol;
 
 #[derive(Debug, PartialEq, Clone)]
 pub enum Token {
@@ -12,12 +13,15 @@ pub enum Token {
     BracketClose,
     BraceOpen,
     BraceClose,
+    SquareBracketOpen,
+    SquareBracketClose,
     Operator(OperatorKind),
-    Identifier(String),
+    Identifier(Symbol),
     Literal(Literal),
     Keyword(Keyword),
     EndOfFile,
-    Whitespace(i8),
+    Whitespace(String),
+    Comment(String),
     Newline
 }
 
@@ -31,6 +35,8 @@ impl<> ToString for Token {
             FatArrow => String::from("=>"),
             BracketClose => ")".to_owned(),
             BracketOpen => "(".to_owned(),
+            SquareBracketClose => "]".to_owned(),
+            SquareBracketOpen => "[".to_owned(),
             Semicolon => ";".to_owned(),
             Colon => ":".to_owned(),
             Comma => ",".to_owned(),
@@ -39,13 +45,8 @@ impl<> ToString for Token {
             Keyword(ref keyword) => keyword.to_string(),
             Literal(ref literal) => literal.to_string(),
             EndOfFile => "".to_owned(),
-            Whitespace(ref count) => {
-                let mut str = "".to_owned();
-                for _ in 0..*count {
-                    str += " ";
-                }
-                return str;
-            },
+            Whitespace(ref whitespace) => whitespace.to_owned(),
+            Comment(ref comment) => comment.to_owned(),
             Newline => "\\n".to_owned(),
         };
         return str;
@@ -1,17 +1,30 @@
 use super::lexicon;
 use super::ast::declaration::DeclarationKind;
-use super::{Chunk,Token,Keyword,QuoteKind,Literal,OperatorKind};
+use super::ast::{Comment, SourceLocation};
+use super::{Chunk,Token,Keyword,QuoteKind,Literal,OperatorKind,Symbol,SymbolTable};
 
 use std::result::Result;
 use std::error::Error;
 use std::fmt;
+use std::collections::BTreeMap;
+use std::mem;
 
 pub struct Tokenizer {
+    token_start: usize,
+    token_end: usize,
+    /// Comments read so far by their start. Tokens are read again after every peek, so a comment
+    /// may be read several times.
+    comments: BTreeMap<usize, Comment>,
+    symbols: SymbolTable,
+    /// The first error met by the methods skipping padding, which read on as if the file ended there.
+    error: Option<TokenizerError>,
 }
 
 #[derive(Debug)]
 pub struct TokenizerError {
-    description: String
+    description: String,
+    location: SourceLocation,
+    early: bool,
 }
 
 impl fmt::Display for TokenizerError {
@@ -22,7 +35,32 @@ impl fmt::Display for TokenizerError {
 
 impl TokenizerError {
     pub fn new(description: &str) -> Self {
-        return TokenizerError { description: description.to_owned() };
+        return TokenizerError { description: description.to_owned(), location: SourceLocation::default(), early: false };
+    }
+
+    /// An error in a token which was read whole, such as a malformed number or escape sequence.
+    pub fn early(description: &str) -> Self {
+        let mut err = TokenizerError::new(description);
+        err.early = true;
+        return err;
+    }
+
+    pub fn at(mut self, location: SourceLocation) -> Self {
+        self.location = location;
+        return self;
+    }
+
+    pub fn description(&self) -> &str {
+        return &self.description;
+    }
+
+    /// The span of the token which could not be read.
+    pub fn location(&self) -> &SourceLocation {
+        return &self.location;
+    }
+
+    pub fn is_early(&self) -> bool {
+        return self.early;
     }
 }
 
@@ -34,7 +72,7 @@ impl Error for TokenizerError {
 
 impl Tokenizer {
     pub fn new() -> Self {
-        return Tokenizer {};
+        return Tokenizer { token_start: 0, token_end: 0, comments: BTreeMap::new(), symbols: SymbolTable::new(), error: None };
     }
 
     pub fn peek_token(&mut self, chunk: &mut Chunk) -> Result<Token, TokenizerError> {
@@ -45,7 +83,120 @@ impl Tokenizer {
         return result;
     }
 
+    /// Index in the chunk where the most recently read token began.
+    pub fn token_start(&self) -> usize {
+        return self.token_start;
+    }
+
+    /// Index in the chunk just past the most recently read token.
+    pub fn token_end(&self) -> usize {
+        return self.token_end;
+    }
+
     pub fn pop_token(&mut self, chunk: &mut Chunk) -> Result<Token, TokenizerError> {
+        self.token_start = chunk.index;
+        let result = self.read_token(chunk);
+        self.token_end = chunk.index;
+        if let Err(err) = result {
+            // Errors inside a token, such as an invalid escape sequence, point at themselves.
+            if err.location != SourceLocation::default() {
+                return Err(err);
+            }
+            return Err(err.at(SourceLocation::new(self.token_start, self.token_end)));
+        }
+        if let Ok(Token::Comment(ref text)) = result {
+            if !self.comments.contains_key(&self.token_start) {
+                let comment = Comment::new(text.to_owned(), SourceLocation::new(self.token_start, self.token_end));
+                self.comments.insert(self.token_start, comment);
+            }
+        }
+        return result;
+    }
+
+    /// Interns a name which was not read as an identifier, such as the `default` of an export.
+    pub fn intern(&mut self, name: &str) -> Symbol {
+        return self.symbols.intern(name);
+    }
+
+    /// Takes the comments read since the last call, in source order.
+    pub fn take_comments(&mut self) -> Vec<Comment> {
+        return mem::replace(&mut self.comments, BTreeMap::new()).into_iter().map(|(_, comment)| comment).collect();
+    }
+
+    /// Takes the error met while skipping padding, if any.
+    pub fn take_error(&mut self) -> Option<TokenizerError> {
+        return self.error.take();
+    }
+
+    fn record_error(&mut self, err: TokenizerError) {
+        if self.error.is_none() {
+            self.error = Some(err);
+        }
+    }
+
+    /// Forgets an error met at or after the index. JSX is read from the chunk again with its own
+    /// rules, where a peek may have failed on text such as `it's` or a `"\"` attribute.
+    fn forget_error_from(&mut self, index: usize) {
+        if self.error.as_ref().map_or(false, |err| err.location.start >= index) {
+            self.error = None;
+        }
+    }
+
+    /// Reads JSX text, the raw children of an element up to the next `{` or `<`.
+    pub fn pop_jsx_text(&mut self, chunk: &mut Chunk) -> String {
+        self.token_start = chunk.index;
+        self.forget_error_from(self.token_start);
+        while let Some(char) = self.peek_char(chunk) {
+            if char == '{' || char == '<' { break; }
+            chunk.bump_char();
+        }
+        self.token_end = chunk.index;
+        return chunk.slice(self.token_start, self.token_end).to_owned();
+    }
+
+    /// Reads a JSX element or attribute name. Unlike identifiers these may contain `-`, and namespaced
+    /// (`svg:rect`) or member (`Foo.Bar`) names are read whole.
+    pub fn pop_jsx_name(&mut self, chunk: &mut Chunk) -> String {
+        self.token_start = chunk.index;
+        self.forget_error_from(self.token_start);
+        while let Some(char) = self.peek_char(chunk) {
+            if !lexicon::is_ident(char) && char != '-' && char != ':' && char != '.' { break; }
+            chunk.bump_char();
+        }
+        self.token_end = chunk.index;
+        return chunk.slice(self.token_start, self.token_end).to_owned();
+    }
+
+    /// Reads a JSX attribute string starting at its opening quote. These have no escape sequences
+    /// and may span several lines.
+    pub fn pop_jsx_string(&mut self, chunk: &mut Chunk) -> Result<(String, QuoteKind), TokenizerError> {
+        self.token_start = chunk.index;
+        self.forget_error_from(self.token_start);
+        let quote = match self.peek_char(chunk) {
+            Some('"') => QuoteKind::SpeechMark,
+            Some('\'') => QuoteKind::Apostrophe,
+            _ => return Err(TokenizerError::new("Expected a JSX attribute string.").at(SourceLocation::new(self.token_start, self.token_start)))
+        };
+        let quote_char = chunk.peek_char();
+        chunk.bump_char();
+
+        let start = chunk.index;
+        loop {
+            match self.peek_char(chunk) {
+                Some(char) if char == quote_char => break,
+                Some(_) => {
+                    chunk.bump_char();
+                }
+                None => return Err(TokenizerError::new("Unterminated JSX attribute string.").at(SourceLocation::new(self.token_start, chunk.index)))
+            }
+        }
+        let value = chunk.slice(start, chunk.index).to_owned();
+        chunk.bump_char();
+        self.token_end = chunk.index;
+        return Ok((value, quote));
+    }
+
+    fn read_token(&mut self, chunk: &mut Chunk) -> Result<Token, TokenizerError> {
         if chunk.is_eof() {
             return Ok(Token::EndOfFile);
         }
@@ -66,37 +217,38 @@ impl Tokenizer {
         }
 
         if char.is_whitespace() {
-            let mut count = 1;
+            let mut whitespace = char.to_string();
             chunk.bump_char();
             loop {
                 if chunk.is_eof() {
                     break;
                 }
-                if chunk.peek_char().is_whitespace() {
-                    count += 1;
+                let char = chunk.peek_char();
+                if char.is_whitespace() && char != '\n' && char != '\r' {
+                    whitespace.push(char);
                     chunk.bump_char();
                 } else {
                     break;
                 }
             }
-            return Ok(Token::Whitespace(count));
+            return Ok(Token::Whitespace(whitespace));
         }
 
         match char {
             '=' => {
                 chunk.bump_char();
-                let operator = match chunk.peek_char() {
-                    '=' => {
+                let operator = match self.peek_char(chunk) {
+                    Some('=') => {
                         chunk.bump_char();
-                        match chunk.peek_char() {
-                            '=' => {
+                        match self.peek_char(chunk) {
+                            Some('=') => {
                                 chunk.bump_char();
                                 OperatorKind::StrictEquality
                             }
                             _ => OperatorKind::Equality
                         }
                     }
-                    '>' => {
+                    Some('>') => {
                         chunk.bump_char();
                         return Ok(Token::FatArrow);
                     }
@@ -128,13 +280,34 @@ impl Tokenizer {
                 chunk.bump_char();
                 return Ok(Token::Colon);
             }
+            '[' => {
+                chunk.bump_char();
+                return Ok(Token::SquareBracketOpen);
+            }
+            ']' => {
+                chunk.bump_char();
+                return Ok(Token::SquareBracketClose);
+            }
+            '?' => {
+                chunk.bump_char();
+                return Ok(Token::Operator(OperatorKind::Conditional));
+            }
+            '~' => {
+                chunk.bump_char();
+                return Ok(Token::Operator(OperatorKind::BitwiseNot));
+            }
             '.' => {
                 let start = chunk.index;
                 chunk.bump_char();
-                let token = match chunk.peek_char() {
-                    '0' ... '9' => Token::Literal(Literal::Number(self.read_number(chunk, start).to_owned())),
-                    '.' => {
-                        unimplemented!();
+                let token = match self.peek_char(chunk) {
+                    Some('0' ... '9') => Token::Literal(Literal::Number(self.read_number(chunk, start)?.to_owned())),
+                    Some('.') => {
+                        chunk.bump_char();
+                        if self.peek_char(chunk) != Some('.') {
+                            return Err(TokenizerError::new("Invalid token '..'."));
+                        }
+                        chunk.bump_char();
+                        Token::Operator(OperatorKind::Spread)
                     }
                     _ => Token::Operator(OperatorKind::Accessor)
                 };
@@ -144,12 +317,101 @@ impl Tokenizer {
                 chunk.bump_char();
                 return Ok(Token::Comma);
             }
+            '/' => {
+                let start = chunk.index;
+                chunk.bump_char();
+                return match self.peek_char(chunk) {
+                    Some('/') => Ok(self.read_line_comment(chunk, start)),
+                    Some('*') => self.read_block_comment(chunk, start),
+                    _ => Ok(Token::Operator(self.read_operator(chunk, OperatorKind::Division, OperatorKind::DivideAssign)))
+                };
+            }
+            '+' => {
+                chunk.bump_char();
+                if self.peek_char(chunk) == Some('+') {
+                    chunk.bump_char();
+                    return Ok(Token::Operator(OperatorKind::Increment));
+                }
+                return Ok(Token::Operator(self.read_operator(chunk, OperatorKind::Addition, OperatorKind::AddAssign)));
+            }
+            '-' => {
+                chunk.bump_char();
+                if self.peek_char(chunk) == Some('-') {
+                    chunk.bump_char();
+                    return Ok(Token::Operator(OperatorKind::Decrement));
+                }
+                return Ok(Token::Operator(self.read_operator(chunk, OperatorKind::Subtraction, OperatorKind::SubtractAssign)));
+            }
+            '*' => {
+                chunk.bump_char();
+                if self.peek_char(chunk) == Some('*') {
+                    chunk.bump_char();
+                    return Ok(Token::Operator(self.read_operator(chunk, OperatorKind::Exponent, OperatorKind::ExponentAssign)));
+                }
+                return Ok(Token::Operator(self.read_operator(chunk, OperatorKind::Multiplication, OperatorKind::MultiplyAssign)));
+            }
+            '%' => {
+                chunk.bump_char();
+                return Ok(Token::Operator(self.read_operator(chunk, OperatorKind::Remainder, OperatorKind::RemainderAssign)));
+            }
+            '^' => {
+                chunk.bump_char();
+                return Ok(Token::Operator(self.read_operator(chunk, OperatorKind::BitwiseXor, OperatorKind::BitXorAssign)));
+            }
+            '&' => {
+                chunk.bump_char();
+                if self.peek_char(chunk) == Some('&') {
+                    chunk.bump_char();
+                    return Ok(Token::Operator(OperatorKind::LogicalAnd));
+                }
+                return Ok(Token::Operator(self.read_operator(chunk, OperatorKind::BitwiseAnd, OperatorKind::BitAndAssign)));
+            }
+            '|' => {
+                chunk.bump_char();
+                if self.peek_char(chunk) == Some('|') {
+                    chunk.bump_char();
+                    return Ok(Token::Operator(OperatorKind::LogicalOr));
+                }
+                return Ok(Token::Operator(self.read_operator(chunk, OperatorKind::BitwiseOr, OperatorKind::BitOrAssign)));
+            }
+            '!' => {
+                chunk.bump_char();
+                if self.peek_char(chunk) == Some('=') {
+                    chunk.bump_char();
+                    if self.peek_char(chunk) == Some('=') {
+                        chunk.bump_char();
+                        return Ok(Token::Operator(OperatorKind::StrictInequality));
+                    }
+                    return Ok(Token::Operator(OperatorKind::Inequality));
+                }
+                return Ok(Token::Operator(OperatorKind::LogicalNot));
+            }
+            '<' => {
+                chunk.bump_char();
+                if self.peek_char(chunk) == Some('<') {
+                    chunk.bump_char();
+                    return Ok(Token::Operator(self.read_operator(chunk, OperatorKind::BitShiftLeft, OperatorKind::BSLAssign)));
+                }
+                return Ok(Token::Operator(self.read_operator(chunk, OperatorKind::Lesser, OperatorKind::LesserEquals)));
+            }
+            '>' => {
+                chunk.bump_char();
+                if self.peek_char(chunk) == Some('>') {
+                    chunk.bump_char();
+                    if self.peek_char(chunk) == Some('>') {
+                        chunk.bump_char();
+                        return Ok(Token::Operator(self.read_operator(chunk, OperatorKind::UBitShiftRight, OperatorKind::UBSRAssign)));
+                    }
+                    return Ok(Token::Operator(self.read_operator(chunk, OperatorKind::BitShiftRight, OperatorKind::BSRAssign)));
+                }
+                return Ok(Token::Operator(self.read_operator(chunk, OperatorKind::Greater, OperatorKind::GreaterEquals)));
+            }
             '0' ... '9' => {
                 let start = chunk.index;
-                return Ok(Token::Literal(Literal::Number(self.read_number(chunk, start).to_owned())));
+                return Ok(Token::Literal(Literal::Number(self.read_number(chunk, start)?.to_owned())));
             }
-            '\'' => Ok(self.read_quote_until(chunk, '\'')),
-            '"' => Ok(self.read_quote_until(chunk, '"')),
+            '\'' => self.read_quote_until(chunk, '\''),
+            '"' => self.read_quote_until(chunk, '"'),
             _ => {
                 if lexicon::is_ident(char) {
                     let label = chunk.consume_label();
@@ -160,60 +422,71 @@ impl Tokenizer {
                         "while" => Ok(Token::Keyword(Keyword::While)),
                         "switch" => Ok(Token::Keyword(Keyword::Switch)),
 
-                        "finally" => Ok(Token::Keyword(Keyword::Function)),
-                        "with" => Ok(Token::Keyword(Keyword::Return)),
-                        "in" => Ok(Token::Keyword(Keyword::In)),
+                        "finally" => Ok(Token::Keyword(Keyword::Finally)),
+                        "with" => Ok(Token::Keyword(Keyword::With)),
+                        "default" => Ok(Token::Keyword(Keyword::Default)),
+                        "this" => Ok(Token::Keyword(Keyword::This)),
                         "break" => Ok(Token::Keyword(Keyword::Break)),
                         "do" => Ok(Token::Keyword(Keyword::Do)),
 
                         "class" => Ok(Token::Keyword(Keyword::Class)),
                         "extends" => Ok(Token::Keyword(Keyword::Extends)),
 
-                        "async" => Ok(Token::Keyword(Keyword::Async)),
-                        "await" => Ok(Token::Keyword(Keyword::Await)),
+                        "super" => Ok(Token::Keyword(Keyword::Super)),
+                        "for" => Ok(Token::Keyword(Keyword::For)),
 
                         "import" => Ok(Token::Keyword(Keyword::Import)),
                         "export" => Ok(Token::Keyword(Keyword::Export)),
 
-                        "from" => Ok(Token::Keyword(Keyword::From)),
                         "try" => Ok(Token::Keyword(Keyword::Try)),
                         "catch" => Ok(Token::Keyword(Keyword::Catch)),
 
-
-                        "of" => Ok(Token::Keyword(Keyword::Of)),
-                        "yield" => Ok(Token::Keyword(Keyword::Yield)),
-
-                        "let" => Ok(Token::Keyword(Keyword::Declaration(DeclarationKind::Let))),
                         "var" => Ok(Token::Keyword(Keyword::Declaration(DeclarationKind::Var))),
                         "const" => Ok(Token::Keyword(Keyword::Declaration(DeclarationKind::Const))),
+
+                        "in" => Ok(Token::Operator(OperatorKind::In)),
+                        "instanceof" => Ok(Token::Operator(OperatorKind::InstanceOf)),
+                        "new" => Ok(Token::Operator(OperatorKind::New)),
+                        "typeof" => Ok(Token::Operator(OperatorKind::Typeof)),
+                        "void" => Ok(Token::Operator(OperatorKind::Void)),
+                        "delete" => Ok(Token::Operator(OperatorKind::Delete)),
+
                         "null" => Ok(Token::Literal(Literal::Null)),
                         "undefined" => Ok(Token::Literal(Literal::Undefined)),
                         "true" => Ok(Token::Literal(Literal::Boolean(true))),
                         "false" => Ok(Token::Literal(Literal::Boolean(false))),
-                        value => Ok(Token::Identifier(value.to_owned()))
+                        // Contextual keywords (let, async, await, yield, of, from, as, get, set, static) are
+                        // read as identifiers, the parser decides whether they act as keywords.
+                        value => Ok(Token::Identifier(self.symbols.intern(value)))
                     };
                 } else {
-                    return Err(TokenizerError::new("Invalid token."));
+                    chunk.bump_char();
+                    return Err(TokenizerError::new(&format!("Unexpected character '{}'.", char)));
                 }
             }
         }
     }
 
-    fn read_quote_until<'a>(&self, chunk: &'a mut Chunk, quote_char: char) -> Token {
+    fn read_quote_until<'a>(&self, chunk: &'a mut Chunk, quote_char: char) -> Result<Token, TokenizerError> {
         let start = chunk.index + 1;
 
         chunk.bump_char();
 
         loop {
-            match chunk.peek_char() {
-                '\\' => {
-                    chunk.bump_char();
+            match self.peek_char(chunk) {
+                Some('\\') => {
+                    let escape = chunk.index;
                     chunk.bump_char();
+                    if !self.read_escape(chunk) {
+                        let description = format!("Invalid escape sequence '{}'.", chunk.slice(escape, chunk.index));
+                        return Err(TokenizerError::early(&description).at(SourceLocation::new(escape, chunk.index)));
+                    }
                 }
-                char => {
+                Some(char) => {
                     if char == quote_char { break; }
                     chunk.bump_char();
                 }
+                None => return Err(TokenizerError::new("Unterminated string literal."))
             }
         }
 
@@ -227,23 +500,156 @@ impl Tokenizer {
             _ => panic!("Invalid char")
         };
 
-        return Token::Literal(Literal::String(value,quote));
+        return Ok(Token::Literal(Literal::String(value,quote)));
+    }
+
+    /// Reads the rest of an escape sequence after its `\`. Returns false for `\x` and `\u` escapes
+    /// without enough hex digits or naming a code point past U+10FFFF.
+    fn read_escape(&self, chunk: &mut Chunk) -> bool {
+        let digits = match self.peek_char(chunk) {
+            Some('x') => 2,
+            Some('u') => 4,
+            Some(_) => {
+                chunk.bump_char();
+                return true;
+            }
+            None => return true
+        };
+        chunk.bump_char();
+
+        if digits == 4 && self.peek_char(chunk) == Some('{') {
+            chunk.bump_char();
+            let mut value: u32 = 0;
+            let mut count = 0;
+            while let Some(digit) = self.peek_char(chunk).and_then(|char| char.to_digit(16)) {
+                value = value.saturating_mul(16).saturating_add(digit);
+                count += 1;
+                chunk.bump_char();
+            }
+            if count > 0 && value <= 0x10FFFF && self.peek_char(chunk) == Some('}') {
+                chunk.bump_char();
+                return true;
+            }
+            // Takes in the rest of the braces, so the error shows the whole sequence.
+            while let Some(char) = self.peek_char(chunk) {
+                if !char.is_ascii_alphanumeric() { break; }
+                chunk.bump_char();
+            }
+            if self.peek_char(chunk) == Some('}') {
+                chunk.bump_char();
+            }
+            return false;
+        }
+
+        for _ in 0..digits {
+            match self.peek_char(chunk) {
+                Some(char) if char.is_ascii_hexdigit() => {
+                    chunk.bump_char();
+                }
+                Some(char) if char.is_ascii_alphanumeric() => {
+                    chunk.bump_char();
+                    return false;
+                }
+                _ => return false
+            }
+        }
+        return true;
+    }
+
+    /// Reads a number, along with any identifier characters following it. A `.` ends the number
+    /// when it already has a fraction or exponent and no digit follows, as in `1.5.toFixed()`.
+    fn read_number<'a>(&self, chunk: &'a mut Chunk, start: usize) -> Result<&'a str, TokenizerError> {
+        let radix_prefix = chunk.index == start && chunk.peek_char() == '0' && start + 1 < chunk.len() && match chunk.char_at(start + 1) {
+            'x' | 'X' | 'o' | 'O' | 'b' | 'B' => true,
+            _ => false
+        };
+
+        if radix_prefix {
+            chunk.bump_char();
+            chunk.bump_char();
+            while let Some(char) = self.peek_char(chunk) {
+                if !lexicon::is_ident(char) { break; }
+                chunk.bump_char();
+            }
+        } else {
+            let mut fraction = chunk.index > start;
+            let mut exponent = false;
+            while let Some(char) = self.peek_char(chunk) {
+                match char {
+                    '.' => {
+                        let digit_follows = chunk.index + 1 < chunk.len() && chunk.char_at(chunk.index + 1).is_ascii_digit();
+                        if (fraction || exponent) && !digit_follows { break; }
+                        fraction = true;
+                    }
+                    'e' | 'E' if !exponent => {
+                        exponent = true;
+                        chunk.bump_char();
+                        match self.peek_char(chunk) {
+                            Some('+') | Some('-') => {
+                                chunk.bump_char();
+                            }
+                            _ => {}
+                        }
+                        continue;
+                    }
+                    _ if lexicon::is_ident(char) => {}
+                    _ => break
+                }
+                chunk.bump_char();
+            }
+        }
+
+        let number = chunk.slice(start, chunk.index);
+        if !is_valid_number(number) {
+            if number.ends_with('n') && is_valid_number(&number[..number.len() - 1]) {
+                return Err(TokenizerError::early(&format!("Invalid BigInt '{}'.", number)));
+            }
+            return Err(TokenizerError::early(&format!("Invalid number '{}'.", number)));
+        }
+        return Ok(number);
+    }
+
+    fn read_operator(&self, chunk: &mut Chunk, operator: OperatorKind, assign_operator: OperatorKind) -> OperatorKind {
+        if self.peek_char(chunk) == Some('=') {
+            chunk.bump_char();
+            return assign_operator;
+        }
+        return operator;
+    }
+
+    fn read_line_comment(&self, chunk: &mut Chunk, start: usize) -> Token {
+        while let Some(char) = self.peek_char(chunk) {
+            if char == '\n' || char == '\r' { break; }
+            chunk.bump_char();
+        }
+        return Token::Comment(chunk.slice(start, chunk.index).to_owned());
     }
 
-    fn read_number<'a>(&self, chunk: &'a mut Chunk, start: usize) -> &'a str {
-        while !chunk.is_eof() {
-            match chunk.peek_char() {
-                '0' ... '9' => {
+    fn read_block_comment(&self, chunk: &mut Chunk, start: usize) -> Result<Token, TokenizerError> {
+        chunk.bump_char();
+        loop {
+            match self.peek_char(chunk) {
+                Some('*') => {
                     chunk.bump_char();
+                    if self.peek_char(chunk) == Some('/') {
+                        chunk.bump_char();
+                        break;
+                    }
                 }
-                '.' => {
+                Some(_) => {
                     chunk.bump_char();
                 }
-                _ => break
+                None => return Err(TokenizerError::new("Unterminated comment."))
             }
         }
+        return Ok(Token::Comment(chunk.slice(start, chunk.index).to_owned()));
+    }
 
-        return chunk.slice(start, chunk.index);
+    fn peek_char(&self, chunk: &Chunk) -> Option<char> {
+        if chunk.is_eof() {
+            return None;
+        }
+        return Some(chunk.peek_char());
     }
 
     pub fn peek_ignore_whitespace<'a>(&mut self, chunk: &'a mut Chunk) -> (Token, String) {
@@ -255,12 +661,20 @@ impl Tokenizer {
         let mut token: Token;
 
         loop {
-            token = self.peek_token(chunk).unwrap();
+            token = match self.peek_token(chunk) {
+                Ok(token) => token,
+                Err(err) => {
+                    self.record_error(err);
+                    Token::EndOfFile
+                }
+            };
             match token {
-                Whitespace(count) => {
-                    for _ in 0..count {
-                        string += " ";
-                    }
+                Whitespace(ref whitespace) => {
+                    string += whitespace;
+                    self.pop_token(chunk);
+                }
+                Comment(ref comment) => {
+                    string += comment;
                     self.pop_token(chunk);
                 }
                 _ => {
@@ -277,13 +691,15 @@ impl Tokenizer {
         use self::Token::*;
 
         loop {
-            match self.peek_token(chunk).unwrap() {
-                Whitespace(count) => self.pop_token(chunk),
+            match self.peek_token(chunk) {
+                Ok(Whitespace(_)) | Ok(Comment(_)) => self.pop_token(chunk),
                 _ => break,
             };
         }
 
-        self.pop_token(chunk);
+        if let Err(err) = self.pop_token(chunk) {
+            self.record_error(err);
+        }
     }
 
     pub fn peek_ignore_padding<'a>(&mut self, chunk: &'a mut Chunk) -> (Token, String) {
@@ -295,16 +711,25 @@ impl Tokenizer {
         let mut token: Token;
 
         loop {
-            token = self.peek_token(chunk).unwrap();
+            token = match self.peek_token(chunk) {
+                Ok(token) => token,
+                Err(err) => {
+                    self.record_error(err);
+                    Token::EndOfFile
+                }
+            };
             match token {
-                Whitespace(count) => {
-                    for _ in 0..count {
-                        string += " ";
-                    }
+                Whitespace(ref whitespace) => {
+                    string += whitespace;
                     self.pop_token(chunk);
                 }
                 Newline => {
-                    string += "\n";
+                    let line_start = chunk.index;
+                    self.pop_token(chunk);
+                    string += &chunk.source[line_start..chunk.index];
+                }
+                Comment(ref comment) => {
+                    string += comment;
                     self.pop_token(chunk);
                 }
                 _ => {
@@ -322,14 +747,65 @@ impl Tokenizer {
         use self::Token::*;
 
         loop {
-            match self.peek_token(chunk).unwrap() {
-                Whitespace(count) => self.pop_token(chunk),
-                Newline => self.pop_token(chunk),
+            match self.peek_token(chunk) {
+                Ok(Whitespace(_)) | Ok(Comment(_)) | Ok(Newline) => self.pop_token(chunk),
                 _ => break,
             };
         }
 
-        self.pop_token(chunk);
+        if let Err(err) = self.pop_token(chunk) {
+            self.record_error(err);
+        }
+    }
+
+}
+
+/// Whether a number holds only digits of its radix, with `_` between digits, at most one `.` and
+/// exponent for decimals, and a BigInt `n` only after an integer.
+fn is_valid_number(number: &str) -> bool {
+    let (radix, body) = match number.get(..2) {
+        Some("0x") | Some("0X") => (16, &number[2..]),
+        Some("0o") | Some("0O") => (8, &number[2..]),
+        Some("0b") | Some("0B") => (2, &number[2..]),
+        _ => (10, number)
+    };
+    let is_digits = |digits: &str, radix: u32| {
+        !digits.is_empty() && !digits.starts_with('_') && !digits.ends_with('_') && !digits.contains("__")
+            && digits.chars().all(|char| char == '_' || char.is_digit(radix))
+    };
+
+    let (body, bigint) = match body.ends_with('n') {
+        true => (&body[..body.len() - 1], true),
+        false => (body, false)
+    };
+    if radix != 10 {
+        return is_digits(body, radix);
+    }
+
+    let (mantissa, exponent) = match body.find(&['e', 'E'][..]) {
+        Some(index) => (&body[..index], Some(&body[index + 1..])),
+        None => (body, None)
+    };
+    let (integer, fraction) = match mantissa.find('.') {
+        Some(index) => (&mantissa[..index], Some(&mantissa[index + 1..])),
+        None => (mantissa, None)
+    };
+    if bigint && (fraction.is_some() || exponent.is_some()) {
+        return false;
+    }
+    // Legacy octals like `017` take neither separators nor a BigInt `n`.
+    if integer.len() > 1 && integer.starts_with('0') && (bigint || integer.contains('_')) {
+        return false;
     }
 
-}
\ No newline at end of file
+    let valid_mantissa = match fraction {
+        Some(fraction) if integer.is_empty() => is_digits(fraction, 10),
+        Some("") => is_digits(integer, 10),
+        Some(fraction) => is_digits(integer, 10) && is_digits(fraction, 10),
+        None => is_digits(integer, 10)
+    };
+    return valid_mantissa && exponent.map_or(true, |exponent| match exponent.starts_with('+') || exponent.starts_with('-') {
+        true => is_digits(&exponent[1..], 10),
+        false => is_digits(exponent, 10)
+    });
+}
@@ -0,0 +1,135 @@
+use super::super::ast::SourceLocation;
+use super::super::diagnostics::{Diagnostic, Label, SourceFile};
+
+pub use super::super::diagnostics::DiagnosticLevel;
+
+use std::error::Error;
+use std::fmt;
+use std::path::PathBuf;
+
+/// A problem reported by a plugin. The plugin name, file and position are filled in by the
+/// `PluginManager`, plugins only give the level, message and location.
+#[derive(Debug, Clone)]
+pub struct PluginDiagnostic {
+    pub level: DiagnosticLevel,
+    pub message: String,
+    pub location: SourceLocation,
+    pub plugin: String,
+    pub path: Option<PathBuf>,
+    /// One-based line and column of the start of `location`, when the diagnostic belongs to a chunk.
+    pub position: Option<(usize, usize)>,
+    /// Code taking part in the problem, shown next to `location` in the source of the chunk.
+    pub labels: Vec<Label>,
+    pub notes: Vec<String>,
+    pub help: Option<String>,
+}
+
+impl PluginDiagnostic {
+    pub fn new(level: DiagnosticLevel, message: String, location: SourceLocation) -> Self {
+        return PluginDiagnostic {
+            level,
+            message,
+            location,
+            plugin: String::new(),
+            path: None,
+            position: None,
+            labels: Vec::new(),
+            notes: Vec::new(),
+            help: None,
+        };
+    }
+
+    pub fn error(message: String, location: SourceLocation) -> Self {
+        return PluginDiagnostic::new(DiagnosticLevel::Error, message, location);
+    }
+
+    pub fn warning(message: String, location: SourceLocation) -> Self {
+        return PluginDiagnostic::new(DiagnosticLevel::Warning, message, location);
+    }
+
+    pub fn with_label(mut self, label: Label) -> Self {
+        self.labels.push(label);
+        return self;
+    }
+
+    pub fn with_note(mut self, note: String) -> Self {
+        self.notes.push(note);
+        return self;
+    }
+
+    pub fn with_help(mut self, help: String) -> Self {
+        self.help = Some(help);
+        return self;
+    }
+
+    pub fn is_error(&self) -> bool {
+        return self.level == DiagnosticLevel::Error;
+    }
+
+    /// The diagnostic as it is reported, coded by the plugin name. Its location and labels are
+    /// only shown when it has a position, as only then are they offsets into `source`, the source
+    /// of the chunk at `path`. A location of `SourceLocation::default()` is not shown.
+    pub fn to_diagnostic(&self, source: Option<&str>) -> Diagnostic {
+        let mut diagnostic = Diagnostic::new(self.level, self.message.clone());
+        if !self.plugin.is_empty() {
+            diagnostic = diagnostic.with_code(&self.plugin);
+        }
+        if let Some(ref path) = self.path {
+            match source {
+                Some(source) if self.position.is_some() => {
+                    diagnostic = diagnostic.with_file(SourceFile::new(path.clone(), source.to_owned()));
+                    if self.location != SourceLocation::default() {
+                        diagnostic = diagnostic.with_label(Label::primary(self.location.clone(), String::new()));
+                    }
+                    diagnostic.labels.extend(self.labels.iter().cloned());
+                }
+                _ => diagnostic = diagnostic.with_file(SourceFile::new(path.clone(), String::new()))
+            }
+        }
+        diagnostic.notes = self.notes.clone();
+        diagnostic.help = self.help.clone();
+        return diagnostic;
+    }
+}
+
+impl fmt::Display for PluginDiagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} [{}]: {}", self.level.to_string(), self.plugin, self.message)?;
+        if let Some(ref path) = self.path {
+            write!(f, "\n    at {}", path.display())?;
+            if let Some((line, column)) = self.position {
+                write!(f, ":{}:{}", line, column)?;
+            }
+        }
+        return Ok(());
+    }
+}
+
+impl Error for PluginDiagnostic {}
+
+/// What a plugin reports back for a pass. `changed` tells whether the plugin modified what it was
+/// handed. Returning `Err` from `Plugin::handle` stops the plugin and fails the build, while
+/// diagnostics collected here are reported once the transform phase is over.
+#[derive(Debug)]
+pub struct PluginResult {
+    pub changed: bool,
+    pub diagnostics: Vec<PluginDiagnostic>,
+}
+
+impl PluginResult {
+    pub fn unchanged() -> Self {
+        return PluginResult { changed: false, diagnostics: Vec::new() };
+    }
+
+    pub fn changed() -> Self {
+        return PluginResult { changed: true, diagnostics: Vec::new() };
+    }
+
+    pub fn warn(&mut self, message: String, location: SourceLocation) {
+        self.diagnostics.push(PluginDiagnostic::warning(message, location));
+    }
+
+    pub fn error(&mut self, message: String, location: SourceLocation) {
+        self.diagnostics.push(PluginDiagnostic::error(message, location));
+    }
+}
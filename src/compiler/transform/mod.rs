@@ -1,6 +1,16 @@
 mod plugin;
 mod plugin_manager;
+mod diagnostic;
+mod plugin_registry;
+mod native_plugin;
+mod wasm_plugin;
+mod template;
 
 pub use self::plugin::Plugin;
-pub use self::plugin::PluginPass;
-pub use self::plugin_manager::PluginManager;
\ No newline at end of file
+pub use self::plugin::{PluginPass, Bundle};
+pub use self::plugin_manager::PluginManager;
+pub use self::diagnostic::{PluginDiagnostic, PluginResult, DiagnosticLevel};
+pub use self::plugin_registry::{PluginRegistry, PluginFactory};
+pub use self::native_plugin::{PluginDeclaration, NativePluginError, PLUGIN_ABI_VERSION, RUSTC_VERSION};
+pub use self::wasm_plugin::{WasmPlugin, WasmPluginError, WASM_ABI_VERSION};
+pub use self::template::{Template, Replacement, TemplateError};
@@ -0,0 +1,124 @@
+//! Plugins compiled to shared libraries and loaded at runtime. A plugin crate is built as a `cdylib`
+//! against the same version of Maxwell and declares its plugin with `declare_plugin!`:
+//!
+//! ```ignore
+//! #[macro_use]
+//! extern crate maxwell;
+//!
+//! declare_plugin!("banner", BannerPlugin::from_config);
+//! ```
+//!
+//! The library exports the ABI version on its own first, so a library built for another version is
+//! rejected before anything else is read from it.
+
+extern crate libloading;
+
+use super::{PluginFactory, PluginRegistry};
+
+use self::libloading::{Library, Symbol};
+
+use std::path::{Path, PathBuf};
+use std::error::Error;
+use std::fmt;
+
+/// Version of the layout of `PluginDeclaration` and of the types plugins are handed. Raised whenever
+/// either changes.
+pub const PLUGIN_ABI_VERSION: u32 = 1;
+
+/// Version of rustc Maxwell was built with. Plugins built with another version are rejected.
+pub const RUSTC_VERSION: &str = env!("MAXWELL_RUSTC_VERSION");
+
+#[doc(hidden)]
+pub const ABI_VERSION_SYMBOL: &[u8] = b"maxwell_plugin_abi_version\0";
+#[doc(hidden)]
+pub const DECLARATION_SYMBOL: &[u8] = b"maxwell_plugin_declaration\0";
+
+/// What a plugin library exports through `declare_plugin!`.
+#[repr(C)]
+pub struct PluginDeclaration {
+    pub abi_version: u32,
+    pub rustc_version: &'static str,
+    /// Name the plugin is enabled by in the `"plugins"` section of `maxwell.json`.
+    pub name: &'static str,
+    pub create: PluginFactory,
+}
+
+#[derive(Debug)]
+pub enum NativePluginError {
+    Load(PathBuf, String),
+    MissingDeclaration(PathBuf),
+    AbiMismatch(PathBuf, u32),
+    RustcMismatch(PathBuf, String),
+}
+
+impl fmt::Display for NativePluginError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use self::NativePluginError::*;
+
+        return match *self {
+            Load(ref path, ref reason) => write!(f, "Cannot load plugin library {}: {}", path.display(), reason),
+            MissingDeclaration(ref path) => write!(f, "{} does not declare a plugin, is `declare_plugin!` missing?", path.display()),
+            AbiMismatch(ref path, version) => write!(f, "{} was built for plugin ABI {} but Maxwell uses {}", path.display(), version, PLUGIN_ABI_VERSION),
+            RustcMismatch(ref path, ref version) => write!(f, "{} was built with {} but Maxwell was built with {}", path.display(), version, RUSTC_VERSION),
+        };
+    }
+}
+
+impl Error for NativePluginError {}
+
+/// Declares the plugin of a plugin library. Takes the name the plugin is enabled by and a
+/// `PluginFactory` creating it from its options.
+#[macro_export]
+macro_rules! declare_plugin {
+    ($name:expr, $create:path) => {
+        #[doc(hidden)]
+        #[no_mangle]
+        #[allow(non_upper_case_globals)]
+        pub static maxwell_plugin_abi_version: u32 = $crate::compiler::transform::PLUGIN_ABI_VERSION;
+
+        #[doc(hidden)]
+        #[no_mangle]
+        #[allow(non_upper_case_globals)]
+        pub static maxwell_plugin_declaration: $crate::compiler::transform::PluginDeclaration = $crate::compiler::transform::PluginDeclaration {
+            abi_version: $crate::compiler::transform::PLUGIN_ABI_VERSION,
+            rustc_version: $crate::compiler::transform::RUSTC_VERSION,
+            name: $name,
+            create: $create,
+        };
+    };
+}
+
+impl PluginRegistry {
+    /// Loads a plugin library and registers its plugin, returning the name it was registered under.
+    /// The library stays loaded for as long as the registry exists.
+    pub fn load_native(&mut self, path: &Path) -> Result<String, NativePluginError> {
+        let library = match Library::new(path) {
+            Ok(library) => library,
+            Err(err) => return Err(NativePluginError::Load(path.to_path_buf(), err.to_string()))
+        };
+
+        let (name, create) = unsafe {
+            let abi_version: Symbol<*const u32> = match library.get(ABI_VERSION_SYMBOL) {
+                Ok(symbol) => symbol,
+                Err(_) => return Err(NativePluginError::MissingDeclaration(path.to_path_buf()))
+            };
+            if **abi_version != PLUGIN_ABI_VERSION {
+                return Err(NativePluginError::AbiMismatch(path.to_path_buf(), **abi_version));
+            }
+
+            let declaration: Symbol<*const PluginDeclaration> = match library.get(DECLARATION_SYMBOL) {
+                Ok(symbol) => symbol,
+                Err(_) => return Err(NativePluginError::MissingDeclaration(path.to_path_buf()))
+            };
+            let declaration = &**declaration;
+            if declaration.rustc_version != RUSTC_VERSION {
+                return Err(NativePluginError::RustcMismatch(path.to_path_buf(), declaration.rustc_version.to_owned()));
+            }
+            (declaration.name.to_owned(), declaration.create)
+        };
+
+        self.register(&name, create);
+        self.libraries.push(library);
+        return Ok(name);
+    }
+}
@@ -1,16 +1,23 @@
-use super::super::{Chunk};
+use super::super::{Chunk, ChunkId, ChunkLocation, CompilerError, CompilerOptions, SourceMap};
 
-use super::super::ast::statement::StatementNode;
-use super::super::ast::expression::ExpressionNode;
+use super::super::ast::SyntaxTree;
+use super::{PluginResult, PluginDiagnostic};
 
 use std::result::Result;
-use std::error::Error;
 use std::hash::{Hash,Hasher};
+use std::collections::{HashMap, BTreeMap};
+use std::path::PathBuf;
 
 pub trait Plugin {
-    fn handle(&self, pass: &mut PluginPass) -> Result<String, Box<Error>>;
+    fn handle(&self, pass: &mut PluginPass) -> Result<PluginResult, PluginDiagnostic>;
 
     fn get_name(&self) -> &str;
+
+    /// Whether the plugin uses `RenderChunk` or `GenerateBundle`, which need the code of the bundle
+    /// in memory. When no plugin does, chunks are streamed to their files as they are generated.
+    fn handles_output(&self) -> bool {
+        return true;
+    }
 }
 
 impl Hash for Plugin {
@@ -27,8 +34,46 @@ impl PartialEq for Plugin {
     }
 }
 
+/// Files written by a build, by their path relative to the out directory.
+pub type Bundle = BTreeMap<PathBuf, Vec<u8>>;
+
+/// The hooks of a build, in the order they are called. A plugin ignores the passes it has no use for.
 pub enum PluginPass<'a> {
-    EmitChunk(&'a mut Chunk),
-    StatementNodeEmit(&'a mut StatementNode),
-    ExpressionNodeEmit(&'a mut ExpressionNode)
-}
\ No newline at end of file
+    /// Once, before any chunk is loaded.
+    BuildStart(&'a CompilerOptions),
+    /// Resolves an import of a chunk. A plugin which knows the import sets `resolved`, and the
+    /// `Resolver` is only used when no plugin does. Plugins after the first to resolve it are skipped.
+    ResolveId {
+        source: &'a str,
+        importer: &'a ChunkLocation,
+        resolved: &'a mut Option<ChunkLocation>,
+    },
+    /// Supplies the source of a chunk instead of it being read from disk. Plugins after the first
+    /// to set `source` are skipped.
+    Load {
+        location: &'a ChunkLocation,
+        source: &'a mut Option<String>,
+    },
+    /// The complete syntax tree of one chunk, once every chunk has been parsed, and the source it
+    /// was parsed from, which the locations of its nodes point into.
+    SyntaxTree {
+        tree: &'a mut SyntaxTree,
+        source: &'a str,
+    },
+    /// Every chunk of the build, after all of their syntax trees have been transformed. Chunks list
+    /// what they require and are used by in their `dependencies`.
+    ModuleGraph(&'a mut HashMap<ChunkId, Chunk>),
+    /// The code generated for a chunk, before it is added to the bundle. A plugin which changes the
+    /// code while source maps are written sets `map` to a map of its code to the code it was
+    /// given, so the source map of the chunk still leads back to its sources. When it sets none,
+    /// the mappings of the chunk are dropped.
+    RenderChunk {
+        chunk: &'a Chunk,
+        code: &'a mut String,
+        map: &'a mut Option<SourceMap>,
+    },
+    /// Every file about to be written. Plugins may change, add or remove files.
+    GenerateBundle(&'a mut Bundle),
+    /// Once, after the bundle was written or the build failed with the given error.
+    BuildEnd(Option<&'a CompilerError>),
+}
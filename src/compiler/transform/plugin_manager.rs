@@ -1,6 +1,14 @@
 use super::Plugin;
-use super::PluginPass;
+use super::{PluginPass, Bundle};
+use super::PluginDiagnostic;
 
+use super::super::{Chunk, ChunkId, ChunkLocation, CompilerError, CompilerOptions, SourceMap};
+
+use std::collections::HashMap;
+
+/// Runs plugins over the hooks of a build. Plugins run one after another in the order they were
+/// added, each seeing the changes of the ones before it. What they report is added to the
+/// diagnostics handed to each hook, the build fails when any of them is an error.
 pub struct PluginManager {
     plugins: Vec<Box<Plugin>>
 }
@@ -12,13 +20,149 @@ impl PluginManager {
         };
     }
 
-    pub fn apply_plugin(&self, mut pass: PluginPass) {
+    /// Hands the pass to a single plugin. Returns whether it changed the pass, or `Err` when it failed.
+    fn run_plugin(plugin: &Box<Plugin>, pass: &mut PluginPass, diagnostics: &mut Vec<PluginDiagnostic>) -> Result<bool, ()> {
+        let name = plugin.get_name().to_owned();
+        return match plugin.handle(pass) {
+            Ok(result) => {
+                for mut diagnostic in result.diagnostics {
+                    diagnostic.plugin = name.clone();
+                    diagnostics.push(diagnostic);
+                }
+                Ok(result.changed)
+            }
+            Err(mut diagnostic) => {
+                diagnostic.plugin = name;
+                diagnostics.push(diagnostic);
+                Err(())
+            }
+        };
+    }
+
+    /// Hands the pass to every plugin, collecting what they report. Returns whether any plugin
+    /// changed the pass. Plugins after one that failed are not run.
+    pub fn apply_plugin(&self, mut pass: PluginPass, diagnostics: &mut Vec<PluginDiagnostic>) -> bool {
+        let mut changed = false;
+        for plugin in &self.plugins {
+            match PluginManager::run_plugin(plugin, &mut pass, diagnostics) {
+                Ok(plugin_changed) => changed |= plugin_changed,
+                Err(_) => break
+            }
+        }
+        return changed;
+    }
+
+    pub fn build_start(&self, options: &CompilerOptions, diagnostics: &mut Vec<PluginDiagnostic>) {
+        self.apply_plugin(PluginPass::BuildStart(options), diagnostics);
+    }
+
+    /// Resolves an import through the plugins, `None` when no plugin knows it.
+    pub fn resolve_id(&self, source: &str, importer: &ChunkLocation, diagnostics: &mut Vec<PluginDiagnostic>) -> Option<ChunkLocation> {
+        let start = diagnostics.len();
+        let mut resolved = None;
+        for plugin in &self.plugins {
+            let result = PluginManager::run_plugin(plugin, &mut PluginPass::ResolveId { source, importer, resolved: &mut resolved }, diagnostics);
+            if result.is_err() || resolved.is_some() {
+                break;
+            }
+        }
+        PluginManager::attach_path(&mut diagnostics[start..], importer);
+        return resolved;
+    }
+
+    /// Loads the source of a chunk through the plugins, `None` when it is to be read from disk.
+    pub fn load(&self, location: &ChunkLocation, diagnostics: &mut Vec<PluginDiagnostic>) -> Option<String> {
+        let start = diagnostics.len();
+        let mut source = None;
+        for plugin in &self.plugins {
+            let result = PluginManager::run_plugin(plugin, &mut PluginPass::Load { location, source: &mut source }, diagnostics);
+            if result.is_err() || source.is_some() {
+                break;
+            }
+        }
+        PluginManager::attach_path(&mut diagnostics[start..], location);
+        return source;
+    }
+
+    /// Transforms the syntax tree of every chunk, then hands the whole module graph to the plugins.
+    /// The graph pass is skipped when a chunk failed. Returns the number of chunks changed.
+    pub fn transform(&self, chunks: &mut HashMap<ChunkId, Chunk>, diagnostics: &mut Vec<PluginDiagnostic>) -> usize {
+        let mut changed_count = 0;
+        for chunk in chunks.values_mut() {
+            if self.transform_chunk(chunk, diagnostics) {
+                changed_count += 1;
+            }
+        }
+        if !diagnostics.iter().any(|diagnostic| diagnostic.is_error()) {
+            self.apply_plugin(PluginPass::ModuleGraph(chunks), diagnostics);
+        }
+        return changed_count;
+    }
+
+    pub fn transform_chunk(&self, chunk: &mut Chunk, diagnostics: &mut Vec<PluginDiagnostic>) -> bool {
+        let start = diagnostics.len();
+        let changed = match chunk.syntax_tree {
+            Some(ref mut tree) => self.apply_plugin(PluginPass::SyntaxTree { tree, source: &chunk.source }, diagnostics),
+            None => false
+        };
+        for diagnostic in &mut diagnostics[start..] {
+            diagnostic.path = Some(chunk.location.path.clone());
+            diagnostic.position = Some(chunk.line_column(diagnostic.location.start));
+        }
+        return changed;
+    }
+
+    /// Lets plugins change the code of a chunk. The source map of the chunk, when one is written,
+    /// is composed with the map each plugin gives of its change.
+    pub fn render_chunk(&self, chunk: &Chunk, code: &mut String, mut source_map: Option<&mut SourceMap>, diagnostics: &mut Vec<PluginDiagnostic>) {
+        let start = diagnostics.len();
         for plugin in &self.plugins {
-            plugin.handle(&mut pass);
+            let before = source_map.as_ref().map(|_| code.clone());
+            let mut map = None;
+            let result = PluginManager::run_plugin(plugin, &mut PluginPass::RenderChunk { chunk, code, map: &mut map }, diagnostics);
+            if let (Some(source_map), Some(before)) = (source_map.as_mut(), before) {
+                match map {
+                    Some(map) => **source_map = map.compose(source_map),
+                    None if before != *code => source_map.clear_mappings(),
+                    None => {}
+                }
+            }
+            if result.is_err() {
+                break;
+            }
+        }
+        PluginManager::attach_path(&mut diagnostics[start..], &chunk.location);
+    }
+
+    /// Whether any plugin needs the code of the bundle, see `Plugin::handles_output`.
+    pub fn handles_output(&self) -> bool {
+        return self.plugins.iter().any(|plugin| plugin.handles_output());
+    }
+
+    pub fn generate_bundle(&self, bundle: &mut Bundle, diagnostics: &mut Vec<PluginDiagnostic>) {
+        self.apply_plugin(PluginPass::GenerateBundle(bundle), diagnostics);
+    }
+
+    pub fn build_end(&self, error: Option<&CompilerError>, diagnostics: &mut Vec<PluginDiagnostic>) {
+        self.apply_plugin(PluginPass::BuildEnd(error), diagnostics);
+    }
+
+    fn attach_path(diagnostics: &mut [PluginDiagnostic], location: &ChunkLocation) {
+        for diagnostic in diagnostics {
+            diagnostic.path = Some(location.path.clone());
         }
     }
 
     pub fn add_plugin<T>(&mut self, plugin: T) where T: Plugin + 'static {
         self.plugins.push(Box::new(plugin));
     }
+
+    pub fn add_boxed_plugin(&mut self, plugin: Box<Plugin>) {
+        self.plugins.push(plugin);
+    }
+
+    /// Adds a plugin which runs before every plugin added so far.
+    pub fn add_plugin_first<T>(&mut self, plugin: T) where T: Plugin + 'static {
+        self.plugins.insert(0, Box::new(plugin));
+    }
 }
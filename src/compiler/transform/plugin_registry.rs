@@ -0,0 +1,51 @@
+extern crate json;
+extern crate libloading;
+
+use super::Plugin;
+
+use self::libloading::Library;
+
+use std::collections::HashMap;
+
+/// Creates a plugin from the options object given to it in `maxwell.json`. The error is a reason
+/// the options were rejected.
+pub type PluginFactory = fn(&json::JsonValue) -> Result<Box<Plugin>, String>;
+
+/// Maps the plugin names used in the `"plugins"` section of `maxwell.json` to plugins.
+pub struct PluginRegistry {
+    factories: HashMap<String, Box<Fn(&json::JsonValue) -> Result<Box<Plugin>, String>>>,
+    /// Native plugin libraries the factories may point into. Dropped last, after the factories.
+    pub(super) libraries: Vec<Library>,
+}
+
+impl PluginRegistry {
+    pub fn new() -> Self {
+        return PluginRegistry { factories: HashMap::new(), libraries: Vec::new() };
+    }
+
+    /// Registers a plugin under a config name, replacing any plugin registered under that name.
+    pub fn register(&mut self, name: &str, factory: PluginFactory) {
+        self.factories.insert(name.to_owned(), Box::new(factory));
+    }
+
+    /// Registers a plugin whose factory needs state of its own, such as a loaded WebAssembly module.
+    pub fn register_boxed(&mut self, name: &str, factory: Box<Fn(&json::JsonValue) -> Result<Box<Plugin>, String>>) {
+        self.factories.insert(name.to_owned(), factory);
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        return self.factories.contains_key(name);
+    }
+
+    /// Names of the registered plugins, sorted.
+    pub fn names(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = self.factories.keys().map(|name| name.as_str()).collect();
+        names.sort();
+        return names;
+    }
+
+    /// Creates the plugin registered under the name, or `None` when there is no such plugin.
+    pub fn create(&self, name: &str, options: &json::JsonValue) -> Option<Result<Box<Plugin>, String>> {
+        return self.factories.get(name).map(|factory| factory(options));
+    }
+}
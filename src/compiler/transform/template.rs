@@ -0,0 +1,345 @@
+use super::super::{Chunk, ChunkLocation, Literal, OperatorKind, QuoteKind};
+use super::super::lexicon;
+use super::super::ast::{NodeTrivia, SourceLocation};
+use super::super::ast::body::BodyNode;
+use super::super::ast::directive::DirectiveNode;
+use super::super::ast::statement::{StatementNode, Statement};
+use super::super::ast::expression::{ExpressionNode, Expression, BracketedTrivia};
+use super::super::ast::expression::{ASSIGNMENT_PRECEDENCE, UNARY_PRECEDENCE, POSTFIX_PRECEDENCE, MEMBER_PRECEDENCE};
+use super::super::ast::property::PropertyNode;
+use super::super::ast::visit_mut::VisitMut;
+use super::super::parser::{JsParser, Parser, ParserError, ParserOptions, SourceType};
+
+use std::mem;
+use std::error::Error;
+use std::fmt;
+
+/// What a `%%name%%` placeholder of a `Template` is replaced with.
+#[derive(Debug, Clone)]
+pub enum Replacement {
+    /// Replaces a placeholder used as an expression.
+    Expression(ExpressionNode),
+    /// Replaces a placeholder used as an expression statement, `%%name%%;`.
+    Statements(Vec<StatementNode>),
+    /// An identifier written in place of the placeholder, for names of declarations or parameters.
+    Name(String),
+    /// A string literal written in place of the placeholder, for import sources.
+    String(String),
+}
+
+#[derive(Debug)]
+pub enum TemplateError {
+    Syntax(ParserError),
+    /// A placeholder has no replacement.
+    MissingReplacement(String),
+    /// A replacement has no placeholder.
+    UnusedReplacement(String),
+    /// An expression or statements replacement is used where the template holds something else.
+    Misplaced(String),
+    /// A name replacement is not an identifier. Holds the placeholder and the name.
+    InvalidName(String, String),
+    /// The template does not hold the kind of code asked for. Holds what was expected.
+    Shape(String),
+}
+
+impl fmt::Display for TemplateError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        return match *self {
+            TemplateError::Syntax(ref err) => write!(f, "Invalid template: {}", err),
+            TemplateError::MissingReplacement(ref name) => write!(f, "No replacement for placeholder '{}'", name),
+            TemplateError::UnusedReplacement(ref name) => write!(f, "No placeholder for replacement '{}'", name),
+            TemplateError::Misplaced(ref name) => write!(f, "Placeholder '{}' cannot be replaced where it is used", name),
+            TemplateError::InvalidName(ref name, ref identifier) => write!(f, "Replacement '{}' of placeholder '{}' is not an identifier", identifier, name),
+            TemplateError::Shape(ref expected) => write!(f, "Expected the template to hold {}", expected),
+        };
+    }
+}
+
+impl Error for TemplateError {
+    fn source(&self) -> Option<&(Error + 'static)> {
+        return match *self {
+            TemplateError::Syntax(ref err) => Some(err),
+            _ => None
+        };
+    }
+}
+
+/// JavaScript source with `%%name%%` placeholders, parsed into nodes for plugins to insert. Nodes
+/// keep the padding written in the template and have no location.
+///
+/// ```ignore
+/// let guard = Template::new("if_defined(%%value%%, %%name%%);");
+/// let statement = guard.statement(vec![("value", Replacement::Expression(value)), ("name", Replacement::String(name))])?;
+/// ```
+pub struct Template {
+    source: String,
+}
+
+impl Template {
+    pub fn new(source: &str) -> Self {
+        return Template { source: source.to_owned() };
+    }
+
+    pub fn statements(&self, replacements: Vec<(&str, Replacement)>) -> Result<Vec<StatementNode>, TemplateError> {
+        let mut body = self.parse(&self.source, replacements)?;
+        if let Some(first) = body.content.first_mut() {
+            first.trivia.prefix = first.trivia.prefix.trim_start().to_owned();
+        }
+        return Ok(body.content);
+    }
+
+    /// The only statement of the template.
+    pub fn statement(&self, replacements: Vec<(&str, Replacement)>) -> Result<StatementNode, TemplateError> {
+        let mut statements = self.statements(replacements)?;
+        if statements.len() != 1 {
+            return Err(TemplateError::Shape(String::from("a single statement")));
+        }
+        return Ok(statements.remove(0));
+    }
+
+    /// The template as one expression. It is parsed in brackets, so object literals need none.
+    pub fn expression(&self, replacements: Vec<(&str, Replacement)>) -> Result<ExpressionNode, TemplateError> {
+        let mut body = self.parse(&format!("({})", self.source), replacements)?;
+        if body.content.len() != 1 || body.directives.len() > 0 {
+            return Err(TemplateError::Shape(String::from("a single expression")));
+        }
+
+        return match body.content.remove(0).statement {
+            Statement::Expression { expression: ExpressionNode { expression: Expression::Bracketed { expression, .. }, .. } } => {
+                let mut expression = *expression;
+                expression.trivia.prefix = String::new();
+                expression.trivia.suffix = String::new();
+                Ok(expression)
+            }
+            _ => Err(TemplateError::Shape(String::from("a single expression")))
+        };
+    }
+
+    fn parse(&self, source: &str, replacements: Vec<(&str, Replacement)>) -> Result<BodyNode, TemplateError> {
+        let mut substitution = Substitution { placeholders: Vec::new() };
+        let source = substitution.write(source, replacements)?;
+
+        let mut chunk = Chunk::new(String::from("template"), ChunkLocation::virtual_module("template"));
+        chunk.set_source(source);
+        let mut parser = JsParser::new();
+        let result = parser.parse(ParserOptions::new(&mut chunk, SourceType::Module)).map_err(TemplateError::Syntax)?;
+
+        let mut body = *result.syntax_tree.base_node;
+        substitution.visit_body(&mut body);
+        if let Some(placeholder) = substitution.placeholders.iter().find(|placeholder| !placeholder.replaced) {
+            return Err(TemplateError::Misplaced(placeholder.name.to_owned()));
+        }
+        return Ok(body);
+    }
+}
+
+struct Placeholder {
+    name: String,
+    /// The identifier written for the placeholder.
+    marker: String,
+    replacement: Replacement,
+    position: Position,
+    replaced: bool,
+}
+
+/// Where a placeholder is used, which decides whether its replacement needs brackets.
+#[derive(Debug, Clone, Copy)]
+enum Position {
+    /// An operand binding at least as tightly as the precedence.
+    Operand(u8),
+    /// The object of a member expression, where the dot after an integer is its decimal point.
+    Object,
+    /// The callee of `new`, where a call would take the arguments of `new` for its own.
+    NewCallee,
+    /// An expression statement, which must not start like a declaration or a block.
+    Statement,
+}
+
+impl Position {
+    fn needs_brackets(&self, expression: &Expression) -> bool {
+        return match *self {
+            Position::Operand(precedence) => expression.precedence() < precedence,
+            Position::Object => expression.precedence() < MEMBER_PRECEDENCE || expression.is_integer(),
+            Position::NewCallee => expression.precedence() < MEMBER_PRECEDENCE || expression.contains_call(),
+            Position::Statement => starts_ambiguously(expression),
+        };
+    }
+}
+
+/// Whether an expression statement starting with the expression would be read as a declaration or
+/// a block.
+fn starts_ambiguously(expression: &Expression) -> bool {
+    return match *expression {
+        Expression::Object { .. } | Expression::Function { .. } | Expression::Class { .. } => true,
+        Expression::Call { ref callee, .. } => starts_ambiguously(&callee.expression),
+        Expression::Member { ref object, .. } | Expression::ComputedMember { ref object, .. } => starts_ambiguously(&object.expression),
+        Expression::Binary { ref left, .. } | Expression::Assignment { ref left, .. } => starts_ambiguously(&left.expression),
+        Expression::Conditional { ref test, .. } => starts_ambiguously(&test.expression),
+        Expression::Update { ref argument, prefix: false, .. } => starts_ambiguously(&argument.expression),
+        _ => false
+    };
+}
+
+/// Writes the placeholders of a template, then replaces those which stand for nodes once it is
+/// parsed. These are written as identifiers no template would use.
+struct Substitution {
+    placeholders: Vec<Placeholder>,
+}
+
+impl Substitution {
+    fn write(&mut self, template: &str, replacements: Vec<(&str, Replacement)>) -> Result<String, TemplateError> {
+        let mut used = vec![false; replacements.len()];
+
+        let mut source = String::new();
+        let mut rest = template;
+        while let Some(start) = rest.find("%%") {
+            let end = match rest[start + 2..].find("%%") {
+                Some(end) => start + 2 + end,
+                None => break
+            };
+            let name = &rest[start + 2..end];
+            source += &rest[..start];
+            rest = &rest[end + 2..];
+
+            let index = match replacements.iter().position(|&(replacement_name, _)| replacement_name == name) {
+                Some(index) => index,
+                None => return Err(TemplateError::MissingReplacement(name.to_owned()))
+            };
+            used[index] = true;
+
+            match replacements[index].1 {
+                Replacement::Name(ref identifier) if !lexicon::is_identifier(identifier) => {
+                    return Err(TemplateError::InvalidName(name.to_owned(), identifier.to_owned()));
+                }
+                Replacement::Name(ref identifier) => source += identifier,
+                Replacement::String(ref value) => source += &Literal::quoted(value, QuoteKind::SpeechMark).to_string(),
+                ref replacement => {
+                    let marker = format!("__maxwell_placeholder_{}", self.placeholders.len());
+                    source += &marker;
+                    let position = Position::Operand(ASSIGNMENT_PRECEDENCE);
+                    self.placeholders.push(Placeholder { name: name.to_owned(), marker, replacement: replacement.clone(), position, replaced: false });
+                }
+            }
+        }
+        source += rest;
+
+        if let Some(index) = used.iter().position(|used| !used) {
+            return Err(TemplateError::UnusedReplacement(replacements[index].0.to_owned()));
+        }
+        return Ok(source);
+    }
+
+    fn placeholder(&mut self, expression: &ExpressionNode) -> Option<&mut Placeholder> {
+        return match expression.expression {
+            Expression::Identifier(ref name) => self.placeholders.iter_mut().find(|placeholder| *name == placeholder.marker),
+            _ => None
+        };
+    }
+
+    /// Records where a placeholder is used, when the expression is one.
+    fn place(&mut self, expression: &ExpressionNode, position: Position) {
+        if let Some(placeholder) = self.placeholder(expression) {
+            placeholder.position = position;
+        }
+    }
+
+    /// The statements replacing a `%%name%%;` statement, if it is one.
+    fn statements(&mut self, statement: &StatementNode) -> Option<Vec<StatementNode>> {
+        let placeholder = match statement.statement {
+            Statement::Expression { ref expression } => self.placeholder(expression)?,
+            _ => return None
+        };
+        return match placeholder.replacement {
+            Replacement::Statements(ref statements) => {
+                placeholder.replaced = true;
+                Some(statements.clone())
+            }
+            _ => None
+        };
+    }
+}
+
+impl VisitMut for Substitution {
+    fn exit_body(&mut self, body: &mut BodyNode) {
+        let mut content = Vec::new();
+        for statement in mem::replace(&mut body.content, Vec::new()) {
+            match self.statements(&statement) {
+                Some(mut statements) => {
+                    if let Some(first) = statements.first_mut() {
+                        first.trivia.prefix = statement.trivia.prefix.to_owned();
+                    }
+                    content.extend(statements);
+                }
+                None => content.push(statement)
+            }
+        }
+        body.content = content;
+    }
+
+    fn enter_directive(&mut self, directive: &mut DirectiveNode) {
+        directive.location = SourceLocation::default();
+    }
+
+    fn enter_statement(&mut self, statement: &mut StatementNode) {
+        statement.location = SourceLocation::default();
+        if let Statement::Expression { ref expression } = statement.statement {
+            self.place(expression, Position::Statement);
+        }
+    }
+
+    fn enter_property(&mut self, property: &mut PropertyNode) {
+        property.location = SourceLocation::default();
+    }
+
+    /// Records the precedence asked of each operand, as the printer brackets them.
+    fn enter_expression(&mut self, expression: &mut ExpressionNode) {
+        match expression.expression {
+            Expression::Binary { ref left, ref operator, ref right, .. } => {
+                let precedence = operator.precedence();
+                // `**` groups to the right and its left operand must not be a unary expression.
+                let (left_precedence, right_precedence) = match *operator {
+                    OperatorKind::Exponent => (POSTFIX_PRECEDENCE, precedence),
+                    _ => (precedence, precedence + 1)
+                };
+                self.place(left, Position::Operand(left_precedence));
+                self.place(right, Position::Operand(right_precedence));
+            }
+            Expression::Assignment { ref left, .. } => self.place(left, Position::Operand(MEMBER_PRECEDENCE)),
+            Expression::Conditional { ref test, .. } => self.place(test, Position::Operand(OperatorKind::LogicalOr.precedence())),
+            Expression::Unary { operator: OperatorKind::Spread, .. } => {}
+            Expression::Unary { ref argument, .. } | Expression::Await { ref argument } => self.place(argument, Position::Operand(UNARY_PRECEDENCE)),
+            Expression::Update { ref argument, .. } => self.place(argument, Position::Operand(MEMBER_PRECEDENCE)),
+            Expression::Call { ref callee, .. } => self.place(callee, Position::Operand(MEMBER_PRECEDENCE)),
+            Expression::New { ref callee, .. } => self.place(callee, Position::NewCallee),
+            Expression::Member { ref object, .. } => self.place(object, Position::Object),
+            Expression::ComputedMember { ref object, .. } => self.place(object, Position::Operand(MEMBER_PRECEDENCE)),
+            Expression::Bracketed { ref expression, .. } => self.place(expression, Position::Operand(0)),
+            _ => {}
+        }
+    }
+
+    fn exit_expression(&mut self, expression: &mut ExpressionNode) {
+        expression.location = SourceLocation::default();
+        let (replacement, position) = match self.placeholder(expression) {
+            Some(placeholder) => match placeholder.replacement {
+                Replacement::Expression(ref replacement) => {
+                    placeholder.replaced = true;
+                    (replacement.clone(), placeholder.position)
+                }
+                _ => return
+            },
+            None => return
+        };
+
+        let prefix = mem::replace(&mut expression.trivia.prefix, String::new());
+        let suffix = mem::replace(&mut expression.trivia.suffix, String::new());
+        // The replacement is bracketed when it binds more loosely than the operators around the
+        // placeholder ask for, as in `a * %%value%%` or `%%value%%.call()`.
+        *expression = match position.needs_brackets(&replacement.expression) {
+            false => replacement,
+            true => ExpressionNode::new(Expression::Bracketed { expression: Box::new(replacement), trivia: BracketedTrivia::new() }, NodeTrivia::new())
+        };
+        expression.trivia.prefix = prefix;
+        expression.trivia.suffix = suffix;
+    }
+}
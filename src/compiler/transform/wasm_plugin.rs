@@ -0,0 +1,248 @@
+//! Transform plugins compiled to WebAssembly, run in an embedded interpreter. A module gets no
+//! imports, so it cannot reach the filesystem or anything else of the host, and every call is
+//! limited in fuel and memory, so a faulty plugin fails the build instead of hanging or crashing it.
+//!
+//! The syntax tree is exchanged as an ESTree `Program` inside a JSON message, with the locations of
+//! its nodes in the source of the chunk. A module exports:
+//!
+//! - `memory`
+//! - `maxwell_abi_version() -> i32`, returning `WASM_ABI_VERSION`
+//! - `maxwell_alloc(len: i32) -> i32`, returning a buffer of `len` bytes for the input
+//! - `maxwell_transform(ptr: i32, len: i32) -> i64`, taking the input and returning the output as
+//!   `ptr << 32 | len`
+//!
+//! The input is `{"program": {...}, "options": {...}}`. The output may hold a `"program"`, which
+//! replaces the tree when it differs from the input, and `"diagnostics"`, a list of
+//! `{"level": "error" | "warning", "message": "...", "start": 0, "end": 0}`. Offsets count UTF-16
+//! code units of the chunk source, like the `start` and `end` of nodes, which a replaced tree keeps
+//! as its locations. The tree is laid out anew when it is replaced, and the dependencies of the
+//! chunk are not updated from it.
+
+extern crate json;
+extern crate wasmi;
+
+use super::{Plugin, PluginPass, PluginRegistry, PluginResult, PluginDiagnostic, DiagnosticLevel};
+use super::super::ast::{SyntaxTree, SourceLocation};
+use super::super::ast::estree::{to_estree, from_estree_in};
+
+use self::wasmi::{Config, Engine, Linker, Module, Store, StoreLimits, StoreLimitsBuilder};
+
+use std::fs::File;
+use std::error::Error;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+/// Version of the exports and messages described above.
+pub const WASM_ABI_VERSION: i32 = 2;
+
+/// Instructions a module may run for a single chunk.
+const FUEL_PER_CALL: u64 = 1_000_000_000;
+
+/// Bytes of linear memory a module may grow to.
+const MEMORY_LIMIT: usize = 256 * 1024 * 1024;
+
+#[derive(Debug)]
+pub enum WasmPluginError {
+    Load(PathBuf, String),
+    AbiMismatch(PathBuf, i32),
+}
+
+impl fmt::Display for WasmPluginError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        return match *self {
+            WasmPluginError::Load(ref path, ref reason) => write!(f, "Cannot load WebAssembly plugin {}: {}", path.display(), reason),
+            WasmPluginError::AbiMismatch(ref path, version) => write!(f, "{} was built for WebAssembly plugin ABI {} but Maxwell uses {}", path.display(), version, WASM_ABI_VERSION),
+        };
+    }
+}
+
+impl Error for WasmPluginError {}
+
+/// A compiled module, shared by every plugin created from it.
+struct WasmModule {
+    engine: Engine,
+    module: Module,
+}
+
+impl WasmModule {
+    fn load(path: &Path) -> Result<Self, String> {
+        let file = match File::open(path) {
+            Ok(file) => file,
+            Err(err) => return Err(err.to_string())
+        };
+
+        let mut config = Config::default();
+        config.consume_fuel(true);
+        let engine = Engine::new(&config);
+        return match Module::new(&engine, file) {
+            Ok(module) => Ok(WasmModule { engine, module }),
+            Err(err) => Err(err.to_string())
+        };
+    }
+
+    /// Instantiates the module in a store of its own and calls the named export.
+    fn call(&self, input: &[u8]) -> Result<Vec<u8>, String> {
+        let limits = StoreLimitsBuilder::new().memory_size(MEMORY_LIMIT).build();
+        let mut store: Store<StoreLimits> = Store::new(&self.engine, limits);
+        store.limiter(|limits| limits);
+        if let Err(err) = store.add_fuel(FUEL_PER_CALL) {
+            return Err(err.to_string());
+        }
+
+        let linker: Linker<StoreLimits> = Linker::new(&self.engine);
+        let instance = match linker.instantiate(&mut store, &self.module).and_then(|instance| instance.start(&mut store)) {
+            Ok(instance) => instance,
+            Err(err) => return Err(err.to_string())
+        };
+
+        let memory = match instance.get_memory(&store, "memory") {
+            Some(memory) => memory,
+            None => return Err(String::from("Module does not export its memory"))
+        };
+        let alloc = match instance.get_typed_func::<i32, i32>(&store, "maxwell_alloc") {
+            Ok(func) => func,
+            Err(err) => return Err(err.to_string())
+        };
+        let transform = match instance.get_typed_func::<(i32, i32), i64>(&store, "maxwell_transform") {
+            Ok(func) => func,
+            Err(err) => return Err(err.to_string())
+        };
+
+        let input_ptr = match alloc.call(&mut store, input.len() as i32) {
+            Ok(ptr) => ptr,
+            Err(err) => return Err(err.to_string())
+        };
+        if let Err(err) = memory.write(&mut store, input_ptr as u32 as usize, input) {
+            return Err(err.to_string());
+        }
+
+        let packed = match transform.call(&mut store, (input_ptr, input.len() as i32)) {
+            Ok(packed) => packed as u64,
+            Err(err) => return Err(err.to_string())
+        };
+        let output_ptr = (packed >> 32) as usize;
+        let output_len = (packed & 0xFFFF_FFFF) as usize;
+        let data = memory.data(&store);
+        return match output_ptr.checked_add(output_len) {
+            Some(output_end) if output_end <= data.len() => Ok(data[output_ptr..output_end].to_vec()),
+            _ => Err(format!("Output of {} bytes at {} lies outside the {} bytes of module memory", output_len, output_ptr, data.len()))
+        };
+    }
+
+    fn abi_version(&self) -> Result<i32, String> {
+        let mut store = Store::new(&self.engine, ());
+        if let Err(err) = store.add_fuel(FUEL_PER_CALL) {
+            return Err(err.to_string());
+        }
+        let linker: Linker<()> = Linker::new(&self.engine);
+        let instance = match linker.instantiate(&mut store, &self.module).and_then(|instance| instance.start(&mut store)) {
+            Ok(instance) => instance,
+            Err(err) => return Err(err.to_string())
+        };
+        return match instance.get_typed_func::<(), i32>(&store, "maxwell_abi_version") {
+            Ok(func) => func.call(&mut store, ()).map_err(|err| err.to_string()),
+            Err(err) => Err(err.to_string())
+        };
+    }
+}
+
+/// A plugin running a WebAssembly module with the options given to it in `maxwell.json`.
+pub struct WasmPlugin {
+    name: String,
+    module: Rc<WasmModule>,
+    options: json::JsonValue,
+}
+
+impl WasmPlugin {
+    fn transform(&self, tree: &mut SyntaxTree, source: &str) -> Result<PluginResult, PluginDiagnostic> {
+        let program = to_estree(tree, source);
+        let mut input = json::JsonValue::new_object();
+        input["program"] = program.clone();
+        input["options"] = self.options.clone();
+
+        let output = match self.module.call(json::stringify(input).as_bytes()) {
+            Ok(output) => output,
+            Err(err) => return Err(PluginDiagnostic::error(format!("WebAssembly plugin failed: {}", err), SourceLocation::default()))
+        };
+        let output = match String::from_utf8(output).ok().and_then(|output| json::parse(&output).ok()) {
+            Some(output) => output,
+            None => return Err(PluginDiagnostic::error(String::from("WebAssembly plugin returned invalid JSON"), SourceLocation::default()))
+        };
+
+        let mut result = PluginResult::unchanged();
+        for diagnostic in output["diagnostics"].members() {
+            let level = match diagnostic["level"].as_str() {
+                Some("error") => DiagnosticLevel::Error,
+                _ => DiagnosticLevel::Warning
+            };
+            let message = diagnostic["message"].as_str().unwrap_or_default().to_owned();
+            let start = WasmPlugin::byte_offset(source, diagnostic["start"].as_usize().unwrap_or(0));
+            let end = WasmPlugin::byte_offset(source, diagnostic["end"].as_usize().unwrap_or(0));
+            let location = SourceLocation::new(start, end.max(start));
+            result.diagnostics.push(PluginDiagnostic::new(level, message, location));
+        }
+
+        if !output["program"].is_null() && output["program"] != program {
+            *tree = match from_estree_in(&output["program"], source) {
+                Ok(new_tree) => new_tree,
+                Err(err) => return Err(PluginDiagnostic::error(format!("WebAssembly plugin returned an invalid program: {}", err), SourceLocation::default()))
+            };
+            result.changed = true;
+        }
+        return Ok(result);
+    }
+
+    /// The byte offset of the source at a UTF-16 offset.
+    fn byte_offset(source: &str, offset: usize) -> usize {
+        let mut utf16_offset = 0;
+        for (index, char) in source.char_indices() {
+            if utf16_offset >= offset {
+                return index;
+            }
+            utf16_offset += char.len_utf16();
+        }
+        return source.len();
+    }
+}
+
+impl Plugin for WasmPlugin {
+    fn handle(&self, pass: &mut PluginPass) -> Result<PluginResult, PluginDiagnostic> {
+        return match *pass {
+            PluginPass::SyntaxTree { ref mut tree, source } => self.transform(tree, source),
+            _ => Ok(PluginResult::unchanged())
+        };
+    }
+
+    fn get_name(&self) -> &str {
+        return &self.name;
+    }
+
+    fn handles_output(&self) -> bool {
+        return false;
+    }
+}
+
+impl PluginRegistry {
+    /// Compiles a WebAssembly plugin and registers it under the file name without its extension,
+    /// returning that name.
+    pub fn load_wasm(&mut self, path: &Path) -> Result<String, WasmPluginError> {
+        let module = match WasmModule::load(path) {
+            Ok(module) => Rc::new(module),
+            Err(reason) => return Err(WasmPluginError::Load(path.to_path_buf(), reason))
+        };
+        match module.abi_version() {
+            Ok(WASM_ABI_VERSION) => {}
+            Ok(version) => return Err(WasmPluginError::AbiMismatch(path.to_path_buf(), version)),
+            Err(reason) => return Err(WasmPluginError::Load(path.to_path_buf(), reason))
+        }
+
+        let name = path.file_stem().and_then(|stem| stem.to_str()).unwrap_or_default().to_owned();
+        let plugin_name = name.clone();
+        self.register_boxed(&name, Box::new(move |options: &json::JsonValue| {
+            let plugin = WasmPlugin { name: plugin_name.clone(), module: module.clone(), options: options.clone() };
+            return Ok(Box::new(plugin) as Box<Plugin>);
+        }));
+        return Ok(name);
+    }
+}
@@ -0,0 +1,4 @@
+pub extern crate json;
+
+pub mod compiler;
+pub mod plugins;
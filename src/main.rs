@@ -1,30 +1,119 @@
-mod compiler;
-mod plugins;
+extern crate maxwell;
 
+use std::env;
+use std::fs::File;
+use std::io;
+use std::io::{IsTerminal, Read};
+use std::path::Path;
+use std::process;
 use std::time::Duration;
 
-use compiler::transform::PluginManager;
+use maxwell::json;
 
-//use plugins::{MinifyPlugin, PrettifyPlugin};
+use maxwell::compiler::transform::PluginManager;
+use maxwell::compiler::ast::estree;
+use maxwell::compiler::ast::estree::EstreeOptions;
+use maxwell::compiler::parser::SourceType;
+use maxwell::compiler::diagnostics::{Diagnostic, Renderer};
 
-use compiler::Compiler;
+use maxwell::compiler::{Compiler, Generator};
 
 fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    match args.first().map(|arg| arg.as_str()) {
+        Some("ast") => ast(&args[1..]),
+        _ => build()
+    }
+}
+
+fn build() {
     let mut compiler = Compiler::new();
 
     let plugins = PluginManager::new();
-    //plugins.add_plugin(MinifyPlugin {});
 
     match compiler.compile(plugins) {
         Ok(output) => {
-            println!("Compiled {} chunks in {}:", output.emit_count, duration_to_string(output.overall_duration));
+            let files = match output.file_count == output.emit_count {
+                true => String::new(),
+                false => format!(" ({} files)", output.file_count)
+            };
+            println!("Compiled {} chunks{} in {}:", output.emit_count, files, duration_to_string(output.overall_duration));
             println!("   Parse took {}", duration_to_string(output.parse_duration));
+            println!("   Transform took {}", duration_to_string(output.transform_duration));
             println!("   Emit took {}", duration_to_string(output.emit_duration));
+            let renderer = renderer();
+            for warning in &output.warnings {
+                eprintln!("{}\n", renderer.render(warning));
+            }
+        }
+        Err(err) => {
+            report(&err.diagnostics());
+            process::exit(1);
         }
+    }
+}
+
+/// Renders diagnostics to stderr, in colour when it is a terminal and `NO_COLOR` is not set.
+fn renderer() -> Renderer {
+    let colour = env::var_os("NO_COLOR").is_none() && io::stderr().is_terminal();
+    return Renderer::new().with_colour(colour);
+}
+
+fn report(diagnostics: &[Diagnostic]) {
+    let renderer = renderer();
+    for diagnostic in diagnostics {
+        eprintln!("{}\n", renderer.render(diagnostic));
+    }
+}
+
+/// `maxwell ast <file>` prints the ESTree JSON of a file, with `--preserve-parens` keeping brackets
+/// as `ParenthesizedExpression` nodes. With `--generate`, ESTree JSON is read from the file instead
+/// and the source generated from it is printed.
+fn ast(args: &[String]) {
+    let result = match args {
+        [ref flag, ref path] if flag == "--generate" => generate_from_estree(Path::new(path)),
+        [ref flag, ref path] if flag == "--preserve-parens" => print_estree(Path::new(path), true),
+        [ref path] => print_estree(Path::new(path), false),
+        _ => Err(String::from("Usage: maxwell ast [--generate | --preserve-parens] <file>"))
+    };
+    if let Err(err) = result {
+        eprintln!("{}", err);
+        process::exit(1);
+    }
+}
+
+fn print_estree(path: &Path, preserve_parens: bool) -> Result<(), String> {
+    let chunk = match Compiler::new().parse_file(path, SourceType::Module) {
+        Ok(chunk) => chunk,
         Err(err) => {
-            println!("Compiler error: \n{}", err.to_string());
+            report(&err.diagnostics());
+            process::exit(1);
         }
+    };
+    if let Some(ref tree) = chunk.syntax_tree {
+        let options = EstreeOptions::new().with_preserve_parens(preserve_parens);
+        println!("{}", json::stringify_pretty(estree::to_estree_with(tree, &chunk.source, &options), 2));
     }
+    return Ok(());
+}
+
+fn generate_from_estree(path: &Path) -> Result<(), String> {
+    let mut contents = String::new();
+    if let Err(err) = File::open(path).and_then(|mut file| file.read_to_string(&mut contents)) {
+        return Err(format!("Cannot read {}: {}", path.display(), err));
+    }
+    let program = match json::parse(&contents) {
+        Ok(program) => program,
+        Err(err) => return Err(format!("Invalid JSON in {}: {}", path.display(), err))
+    };
+    return match estree::from_estree(&program) {
+        Ok(tree) => {
+            let stdout = io::stdout();
+            let result = Generator::new().stream_tree(&tree, stdout.lock());
+            result.map(|_| ()).map_err(|err| format!("Cannot write the code: {}", err))
+        }
+        Err(err) => Err(err.to_string())
+    };
 }
 
 fn duration_to_string(duration: Duration) -> String {
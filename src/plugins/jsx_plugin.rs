@@ -0,0 +1,293 @@
+use compiler::transform::{PluginPass, PluginResult, PluginDiagnostic};
+use compiler::transform::Plugin;
+use compiler::{JsxOptions, JsxRuntime, Literal};
+
+use std::result::Result;
+use std::mem;
+
+use compiler::ast::{SourceLocation, SyntaxTree};
+use compiler::ast::expression::{ExpressionNode, Expression};
+use compiler::ast::property::PropertyNode;
+use compiler::ast::jsx::{JsxElement, JsxAttribute, JsxAttributeValue, JsxChild};
+use compiler::ast::visit_mut::VisitMut;
+use compiler::ast::build::{identifier, literal, string, path, call, array, object, property, import_named};
+
+/// Helpers of the automatic runtime, in the order they are imported.
+const RUNTIME_HELPERS: [&str; 3] = ["jsx", "jsxs", "Fragment"];
+
+/// Lowers JSX to calls of the classic factory or of the automatic runtime.
+pub struct JsxPlugin {
+    options: JsxOptions,
+}
+
+impl JsxPlugin {
+    pub fn new(options: JsxOptions) -> Self {
+        return JsxPlugin { options };
+    }
+}
+
+/// Lowers the elements of one syntax tree. Elements are replaced when they are exited, after their
+/// children, so an element only ever holds children which are already calls.
+struct JsxLowering<'a> {
+    options: &'a JsxOptions,
+    used_helpers: Vec<&'static str>,
+    result: PluginResult,
+}
+
+impl<'a> JsxLowering<'a> {
+    fn use_helper(&mut self, helper: &'static str) -> ExpressionNode {
+        if !self.used_helpers.contains(&helper) {
+            self.used_helpers.push(helper);
+        }
+        return identifier(&format!("_{}", helper));
+    }
+
+    fn lower(&mut self, element: JsxElement, location: &SourceLocation) -> Expression {
+        let automatic = self.options.runtime == JsxRuntime::Automatic;
+        self.result.changed = true;
+
+        if let Some(ref name) = element.name {
+            if name.contains(':') {
+                self.result.warn(format!("Namespaced JSX name '{}' is not supported by React, it is passed on as a string.", name), location.clone());
+            }
+        }
+
+        let element_type = match element.name {
+            Some(ref name) => element_type(name),
+            None if automatic => self.use_helper("Fragment"),
+            None => path(&self.options.fragment)
+        };
+
+        let mut key = None;
+        let mut segments = Vec::new();
+        let mut properties = Vec::new();
+        for attribute in element.attributes {
+            match attribute {
+                JsxAttribute::Named { name, value, .. } => {
+                    let value = match value {
+                        None => literal(Literal::Boolean(true)),
+                        Some(JsxAttributeValue::String(value, quote)) => literal(Literal::quoted(&decode_entities(&value), quote)),
+                        Some(JsxAttributeValue::Container(container)) => match container.expression {
+                            Some(expression) => expression,
+                            None => continue
+                        },
+                        Some(JsxAttributeValue::Element(element)) => element,
+                    };
+                    if automatic && name == "key" {
+                        key = Some(value);
+                        continue;
+                    }
+                    properties.push(property(&name, value));
+                }
+                JsxAttribute::Spread { argument, .. } => {
+                    if properties.len() > 0 {
+                        segments.push(object(mem::replace(&mut properties, Vec::new())));
+                    }
+                    segments.push(argument);
+                }
+            }
+        }
+
+        let children = lower_children(element.children.unwrap_or_default());
+        let callee = match automatic {
+            true if children.len() > 1 => self.use_helper("jsxs"),
+            true => self.use_helper("jsx"),
+            false => path(&self.options.factory)
+        };
+
+        let mut arguments = vec![element_type];
+        if automatic {
+            match children.len() {
+                0 => {}
+                1 => properties.push(property("children", children.into_iter().next().unwrap())),
+                _ => properties.push(property("children", array(children))),
+            }
+            arguments.push(merge_props(segments, properties, false));
+            if let Some(key) = key {
+                arguments.push(key);
+            }
+        } else {
+            arguments.push(merge_props(segments, properties, true));
+            arguments.extend(children);
+        }
+
+        return call(callee, arguments).expression;
+    }
+
+    /// Imports the runtime helpers used by the chunk, `import { jsx as _jsx } from "react/jsx-runtime";`.
+    fn insert_runtime_import(&self, tree: &mut SyntaxTree) {
+        if self.used_helpers.len() == 0 {
+            return;
+        }
+
+        let aliases: Vec<String> = RUNTIME_HELPERS.iter().map(|helper| format!("_{}", helper)).collect();
+        let specifiers: Vec<(&str, Option<&str>)> = RUNTIME_HELPERS.iter().zip(aliases.iter())
+            .filter(|&(helper, _)| self.used_helpers.contains(helper))
+            .map(|(helper, alias)| (*helper, Some(alias.as_str())))
+            .collect();
+
+        let source = format!("{}/jsx-runtime", self.options.import_source);
+        tree.base_node.insert_statements(0, vec![import_named(&specifiers, &source)]);
+    }
+}
+
+impl<'a> VisitMut for JsxLowering<'a> {
+    fn exit_expression(&mut self, expression_node: &mut ExpressionNode) {
+        if let Expression::Jsx(_) = expression_node.expression {
+            if let Expression::Jsx(element) = mem::replace(&mut expression_node.expression, Expression::Void) {
+                expression_node.expression = self.lower(*element, &expression_node.location);
+            }
+        }
+    }
+
+    fn exit_syntax_tree(&mut self, tree: &mut SyntaxTree) {
+        self.insert_runtime_import(tree);
+    }
+}
+
+impl Plugin for JsxPlugin {
+    fn handle(&self, pass: &mut PluginPass) -> Result<PluginResult, PluginDiagnostic> {
+        use self::PluginPass::*;
+
+        if let &mut SyntaxTree { ref mut tree, .. } = pass {
+            let mut lowering = JsxLowering { options: &self.options, used_helpers: Vec::new(), result: PluginResult::unchanged() };
+            lowering.visit_syntax_tree(tree);
+            return Ok(lowering.result);
+        }
+        return Ok(PluginResult::unchanged());
+    }
+
+    fn get_name(&self) -> &str {
+        return "internal.jsx";
+    }
+
+    fn handles_output(&self) -> bool {
+        return false;
+    }
+}
+
+/// Lowercase and dashed names are intrinsic elements passed as strings, anything else is a reference
+/// to a component.
+fn element_type(name: &str) -> ExpressionNode {
+    if name.contains(':') {
+        return string(name);
+    }
+    if name.contains('.') {
+        return path(name);
+    }
+    let intrinsic = name.contains('-') || name.chars().next().map_or(false, |char| char.is_lowercase());
+    return match intrinsic {
+        true => string(name),
+        false => identifier(name)
+    };
+}
+
+/// Combines attribute objects and spread arguments into one props expression. Spreads are merged
+/// with `Object.assign` into a fresh object, so they are never mutated.
+fn merge_props(mut segments: Vec<ExpressionNode>, properties: Vec<PropertyNode>, null_if_empty: bool) -> ExpressionNode {
+    if properties.len() > 0 || segments.len() == 0 {
+        if segments.len() == 0 && properties.len() == 0 && null_if_empty {
+            return literal(Literal::Null);
+        }
+        segments.push(object(properties));
+    }
+    if segments.len() == 1 {
+        if let Expression::Object { .. } = segments[0].expression {
+            return segments.remove(0);
+        }
+    }
+
+    let mut arguments = vec![object(Vec::new())];
+    arguments.extend(segments);
+    return call(path("Object.assign"), arguments);
+}
+
+/// Children as call arguments. Text is trimmed the way React does, empty containers are dropped,
+/// and spread children are passed as the array they spread.
+fn lower_children(children: Vec<JsxChild>) -> Vec<ExpressionNode> {
+    let mut lowered = Vec::new();
+    for child in children {
+        match child {
+            JsxChild::Text(text) => {
+                let text = clean_text(&text);
+                if !text.is_empty() {
+                    lowered.push(string(&decode_entities(&text)));
+                }
+            }
+            JsxChild::Container(container) => {
+                if let Some(expression) = container.expression {
+                    lowered.push(expression);
+                }
+            }
+            JsxChild::Spread { argument, .. } => lowered.push(argument),
+            JsxChild::Element(element) => lowered.push(element),
+        }
+    }
+    return lowered;
+}
+
+/// Collapses the whitespace of JSX text. Lines are trimmed except at the outer edges of the text,
+/// blank lines are dropped and the rest are joined with single spaces.
+fn clean_text(text: &str) -> String {
+    let lines: Vec<&str> = text.split('\n').map(|line| line.trim_end_matches('\r')).collect();
+    let last_non_empty = lines.iter().rposition(|line| line.chars().any(|char| char != ' ' && char != '\t'));
+
+    let mut string = String::new();
+    for (i, line) in lines.iter().enumerate() {
+        let mut trimmed = line.replace('\t', " ");
+        if i > 0 {
+            trimmed = trimmed.trim_start_matches(' ').to_owned();
+        }
+        if i < lines.len() - 1 {
+            trimmed = trimmed.trim_end_matches(' ').to_owned();
+        }
+        if trimmed.is_empty() {
+            continue;
+        }
+        string += &trimmed;
+        if Some(i) != last_non_empty {
+            string += " ";
+        }
+    }
+    return string;
+}
+
+/// Decodes the HTML character references JSX text and attribute strings may contain.
+fn decode_entities(text: &str) -> String {
+    let mut string = String::new();
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        string += &rest[..start];
+        rest = &rest[start..];
+
+        let decoded = rest.find(';').and_then(|end| {
+            let entity = &rest[1..end];
+            let char = match entity {
+                "amp" => Some('&'),
+                "lt" => Some('<'),
+                "gt" => Some('>'),
+                "quot" => Some('"'),
+                "apos" => Some('\''),
+                "nbsp" => Some('\u{A0}'),
+                _ if entity.starts_with("#x") || entity.starts_with("#X") => u32::from_str_radix(&entity[2..], 16).ok().and_then(::std::char::from_u32),
+                _ if entity.starts_with('#') => entity[1..].parse::<u32>().ok().and_then(::std::char::from_u32),
+                _ => None
+            };
+            char.map(|char| (char, end))
+        });
+
+        match decoded {
+            Some((char, end)) => {
+                string.push(char);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                string.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    string += rest;
+    return string;
+}
+
@@ -1,49 +1,67 @@
-use compiler::transform::PluginPass;
+extern crate json;
+
+use compiler::transform::{PluginPass, PluginResult, PluginDiagnostic};
 use compiler::transform::Plugin;
 
 use std::result::Result;
-use std::error::Error;
-
-use compiler::ast::statement::StatementTerminator;
 
-use compiler::ast::expression::Expression;
+use compiler::ast::statement::{StatementNode, StatementTerminator};
+use compiler::ast::expression::{ExpressionNode, Expression};
+use compiler::ast::visit_mut::VisitMut;
 
 pub struct MinifyPlugin {}
 
+impl MinifyPlugin {
+    /// Takes no options yet, so only an empty options object is accepted.
+    pub fn from_config(options: &json::JsonValue) -> Result<Box<Plugin>, String> {
+        if options.len() > 0 {
+            return Err(String::from("No options are supported"));
+        }
+        return Ok(Box::new(MinifyPlugin {}));
+    }
+}
+
 impl Plugin for MinifyPlugin {
-    fn handle(&self, pass: &mut PluginPass) -> Result<String, Box<Error>> {
+    fn handle(&self, pass: &mut PluginPass) -> Result<PluginResult, PluginDiagnostic> {
         use self::PluginPass::*;
-        use self::Expression::*;
 
         match pass {
-            &mut StatementNodeEmit(ref mut statement_node) => {
-                statement_node.trivia.prefix = String::from("");
-                statement_node.trivia.suffix = String::from("");
-                if statement_node.terminator == StatementTerminator::Newline {
-                    statement_node.terminator = StatementTerminator::Semicolon;
-                }
-                Ok(String::from("hello world"))
-            }
-            &mut ExpressionNodeEmit(ref mut expression_node) => {
-                match expression_node.expression {
-                    Function { ref mut trivia ,..} => {
-                        //TODO: trivia.cleanup()
-                        trivia.identifier_gap = String::from(" ");
-                        trivia.parameters_gap = String::from("");
-                        trivia.body_gap = String::from("");
-                        trivia.body_suffix = String::from("");
-                        trivia.parameters_padding = String::from("");
-
-                        Ok(String::from(""))
-                    },
-                    _ => Ok(String::from(""))
-                }
+            &mut SyntaxTree { ref mut tree, .. } => {
+                MinifyVisitor {}.visit_syntax_tree(tree);
+                Ok(PluginResult::changed())
             }
-            _ => Ok("".to_owned())
+            _ => Ok(PluginResult::unchanged())
         }
     }
 
     fn get_name(&self) -> &str {
         return "internal.minify";
     }
-}
\ No newline at end of file
+
+    fn handles_output(&self) -> bool {
+        return false;
+    }
+}
+
+struct MinifyVisitor {}
+
+impl VisitMut for MinifyVisitor {
+    fn enter_statement(&mut self, statement_node: &mut StatementNode) {
+        statement_node.trivia.prefix = String::from("");
+        statement_node.trivia.suffix = String::from("");
+        if let StatementTerminator::Newline(_) = statement_node.terminator {
+            statement_node.terminator = StatementTerminator::Semicolon;
+        }
+    }
+
+    fn enter_expression(&mut self, expression_node: &mut ExpressionNode) {
+        if let Expression::Function { ref mut trivia, .. } = expression_node.expression {
+            //TODO: trivia.cleanup()
+            trivia.identifier_gap = String::from(" ");
+            trivia.parameters_gap = String::from("");
+            trivia.body_gap = String::from("");
+            trivia.body_suffix = String::from("");
+            trivia.parameters_padding = String::from("");
+        }
+    }
+}
@@ -1,5 +1,16 @@
 mod minify_plugin;
 mod prettify_plugin;
+mod jsx_plugin;
+mod virtual_plugin;
 
 pub use self::prettify_plugin::PrettifyPlugin;
-pub use self::minify_plugin::MinifyPlugin;
\ No newline at end of file
+pub use self::minify_plugin::MinifyPlugin;
+pub use self::jsx_plugin::JsxPlugin;
+pub use self::virtual_plugin::VirtualModulesPlugin;
+use compiler::transform::PluginRegistry;
+
+/// Registers the plugins which can be enabled from `maxwell.json`.
+pub fn register_builtins(registry: &mut PluginRegistry) {
+    registry.register("minify", MinifyPlugin::from_config);
+    registry.register("prettify", PrettifyPlugin::from_config);
+}
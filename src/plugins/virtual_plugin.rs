@@ -0,0 +1,45 @@
+use compiler::transform::{PluginPass, PluginResult, PluginDiagnostic};
+use compiler::transform::Plugin;
+use compiler::ChunkLocation;
+
+use std::result::Result;
+use std::collections::HashMap;
+
+/// Serves the modules of the `"virtualModules"` section of `maxwell.json`, which map an import
+/// specifier such as `virtual:build-info` to its source.
+pub struct VirtualModulesPlugin {
+    modules: HashMap<String, String>,
+}
+
+impl VirtualModulesPlugin {
+    pub fn new(modules: HashMap<String, String>) -> Self {
+        return VirtualModulesPlugin { modules };
+    }
+}
+
+impl Plugin for VirtualModulesPlugin {
+    fn handle(&self, pass: &mut PluginPass) -> Result<PluginResult, PluginDiagnostic> {
+        use self::PluginPass::*;
+
+        match *pass {
+            ResolveId { source, ref mut resolved, .. } if self.modules.contains_key(source) => {
+                **resolved = Some(ChunkLocation::virtual_module(source));
+            }
+            Load { location, ref mut source } if location.is_virtual => {
+                if let Some(module) = location.path.to_str().and_then(|id| self.modules.get(id)) {
+                    **source = Some(module.to_owned());
+                }
+            }
+            _ => {}
+        }
+        return Ok(PluginResult::unchanged());
+    }
+
+    fn get_name(&self) -> &str {
+        return "internal.virtual";
+    }
+
+    fn handles_output(&self) -> bool {
+        return false;
+    }
+}
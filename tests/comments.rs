@@ -0,0 +1,84 @@
+extern crate maxwell;
+
+mod common;
+
+use maxwell::compiler::ast::SourceLocation;
+use maxwell::compiler::comments::{CommentMap, CommentPlacement};
+use common::{at, parse};
+
+/// The placement and node of the only comment of a source.
+fn attachment(source: &str) -> (CommentPlacement, SourceLocation) {
+    let map = CommentMap::attach(&parse(source), source);
+    assert_eq!(map.comments.len(), 1);
+    let attachment = map.attachment(0);
+    return (attachment.placement, attachment.node.clone());
+}
+
+#[test]
+fn leads_the_node_after_a_comment_on_its_own_line() {
+    let source = "x = 1;\n// next\ny = 2;\n";
+    assert_eq!(attachment(source), (CommentPlacement::Leading, at(source, "y = 2;")));
+}
+
+#[test]
+fn trails_the_node_before_a_comment_ending_a_line() {
+    let source = "x = 1; // first\ny = 2;\n";
+    assert_eq!(attachment(source), (CommentPlacement::Trailing, at(source, "x = 1;")));
+}
+
+#[test]
+fn leads_the_node_after_a_comment_inside_a_line() {
+    let source = "x = /* value */ 1;\n";
+    assert_eq!(attachment(source), (CommentPlacement::Leading, at(source, "1")));
+}
+
+#[test]
+fn trails_a_comment_at_the_end_of_the_source() {
+    let source = "x = 1;\n// end\n";
+    assert_eq!(attachment(source), (CommentPlacement::Trailing, at(source, "x = 1;")));
+}
+
+#[test]
+fn puts_comments_without_nodes_next_to_them_inside_the_enclosing_node() {
+    let source = "function f() {\n  // todo\n}\n";
+    assert_eq!(attachment(source), (CommentPlacement::Inner, at(source, "function f() {\n  // todo\n}")));
+
+    let source = "f(/* nothing */);\n";
+    assert_eq!(attachment(source), (CommentPlacement::Inner, at(source, "f(/* nothing */)")));
+}
+
+#[test]
+fn keeps_comments_inside_brackets_away_from_nodes_outside_of_them() {
+    let source = "x = [a, /* hole */];\n";
+    assert_eq!(attachment(source), (CommentPlacement::Trailing, at(source, "a")));
+
+    let source = "x = a + f(/* none */);\n";
+    assert_eq!(attachment(source), (CommentPlacement::Inner, at(source, "f(/* none */)")));
+}
+
+#[test]
+fn attaches_comments_to_parameters() {
+    let source = "function f(a /* first */, b) {}\n";
+    assert_eq!(attachment(source), (CommentPlacement::Trailing, at(source, "a")));
+
+    let source = "function f(a, /* second */ b) {}\n";
+    assert_eq!(attachment(source), (CommentPlacement::Leading, at(source, "b")));
+}
+
+#[test]
+fn finds_pure_annotations() {
+    let source = "var x = /*#__PURE__*/ make();\nvar y = make();\n";
+    let map = CommentMap::attach(&parse(source), source);
+    let second = source.rfind("make()").unwrap();
+    assert!(map.is_pure(&at(source, "make()")));
+    assert!(!map.is_pure(&SourceLocation::new(second, second + 6)));
+}
+
+#[test]
+fn finds_the_closest_jsdoc() {
+    let source = "/** Old. */\n// note\n/** Adds. */\nfunction add(a, b) { return a + b; }\n";
+    let map = CommentMap::attach(&parse(source), source);
+    let jsdoc = map.jsdoc(&at(source, "function add(a, b) { return a + b; }")).unwrap();
+    assert_eq!(jsdoc.text, "/** Adds. */");
+    assert_eq!(map.leading(&at(source, "function add(a, b) { return a + b; }")).len(), 3);
+}
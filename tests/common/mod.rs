@@ -0,0 +1,24 @@
+//! Helpers shared by the integration tests. Each test crate only uses some of them.
+#![allow(dead_code)]
+
+use maxwell::compiler::{Chunk, ChunkLocation};
+use maxwell::compiler::ast::{SourceLocation, SyntaxTree};
+use maxwell::compiler::parser::{JsParser, Parser, ParserError, ParserOptions, ParserResult, SourceType};
+
+/// Parses a source as the virtual chunk `name`.
+pub fn parse_with<P: Parser>(mut parser: P, name: &str, source: &str, source_type: SourceType) -> Result<ParserResult, ParserError> {
+    let mut chunk = Chunk::new(String::from(name), ChunkLocation::virtual_module(name));
+    chunk.set_source(source.to_owned());
+    return parser.parse(ParserOptions::new(&mut chunk, source_type));
+}
+
+/// Parses a JavaScript module.
+pub fn parse(source: &str) -> SyntaxTree {
+    return parse_with(JsParser::new(), "input.js", source, SourceType::Module).unwrap().syntax_tree;
+}
+
+/// The location of the first occurrence of some code in the source.
+pub fn at(source: &str, code: &str) -> SourceLocation {
+    let start = source.find(code).unwrap();
+    return SourceLocation::new(start, start + code.len());
+}
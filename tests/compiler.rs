@@ -0,0 +1,176 @@
+extern crate maxwell;
+
+use maxwell::compiler::{ChunkLocation, CompileResult, Compiler, CompilerError, CompilerOptions};
+use maxwell::compiler::ast::SourceLocation;
+use maxwell::compiler::transform::{Plugin, PluginDiagnostic, PluginManager, PluginPass, PluginResult};
+
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// A project in a fresh temporary directory, with `maxwell.json` and the given files under `src`.
+fn project(name: &str, config: &str, files: &[(&str, &str)]) -> PathBuf {
+    let dir = env::temp_dir().join(format!("maxwell-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(dir.join("src")).unwrap();
+    fs::write(dir.join("maxwell.json"), config).unwrap();
+    for &(path, source) in files {
+        fs::write(dir.join("src").join(path), source).unwrap();
+    }
+    return dir;
+}
+
+fn compile(dir: &Path, plugin_manager: PluginManager) -> Result<CompileResult, CompilerError> {
+    let options = CompilerOptions::load_from(dir.to_path_buf()).unwrap();
+    return Compiler::new().compile_with(options, plugin_manager);
+}
+
+fn compile_error(dir: &Path, plugin_manager: PluginManager) -> CompilerError {
+    return match compile(dir, plugin_manager) {
+        Ok(_) => panic!("the build should fail"),
+        Err(err) => err
+    };
+}
+
+fn messages(err: &CompilerError) -> Vec<String> {
+    return err.diagnostics().into_iter().map(|diagnostic| diagnostic.message).collect();
+}
+
+const CONFIG: &str = r#"{ "out": "out", "src": { "base": "src", "sourceType": "module", "entry": "src/main.js" } }"#;
+
+/// Resolves `virtual:broken` and fails to load it.
+struct BrokenLoad;
+
+impl Plugin for BrokenLoad {
+    fn handle(&self, pass: &mut PluginPass) -> Result<PluginResult, PluginDiagnostic> {
+        match *pass {
+            PluginPass::ResolveId { source, ref mut resolved, .. } if source == "virtual:broken" => {
+                **resolved = Some(ChunkLocation::virtual_module(source));
+            }
+            PluginPass::Load { location, .. } if location.is_virtual => {
+                return Err(PluginDiagnostic::error(String::from("cannot load virtual:broken"), SourceLocation::default()));
+            }
+            _ => {}
+        }
+        return Ok(PluginResult::unchanged());
+    }
+
+    fn get_name(&self) -> &str {
+        return "broken-load";
+    }
+}
+
+/// Serves `virtual:plugin` as a module of its own.
+struct VirtualPlugin;
+
+impl Plugin for VirtualPlugin {
+    fn handle(&self, pass: &mut PluginPass) -> Result<PluginResult, PluginDiagnostic> {
+        match *pass {
+            PluginPass::ResolveId { source, ref mut resolved, .. } if source == "virtual:plugin" => {
+                **resolved = Some(ChunkLocation::virtual_module(source));
+            }
+            PluginPass::Load { location, ref mut source } if location.path == PathBuf::from("virtual:plugin") => {
+                **source = Some(String::from("export const c = 3;\n"));
+            }
+            _ => {}
+        }
+        return Ok(PluginResult::unchanged());
+    }
+
+    fn get_name(&self) -> &str {
+        return "virtual";
+    }
+}
+
+/// Warns once a build starts.
+struct Warn;
+
+impl Plugin for Warn {
+    fn handle(&self, pass: &mut PluginPass) -> Result<PluginResult, PluginDiagnostic> {
+        if let PluginPass::BuildStart(_) = *pass {
+            let mut result = PluginResult::unchanged();
+            result.warn(String::from("build is slow"), SourceLocation::default());
+            return Ok(result);
+        }
+        return Ok(PluginResult::unchanged());
+    }
+
+    fn get_name(&self) -> &str {
+        return "warn";
+    }
+}
+
+#[test]
+fn reports_errors_of_load_plugins() {
+    let dir = project("load-error", CONFIG, &[("main.js", "import broken from \"virtual:broken\";\n")]);
+    let mut plugin_manager = PluginManager::new();
+    plugin_manager.add_plugin(BrokenLoad);
+
+    let err = compile_error(&dir, plugin_manager);
+    match err {
+        CompilerError::PluginError(_) => {}
+        ref err => panic!("expected the error of the plugin, got {:?}", err)
+    }
+    assert_eq!(messages(&err), vec![String::from("cannot load virtual:broken")]);
+}
+
+#[test]
+fn keeps_plugin_diagnostics_when_a_build_fails() {
+    let dir = project("failed-build", CONFIG, &[("main.js", "let = ;\n")]);
+    let mut plugin_manager = PluginManager::new();
+    plugin_manager.add_plugin(Warn);
+
+    let err = compile_error(&dir, plugin_manager);
+    let messages = messages(&err);
+    assert_eq!(messages.len(), 2, "{:?}", messages);
+    assert_eq!(messages[0], "build is slow");
+}
+
+/// The files under a directory, relative to `base`.
+fn files(dir: &Path, base: &Path) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    for entry in fs::read_dir(dir).unwrap() {
+        let path = entry.unwrap().path();
+        match path.is_dir() {
+            true => files.extend(self::files(&path, base)),
+            false => files.push(path.strip_prefix(base).unwrap().to_path_buf())
+        }
+    }
+    return files;
+}
+
+#[test]
+fn writes_virtual_modules_from_the_config_and_plugins_to_files_of_their_own() {
+    let config = r#"{
+        "out": "out",
+        "src": { "base": "src", "sourceType": "module", "entry": "src/main.js" },
+        "virtualModules": { "virtual:a": "export const a = 1;\n", "virtual_a": "export const b = 2;\n" }
+    }"#;
+    let main = "import { a } from \"virtual:a\";\nimport { b } from \"virtual_a\";\nimport { c } from \"virtual:plugin\";\n";
+    let dir = project("virtual-modules", config, &[("main.js", main)]);
+    let mut plugin_manager = PluginManager::new();
+    plugin_manager.add_plugin(VirtualPlugin);
+    compile(&dir, plugin_manager).unwrap();
+
+    let out = dir.join("out");
+    assert_eq!(files(&out, &out).len(), 4, "{:?}", files(&out, &out));
+
+    // Each import of the entry points at the file its module was written to.
+    let main = fs::read_to_string(out.join("main.js")).unwrap();
+    let modules: Vec<String> = main.lines().map(|line| {
+        let specifier = line.split('"').nth(1).unwrap();
+        assert!(specifier.starts_with("./_virtual/"), "{}", line);
+        fs::read_to_string(out.join(specifier)).unwrap()
+    }).collect();
+    assert_eq!(modules, vec!["export const a = 1;\n", "export const b = 2;\n", "export const c = 3;\n"]);
+}
+
+#[test]
+fn counts_chunks_apart_from_the_files_written() {
+    let config = r#"{ "out": "out", "src": { "base": "src", "sourceType": "module", "entry": "src/main.js" }, "sourceMap": true }"#;
+    let dir = project("counts", config, &[("main.js", "import { a } from \"./a.js\";\n"), ("a.js", "export const a = 1;\n")]);
+
+    let result = compile(&dir, PluginManager::new()).unwrap();
+    assert_eq!(result.emit_count, 2);
+    assert_eq!(result.file_count, 4);
+}
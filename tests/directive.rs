@@ -0,0 +1,34 @@
+extern crate maxwell;
+
+mod common;
+
+use maxwell::compiler::Generator;
+use maxwell::compiler::ast::SyntaxTree;
+use maxwell::compiler::parser::{JsParser, SourceType};
+use common::parse_with;
+
+fn parse(source: &str) -> SyntaxTree {
+    return parse_with(JsParser::new(), "input.js", source, SourceType::Script).unwrap().syntax_tree;
+}
+
+fn insert_directive(source: &str) -> String {
+    let mut tree = parse(source);
+    tree.insert_directive("use strict");
+    return Generator::new().generate_tree(&tree);
+}
+
+#[test]
+fn inserts_directive_before_first_statement() {
+    assert_eq!(insert_directive("foo();\n"), "'use strict';\nfoo();\n");
+}
+
+#[test]
+fn inserts_directive_after_hashbang() {
+    assert_eq!(insert_directive("#!/usr/bin/env node\nfoo();\n"), "#!/usr/bin/env node\n'use strict';\nfoo();\n");
+    assert_eq!(insert_directive("#!/usr/bin/env node\n"), "#!/usr/bin/env node\n'use strict';\n");
+}
+
+#[test]
+fn inserts_directive_after_existing_directives() {
+    assert_eq!(insert_directive("#!/usr/bin/env node\n\"use client\";\nfoo();\n"), "#!/usr/bin/env node\n\"use client\";\n\"use strict\";\nfoo();\n");
+}
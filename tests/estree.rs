@@ -0,0 +1,72 @@
+extern crate json;
+extern crate maxwell;
+
+mod common;
+
+use json::JsonValue;
+use maxwell::compiler::Generator;
+use maxwell::compiler::ast::estree::{to_estree, to_estree_with, from_estree, from_estree_in, EstreeOptions, EstreeError};
+use common::parse;
+
+#[test]
+fn keeps_locations_in_the_exported_source() {
+    // The `é` and the emoji are wider in UTF-8 than in UTF-16, which ESTree offsets count.
+    let source = "let é = '😀';\nfoo(é);\n";
+    let tree = parse(source);
+    let imported = from_estree_in(&to_estree(&tree, source), source).unwrap();
+
+    let original = &tree.base_node.content[1];
+    let statement = &imported.base_node.content[1];
+    assert_eq!(statement.location, original.location);
+    assert_eq!(&source[statement.location.start..statement.location.end], "foo(é);");
+}
+
+fn regenerate(source: &str) -> String {
+    let tree = parse(source);
+    let imported = from_estree(&to_estree(&tree, source)).unwrap();
+    return Generator::new().generate_tree(&imported);
+}
+
+#[test]
+fn brackets_operands_by_precedence() {
+    assert_eq!(regenerate("x = (a + b) * c;"), "x = (a + b) * c;\n");
+    assert_eq!(regenerate("x = a - (b - c);"), "x = a - (b - c);\n");
+    assert_eq!(regenerate("x = (-a) ** b;"), "x = (-a) ** b;\n");
+    assert_eq!(regenerate("x = (a ? b : c) ? d : e;"), "x = (a ? b : c) ? d : e;\n");
+    assert_eq!(regenerate("new (f())();"), "new (f())();\n");
+    assert_eq!(regenerate("(1).toString();"), "(1).toString();\n");
+}
+
+#[test]
+fn exports_parenthesized_expressions_when_asked() {
+    let source = "x = (a);";
+    let tree = parse(source);
+    let expression = |program: JsonValue| program["body"][0]["expression"]["right"]["type"].as_str().unwrap_or("").to_owned();
+    assert_eq!(expression(to_estree(&tree, source)), "Identifier");
+    let options = EstreeOptions::new().with_preserve_parens(true);
+    assert_eq!(expression(to_estree_with(&tree, source, &options)), "ParenthesizedExpression");
+}
+
+#[test]
+fn locates_names() {
+    let source = "let value = a.property;";
+    let program = to_estree(&parse(source), source);
+    let declarator = &program["body"][0]["declarations"][0];
+    let text = |node: &JsonValue| source[node["start"].as_usize().unwrap()..node["end"].as_usize().unwrap()].to_owned();
+    assert_eq!(text(&declarator["id"]), "value");
+    assert_eq!(text(&declarator["init"]["property"]), "property");
+}
+
+#[test]
+fn rejects_invalid_identifiers() {
+    let program = json::parse(r#"{"type": "Program", "body": [{"type": "ExpressionStatement", "expression": {"type": "Identifier", "name": "a b"}}]}"#).unwrap();
+    assert_eq!(from_estree(&program), Err(EstreeError::InvalidIdentifier(String::from("a b"))));
+    let program = json::parse(r#"{"type": "Program", "body": [{"type": "ExpressionStatement", "expression": {"type": "Identifier", "name": "class"}}]}"#).unwrap();
+    assert_eq!(from_estree(&program), Err(EstreeError::InvalidIdentifier(String::from("class"))));
+}
+
+#[test]
+fn rejects_declarations_without_declarators() {
+    let program = json::parse(r#"{"type": "Program", "body": [{"type": "VariableDeclaration", "kind": "let", "declarations": []}]}"#).unwrap();
+    assert_eq!(from_estree(&program), Err(EstreeError::InvalidNode(String::from("a VariableDeclaration with declarators"))));
+}
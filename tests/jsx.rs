@@ -0,0 +1,59 @@
+extern crate maxwell;
+
+mod common;
+
+use maxwell::compiler::{Generator, JsxOptions, JsxRuntime};
+use maxwell::compiler::transform::{Plugin, PluginPass};
+use maxwell::plugins::JsxPlugin;
+use common::parse;
+
+fn lower(source: &str, runtime: JsxRuntime) -> String {
+    let mut options = JsxOptions::new();
+    options.runtime = runtime;
+    let mut tree = parse(source);
+    JsxPlugin::new(options).handle(&mut PluginPass::SyntaxTree { tree: &mut tree, source }).unwrap();
+    return Generator::new().generate_tree(&tree);
+}
+
+fn classic(source: &str) -> String {
+    return lower(source, JsxRuntime::Classic);
+}
+
+fn automatic(source: &str) -> String {
+    return lower(source, JsxRuntime::Automatic);
+}
+
+#[test]
+fn decodes_entities_in_text_and_attributes() {
+    let source = "x = <p title=\"a &amp; b\">&lt;&#65;&#x42;&nbsp;&unknown; &amp</p>;\n";
+    assert_eq!(classic(source), "x = React.createElement(\"p\", { title: \"a & b\" }, \"<AB\\u00A0&unknown; &amp\");\n");
+}
+
+#[test]
+fn collapses_whitespace_in_text() {
+    let source = "x = <p>\n  Hello,\n\n  world!  {name}  \n</p>;\n";
+    assert_eq!(classic(source), "x = React.createElement(\"p\", null, \"Hello, world!  \", name);\n");
+
+    let source = "x = <p>\n  {a}\n  {b}\n</p>;\n";
+    assert_eq!(classic(source), "x = React.createElement(\"p\", null, a, b);\n");
+}
+
+#[test]
+fn passes_key_apart_from_spread_props() {
+    let source = "x = <A {...props} key=\"k\" b={1} />;\n";
+    assert_eq!(classic(source), "x = React.createElement(A, Object.assign({}, props, { key: \"k\", b: 1 }));\n");
+    assert_eq!(automatic(source), "import { jsx as _jsx } from \"react/jsx-runtime\";\nx = _jsx(A, Object.assign({}, props, { b: 1 }), \"k\");\n");
+}
+
+#[test]
+fn lowers_fragments() {
+    let source = "x = <><a /><b /></>;\n";
+    assert_eq!(classic(source), "x = React.createElement(React.Fragment, null, React.createElement(\"a\", null), React.createElement(\"b\", null));\n");
+    assert_eq!(automatic(source), "import { jsx as _jsx, jsxs as _jsxs, Fragment as _Fragment } from \"react/jsx-runtime\";\nx = _jsxs(_Fragment, { children: [_jsx(\"a\", {}), _jsx(\"b\", {})] });\n");
+}
+
+#[test]
+fn imports_the_runtime_after_directives_and_the_hashbang() {
+    let source = "#!/usr/bin/env node\n\"use strict\";\nx = <a />;\n";
+    assert_eq!(automatic(source), "#!/usr/bin/env node\n\"use strict\";\nimport { jsx as _jsx } from \"react/jsx-runtime\";\nx = _jsx(\"a\", {});\n");
+}
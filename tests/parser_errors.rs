@@ -0,0 +1,85 @@
+extern crate maxwell;
+
+mod common;
+
+use maxwell::compiler::ast::SourceLocation;
+use maxwell::compiler::diagnostics::{codes, Diagnostic};
+use maxwell::compiler::parser::{JsParser, SourceType};
+use common::parse_with;
+
+fn diagnostic(source: &str) -> Diagnostic {
+    return match parse_with(JsParser::new(), "input.js", source, SourceType::Module) {
+        Ok(_) => panic!("Expected '{}' not to parse.", source),
+        Err(err) => err.to_diagnostic()
+    };
+}
+
+fn primary(diagnostic: &Diagnostic) -> SourceLocation {
+    return diagnostic.primary_label().unwrap().location.clone();
+}
+
+#[test]
+fn reports_unterminated_tokens() {
+    let string = diagnostic("let a = 'abc");
+    assert_eq!(string.message, "Unterminated string literal.");
+    assert_eq!(primary(&string), SourceLocation::new(8, 12));
+
+    let comment = diagnostic("foo();\n/* bar");
+    assert_eq!(comment.message, "Unterminated comment.");
+    assert_eq!(primary(&comment), SourceLocation::new(7, 13));
+}
+
+#[test]
+fn reports_unexpected_characters() {
+    let diagnostic = diagnostic("class A {\n  #count = 0;\n}");
+    assert_eq!(diagnostic.message, "Unexpected character '#'.");
+    assert_eq!(primary(&diagnostic), SourceLocation::new(12, 13));
+}
+
+#[test]
+fn points_at_first_declaration() {
+    let diagnostic = diagnostic("let a = 1;\nlet a = 2;");
+    assert_eq!(diagnostic.message, "Identifier 'a' has already been declared.");
+    assert_eq!(primary(&diagnostic), SourceLocation::new(15, 16));
+    let secondary = diagnostic.labels.iter().find(|label| !label.primary).unwrap();
+    assert_eq!(secondary.location, SourceLocation::new(4, 5));
+    assert_eq!(secondary.message, "first declared here");
+}
+
+#[test]
+fn reports_missing_terminator() {
+    assert_eq!(diagnostic("let x = 1 2;").message, "Expected ';' or a line break but found '2'.");
+}
+
+#[test]
+fn rejects_malformed_numbers() {
+    let number = diagnostic("let a = 1.5.5;");
+    assert_eq!(number.message, "Invalid number '1.5.5'.");
+    assert_eq!(number.code, Some(String::from(codes::EARLY_ERROR)));
+    assert_eq!(primary(&number), SourceLocation::new(8, 13));
+
+    assert_eq!(diagnostic("let a = 1e5.5;").message, "Invalid number '1e5.5'.");
+    assert_eq!(diagnostic("let a = 0b102;").message, "Invalid number '0b102'.");
+    assert_eq!(diagnostic("let a = 3in b;").message, "Invalid number '3in'.");
+    assert_eq!(diagnostic("x = 0_1;").message, "Invalid number '0_1'.");
+    assert_eq!(diagnostic("x = 07n;").message, "Invalid BigInt '07n'.");
+    assert_eq!(diagnostic("x = 1.5n;").message, "Invalid BigInt '1.5n'.");
+}
+
+#[test]
+fn rejects_unary_expressions_before_exponents() {
+    let diagnostic = diagnostic("y = -a ** 2;");
+    assert_eq!(diagnostic.message, "Unary expressions before '**' must be bracketed.");
+    assert_eq!(primary(&diagnostic), SourceLocation::new(7, 9));
+}
+
+#[test]
+fn rejects_invalid_escape_sequences() {
+    let escape = diagnostic("let a = \"\\u{zz}\";");
+    assert_eq!(escape.message, "Invalid escape sequence '\\u{zz}'.");
+    assert_eq!(escape.code, Some(String::from(codes::EARLY_ERROR)));
+    assert_eq!(primary(&escape), SourceLocation::new(9, 15));
+
+    assert_eq!(diagnostic("let a = '\\u{110000}';").message, "Invalid escape sequence '\\u{110000}'.");
+    assert_eq!(diagnostic("let a = '\\x4g';").message, "Invalid escape sequence '\\x4g'.");
+}
@@ -0,0 +1,98 @@
+extern crate maxwell;
+
+mod common;
+
+use maxwell::compiler::{Generator, GeneratorOptions, OutputFormat, QuoteStyle};
+use common::parse;
+
+fn print(source: &str, options: GeneratorOptions) -> String {
+    return Generator::with_options(options).generate_tree_from(&parse(source), source);
+}
+
+fn compact() -> GeneratorOptions {
+    let mut options = GeneratorOptions::new();
+    options.format = OutputFormat::Compact;
+    return options;
+}
+
+#[test]
+fn brackets_operands_by_precedence() {
+    let source = "x = (a + b) * c;\ny = a - (b - c);\nz = (a - b) - c;\nw = (-a) ** 2;\nv = (a ** b) ** c;\nu = a ** (b ** c);\n";
+    assert_eq!(print(source, compact()), "x=(a+b)*c;y=a-(b-c);z=a-b-c;w=(-a)**2;v=(a**b)**c;u=a**b**c;");
+}
+
+#[test]
+fn brackets_calls_in_new_and_integers_before_a_dot() {
+    let source = "x = new (f())();\ny = new a.b();\nz = (1).toString();\nw = (1.5).toFixed();\n";
+    assert_eq!(print(source, compact()), "x=new(f())();y=new a.b();z=(1).toString();w=1.5.toFixed();");
+}
+
+#[test]
+fn keeps_statements_from_being_read_as_declarations() {
+    let source = "(function () {})();\n({ a: 1 }).a;\n";
+    assert_eq!(print(source, compact()), "(function(){}());({a:1}.a);");
+}
+
+#[test]
+fn requotes_strings() {
+    let source = "a = 'it\\'s';\nb = \"say \\\"hi\\\"\";\n";
+
+    let mut options = compact();
+    options.quotes = QuoteStyle::Double;
+    assert_eq!(print(source, options.clone()), "a=\"it's\";b=\"say \\\"hi\\\"\";");
+
+    options.quotes = QuoteStyle::Single;
+    assert_eq!(print(source, options.clone()), "a='it\\'s';b='say \"hi\"';");
+
+    options.quotes = QuoteStyle::Preserve;
+    assert_eq!(print(source, options), "a='it\\'s';b=\"say \\\"hi\\\"\";");
+}
+
+#[test]
+fn escapes_characters_outside_of_ascii() {
+    let source = "var café = \"naïve 😀\";\n";
+
+    let mut options = compact();
+    options.ascii_only = true;
+    assert_eq!(print(source, options), "var caf\\u00E9=\"na\\u00EFve \\uD83D\\uDE00\";");
+    assert_eq!(print(source, compact()), "var café=\"naïve 😀\";");
+}
+
+#[test]
+fn guards_statements_without_semicolons() {
+    let source = "a = b;\n(c || d).e();\n[1, 2].forEach(f);\n-x;\n+y;\n";
+
+    let mut options = compact();
+    options.semicolons = false;
+    assert_eq!(print(source, options), "a=b\n;(c||d).e()\n;[1,2].forEach(f)\n;-x\n;+y");
+}
+
+#[test]
+fn keeps_license_comments_and_annotations_in_compact_output() {
+    let source = "/*! MIT License */\n/** Adds. */\nfunction add(a, b) { return a + b; }\n// dropped\nvar x = /*#__PURE__*/ make();\n";
+    assert_eq!(print(source, compact()), "/*! MIT License */function add(a,b){return a+b;}var x=/*#__PURE__*/make();");
+}
+
+#[test]
+fn keeps_every_comment_in_pretty_output() {
+    let source = "/*! MIT License */\n/**\n * Adds.\n */\nfunction add(a, b) {\n  return a + b; // sum\n}\nvar o = {\n  a: 1, // first\n  /* second */ b: 2\n};\nvar x = /*#__PURE__*/ make();\n";
+    let expected = "/*! MIT License */\n/**\n * Adds.\n */\nfunction add(a, b) {\n  return a + b; // sum\n}\nvar o = {\n  a: 1, // first\n  /* second */\n  b: 2\n};\nvar x = /*#__PURE__*/ make();";
+    assert_eq!(print(source, GeneratorOptions::new()), expected);
+}
+
+#[test]
+fn breaks_the_line_after_a_line_comment_inside_an_expression() {
+    let source = "f(a, // first\n  b);\n";
+    assert_eq!(print(source, GeneratorOptions::new()), "f(a, // first\nb);");
+}
+
+#[test]
+fn writes_inner_comments_between_empty_brackets() {
+    let source = "function f() {\n  // todo\n}\ng(/* nothing */);\n";
+    assert_eq!(print(source, GeneratorOptions::new()), "function f() { // todo\n}\ng(/* nothing */);");
+}
+
+#[test]
+fn keeps_the_comments_of_a_source_without_code() {
+    assert_eq!(print("// only\n/* comments */\n", GeneratorOptions::new()), "// only\n/* comments */");
+}
@@ -0,0 +1,28 @@
+extern crate maxwell;
+
+mod common;
+
+use maxwell::compiler::Generator;
+use common::parse;
+
+fn round_trip(source: &str) -> String {
+    return Generator::new().generate_tree(&parse(source));
+}
+
+#[test]
+fn keeps_crlf_line_breaks() {
+    let source = "import a from './a'\r\nlet x = 1\r\n\r\nfunction f(a,\r\n  b) {\r\n  return a\r\n}\r\nf(x,\r\n  2);\r\n";
+    assert_eq!(round_trip(source), source);
+}
+
+#[test]
+fn keeps_lone_cr_line_breaks() {
+    let source = "let x = 1\rlet y = {\r  a: 1\r}\rx\r";
+    assert_eq!(round_trip(source), source);
+}
+
+#[test]
+fn keeps_padding_before_closing_brackets() {
+    let source = "x = (a /* c */);\nx = (\n  a\n);\n";
+    assert_eq!(round_trip(source), source);
+}
@@ -0,0 +1,66 @@
+extern crate maxwell;
+
+mod common;
+
+use maxwell::compiler::scope::{BindingKind, Reference, ScopeKind, ScopeTree};
+use common::parse;
+
+/// The references to a name, in source order.
+fn references<'a>(scopes: &'a ScopeTree, name: &str) -> Vec<&'a Reference> {
+    return scopes.references.iter().filter(|reference| reference.name == name).collect();
+}
+
+/// The kind of the binding a reference resolves to.
+fn resolved(scopes: &ScopeTree, reference: &Reference) -> BindingKind {
+    return scopes.binding(reference.binding.expect("reference to resolve")).kind;
+}
+
+#[test]
+fn hoists_vars_and_functions() {
+    let scopes = ScopeTree::analyze(&parse("f(x);\nfunction f() {}\nvar x = 1;\nfunction g() { { var y = 1; } return y; }\n"));
+    assert_eq!(resolved(&scopes, references(&scopes, "f")[0]), BindingKind::Function);
+    assert_eq!(resolved(&scopes, references(&scopes, "x")[0]), BindingKind::Var);
+
+    let y = references(&scopes, "y")[0];
+    let binding = scopes.binding(y.binding.unwrap());
+    assert_eq!(binding.kind, BindingKind::Var);
+    assert_eq!(scopes.scope(binding.scope).kind, ScopeKind::Function);
+}
+
+#[test]
+fn marks_references_in_the_temporal_dead_zone() {
+    let scopes = ScopeTree::analyze(&parse("a;\nlet a = 1;\na;\nfunction f() { return a; }\n"));
+    let references = references(&scopes, "a");
+    assert_eq!(references.len(), 3);
+    assert!(references[0].in_tdz);
+    assert!(!references[1].in_tdz);
+    assert!(!references[2].in_tdz);
+}
+
+#[test]
+fn finds_captured_bindings() {
+    let scopes = ScopeTree::analyze(&parse("function outer() {\n  let count = 0;\n  let local = 1;\n  local;\n  return function () { return count; };\n}\n"));
+    let count = references(&scopes, "count")[0].binding.unwrap();
+    let local = references(&scopes, "local")[0].binding.unwrap();
+    assert!(scopes.is_captured(count));
+    assert!(!scopes.is_captured(local));
+}
+
+#[test]
+fn resolves_shadowed_names_to_the_closest_binding() {
+    let scopes = ScopeTree::analyze(&parse("let a = 1;\nfunction f(a) { return a; }\n{ let a = 2; a; }\na;\n"));
+    let references = references(&scopes, "a");
+    assert_eq!(resolved(&scopes, references[0]), BindingKind::Parameter);
+    assert_eq!(resolved(&scopes, references[1]), BindingKind::Let);
+    assert_eq!(scopes.scope(scopes.binding(references[1].binding.unwrap()).scope).kind, ScopeKind::Block);
+    assert_eq!(scopes.scope(scopes.binding(references[2].binding.unwrap()).scope).kind, ScopeKind::Module);
+}
+
+#[test]
+fn lets_functions_shadow_their_expression_name() {
+    let scopes = ScopeTree::analyze(&parse("let g = function f(f) { return f; };\nlet h = function f() { var f = 1; return f; };\nlet i = function f() { return f; };\n"));
+    let references = references(&scopes, "f");
+    assert_eq!(resolved(&scopes, references[0]), BindingKind::Parameter);
+    assert_eq!(resolved(&scopes, references[1]), BindingKind::Var);
+    assert_eq!(resolved(&scopes, references[2]), BindingKind::ExpressionName);
+}
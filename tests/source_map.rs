@@ -0,0 +1,93 @@
+extern crate maxwell;
+
+mod common;
+
+use maxwell::compiler::{Generator, GeneratorOptions, OutputFormat, SourceMap, SourceMapBuilder, Symbol};
+use maxwell::compiler::ast::SyntaxTree;
+use maxwell::compiler::ast::declaration::DeclarationNode;
+use maxwell::compiler::ast::expression::{ExpressionNode, Expression};
+use maxwell::compiler::ast::visit_mut::VisitMut;
+use common::parse;
+
+fn generate(generator: &Generator, tree: &SyntaxTree, source: &str) -> (String, SourceMap) {
+    let mut builder = SourceMapBuilder::new(None);
+    builder.add_source(String::from("input.js"), None);
+    let code = generator.generate_tree_mapped(tree, source, &mut builder, 0);
+    return (code, builder.build());
+}
+
+/// The original line, column and name the code at a generated line and column maps to.
+fn original(map: &SourceMap, line: usize, column: usize) -> (usize, usize, Option<String>) {
+    let mapping = map.mappings.iter().find(|mapping| mapping.generated_line == line && mapping.generated_column == column)
+        .unwrap_or_else(|| panic!("No mapping at {}:{}", line, column));
+    return (mapping.original_line, mapping.original_column, mapping.name.map(|index| map.names[index].clone()));
+}
+
+/// Renames `value` to `v`.
+struct Rename;
+
+impl VisitMut for Rename {
+    fn enter_declaration(&mut self, declaration: &mut DeclarationNode) {
+        if declaration.name == "value" {
+            declaration.name = Symbol::new("v");
+        }
+    }
+
+    fn enter_expression(&mut self, expression: &mut ExpressionNode) {
+        let renamed = match expression.expression {
+            Expression::Identifier(ref name) => name == "value",
+            _ => false
+        };
+        if renamed {
+            expression.expression = Expression::Identifier(Symbol::new("v"));
+        }
+    }
+}
+
+#[test]
+fn maps_every_located_node() {
+    let source = "let total = sum(a, b);\nlog(total);\n";
+    let (code, map) = generate(&Generator::new(), &parse(source), source);
+    assert_eq!(code, source);
+    assert_eq!(original(&map, 0, 0), (0, 0, None));
+    assert_eq!(original(&map, 0, 4), (0, 4, None));
+    assert_eq!(original(&map, 0, 12), (0, 12, None));
+    assert_eq!(original(&map, 0, 16), (0, 16, None));
+    assert_eq!(original(&map, 0, 19), (0, 19, None));
+    assert_eq!(original(&map, 1, 4), (1, 4, None));
+}
+
+#[test]
+fn keeps_names_of_renamed_identifiers() {
+    let source = "const value = 1;\nfoo(value);\n";
+    let mut tree = parse(source);
+    Rename.visit_syntax_tree(&mut tree);
+
+    let (code, map) = generate(&Generator::new(), &tree, source);
+    assert_eq!(code, "const v = 1;\nfoo(v);\n");
+    assert_eq!(original(&map, 0, 6), (0, 6, Some(String::from("value"))));
+    assert_eq!(original(&map, 1, 4), (1, 4, Some(String::from("value"))));
+
+    let mut options = GeneratorOptions::new();
+    options.format = OutputFormat::Compact;
+    let (code, map) = generate(&Generator::with_options(options), &tree, source);
+    assert_eq!(code, "const v=1;foo(v);");
+    assert_eq!(original(&map, 0, 6), (0, 6, Some(String::from("value"))));
+    assert_eq!(original(&map, 0, 14), (1, 4, Some(String::from("value"))));
+}
+
+#[test]
+fn streams_the_code_it_generates() {
+    let source = "let total = sum(a, b);\nlog(total)";
+    let tree = parse(source);
+    let generator = Generator::new();
+    let (code, map) = generate(&generator, &tree, source);
+
+    let mut streamed = Vec::new();
+    let mut builder = SourceMapBuilder::new(None);
+    builder.add_source(String::from("input.js"), None);
+    let end = generator.stream_tree_mapped(&tree, source, &mut streamed, &mut builder, 0).unwrap();
+    assert_eq!(String::from_utf8(streamed).unwrap(), code);
+    assert_eq!(builder.build().mappings, map.mappings);
+    assert_eq!((end.line, end.column), (1, 10));
+}
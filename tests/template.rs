@@ -0,0 +1,83 @@
+extern crate maxwell;
+
+use maxwell::compiler::ast::Node;
+use maxwell::compiler::transform::{Replacement, Template, TemplateError};
+
+fn expression(source: &str) -> Replacement {
+    return Replacement::Expression(Template::new(source).expression(Vec::new()).unwrap());
+}
+
+#[test]
+fn brackets_replacement_inside_operator() {
+    let statement = Template::new("var x = a * %%v%%;").statement(vec![("v", expression("b + c"))]).unwrap();
+    assert_eq!(statement.generate(), "var x = a * (b + c);");
+}
+
+#[test]
+fn brackets_replacement_as_callee_object() {
+    let statement = Template::new("%%v%%.foo();").statement(vec![("v", expression("b + c"))]).unwrap();
+    assert_eq!(statement.generate(), "(b + c).foo();");
+}
+
+#[test]
+fn keeps_atomic_replacement_bare() {
+    let statement = Template::new("f(%%v%%);").statement(vec![("v", expression("value"))]).unwrap();
+    assert_eq!(statement.generate(), "f(value);");
+}
+
+#[test]
+fn leaves_replacements_binding_tightly_enough_bare() {
+    let statement = Template::new("f(%%v%%);").statement(vec![("v", expression("b + c"))]).unwrap();
+    assert_eq!(statement.generate(), "f(b + c);");
+
+    let statement = Template::new("let x = %%v%%;").statement(vec![("v", expression("a.b()"))]).unwrap();
+    assert_eq!(statement.generate(), "let x = a.b();");
+
+    let statement = Template::new("x = %%v%% - c;").statement(vec![("v", expression("a - b"))]).unwrap();
+    assert_eq!(statement.generate(), "x = a - b - c;");
+}
+
+#[test]
+fn brackets_replacements_by_the_side_of_the_operator() {
+    let statement = Template::new("x = a - %%v%%;").statement(vec![("v", expression("b - c"))]).unwrap();
+    assert_eq!(statement.generate(), "x = a - (b - c);");
+
+    let statement = Template::new("x = %%v%% ** 2;").statement(vec![("v", expression("-a"))]).unwrap();
+    assert_eq!(statement.generate(), "x = (-a) ** 2;");
+
+    let statement = Template::new("x = %%v%% ? a : b;").statement(vec![("v", expression("c ? d : e"))]).unwrap();
+    assert_eq!(statement.generate(), "x = (c ? d : e) ? a : b;");
+}
+
+#[test]
+fn brackets_integers_before_a_dot_and_calls_after_new() {
+    let statement = Template::new("x = %%v%%.toString();").statement(vec![("v", expression("1"))]).unwrap();
+    assert_eq!(statement.generate(), "x = (1).toString();");
+
+    let statement = Template::new("x = new %%v%%();").statement(vec![("v", expression("f()"))]).unwrap();
+    assert_eq!(statement.generate(), "x = new (f())();");
+}
+
+#[test]
+fn brackets_statements_starting_like_declarations() {
+    let statement = Template::new("%%v%%;").statement(vec![("v", expression("function () {}"))]).unwrap();
+    assert_eq!(statement.generate(), "(function () {});");
+
+    let statement = Template::new("%%v%%;").statement(vec![("v", expression("a.b()"))]).unwrap();
+    assert_eq!(statement.generate(), "a.b();");
+}
+
+#[test]
+fn writes_names_in_place() {
+    let statement = Template::new("var %%name%% = 1;").statement(vec![("name", Replacement::Name(String::from("count")))]).unwrap();
+    assert_eq!(statement.generate(), "var count = 1;");
+}
+
+#[test]
+fn rejects_names_which_are_not_identifiers() {
+    let result = Template::new("var %%name%% = 1;").statement(vec![("name", Replacement::Name(String::from("a = 2, b")))]);
+    match result {
+        Err(TemplateError::InvalidName(ref name, ref identifier)) => assert_eq!((name.as_str(), identifier.as_str()), ("name", "a = 2, b")),
+        _ => panic!("expected an invalid name, got {:?}", result)
+    }
+}
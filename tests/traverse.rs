@@ -0,0 +1,135 @@
+extern crate maxwell;
+
+mod common;
+
+use maxwell::compiler::Generator;
+use maxwell::compiler::ast::{Node, SourceLocation};
+use maxwell::compiler::ast::statement::StatementNode;
+use maxwell::compiler::ast::expression::ExpressionNode;
+use maxwell::compiler::ast::traverse::{traverse, NodePath, Traverse};
+use maxwell::compiler::scope::{ScopeKind, ScopeTree};
+use maxwell::compiler::transform::Template;
+use common::{at, parse};
+
+fn statements(source: &str) -> Vec<StatementNode> {
+    return Template::new(source).statements(Vec::new()).unwrap();
+}
+
+/// Calls `edit` when entering the statement at `target`, and records every node entered.
+struct Edit<F> {
+    target: SourceLocation,
+    edit: F,
+    entered: Vec<SourceLocation>,
+}
+
+impl<F: FnMut(&mut NodePath)> Traverse for Edit<F> {
+    fn enter_statement(&mut self, _statement: &mut StatementNode, path: &mut NodePath) {
+        self.entered.push(path.location().clone());
+        if *path.location() == self.target {
+            (self.edit)(path);
+        }
+    }
+
+    fn enter_expression(&mut self, _expression: &mut ExpressionNode, path: &mut NodePath) {
+        self.entered.push(path.location().clone());
+    }
+}
+
+/// Traverses the source, editing the statement `target`. Returns the output and the code of every
+/// node entered.
+fn edit<F: FnMut(&mut NodePath)>(source: &str, target: &str, edit: F) -> (String, Vec<String>) {
+    let mut tree = parse(source);
+    let mut visitor = Edit { target: at(source, target), edit, entered: Vec::new() };
+    traverse(&mut visitor, &mut tree);
+    let entered = visitor.entered.iter().map(|location| source[location.start..location.end].to_owned()).collect();
+    return (Generator::new().generate_tree(&tree), entered);
+}
+
+const TOP_LEVEL: &str = "a();\nmark();\nb();\n";
+const FUNCTION: &str = "function f() {\n  a();\n  mark();\n  b();\n}\n";
+const FOR: &str = "for (i = 0; i < n; i++) mark();\n";
+
+#[test]
+fn replaces_statements() {
+    let replace = |path: &mut NodePath| path.replace_with(statements("x();\ny();"));
+    assert_eq!(edit(TOP_LEVEL, "mark();", replace).0, "a();\nx();\ny();\nb();\n");
+    assert_eq!(edit(FUNCTION, "mark();", replace).0, "function f() {\n  a();\n  x();\n  y();\n  b();\n}\n");
+    assert_eq!(edit(FOR, "mark();", replace).0, "for (i = 0; i < n; i++) {\nx();\ny();\n}\n");
+}
+
+#[test]
+fn removes_statements() {
+    let remove = |path: &mut NodePath| path.remove();
+    assert_eq!(edit(TOP_LEVEL, "mark();", remove).0, "a();\nb();\n");
+    assert_eq!(edit(FUNCTION, "mark();", remove).0, "function f() {\n  a();\n  b();\n}\n");
+    assert_eq!(edit(FOR, "mark();", remove).0, "for (i = 0; i < n; i++) ;\n");
+}
+
+#[test]
+fn inserts_statements_around_others() {
+    let insert = |path: &mut NodePath| {
+        path.insert_before(statements("before();"));
+        path.insert_after(statements("after();"));
+    };
+    assert_eq!(edit(TOP_LEVEL, "mark();", insert).0, "a();\nbefore();\nmark();\nafter();\nb();\n");
+    assert_eq!(edit(FUNCTION, "mark();", insert).0, "function f() {\n  a();\n  before();\n  mark();\n  after();\n  b();\n}\n");
+    assert_eq!(edit(FOR, "mark();", insert).0, "for (i = 0; i < n; i++) {\nbefore();\nmark();\nafter();\n}\n");
+}
+
+#[test]
+fn inserts_statements_at_the_start_of_a_source() {
+    let insert = |path: &mut NodePath| path.insert_before(statements("first();"));
+    assert_eq!(edit(TOP_LEVEL, "a();", insert).0, "first();\na();\nmark();\nb();\n");
+}
+
+#[test]
+fn does_not_traverse_replacements() {
+    let (_, entered) = edit(TOP_LEVEL, "mark();", |path: &mut NodePath| path.replace_with(statements("x();")));
+    assert_eq!(entered, vec!["a();", "a()", "a", "mark();", "b();", "b()", "b"]);
+}
+
+#[test]
+fn skips_the_children_of_a_node() {
+    let source = "function f() {\n  a();\n}\nb();\n";
+    let (output, entered) = edit(source, "function f() {\n  a();\n}", |path: &mut NodePath| path.skip());
+    assert_eq!(output, source);
+    assert_eq!(entered, vec!["function f() {\n  a();\n}", "b();", "b()", "b"]);
+}
+
+#[test]
+fn stops_the_traversal_and_keeps_the_edits_made() {
+    let (output, entered) = edit(FUNCTION, "mark();", |path: &mut NodePath| {
+        path.insert_after(statements("after();"));
+        path.stop();
+    });
+    assert_eq!(output, "function f() {\n  a();\n  mark();\n  after();\n  b();\n}\n");
+    assert_eq!(entered.last().unwrap(), "mark();");
+}
+
+#[test]
+fn gives_the_siblings_of_statements_in_a_body() {
+    let siblings = |source: &str| {
+        let mut siblings = (Vec::new(), Vec::new());
+        edit(source, "mark();", |path: &mut NodePath| {
+            let code = |statements: &[StatementNode]| statements.iter().map(|statement| statement.generate().trim().to_owned()).collect::<Vec<_>>();
+            siblings = (code(path.previous_siblings()), code(path.next_siblings()));
+        });
+        return siblings;
+    };
+    assert_eq!(siblings(TOP_LEVEL), (vec![String::from("a();")], vec![String::from("b();")]));
+    assert_eq!(siblings(FUNCTION), (vec![String::from("a();")], vec![String::from("b();")]));
+    assert_eq!(siblings(FOR), (Vec::new(), Vec::new()));
+}
+
+#[test]
+fn finds_the_scope_of_a_statement() {
+    let scope = |source: &str| {
+        let scopes = ScopeTree::analyze(&parse(source));
+        let mut scope = None;
+        edit(source, "mark();", |path: &mut NodePath| scope = Some(path.scope(&scopes)));
+        return scopes.scope(scope.unwrap()).kind.clone();
+    };
+    assert_eq!(scope(TOP_LEVEL), ScopeKind::Module);
+    assert_eq!(scope(FUNCTION), ScopeKind::Function);
+    assert_eq!(scope("{\n  mark();\n}\n"), ScopeKind::Block);
+}
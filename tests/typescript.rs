@@ -0,0 +1,56 @@
+extern crate maxwell;
+
+mod common;
+
+use maxwell::compiler::Generator;
+use maxwell::compiler::parser::{SourceType, TsParser};
+use common::parse_with;
+
+fn lower(source: &str) -> String {
+    let tree = parse_with(TsParser::new(), "input.ts", source, SourceType::Module).unwrap().syntax_tree;
+    return Generator::new().generate_tree(&tree);
+}
+
+#[test]
+fn maps_only_numeric_enum_members_back_to_names() {
+    let code = lower("enum S { A = \"a\", B = A, C = S.A + \"x\", D = 1, E }");
+    assert!(code.contains("S[\"A\"] = \"a\";"), "{}", code);
+    assert!(code.contains("S[\"B\"] = S.A;"), "{}", code);
+    assert!(code.contains("S[\"C\"] = S.A + \"x\";"), "{}", code);
+    assert!(code.contains("S[S[\"D\"] = 1] = \"D\";"), "{}", code);
+    assert!(code.contains("S[S[\"E\"] = 2] = \"E\";"), "{}", code);
+}
+
+#[test]
+fn strips_annotations_and_casts() {
+    let source = "let a: number = 1;\nfunction f(x: string, y?: Array<number>): void {}\nlet b = <any>a;\nlet c = a as unknown as string;\nlet d = a!;\n";
+    assert_eq!(lower(source), "let a = 1;\nfunction f(x, y) {}\nlet b = a;\nlet c = a;\nlet d = a;\n");
+}
+
+#[test]
+fn assigns_parameter_properties_after_super() {
+    let source = "class A extends B {\n    constructor(private x: number, public readonly y: string) {\n        super();\n        this.z = 1;\n    }\n}\n";
+    let expected = "class A extends B {\n    constructor(x, y) {\n        super();\n        this.x = x;\n        this.y = y;\n        this.z = 1;\n    }\n}\n";
+    assert_eq!(lower(source), expected);
+}
+
+#[test]
+fn removes_declarations_and_types() {
+    let source = "declare const x: number;\ndeclare function f(): void;\ninterface I { a: number }\ntype T = string;\nimport type { U } from './u';\nlet y = 1;\n";
+    assert_eq!(lower(source).trim(), "let y = 1;");
+}
+
+#[test]
+fn reads_exported_variables_off_the_namespace() {
+    let code = lower("namespace N {\n    export var a = 1;\n    a = 2;\n    function g(a) { return a; }\n    export function f() { return { a }; }\n}\n");
+    let expected = "var N = (function (N) {\n    N.a = 1;\n    N.a = 2;\n    function g(a) { return a; }\n    function f() { return { a: N.a }; }\n    N.f = f;\n    return N;\n})(N || {});\n";
+    assert_eq!(code, expected);
+}
+
+#[test]
+fn nests_namespaces() {
+    let code = lower("namespace A.B { export const x = 1; }\n");
+    assert!(code.contains("var B = (function (B) { B.x = 1;"), "{}", code);
+    assert!(code.contains("A.B = B;"), "{}", code);
+    assert!(code.ends_with("})(A || {});\n"), "{}", code);
+}